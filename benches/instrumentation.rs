@@ -0,0 +1,46 @@
+//! Compare the parse time without a trace sink or a profile against the traced and the profiled parse.
+//!
+//! Run the benchmark with and without the `trace` and `profile` features
+//! to compare the overhead of the instrumentation in the release build.
+//! The traced parse only counts the labeled events without the `trace` feature.
+//! ```text
+//! cargo bench --bench instrumentation
+//! cargo bench --bench instrumentation --features trace,profile
+//! ```
+use lang_pt::{examples::json::tokenized::json_grammar, with_trace_sink, TraceEvent};
+use std::time::Instant;
+
+fn main() {
+    let parser = json_grammar();
+    let code_part = std::fs::read("./src/examples/__tests__/benchmark/example.json").unwrap();
+    let times = 10000;
+
+    let plain_instant = Instant::now();
+    for _ in 0..times {
+        parser.tokenize_n_parse(&code_part).unwrap();
+    }
+    println!("Plain time:{:?}", plain_instant.elapsed());
+
+    let mut events = 0;
+    let mut count_events = |_: TraceEvent| events += 1;
+    let traced_instant = Instant::now();
+    for _ in 0..times {
+        with_trace_sink(&mut count_events, || {
+            parser.tokenize_n_parse(&code_part).unwrap();
+        });
+    }
+    println!(
+        "Traced time:{:?} for {} events",
+        traced_instant.elapsed(),
+        events
+    );
+
+    #[cfg(feature = "profile")]
+    {
+        let profiled_instant = Instant::now();
+        for _ in 0..times {
+            lang_pt::with_profile(|| parser.tokenize_n_parse(&code_part).unwrap());
+        }
+        println!("Profiled time:{:?}", profiled_instant.elapsed());
+    }
+}
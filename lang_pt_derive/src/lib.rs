@@ -0,0 +1,234 @@
+//! Derive macro to generate typed views over the [AST](https://docs.rs/lang_pt/latest/lang_pt/struct.ASTNode.html) parsed by `lang_pt`.
+//!
+//! The `TypedNode` derive implements the `lang_pt::TypedNode` trait for annotated structs and enums.
+//! The derived views check the shape of the tree when the tree is converted into the view.
+//!
+//! A struct annotated with `#[node(Node::Variant)]` matches a tree with the node value `Node::Variant`.
+//! Each field consumes the children of the tree in the declared order,
+//! and all the children of the tree should be consumed by the fields.
+//! * A field of type `Option<T>` consumes the next child if the child matches `T`.
+//! * A field of type `Vec<T>` consumes the consecutive children which match `T`.
+//! * A field annotated with `#[tree]` is the reference of the tree itself and does not consume any child.
+//! * Any other field consumes exactly one child matching the type of the field.
+//!
+//! An enum of single field variants matches a tree matching any of the variants in the declared order.
+//!
+//! ```ignore
+//! use lang_pt::{ASTNode, TypedNode};
+//!
+//! #[derive(TypedNode)]
+//! #[node(JSONNode::Object)]
+//! struct Object<'a> {
+//!     items: Vec<Item<'a>>,
+//! }
+//!
+//! #[derive(TypedNode)]
+//! #[node(JSONNode::Item)]
+//! struct Item<'a> {
+//!     key: Key<'a>,
+//!     value: Value<'a>,
+//! }
+//!
+//! #[derive(TypedNode)]
+//! #[node(JSONNode::Key)]
+//! struct Key<'a>(#[tree] &'a ASTNode<JSONNode>);
+//!
+//! #[derive(TypedNode)]
+//! enum Value<'a> {
+//!     Object(Object<'a>),
+//!     Key(Key<'a>),
+//! }
+//! ```
+
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::{
+    parse_macro_input, parse_quote, Data, DataEnum, DataStruct, DeriveInput, Error, Fields,
+    Generics, Lifetime, LifetimeParam, Path, PathArguments, Type,
+};
+
+#[proc_macro_derive(TypedNode, attributes(node, tree))]
+pub fn derive_typed_node(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    let result = match &input.data {
+        Data::Struct(data) => derive_struct(&input, data),
+        Data::Enum(data) => derive_enum(&input, data),
+        Data::Union(_) => Err(Error::new_spanned(
+            &input.ident,
+            "TypedNode can not be derived for a union.",
+        )),
+    };
+    result.unwrap_or_else(Error::into_compile_error).into()
+}
+
+/// Get the lifetime of the tree from the generics or introduce a new one.
+fn tree_lifetime(generics: &Generics) -> (Lifetime, Generics) {
+    let mut impl_generics = generics.clone();
+    match generics.lifetimes().next() {
+        Some(param) => (param.lifetime.clone(), impl_generics),
+        None => {
+            let lifetime: Lifetime = parse_quote!('__tree);
+            impl_generics
+                .params
+                .insert(0, LifetimeParam::new(lifetime.clone()).into());
+            (lifetime, impl_generics)
+        }
+    }
+}
+
+/// Get the node value path of the `#[node(Node::Variant)]` attribute.
+fn node_path(input: &DeriveInput) -> Result<Path, Error> {
+    let attr = input
+        .attrs
+        .iter()
+        .find(|attr| attr.path().is_ident("node"))
+        .ok_or_else(|| {
+            Error::new_spanned(
+                &input.ident,
+                "A struct deriving TypedNode requires the #[node(Node::Variant)] attribute.",
+            )
+        })?;
+    let path: Path = attr.parse_args()?;
+    if path.segments.len() < 2 {
+        return Err(Error::new_spanned(
+            path,
+            "The node value should be a path like Node::Variant.",
+        ));
+    }
+    Ok(path)
+}
+
+/// Whether the type of the field is the provided wrapper like `Option<T>` or `Vec<T>`.
+fn is_wrapped(ty: &Type, wrapper: &str) -> bool {
+    let Type::Path(type_path) = ty else {
+        return false;
+    };
+    type_path.path.segments.last().is_some_and(|segment| {
+        segment.ident == wrapper
+            && matches!(&segment.arguments, PathArguments::AngleBracketed(args) if args.args.len() == 1)
+    })
+}
+
+fn derive_struct(input: &DeriveInput, data: &DataStruct) -> Result<TokenStream, Error> {
+    let ident = &input.ident;
+    let path = node_path(input)?;
+    let mut node_type = path.clone();
+    node_type.segments.pop();
+    node_type.segments.pop_punct();
+
+    let (lifetime, impl_generics) = tree_lifetime(&input.generics);
+    let (impl_generics, _, _) = impl_generics.split_for_impl();
+    let (_, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let mut bindings = Vec::new();
+    let mut consumers = Vec::new();
+    for (index, field) in data.fields.iter().enumerate() {
+        let binding = field
+            .ident
+            .clone()
+            .unwrap_or_else(|| format_ident!("field_{}", index));
+        let name = field
+            .ident
+            .as_ref()
+            .map_or_else(|| index.to_string(), |ident| ident.to_string());
+        let consumer = if field.attrs.iter().any(|attr| attr.path().is_ident("tree")) {
+            quote!(__tree)
+        } else if is_wrapped(&field.ty, "Option") {
+            quote!(__cursor.optional()?)
+        } else if is_wrapped(&field.ty, "Vec") {
+            quote!(__cursor.many()?)
+        } else {
+            quote!(__cursor.one(#name)?)
+        };
+        consumers.push(quote!(let #binding = #consumer;));
+        bindings.push(binding);
+    }
+    let construct = match &data.fields {
+        Fields::Named(_) => quote!(Self { #(#bindings),* }),
+        Fields::Unnamed(_) => quote!(Self(#(#bindings),*)),
+        Fields::Unit => quote!(Self),
+    };
+    let expected = quote!(#path).to_string().replace(' ', "");
+
+    Ok(quote! {
+        impl #impl_generics ::lang_pt::TypedNode<#lifetime, #node_type> for #ident #ty_generics #where_clause {
+            fn is_match(tree: &::lang_pt::ASTNode<#node_type>) -> bool {
+                matches!(tree.node, #path)
+            }
+
+            fn from_tree(
+                __tree: &#lifetime ::lang_pt::ASTNode<#node_type>,
+            ) -> ::std::result::Result<Self, ::lang_pt::ShapeError> {
+                if !<Self as ::lang_pt::TypedNode<#lifetime, #node_type>>::is_match(__tree) {
+                    return Err(::lang_pt::ShapeError::mismatch(__tree, #expected));
+                }
+                #[allow(unused_mut)]
+                let mut __cursor = ::lang_pt::NodeCursor::new(__tree);
+                #(#consumers)*
+                __cursor.finish()?;
+                Ok(#construct)
+            }
+        }
+    })
+}
+
+fn derive_enum(input: &DeriveInput, data: &DataEnum) -> Result<TokenStream, Error> {
+    let ident = &input.ident;
+    let (lifetime, mut impl_generics) = tree_lifetime(&input.generics);
+    impl_generics.params.push(parse_quote!(__TNode));
+    let (_, ty_generics, _) = input.generics.split_for_impl();
+
+    let mut variant_types = Vec::new();
+    let mut variant_idents = Vec::new();
+    for variant in &data.variants {
+        match &variant.fields {
+            Fields::Unnamed(fields) if fields.unnamed.len() == 1 => {
+                variant_types.push(&fields.unnamed[0].ty);
+                variant_idents.push(&variant.ident);
+            }
+            _ => {
+                return Err(Error::new_spanned(
+                    variant,
+                    "A variant of an enum deriving TypedNode should have a single unnamed field.",
+                ))
+            }
+        }
+    }
+
+    let where_clause = impl_generics.make_where_clause();
+    where_clause
+        .predicates
+        .push(parse_quote!(__TNode: ::std::fmt::Debug));
+    for ty in &variant_types {
+        where_clause
+            .predicates
+            .push(parse_quote!(#ty: ::lang_pt::TypedNode<#lifetime, __TNode>));
+    }
+    let (impl_generics, _, where_clause) = impl_generics.split_for_impl();
+    let expected = ident.to_string();
+    let is_match = variant_types
+        .iter()
+        .map(|ty| quote!(<#ty as ::lang_pt::TypedNode<#lifetime, __TNode>>::is_match(tree)))
+        .reduce(|matches, next| quote!(#matches || #next))
+        .unwrap_or_else(|| quote!(false));
+
+    Ok(quote! {
+        impl #impl_generics ::lang_pt::TypedNode<#lifetime, __TNode> for #ident #ty_generics #where_clause {
+            fn is_match(tree: &::lang_pt::ASTNode<__TNode>) -> bool {
+                #is_match
+            }
+
+            fn from_tree(
+                tree: &#lifetime ::lang_pt::ASTNode<__TNode>,
+            ) -> ::std::result::Result<Self, ::lang_pt::ShapeError> {
+                #(
+                    if <#variant_types as ::lang_pt::TypedNode<#lifetime, __TNode>>::is_match(tree) {
+                        return <#variant_types as ::lang_pt::TypedNode<#lifetime, __TNode>>::from_tree(tree)
+                            .map(Self::#variant_idents);
+                    }
+                )*
+                Err(::lang_pt::ShapeError::mismatch(tree, #expected))
+            }
+        }
+    })
+}
@@ -0,0 +1,165 @@
+use lang_pt::{
+    examples::json::tokenized::{json_grammar, JSONNode},
+    ASTNode, NodeImpl, ShapeError, TypedNode,
+};
+
+#[derive(TypedNode)]
+#[node(JSONNode::Main)]
+struct Main<'a> {
+    value: Value<'a>,
+}
+
+#[derive(TypedNode)]
+enum Value<'a> {
+    Object(Object<'a>),
+    Array(Array<'a>),
+    Number(Number<'a>),
+    Constant(Constant),
+}
+
+#[derive(TypedNode)]
+#[node(JSONNode::Object)]
+struct Object<'a> {
+    items: Vec<Item<'a>>,
+}
+
+#[derive(TypedNode)]
+#[node(JSONNode::Item)]
+struct Item<'a> {
+    key: Key<'a>,
+    value: Box<Value<'a>>,
+}
+
+#[derive(TypedNode)]
+#[node(JSONNode::Key)]
+struct Key<'a>(#[tree] &'a ASTNode<JSONNode>);
+
+#[derive(TypedNode)]
+#[node(JSONNode::Array)]
+struct Array<'a> {
+    #[tree]
+    tree: &'a ASTNode<JSONNode>,
+    first: Option<Number<'a>>,
+    rest: Vec<Value<'a>>,
+}
+
+#[derive(TypedNode)]
+#[node(JSONNode::Number)]
+struct Number<'a>(#[tree] &'a ASTNode<JSONNode>);
+
+#[derive(TypedNode)]
+#[node(JSONNode::Constant)]
+struct Constant;
+
+fn text<'a>(tree: &ASTNode<JSONNode>, input: &'a str) -> &'a str {
+    &input[tree.start..tree.end]
+}
+
+#[test]
+fn typed_view_test() {
+    let parser = json_grammar();
+    let input = r#"{"a": [1, 2, true], "b": {}, "c": []}"#;
+    let tree = parser.parse(input.as_bytes()).unwrap();
+
+    let main = Main::from_tree(&tree[0]).unwrap();
+    let Value::Object(object) = main.value else {
+        panic!("Expected an object.");
+    };
+    let keys: Vec<&str> = object
+        .items
+        .iter()
+        .map(|item| text(item.key.0, input))
+        .collect();
+    assert_eq!(keys, vec![r#""a""#, r#""b""#, r#""c""#]);
+
+    let Value::Array(array) = object.items[0].value.as_ref() else {
+        panic!("Expected an array.");
+    };
+    assert_eq!(text(array.tree, input), "[1, 2, true]");
+    assert_eq!(text(array.first.as_ref().unwrap().0, input), "1");
+    let [Value::Number(number), Value::Constant(_)] = &array.rest[..] else {
+        panic!("Expected a number and a constant.");
+    };
+    assert_eq!(text(number.0, input), "2");
+
+    assert!(matches!(object.items[1].value.as_ref(), Value::Object(o) if o.items.is_empty()));
+    assert!(
+        matches!(object.items[2].value.as_ref(), Value::Array(a) if a.first.is_none() && a.rest.is_empty())
+    );
+}
+
+#[test]
+fn shape_error_test() {
+    let parser = json_grammar();
+    let input = r#"{"a": "text"}"#;
+    let tree = parser.parse(input.as_bytes()).unwrap();
+
+    assert!(Main::is_match(&tree[0]));
+    assert!(!Object::is_match(&tree[0]));
+    assert_eq!(
+        Object::from_tree(&tree[0]).err(),
+        Some(ShapeError::new(
+            0,
+            "Expected JSONNode::Object but found Main.".into()
+        ))
+    );
+
+    // The string value is not a variant of the typed value.
+    let err = Main::from_tree(&tree[0]).err().unwrap();
+    assert_eq!(err.pointer, 6);
+    assert_eq!(err.message, "Expected value of Item but found String.");
+}
+
+/// A node type which does not implement [PartialEq].
+#[derive(Debug, Clone)]
+enum Node {
+    Pair,
+    Number,
+    Null,
+}
+
+impl NodeImpl for Node {
+    fn null() -> Self {
+        Node::Null
+    }
+    fn is_null(&self) -> bool {
+        matches!(self, Node::Null)
+    }
+}
+
+#[derive(TypedNode)]
+#[node(Node::Pair)]
+struct Pair<'a> {
+    first: Option<Leaf<'a>>,
+    rest: Vec<Leaf<'a>>,
+}
+
+#[derive(TypedNode)]
+#[node(Node::Number)]
+struct Leaf<'a>(#[tree] &'a ASTNode<Node>);
+
+#[test]
+fn null_placeholder_test() {
+    let number = |start| ASTNode::leaf(Node::Number, start, start + 1, None);
+    let tree = ASTNode::new(
+        Node::Pair,
+        0,
+        3,
+        None,
+        vec![ASTNode::null(0, None), number(0), number(2)],
+    );
+    let pair = Pair::from_tree(&tree).unwrap();
+    assert!(pair.first.is_none());
+    assert_eq!(pair.rest.len(), 2);
+
+    let tree = ASTNode::new(
+        Node::Pair,
+        0,
+        1,
+        None,
+        vec![number(0), ASTNode::null(1, None)],
+    );
+    let pair = Pair::from_tree(&tree).unwrap();
+    assert_eq!(pair.first.unwrap().0.start, 0);
+    assert!(pair.rest.is_empty());
+}
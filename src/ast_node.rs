@@ -1,6 +1,6 @@
-use crate::{ASTNode, NodeImpl, TokenPtr};
+use crate::{ASTNode, Code, NodeImpl, TokenPtr, VisitAction, Visitor};
 use ptree::TreeItem;
-use std::fmt::{Debug, Display, Formatter};
+use std::fmt::{Debug, Display, Formatter, Write};
 
 impl<TNode: Debug> Display for ASTNode<TNode> {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
@@ -82,6 +82,46 @@ impl<TNode: Debug + Clone> ASTNode<TNode> {
         ptree::print_tree(self)
     }
 }
+
+/// A [Visitor] to write the tree as an S-expression.
+struct SExpWriter<'c> {
+    code: Option<&'c Code<'c>>,
+    sexp: String,
+}
+
+impl<'c, TNode: Debug> Visitor<TNode> for SExpWriter<'c> {
+    fn enter(&mut self, tree: &ASTNode<TNode>) -> VisitAction {
+        if !self.sexp.is_empty() {
+            self.sexp.push(' ');
+        }
+        write!(self.sexp, "({:?}", tree.node).unwrap();
+        if let (Some(code), true) = (self.code, tree.children.is_empty()) {
+            let source = String::from_utf8_lossy(&code.value[tree.start..tree.end]);
+            write!(self.sexp, " {:?}", source).unwrap();
+        }
+        VisitAction::Continue
+    }
+
+    fn leave(&mut self, _tree: &ASTNode<TNode>) -> VisitAction {
+        self.sexp.push(')');
+        VisitAction::Continue
+    }
+}
+
+impl<TNode: Debug> ASTNode<TNode> {
+    /// Write the tree as an S-expression like `(Object (Item (Key) (Number)))`.
+    ///
+    /// If the [Code] is provided, the matched source of each leaf is added as a string literal
+    /// i.e. `(Object (Item (Key "\"a\"") (Number "1")))`.
+    pub fn to_sexp(&self, code: Option<&Code>) -> String {
+        let mut writer = SExpWriter {
+            code,
+            sexp: String::new(),
+        };
+        self.walk(&mut writer);
+        writer.sexp
+    }
+}
 impl<TNode: Debug + Clone + Eq> ASTNode<TNode> {
     /// Find a AST child node for a given Token searching through all nested children  
 
@@ -0,0 +1,327 @@
+//! A command line tool to check inputs with the example grammars of the library
+//! or with a grammar loaded from a grammar definition file.
+
+use lang_pt::{
+    examples::json::{
+        lexerless::{self, json_lexerless_grammar_with_log},
+        tokenized::{self, json_grammar_with_log},
+    },
+    grammar::{Grammar, NodeName, TokenName},
+    ASTNode, Code, DefaultParser, LexerlessParser, Log, NodeImpl, TokenImpl, TraceEvent, TraceSink,
+};
+use serde::Serialize;
+use std::{
+    io::{ErrorKind, Read, Write},
+    process::ExitCode,
+};
+
+const USAGE: &str = "\
+Usage: lang_pt <COMMAND> (--example <NAME> | --grammar <FILE>) [--json] [INPUT]
+
+Commands:
+  tokenize  Print the tokens of the input with their positions.
+  parse     Print the parsed tree of the input.
+  grammar   Print the grammar of the parser.
+  trace     Parse the input with all the logs of the lexemes and the productions.
+
+Options:
+  --example <NAME>  Use an example grammar: json, json-lexerless.
+  --grammar <FILE>  Use a grammar loaded from a grammar definition file.
+                    A grammar with lexer rules is built into a tokenized parser.
+                    Rules whose names start with '_' do not create any node.
+  --json            Print the parsed tree as JSON.
+  -h, --help        Print this message.
+
+The input is read from the INPUT file or from the standard input if INPUT is omitted or '-'.";
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Command {
+    Tokenize,
+    Parse,
+    Grammar,
+    Trace,
+}
+
+struct Options {
+    command: Command,
+    example: Option<String>,
+    grammar: Option<String>,
+    json: bool,
+    input: Option<String>,
+}
+
+enum Parser {
+    Json(DefaultParser<tokenized::JSONNode, tokenized::JSONToken>),
+    JsonLexerless(LexerlessParser<lexerless::JSONNode>),
+    Tokenized(DefaultParser<NodeName, TokenName>),
+    Lexerless(LexerlessParser<NodeName>),
+}
+
+/// An error to print before exiting with the exit code.
+struct Failure {
+    message: String,
+    code: u8,
+}
+
+impl Failure {
+    fn usage(message: String) -> Self {
+        Self {
+            message: format!("{}\n\n{}", message, USAGE),
+            code: 2,
+        }
+    }
+}
+
+impl From<String> for Failure {
+    fn from(message: String) -> Self {
+        Self { message, code: 1 }
+    }
+}
+
+impl From<std::io::Error> for Failure {
+    /// The standard output closed by the reader e.g. `| head` is not reported.
+    fn from(err: std::io::Error) -> Self {
+        match err.kind() {
+            ErrorKind::BrokenPipe => Self {
+                message: String::new(),
+                code: 0,
+            },
+            _ => format!("Failed to write the output. {}", err).into(),
+        }
+    }
+}
+
+/// A [TraceSink] which writes the events to the output until the first write error.
+struct TraceWriter<W: Write> {
+    writer: W,
+    result: std::io::Result<()>,
+}
+
+impl<W: Write> TraceWriter<W> {
+    fn new(writer: W) -> Self {
+        Self {
+            writer,
+            result: Ok(()),
+        }
+    }
+
+    /// Take back the writer after the tracing, or the error of writing the events.
+    fn finish(self) -> std::io::Result<W> {
+        self.result.map(|_| self.writer)
+    }
+}
+
+impl<W: Write> TraceSink for TraceWriter<W> {
+    fn receive(&mut self, event: TraceEvent) {
+        if self.result.is_ok() {
+            self.result = writeln!(self.writer, "{}", event);
+        }
+    }
+}
+
+fn parse_args(mut args: impl Iterator<Item = String>) -> Result<Option<Options>, Failure> {
+    let command = match args.next().as_deref() {
+        Some("tokenize") => Command::Tokenize,
+        Some("parse") => Command::Parse,
+        Some("grammar") => Command::Grammar,
+        Some("trace") => Command::Trace,
+        Some("-h" | "--help" | "help") => return Ok(None),
+        Some(command) => return Err(Failure::usage(format!("Unknown command '{}'.", command))),
+        None => return Err(Failure::usage("A command is required.".into())),
+    };
+    let mut options = Options {
+        command,
+        example: None,
+        grammar: None,
+        json: false,
+        input: None,
+    };
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--example" | "--grammar" => {
+                let value = args
+                    .next()
+                    .ok_or_else(|| Failure::usage(format!("Option '{}' requires a value.", arg)))?;
+                match arg.as_str() {
+                    "--example" => options.example = Some(value),
+                    _ => options.grammar = Some(value),
+                }
+            }
+            "--json" => options.json = true,
+            "-h" | "--help" => return Ok(None),
+            option if option.starts_with("--") => {
+                return Err(Failure::usage(format!("Unknown option '{}'.", option)))
+            }
+            _ if options.input.is_none() => options.input = Some(arg),
+            _ => return Err(Failure::usage(format!("Unexpected argument '{}'.", arg))),
+        }
+    }
+    match (&options.example, &options.grammar) {
+        (None, None) => Err(Failure::usage(
+            "Either '--example' or '--grammar' is required.".into(),
+        )),
+        (Some(_), Some(_)) => Err(Failure::usage(
+            "Options '--example' and '--grammar' can not be used together.".into(),
+        )),
+        _ => Ok(Some(options)),
+    }
+}
+
+fn read_input(path: Option<&str>) -> Result<Vec<u8>, Failure> {
+    let mut input = Vec::new();
+    match path {
+        None | Some("-") => std::io::stdin()
+            .read_to_end(&mut input)
+            .map(|_| input)
+            .map_err(|err| format!("Failed to read the standard input. {}", err).into()),
+        Some(path) => std::fs::read(path)
+            .map_err(|err| format!("Failed to read the input file '{}'. {}", path, err).into()),
+    }
+}
+
+fn load_parser(options: &Options) -> Result<Parser, Failure> {
+    let log = match options.command {
+        Command::Trace => Log::Verbose(()),
+        _ => Log::None,
+    };
+    if let Some(example) = &options.example {
+        return match example.as_str() {
+            "json" => Ok(Parser::Json(json_grammar_with_log(log))),
+            "json-lexerless" => Ok(Parser::JsonLexerless(json_lexerless_grammar_with_log(log))),
+            _ => Err(Failure::usage(format!("Unknown example '{}'.", example))),
+        };
+    }
+    let path = options.grammar.as_deref().unwrap();
+    let source = std::fs::read_to_string(path)
+        .map_err(|err| format!("Failed to read the grammar file '{}'. {}", path, err))?;
+    let grammar = Grammar::from_dsl(&source)
+        .map_err(|err| format!("{}: {}", path, err.render(&Code::new(source.as_bytes()))))?;
+    let node_value = |name: &'static str| (!name.starts_with('_')).then_some(NodeName(name));
+    let parser = match grammar.lexer_rules.is_empty() {
+        true => grammar
+            .build_lexerless_with_log(node_value, log)
+            .map(Parser::Lexerless),
+        false => grammar
+            .build_parser_with_log(node_value, log)
+            .map(Parser::Tokenized),
+    };
+    parser.map_err(|err| err.to_string().into())
+}
+
+fn print_tree<TN: NodeImpl + Serialize>(
+    tree: &[ASTNode<TN>],
+    json: bool,
+    out: &mut dyn Write,
+) -> Result<(), Failure> {
+    if json {
+        let json = serde_json::to_string_pretty(tree).map_err(|err| err.to_string())?;
+        writeln!(out, "{}", json)?;
+        return Ok(());
+    }
+    for node in tree {
+        ptree::write_tree(node, &mut *out)?;
+    }
+    Ok(())
+}
+
+fn run_tokenized<TN: NodeImpl + Serialize, TL: TokenImpl>(
+    parser: &DefaultParser<TN, TL>,
+    options: &Options,
+    out: &mut dyn Write,
+) -> Result<(), Failure> {
+    if options.command == Command::Grammar {
+        writeln!(out, "{}", parser.grammar().map_err(|err| err.to_string())?)?;
+        return Ok(());
+    }
+    let input = read_input(options.input.as_deref())?;
+    let code = Code::new(&input);
+    if options.command == Command::Tokenize {
+        let tokens = parser.tokenize(&code).map_err(|err| err.render(&code))?;
+        for lex in tokens {
+            let start = code.obtain_position(lex.start);
+            let text = String::from_utf8_lossy(&input[lex.start..lex.end]);
+            writeln!(
+                out,
+                "{:>4}:{:<4} {:>6}..{:<6} {:<16} {:?}",
+                start.line,
+                start.column,
+                lex.start,
+                lex.end,
+                format!("{:?}", lex.token),
+                text
+            )?;
+        }
+        return Ok(());
+    }
+    let tree = match options.command {
+        Command::Trace => {
+            let mut sink = TraceWriter::new(&mut *out);
+            let result = parser.parse_with_trace(&input, &mut sink);
+            sink.finish()?;
+            result
+        }
+        _ => parser.parse(&input),
+    };
+    let tree = tree.map_err(|err| err.render(&code))?;
+    print_tree(&tree, options.json, out)
+}
+
+fn run_lexerless<TN: NodeImpl + Serialize>(
+    parser: &LexerlessParser<TN>,
+    options: &Options,
+    out: &mut dyn Write,
+) -> Result<(), Failure> {
+    match options.command {
+        Command::Grammar => {
+            writeln!(out, "{}", parser.grammar().map_err(|err| err.to_string())?)?;
+            Ok(())
+        }
+        Command::Tokenize => {
+            Err(String::from("A lexerless grammar does not have a tokenizer.").into())
+        }
+        Command::Parse | Command::Trace => {
+            let input = read_input(options.input.as_deref())?;
+            let tree = match options.command {
+                Command::Trace => {
+                    let mut sink = TraceWriter::new(&mut *out);
+                    let result = parser.parse_with_trace(&input, &mut sink);
+                    sink.finish()?;
+                    result
+                }
+                _ => parser.parse(&input),
+            };
+            let tree = tree.map_err(|err| err.render(&Code::new(&input)))?;
+            print_tree(&tree, options.json, out)
+        }
+    }
+}
+
+fn run() -> Result<(), Failure> {
+    let mut out = std::io::stdout().lock();
+    let options = match parse_args(std::env::args().skip(1))? {
+        Some(options) => options,
+        None => {
+            writeln!(out, "{}", USAGE)?;
+            return Ok(());
+        }
+    };
+    let result = match load_parser(&options)? {
+        Parser::Json(parser) => run_tokenized(&parser, &options, &mut out),
+        Parser::JsonLexerless(parser) => run_lexerless(&parser, &options, &mut out),
+        Parser::Tokenized(parser) => run_tokenized(&parser, &options, &mut out),
+        Parser::Lexerless(parser) => run_lexerless(&parser, &options, &mut out),
+    };
+    result.and_then(|_| Ok(out.flush()?))
+}
+
+fn main() -> ExitCode {
+    match run() {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(failure) => {
+            if !failure.message.is_empty() {
+                eprintln!("{}", failure.message);
+            }
+            ExitCode::from(failure.code)
+        }
+    }
+}
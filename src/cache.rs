@@ -1,16 +1,46 @@
 use crate::Code;
 use crate::{
-    Cache, CacheKey, FltrPtr, NodeImpl, ParseError, ParsedResult, ProductionError, TokenImpl,
-    TokenStream,
+    Cache, CacheKey, Expected, FltrPtr, Memo, NodeImpl, ParseError, ParseErrorKind, ParsedResult,
+    ProductionError, ReusableMemo, Seeds, TokenImpl, TokenPtr, TokenStream,
 };
-use std::fmt::Write;
-use std::{collections::HashMap, hash::Hash};
+use std::{cmp::Ordering, collections::HashMap, hash::Hash};
+
+/// Select the seeds of the left recursive productions whose parsed results are indexed by `I`.
+type SeedSelector<TP, I, TToken> = fn(&mut Cache<TP, TToken>) -> &mut Seeds<I, TToken>;
 
 impl<TP: Default + Eq + Hash + Ord + Copy, TToken> Cache<TP, TToken> {
     pub fn root() -> Self {
         Self {
             parsed_result_cache: HashMap::new(),
             max_parsed_point: 0,
+            examined_point: 0,
+            recovered_errors: None,
+            expected: Vec::new(),
+            expected_point: 0,
+            reusable: None,
+            growing_seeds: Vec::new(),
+            seed_cache: HashMap::new(),
+            token_seed_cache: HashMap::new(),
+            recovered_memo: HashMap::new(),
+        }
+    }
+
+    /// Create a root cache for parsing in the error recovery mode.
+    ///
+    /// Productions with recovery points will record the syntax errors into the cache and continue parsing.
+    pub fn recovery_root() -> Self {
+        Self {
+            parsed_result_cache: HashMap::new(),
+            max_parsed_point: 0,
+            examined_point: 0,
+            recovered_errors: Some(Vec::new()),
+            expected: Vec::new(),
+            expected_point: 0,
+            reusable: None,
+            growing_seeds: Vec::new(),
+            seed_cache: HashMap::new(),
+            token_seed_cache: HashMap::new(),
+            recovered_memo: HashMap::new(),
         }
     }
 
@@ -19,6 +49,15 @@ impl<TP: Default + Eq + Hash + Ord + Copy, TToken> Cache<TP, TToken> {
         Self {
             parsed_result_cache: HashMap::new(),
             max_parsed_point: starting_point,
+            examined_point: starting_point,
+            recovered_errors: None,
+            expected: Vec::new(),
+            expected_point: 0,
+            reusable: None,
+            growing_seeds: Vec::new(),
+            seed_cache: HashMap::new(),
+            token_seed_cache: HashMap::new(),
+            recovered_memo: HashMap::new(),
         }
     }
 
@@ -27,8 +66,10 @@ impl<TP: Default + Eq + Hash + Ord + Copy, TToken> Cache<TP, TToken> {
     }
 
     pub fn find(&self, key: CacheKey, index: usize) -> Option<&ParsedResult<TP, TToken>> {
-        if index <= self.max_parsed_point {
-            self.parsed_result_cache.get(&(key, index))
+        if index <= self.examined_point {
+            self.parsed_result_cache
+                .get(&(key, index))
+                .map(|(result, _)| result)
         } else {
             None
         }
@@ -40,103 +81,397 @@ impl<TP: Default + Eq + Hash + Ord + Copy, TToken> Cache<TP, TToken> {
         index: usize,
         result: ParsedResult<TP, TToken>,
     ) -> Option<ParsedResult<TP, TToken>> {
-        self.max_parsed_point = std::cmp::max(index, self.max_parsed_point);
-        self.parsed_result_cache.insert((key, index), result)
+        self.update_index(index);
+        // Every code point examined so far is considered to be examined by the result.
+        let examined_point = std::cmp::max(self.examined_point, self.expected_point + 1);
+        if let Some((_, keys, _)) = self
+            .growing_seeds
+            .iter_mut()
+            .rev()
+            .find(|(p, _, _)| *p == index)
+        {
+            keys.push(key);
+        }
+        self.parsed_result_cache
+            .insert((key, index), (result, examined_point))
+            .map(|(result, _)| result)
+    }
+
+    /// Save the seed of a left recursive production along with the errors recovered to obtain the seed.
+    fn insert_seed<I>(
+        &mut self,
+        seeds: SeedSelector<TP, I, TToken>,
+        key: CacheKey,
+        pointer: usize,
+        seed: ParsedResult<I, TToken>,
+        recovered: Vec<ParseError>,
+    ) {
+        self.update_index(pointer);
+        if let Some((_, _, seed_keys)) = self
+            .growing_seeds
+            .iter_mut()
+            .rev()
+            .find(|(p, _, _)| *p == pointer)
+        {
+            seed_keys.push(key);
+        }
+        seeds(self).insert((key, pointer), (seed, recovered));
+    }
+
+    /// Parse a left recursive production by growing the seed parsed result at the pointer.
+    ///
+    /// The seed saved with the `key` is returned when the production is derived again at the same pointer.
+    /// Starting from a failed seed, the production is parsed repeatedly
+    /// until the parsed result does not consume more input than the previous seed.
+    pub fn grow_left_recursion<F: FnMut(&mut Self) -> ParsedResult<TP, TToken>>(
+        &mut self,
+        key: CacheKey,
+        pointer: usize,
+        parse: F,
+    ) -> ParsedResult<TP, TToken>
+    where
+        TToken: Clone,
+    {
+        self.grow_seed(|cache| &mut cache.seed_cache, key, pointer, parse)
+    }
+
+    fn grow_seed<I: Copy + Ord, F: FnMut(&mut Self) -> ParsedResult<I, TToken>>(
+        &mut self,
+        seeds: SeedSelector<TP, I, TToken>,
+        key: CacheKey,
+        pointer: usize,
+        mut parse: F,
+    ) -> ParsedResult<I, TToken>
+    where
+        TToken: Clone,
+    {
+        if let Some((result, recovered)) = seeds(self).get(&(key, pointer)) {
+            let (result, recovered) = (result.clone(), recovered.clone());
+            if let Some(errors) = &mut self.recovered_errors {
+                errors.extend(recovered);
+            }
+            return result;
+        }
+        let start_checkpoint = self.recovery_checkpoint();
+        self.growing_seeds.push((pointer, Vec::new(), Vec::new()));
+
+        let mut seed: ParsedResult<I, TToken> = Err(ProductionError::Unparsed);
+        loop {
+            self.insert_seed(seeds, key, pointer, seed.clone(), Vec::new());
+            let checkpoint = self.recovery_checkpoint();
+            let result = parse(self);
+
+            // The results parsed at the pointer may have been derived from the previous seed.
+            if let Some((_, keys, seed_keys)) = self.growing_seeds.last_mut() {
+                for key in std::mem::take(keys) {
+                    self.parsed_result_cache.remove(&(key, pointer));
+                    self.recovered_memo.remove(&(key, pointer));
+                }
+                for key in std::mem::take(seed_keys) {
+                    self.seed_cache.remove(&(key, pointer));
+                    self.token_seed_cache.remove(&(key, pointer));
+                }
+            }
+
+            let has_grown = match (&result, &seed) {
+                (Ok(data), Ok(seed_data)) => data.consumed_index > seed_data.consumed_index,
+                (Ok(_), Err(_)) => true,
+                (Err(_), _) => false,
+            };
+            if has_grown {
+                seed = result;
+            } else {
+                if seed.is_err() {
+                    // Report the validation error of the first derivation.
+                    seed = result;
+                } else {
+                    self.rollback_recovery(checkpoint);
+                }
+                break;
+            }
+        }
+
+        self.growing_seeds.pop();
+        let recovered = match &self.recovered_errors {
+            Some(errors) => errors[start_checkpoint..].to_vec(),
+            None => Vec::new(),
+        };
+        self.insert_seed(seeds, key, pointer, seed.clone(), recovered);
+        seed
     }
 
     pub fn update_index(&mut self, index: usize) {
         if self.max_parsed_point < index {
             self.max_parsed_point = index;
         }
+        if self.examined_point < index {
+            self.examined_point = index;
+        }
+    }
+
+    /// Restore the max parsed point obtained by [get_index](Cache::get_index) before a derivation whose success is discarded,
+    /// so that the syntax error is not reported beyond the discarded derivation.
+    ///
+    /// The code points examined by the derivation are still considered to be examined by the results saved afterward.
+    pub fn restore_index(&mut self, index: usize) {
+        self.max_parsed_point = index;
     }
 
     pub fn get_index(&self) -> usize {
         self.max_parsed_point
     }
+
+    /// Parse a derivation from the pointer regardless of the points parsed and the terminals expected by the derivations tried before,
+    /// so that a syntax error created within the derivation is located by the derivation itself.
+    ///
+    /// The furthest parsed point and the expected terminals are merged with the previous ones afterward.
+    pub fn isolate<T, F: FnOnce(&mut Self) -> T>(&mut self, pointer: usize, parse: F) -> T {
+        let max_parsed_point = std::mem::replace(&mut self.max_parsed_point, pointer);
+        let expected_point = std::mem::replace(&mut self.expected_point, pointer);
+        let expected = std::mem::take(&mut self.expected);
+
+        let result = parse(self);
+
+        self.max_parsed_point = std::cmp::max(self.max_parsed_point, max_parsed_point);
+        match expected_point.cmp(&self.expected_point) {
+            Ordering::Greater => {
+                self.expected_point = expected_point;
+                self.expected = expected;
+            }
+            Ordering::Equal => {
+                let isolated = std::mem::replace(&mut self.expected, expected);
+                for symbol in isolated {
+                    if !self.expected.contains(&symbol) {
+                        self.expected.push(symbol);
+                    }
+                }
+            }
+            Ordering::Less => {}
+        }
+        result
+    }
+
+    pub fn is_recovering(&self) -> bool {
+        self.recovered_errors.is_some()
+    }
+
+    /// Add a syntax error which has been recovered by a production.
+    pub fn add_recovered_error(&mut self, err: ParseError) {
+        if let Some(errors) = &mut self.recovered_errors {
+            errors.push(err);
+        }
+    }
+
+    /// Get a checkpoint of the recorded errors before trying an alternative derivation.
+    pub fn recovery_checkpoint(&self) -> usize {
+        self.recovered_errors
+            .as_ref()
+            .map_or(0, |errors| errors.len())
+    }
+
+    /// Discard the errors recorded by a derivation after the checkpoint, when the derivation has been failed.
+    pub fn rollback_recovery(&mut self, checkpoint: usize) {
+        if let Some(errors) = &mut self.recovered_errors {
+            errors.truncate(checkpoint);
+        }
+    }
+
+    /// Save the errors recorded after the checkpoint along with the parsed result saved at the index,
+    /// so that the errors are not lost when the result is reused after the rollback of the errors.
+    pub fn save_recovered(&mut self, key: CacheKey, index: usize, checkpoint: usize) {
+        if let Some(errors) = &self.recovered_errors {
+            match errors.get(checkpoint..) {
+                Some(recovered) if !recovered.is_empty() => {
+                    self.recovered_memo.insert((key, index), recovered.to_vec());
+                }
+                _ => {
+                    self.recovered_memo.remove(&(key, index));
+                }
+            }
+        }
+    }
+
+    /// Record the errors of the parsed result saved at the index again, when the result is reused from the cache.
+    pub fn replay_recovered(&mut self, key: CacheKey, index: usize) {
+        if let (Some(errors), Some(recovered)) = (
+            &mut self.recovered_errors,
+            self.recovered_memo.get(&(key, index)),
+        ) {
+            errors.extend(recovered.iter().cloned());
+        }
+    }
+
+    /// Record a terminal which has failed to parse the input at the pointer.
+    ///
+    /// Only the terminals tried at the furthest pointer are kept to report the expected symbols with the [ParseError].
+    pub fn add_expected<F: FnOnce() -> Expected>(&mut self, pointer: usize, expected: F) {
+        if pointer < self.expected_point {
+            return;
+        }
+        if pointer > self.expected_point {
+            self.expected_point = pointer;
+            self.expected.clear();
+        }
+        let expected = expected();
+        if !self.expected.contains(&expected) {
+            self.expected.push(expected);
+        }
+    }
+
+    /// Get a checkpoint of the expected terminals before a derivation whose failure is not a syntax error.
+    pub fn expected_checkpoint(&self) -> (usize, Vec<Expected>) {
+        (self.expected_point, self.expected.clone())
+    }
+
+    /// Restore the expected terminals recorded before the checkpoint.
+    pub fn rollback_expected(&mut self, checkpoint: (usize, Vec<Expected>)) {
+        (self.expected_point, self.expected) = checkpoint;
+    }
+
+    /// Get the terminals expected at the pointer.
+    pub fn expected_at(&self, pointer: usize) -> &[Expected] {
+        if pointer == self.expected_point {
+            &self.expected
+        } else {
+            &[]
+        }
+    }
+
+    fn attach_expected(&self, mut err: ParseError) -> ParseError {
+        if err.kind != ParseErrorKind::Validation {
+            err.expected = self.expected_at(err.pointer).to_vec();
+        }
+        err
+    }
+
+    pub fn take_recovered_errors(&mut self) -> Vec<ParseError> {
+        self.recovered_errors.take().unwrap_or_default()
+    }
 }
 
 impl<TNode: NodeImpl> Cache<FltrPtr, TNode> {
+    /// Parse a left recursive production by growing the seed parsed result at the pointer
+    /// like [grow_left_recursion](Cache::grow_left_recursion), where the production consumes the non structural tokens.
+    pub fn grow_token_left_recursion<F: FnMut(&mut Self) -> ParsedResult<TokenPtr, TNode>>(
+        &mut self,
+        key: CacheKey,
+        pointer: usize,
+        parse: F,
+    ) -> ParsedResult<TokenPtr, TNode> {
+        self.grow_seed(|cache| &mut cache.token_seed_cache, key, pointer, parse)
+    }
+
+    /// Create a root cache which reuses the parsed results of the previous parse after a text edit.
+    pub(crate) fn reuse_root(reusable: Option<ReusableMemo<TNode>>) -> Self {
+        let mut cache = Self::root();
+        cache.reusable = reusable.map(Box::new);
+        cache
+    }
+
+    pub(crate) fn into_memo(self) -> Memo<FltrPtr, TNode> {
+        self.parsed_result_cache
+    }
+
+    /// Find a parsed result of the previous parse which is not affected by the text edit.
+    ///
+    /// The reused result is saved into the cache to be found by [find](Cache::find) afterward.
+    pub fn find_reusable<'lex, TL: TokenImpl>(
+        &mut self,
+        key: CacheKey,
+        index: FltrPtr,
+        stream: &TokenStream<'lex, TL>,
+    ) -> Option<ParsedResult<FltrPtr, TNode>> {
+        let reusable = self.reusable.as_ref()?;
+        let pointer = stream[index].start;
+
+        let (result, examined_point) = if reusable.is_before(pointer) {
+            reusable.find_before((key, pointer), |i| stream.get_token_ptr(i))?
+        } else if index > FltrPtr::default() && reusable.is_after(stream.get_token_ptr(index - 1)) {
+            // The preceding structural token is also examined to parse non structural tokens.
+            reusable.find_after(key, pointer)?
+        } else {
+            return None;
+        };
+
+        self.update_index(examined_point);
+        self.parsed_result_cache
+            .insert((key, pointer), (result.clone(), examined_point));
+        Some(result)
+    }
+
     pub fn create_error<'lex, TL: TokenImpl>(
         &self,
         code: &Code,
         stream: &TokenStream<'lex, TL>,
         err: ProductionError,
     ) -> ParseError {
-        let mut error_message = String::new();
-        let pointer = match err {
+        let (kind, span, message) = match err {
             ProductionError::Unparsed => {
                 let failed_index = match stream.filtered_index_at(self.max_parsed_point) {
-                    Ok(i) => i + 1,
-                    Err(i) => i,
+                    Ok(i) | Err(i) => i,
                 };
 
                 match stream.get(failed_index) {
-                    Some(lex_data) => {
-                        if lex_data.token == TL::eof() {
-                            writeln!(error_message, "Unexpected end of file.").unwrap();
+                    Some(lex_data) if lex_data.token != TL::eof() => {
+                        let s = unsafe {
+                            std::str::from_utf8_unchecked(&code.value[lex_data.start..lex_data.end])
+                        };
+                        let message = if cfg!(debug_assertions) {
+                            format!("Unexpected token {:?}({:?}).", s, lex_data.token)
                         } else {
-                            let s = unsafe {
-                                std::str::from_utf8_unchecked(
-                                    &code.value[lex_data.start..lex_data.end],
-                                )
-                            };
-                            if cfg!(debug_assertions) {
-                                writeln!(
-                                    error_message,
-                                    "Unexpected token {:?}({:?}).",
-                                    s, lex_data.token,
-                                )
-                                .unwrap();
-                            } else {
-                                writeln!(error_message, "Unexpected {:?}.", s).unwrap();
-                            }
-                        }
-                        lex_data.start
-                    }
-                    None => {
-                        writeln!(error_message, "Unexpected end of file.").unwrap();
-                        code.value.len()
+                            format!("Unexpected {:?}.", s)
+                        };
+                        (
+                            ParseErrorKind::UnexpectedToken,
+                            lex_data.start..lex_data.end,
+                            message,
+                        )
                     }
+                    Some(lex_data) => (
+                        ParseErrorKind::UnexpectedEOF,
+                        lex_data.start..lex_data.start,
+                        "Unexpected end of file.".to_string(),
+                    ),
+                    None => (
+                        ParseErrorKind::UnexpectedEOF,
+                        code.value.len()..code.value.len(),
+                        "Unexpected end of file.".to_string(),
+                    ),
                 }
             }
             ProductionError::Validation(pointer, message) => {
-                writeln!(error_message, "{}", message).unwrap();
-                pointer
+                (ParseErrorKind::Validation, pointer..pointer, message)
             }
         };
 
-        let position = code.obtain_position(pointer);
-
-        writeln!(error_message, "Failed to parse at {}.", position).unwrap();
-
-        ParseError::new(pointer, error_message)
+        self.attach_expected(ParseError::new(kind, code, span, message))
     }
 }
 impl<TToken> Cache<usize, TToken> {
     pub fn create_error(&self, code: &Code, err: ProductionError) -> ParseError {
-        let (pointer, mut error_message) = match err {
+        let (kind, span, message) = match err {
             ProductionError::Unparsed => {
-                if self.get_index() == code.value.len() {
-                    (self.get_index(), format!("Unexpected end of file."))
-                } else {
-                    let failed_index = self.get_index();
+                let failed_index = self.get_index();
+                if failed_index >= code.value.len() {
                     (
-                        failed_index,
-                        format!("Unexpected '{}'.", unsafe {
-                            std::str::from_utf8_unchecked(
-                                &code.value[failed_index..failed_index + 1],
-                            )
-                        },),
+                        ParseErrorKind::UnexpectedEOF,
+                        failed_index..failed_index,
+                        "Unexpected end of file.".to_string(),
                     )
+                } else {
+                    let span = failed_index..code.char_end(failed_index);
+                    let message = format!(
+                        "Unexpected '{}'.",
+                        String::from_utf8_lossy(&code.value[span.clone()])
+                    );
+                    (ParseErrorKind::UnexpectedToken, span, message)
                 }
             }
-            ProductionError::Validation(pointer, message) => (pointer, message),
+            ProductionError::Validation(pointer, message) => {
+                (ParseErrorKind::Validation, pointer..pointer, message)
+            }
         };
 
-        let position = code.obtain_position(pointer);
-
-        writeln!(error_message, "\nFailed to parse at {}.", position).unwrap();
-
-        ParseError::new(pointer, error_message)
+        self.attach_expected(ParseError::new(kind, code, span, message))
     }
 }
@@ -45,3 +45,39 @@ impl<'c> Code<'c> {
         }
     }
 }
+
+impl<'c> Code<'c> {
+    /// Get the end pointer of the UTF-8 character which starts at the pointer.
+    pub fn char_end(&self, pointer: usize) -> usize {
+        let len = match self.value.get(pointer) {
+            None => return pointer,
+            Some(b) if *b >= 0xF0 => 4,
+            Some(b) if *b >= 0xE0 => 3,
+            Some(b) if *b >= 0xC0 => 2,
+            Some(_) => 1,
+        };
+        std::cmp::min(pointer + len, self.value.len())
+    }
+
+    /// Get the code point range of the line excluding the line break.
+    /// ## Arguments
+    /// * `line` - Line number starting from 1 as in [Position].
+    pub fn line_range(&self, line: usize) -> std::ops::Range<usize> {
+        let line = line.max(1);
+        let line_breaks = self.obtain_line_breaks();
+        let start = if line > 1 {
+            line_breaks
+                .get(line - 2)
+                .map_or(self.value.len(), |index| index + 1)
+        } else {
+            0
+        };
+        let mut end = line_breaks
+            .get(line - 1)
+            .map_or(self.value.len(), |index| *index);
+        if end > start && self.value[end - 1] == b'\r' {
+            end -= 1;
+        }
+        start..end
+    }
+}
@@ -0,0 +1,177 @@
+use crate::{ASTNode, CSTElement, CSTNode, CSTToken, ConcreteSyntaxTree, Lex, TokenImpl};
+use std::iter::Peekable;
+
+impl<TN: Clone, TL: TokenImpl> ConcreteSyntaxTree<TN, TL> {
+    /// Create the concrete syntax tree from the parsed trees and all the tokens of the text.
+    pub(crate) fn new(text: &[u8], trees: &[ASTNode<TN>], lexical_stream: Vec<Lex<TL>>) -> Self {
+        let mut tokens = CSTToken::attach_trivia(text, lexical_stream)
+            .into_iter()
+            .peekable();
+        Self {
+            text: text.to_vec(),
+            children: CSTElement::build_list(trees, usize::MAX, &mut tokens),
+        }
+    }
+}
+
+impl<TN, TL> ConcreteSyntaxTree<TN, TL> {
+    /// Parsed text of the tree.
+    pub fn text(&self) -> &[u8] {
+        &self.text
+    }
+
+    /// Top level elements of the tree.
+    pub fn children(&self) -> &Vec<CSTElement<TN, TL>> {
+        &self.children
+    }
+
+    /// Reproduce the text from the tokens and the trivia of the tree.
+    pub fn to_text(&self) -> Vec<u8> {
+        let mut writer = Vec::with_capacity(self.text.len());
+        for element in &self.children {
+            element.write_text(&self.text, &mut writer);
+        }
+        writer
+    }
+}
+
+impl<TN: Clone, TL> CSTElement<TN, TL> {
+    /// Create the elements of the trees,
+    /// and add the remaining tokens which start before the end code point.
+    fn build_list<I: Iterator<Item = CSTToken<TL>>>(
+        trees: &[ASTNode<TN>],
+        end: usize,
+        tokens: &mut Peekable<I>,
+    ) -> Vec<Self> {
+        let mut elements = Vec::new();
+        for tree in trees {
+            Self::take_tokens(tree.start, tokens, &mut elements);
+            let children = Self::build_list(&tree.children, tree.end, tokens);
+            elements.push(CSTElement::Node(CSTNode::new(
+                tree.node.clone(),
+                tree.start,
+                children,
+            )));
+        }
+        Self::take_tokens(end, tokens, &mut elements);
+        elements
+    }
+
+    fn take_tokens<I: Iterator<Item = CSTToken<TL>>>(
+        end: usize,
+        tokens: &mut Peekable<I>,
+        elements: &mut Vec<Self>,
+    ) {
+        while let Some(token) = tokens.next_if(|token| token.lex.start < end) {
+            elements.push(CSTElement::Token(token));
+        }
+    }
+}
+
+impl<TN, TL> CSTElement<TN, TL> {
+    /// First structural token of the element.
+    pub fn first_token(&self) -> Option<&CSTToken<TL>> {
+        match self {
+            CSTElement::Node(node) => node.children.iter().find_map(|c| c.first_token()),
+            CSTElement::Token(token) => Some(token),
+        }
+    }
+
+    /// Last structural token of the element.
+    pub fn last_token(&self) -> Option<&CSTToken<TL>> {
+        match self {
+            CSTElement::Node(node) => node.children.iter().rev().find_map(|c| c.last_token()),
+            CSTElement::Token(token) => Some(token),
+        }
+    }
+
+    /// Write the text of the element including the trivia.
+    pub fn write_text(&self, text: &[u8], writer: &mut Vec<u8>) {
+        match self {
+            CSTElement::Node(node) => {
+                for child in &node.children {
+                    child.write_text(text, writer);
+                }
+            }
+            CSTElement::Token(token) => token.write_text(text, writer),
+        }
+    }
+}
+
+impl<TN, TL> CSTNode<TN, TL> {
+    /// Create a node whose position is obtained from the tokens of the children.
+    /// A node without any token is placed at the provided pointer.
+    fn new(node: TN, pointer: usize, children: Vec<CSTElement<TN, TL>>) -> Self {
+        let start = children
+            .iter()
+            .find_map(|c| c.first_token())
+            .map_or(pointer, |token| token.lex.start);
+        let end = children
+            .iter()
+            .rev()
+            .find_map(|c| c.last_token())
+            .map_or(pointer, |token| token.lex.end);
+        Self {
+            node,
+            start,
+            end,
+            children,
+        }
+    }
+}
+
+impl<TL: TokenImpl> CSTToken<TL> {
+    /// Attach the non structural tokens to the adjacent structural tokens.
+    ///
+    /// The trivia following a structural token on the same line are the trailing trivia of the token,
+    /// and the rest of the trivia are the leading trivia of the next structural token.
+    /// The last token of the stream i.e. the end of file token is always treated as a structural token
+    /// so that the trivia at the end of the text are attached to it.
+    fn attach_trivia(text: &[u8], lexical_stream: Vec<Lex<TL>>) -> Vec<Self> {
+        let mut tokens: Vec<Self> = Vec::new();
+        let mut leading = Vec::new();
+        let mut is_same_line = false;
+        let last_index = lexical_stream.len().saturating_sub(1);
+        for (index, lex) in lexical_stream.into_iter().enumerate() {
+            if lex.token.is_structural() || index == last_index {
+                tokens.push(CSTToken {
+                    lex,
+                    leading: std::mem::take(&mut leading),
+                    trailing: Vec::new(),
+                });
+                is_same_line = true;
+            } else {
+                is_same_line = is_same_line && !text[lex.start..lex.end].contains(&b'\n');
+                match tokens.last_mut() {
+                    Some(token) if is_same_line => token.trailing.push(lex),
+                    _ => leading.push(lex),
+                }
+            }
+        }
+        tokens
+    }
+}
+
+impl<TL> CSTToken<TL> {
+    /// Starting code point of the token including the leading trivia.
+    pub fn full_start(&self) -> usize {
+        self.leading.first().map_or(self.lex.start, |lex| lex.start)
+    }
+
+    /// End code point of the token including the trailing trivia.
+    pub fn full_end(&self) -> usize {
+        self.trailing.last().map_or(self.lex.end, |lex| lex.end)
+    }
+
+    /// Write the text of the token including the trivia.
+    pub fn write_text(&self, text: &[u8], writer: &mut Vec<u8>) {
+        for lex in self
+            .leading
+            .iter()
+            .chain(std::iter::once(&self.lex))
+            .chain(&self.trailing)
+        {
+            writer.extend_from_slice(&text[lex.start..lex.end]);
+        }
+    }
+}
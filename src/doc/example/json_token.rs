@@ -1,10 +1,9 @@
 use crate::Code;
 use crate::{
     lexeme::{Pattern, Punctuations},
-    TokenImpl, Tokenizer,
+    Rc, TokenImpl, Tokenizer,
 };
 use crate::{ITokenization, Lex};
-use std::rc::Rc;
 
 #[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy)]
 /// JSON token for the parsed document
@@ -1,9 +1,7 @@
 use crate::{
     lexeme::{Action, Pattern, Punctuations, StateMixin},
-    Code,
-    CombinedTokenizer, ITokenization, Lex, TokenImpl,
+    Code, CombinedTokenizer, ITokenization, Lex, Rc, TokenImpl,
 };
-use std::rc::Rc;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 enum Token {
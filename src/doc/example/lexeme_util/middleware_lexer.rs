@@ -1,9 +1,7 @@
 use crate::{
     lexeme::{Middleware, Pattern, Punctuations},
-    Code,
-    ITokenization, Lex, TokenImpl, Tokenizer,
+    Code, ITokenization, Lex, Rc, TokenImpl, Tokenizer,
 };
-use std::rc::Rc;
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 enum Token {
     RegexLiteral,
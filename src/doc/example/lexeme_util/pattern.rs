@@ -1,5 +1,4 @@
-use crate::{lexeme::Pattern, Code, ITokenization, Lex, TokenImpl, Tokenizer};
-use std::rc::Rc;
+use crate::{lexeme::Pattern, Code, ITokenization, Lex, Rc, TokenImpl, Tokenizer};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
 enum Token {
@@ -1,9 +1,8 @@
 use crate::{
     lexeme::{Pattern, ThunkMapper},
-    Code,
-    ITokenization, Lex, TokenImpl, Tokenizer,
+    Code, ITokenization, Lex, Rc, TokenImpl, Tokenizer,
 };
-use std::{io::BufRead, rc::Rc};
+use std::io::BufRead;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 enum Token {
@@ -1,8 +1,7 @@
 use crate::{
     production::{Concat, ConstantField, EOFProd, Node, PunctuationsField, RegexField},
-    LexerlessParser, NodeImpl,
+    LexerlessParser, NodeImpl, Rc,
 };
-use std::rc::Rc;
 
 #[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, PartialOrd, Ord)]
 pub enum NodeValue {
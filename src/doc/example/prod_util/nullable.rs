@@ -2,9 +2,8 @@ use crate::{
     production::{
         Concat, EOFProd, List, Nullable, ProductionBuilder, PunctuationsField, RegexField,
     },
-    LexerlessParser, NodeImpl,
+    LexerlessParser, NodeImpl, Rc,
 };
-use std::rc::Rc;
 
 #[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, PartialOrd, Ord)]
 enum Token {
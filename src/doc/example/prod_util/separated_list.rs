@@ -1,9 +1,8 @@
 use crate::production::ProductionBuilder;
 use crate::{
     production::{Concat, ConstantField, EOFProd, RegexField, SeparatedList, Union},
-    LexerlessParser, NodeImpl,
+    LexerlessParser, NodeImpl, Rc,
 };
-use std::rc::Rc;
 
 #[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, PartialOrd, Ord)]
 enum NodeValue {
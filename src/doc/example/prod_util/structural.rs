@@ -4,9 +4,8 @@ use crate::{
         Concat, EOFProd, List, Lookahead, NonStructural, ProductionBuilder, TokenField,
         TokenFieldSet, Union,
     },
-    DefaultParser, Log, NodeImpl, TokenImpl, Tokenizer,
+    DefaultParser, Log, NodeImpl, Rc, TokenImpl, Tokenizer,
 };
-use std::rc::Rc;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 enum Token {
@@ -2,9 +2,8 @@ use crate::production::{ConstantField, ProductionBuilder};
 use crate::NodeImpl;
 use crate::{
     production::{Concat, RegexField, SeparatedList, Suffixes, Union},
-    LexerlessParser,
+    LexerlessParser, Rc,
 };
-use std::rc::Rc;
 
 #[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, PartialOrd, Ord)]
 enum NodeValue {
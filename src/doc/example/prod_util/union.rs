@@ -2,9 +2,8 @@ use crate::production::{EOFProd, Node};
 use crate::NodeImpl;
 use crate::{
     production::{Concat, ConstantField, RegexField, Union},
-    LexerlessParser,
+    LexerlessParser, Rc,
 };
-use std::rc::Rc;
 
 #[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, PartialOrd, Ord)]
 enum NodeValue {
@@ -3,9 +3,8 @@ use crate::production::ProductionBuilder;
 use crate::NodeImpl;
 use crate::{
     production::{Concat, EOFProd, RegexField, Validator},
-    LexerlessParser, ProductionError,
+    LexerlessParser, ProductionError, Rc,
 };
-use std::rc::Rc;
 
 #[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, PartialOrd, Ord)]
 enum NodeValue {
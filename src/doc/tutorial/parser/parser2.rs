@@ -1,9 +1,8 @@
-use std::rc::Rc;
 
 use crate::{
     lexeme::{Action, Mapper, Pattern, Punctuations, StateMixin},
     production::{Concat, EOFProd, Node, SeparatedList, TokenField, TokenFieldSet, Union},
-    CombinedTokenizer, DefaultParser, NodeImpl, TokenImpl,
+    CombinedTokenizer, DefaultParser, NodeImpl, Rc, TokenImpl,
 };
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
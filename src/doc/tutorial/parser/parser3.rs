@@ -4,9 +4,8 @@ use crate::{
     production::{
         Concat, EOFProd, Lookahead, Node, SeparatedList, Suffixes, TokenField, TokenFieldSet, Union,
     },
-    CombinedTokenizer, DefaultParser, NodeImpl, TokenImpl,
+    CombinedTokenizer, DefaultParser, NodeImpl, Rc, TokenImpl,
 };
-use std::rc::Rc;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 enum Token {
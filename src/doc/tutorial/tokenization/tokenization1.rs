@@ -2,8 +2,7 @@ use crate::lexeme::{Pattern, Punctuations};
 use crate::Code;
 use crate::Lex;
 use crate::TokenImpl;
-use crate::{ITokenization, Tokenizer};
-use std::rc::Rc;
+use crate::{ITokenization, Rc, Tokenizer};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 enum Token {
@@ -4,9 +4,9 @@ use crate::lexeme::Punctuations;
 use crate::Code;
 use crate::ITokenization;
 use crate::Lex;
+use crate::Rc;
 use crate::TokenImpl;
 use crate::Tokenizer;
-use std::rc::Rc;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 enum Token {
@@ -1,9 +1,8 @@
 use crate::lexeme::{Action, Mapper, Middleware, Pattern, Punctuations, StateMixin};
-use crate::{Code, Log};
 use crate::Lex;
 use crate::TokenImpl;
+use crate::{Code, Log, Rc};
 use crate::{CombinedTokenizer, ITokenization};
-use std::rc::Rc;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 enum Token {
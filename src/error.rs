@@ -1,5 +1,6 @@
-use crate::{ImplementationError, ParseError, ProductionError};
-use std::fmt::{Display, Formatter};
+use crate::{Code, Expected, ImplementationError, ParseError, ParseErrorKind, ProductionError};
+use std::fmt::{Display, Formatter, Write};
+use std::ops::Range;
 
 impl ImplementationError {
     pub fn new(what: String, message: String) -> Self {
@@ -29,13 +30,113 @@ impl ProductionError {
 }
 
 impl ParseError {
-    pub fn new(pointer: usize, message: String) -> Self {
-        Self { pointer, message }
+    /// Create a new [ParseError].
+    /// ## Arguments
+    /// * `kind` - The reason of the error.
+    /// * `code` - The input code to obtain the line and column position of the span.
+    /// * `span` - Range of the code points where the error occurred.
+    /// * `message` - Error message without the position information.
+    pub fn new(kind: ParseErrorKind, code: &Code, span: Range<usize>, message: String) -> Self {
+        Self {
+            kind,
+            pointer: span.start,
+            start: code.obtain_position(span.start),
+            end: code.obtain_position(span.end),
+            span,
+            message,
+            expected: Vec::new(),
+        }
+    }
+
+    fn label(&self) -> &'static str {
+        match self.kind {
+            ParseErrorKind::UnexpectedToken | ParseErrorKind::UnexpectedEOF => "SyntaxError",
+            ParseErrorKind::Validation => "ValidationError",
+            ParseErrorKind::Tokenization => "TokenizationError",
+        }
+    }
+
+    fn expected_note(&self) -> Option<String> {
+        let symbols: Vec<String> = self.expected.iter().map(|e| e.to_string()).collect();
+        match symbols.len() {
+            0 => None,
+            1 => Some(symbols[0].clone()),
+            _ => Some(format!("one of {}", symbols.join(", "))),
+        }
+    }
+
+    /// Render the error with the source line and a caret underline of the span.
+    ///
+    /// ```text
+    /// SyntaxError: Unexpected "3".
+    ///  --> 1:7
+    ///   |
+    /// 1 | [1, 2 3]
+    ///   |       ^ expected one of `,`, `]`
+    /// ```
+    /// ## Arguments
+    /// * `code` - The same input code which has been parsed.
+    pub fn render(&self, code: &Code) -> String {
+        let line_range = code.line_range(self.start.line);
+        let line_text = String::from_utf8_lossy(&code.value[line_range.clone()]);
+
+        let start = self.span.start.clamp(line_range.start, line_range.end);
+        let end = self.span.end.clamp(start, line_range.end);
+
+        let prefix = String::from_utf8_lossy(&code.value[line_range.start..start]);
+        let padding: String = prefix
+            .chars()
+            .map(|c| if c == '\t' { '\t' } else { ' ' })
+            .collect();
+        let underline = String::from_utf8_lossy(&code.value[start..end]);
+        let width = std::cmp::max(underline.chars().count(), 1);
+
+        let line_number = self.start.line.to_string();
+        let gutter = " ".repeat(line_number.len());
+
+        let mut rendered = String::new();
+        writeln!(rendered, "{}: {}", self.label(), self.message).unwrap();
+        writeln!(
+            rendered,
+            "{}--> {}:{}",
+            gutter, self.start.line, self.start.column
+        )
+        .unwrap();
+        writeln!(rendered, "{} |", gutter).unwrap();
+        writeln!(rendered, "{} | {}", line_number, line_text).unwrap();
+        write!(rendered, "{} | {}{}", gutter, padding, "^".repeat(width)).unwrap();
+        if let Some(note) = self.expected_note() {
+            write!(rendered, " expected {}", note).unwrap();
+        }
+        rendered.push('\n');
+        rendered
     }
 }
 
 impl Display for ParseError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        writeln!(f, "SyntaxError: {}", self.message)
+        write!(
+            f,
+            "{} at line {}, column {}: {}",
+            self.label(),
+            self.start.line,
+            self.start.column,
+            self.message
+        )?;
+        match self.expected_note() {
+            Some(note) => write!(f, " Expected {}.", note),
+            None => Ok(()),
+        }
+    }
+}
+
+impl Display for Expected {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Expected::Token(token) => write!(f, "`{}`", token),
+            Expected::Literal(value) => write!(f, "`{}`", value),
+            Expected::Pattern(pattern) => write!(f, "`{}`", pattern),
+            Expected::EndOfFile => write!(f, "end of file"),
+        }
     }
 }
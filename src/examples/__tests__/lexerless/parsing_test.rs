@@ -1,4 +1,5 @@
 use crate::examples::json::lexerless::{json_lexerless_grammar, JSONNode};
+use crate::{Code, Expected, ParseErrorKind};
 
 #[test]
 pub fn test1() {
@@ -87,3 +88,43 @@ pub fn test2() {
 
     // println!("{:#?}", parsed_tree);
 }
+
+#[test]
+pub fn expected_fields_test() {
+    let parser = json_lexerless_grammar();
+
+    let err = parser.parse(b"[1, 2 3]").unwrap_err();
+    assert_eq!(err.pointer, 6);
+    assert_eq!(
+        err.expected,
+        vec![Expected::Literal(",".into()), Expected::Literal("]".into())]
+    );
+
+    let err = parser.parse(b"[1, 2").unwrap_err();
+    assert_eq!(err.pointer, 5);
+    assert_eq!(
+        err.expected,
+        vec![Expected::Literal(",".into()), Expected::Literal("]".into())]
+    );
+
+    let err = parser.parse(br#"{"a" 1}"#).unwrap_err();
+    assert_eq!(err.expected, vec![Expected::Literal(":".into())]);
+}
+
+#[test]
+pub fn error_render_test() {
+    let parser = json_lexerless_grammar();
+    let code = "[\"é\", é]";
+    let err = parser.parse(code.as_bytes()).unwrap_err();
+    assert_eq!(err.kind, ParseErrorKind::UnexpectedToken);
+    assert_eq!(err.span, 7..9);
+    // The column counts the bytes while the caret is aligned to the characters.
+    assert_eq!((err.start.column, err.end.column), (8, 10));
+
+    let rendered = err.render(&Code::from(code));
+    let lines: Vec<&str> = rendered.lines().collect();
+    assert_eq!(lines[0], "SyntaxError: Unexpected 'é'.");
+    assert_eq!(lines[1], " --> 1:8");
+    assert_eq!(lines[3], "1 | [\"é\", é]");
+    assert!(lines[4].starts_with("  |       ^ expected one of"));
+}
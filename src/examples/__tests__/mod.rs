@@ -1,3 +1,5 @@
 mod benchmark;
 mod lexerless;
+#[cfg(feature = "sync")]
+mod sync;
 mod tokenized;
@@ -0,0 +1,29 @@
+use crate::examples::json::{lexerless, tokenized};
+use crate::{DefaultParser, LexerlessParser};
+use once_cell::sync::Lazy;
+use std::thread;
+
+static JSON_PARSER: Lazy<DefaultParser<tokenized::JSONNode, tokenized::JSONToken>> =
+    Lazy::new(tokenized::json_grammar);
+
+static LEXERLESS_JSON_PARSER: Lazy<LexerlessParser<lexerless::JSONNode>> =
+    Lazy::new(lexerless::json_lexerless_grammar);
+
+#[test]
+fn shared_parser_test() {
+    let handles: Vec<_> = (0..4)
+        .map(|i| {
+            thread::spawn(move || {
+                let code = format!(r#"{{"thread":{}, "values":[{}, "{}", null]}}"#, i, i, i);
+                let tree = JSON_PARSER.parse(code.as_bytes()).unwrap();
+                let lexerless_tree = LEXERLESS_JSON_PARSER.parse(code.as_bytes()).unwrap();
+                (tree[0].children.len(), lexerless_tree[0].children.len())
+            })
+        })
+        .collect();
+
+    for handle in handles {
+        let (tree_len, lexerless_tree_len) = handle.join().unwrap();
+        assert_eq!(tree_len, lexerless_tree_len);
+    }
+}
@@ -1,4 +1,5 @@
 use crate::examples::json::tokenized::{json_grammar, JSONNode};
+use crate::{Code, Expected, ParseErrorKind};
 
 #[test]
 pub fn simple_json_parsing_test() {
@@ -98,3 +99,81 @@ fn json_tutorial() {
     let tree_list = parser.parse(code_part.as_bytes()).unwrap();
     tree_list[0].print().unwrap();
 }
+
+#[test]
+pub fn expected_tokens_test() {
+    let parser = json_grammar();
+
+    let err = parser.parse(b"[1, 2 3]").unwrap_err();
+    assert_eq!(err.pointer, 6);
+    assert_eq!(
+        err.expected,
+        vec![
+            Expected::Literal(",".into()),
+            Expected::Literal("]".into())
+        ]
+    );
+
+    let err = parser.parse(b"[1, 2").unwrap_err();
+    assert_eq!(err.pointer, 5);
+    assert_eq!(
+        err.expected,
+        vec![
+            Expected::Literal(",".into()),
+            Expected::Literal("]".into())
+        ]
+    );
+
+    let err = parser.parse(br#"{"a" 1}"#).unwrap_err();
+    assert_eq!(err.expected, vec![Expected::Literal(":".into())]);
+}
+
+#[test]
+pub fn error_render_test() {
+    let parser = json_grammar();
+    let code = "{\n\t\"a\": [1, 2 3]\n}";
+    let err = parser.parse(code.as_bytes()).unwrap_err();
+    assert_eq!(err.kind, ParseErrorKind::UnexpectedToken);
+    assert_eq!(err.span, 14..15);
+    assert_eq!((err.start.line, err.start.column), (2, 13));
+    let rendered = err.render(&Code::from(code));
+    let lines: Vec<&str> = rendered.lines().collect();
+    assert!(lines[0].starts_with("SyntaxError: Unexpected"));
+    assert_eq!(
+        lines[1..],
+        [
+            " --> 2:13",
+            "  |",
+            "2 | \t\"a\": [1, 2 3]",
+            "  | \t           ^ expected one of `,`, `]`",
+        ]
+    );
+
+    let err = parser.parse(b"[1, 2").unwrap_err();
+    assert_eq!(err.kind, ParseErrorKind::UnexpectedEOF);
+    assert_eq!(err.span, 5..5);
+}
+#[test]
+pub fn expected_token_text_test() {
+    let parser = json_grammar();
+
+    // Tokens without a single literal text are reported by the token name.
+    let err = parser.parse(b"[1, :]").unwrap_err();
+    assert_eq!(
+        err.expected,
+        vec![
+            Expected::Token("String".into()),
+            Expected::Token("Number".into()),
+            Expected::Token("Constant".into()),
+            Expected::Literal("{".into()),
+            Expected::Literal("[".into()),
+            Expected::Literal("]".into()),
+        ]
+    );
+
+    let err = parser.parse(br#"{"a": 1 "b": 2}"#).unwrap_err();
+    assert_eq!(
+        err.render(&Code::new(br#"{"a": 1 "b": 2}"#)).lines().last(),
+        Some("  |         ^^^ expected one of `,`, `}`")
+    );
+}
@@ -3,11 +3,11 @@ use crate::{
     production::{
         Concat, ConstantField, EOFProd, Node, Nullable, RegexField, SeparatedList, Union,
     },
-    LexerlessParser, NodeImpl,
+    LexerlessParser, Log, NodeImpl, Rc,
 };
-use std::rc::Rc;
 
 #[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum JSONNode {
     String,
     Number,
@@ -23,9 +23,17 @@ impl NodeImpl for JSONNode {
     fn null() -> Self {
         JSONNode::NULL
     }
+    fn is_null(&self) -> bool {
+        matches!(self, JSONNode::NULL)
+    }
 }
 
 pub fn json_lexerless_grammar() -> LexerlessParser<JSONNode> {
+    json_lexerless_grammar_with_log(Log::None)
+}
+
+/// Create the lexerless JSON parser where the non terminal productions are logged with the level of the `log`.
+pub fn json_lexerless_grammar_with_log(log: Log<()>) -> LexerlessParser<JSONNode> {
     let eof = Rc::new(EOFProd::new(None));
 
     let json_string = Rc::new(
@@ -152,7 +160,7 @@ pub fn json_lexerless_grammar() -> LexerlessParser<JSONNode> {
             "MAIN",
             vec![
                 nullable_hidden_space.clone(),
-                json_value_union,
+                json_value_union.clone(),
                 nullable_hidden_space.clone(),
                 eof,
             ],
@@ -160,6 +168,24 @@ pub fn json_lexerless_grammar() -> LexerlessParser<JSONNode> {
         .into_node(JSONNode::Main),
     );
 
+    if log != Log::None {
+        hidden_space_comma_space
+            .set_log(log.with_label("SPACE_COMMA"))
+            .unwrap();
+        hidden_space_colon_space
+            .set_log(log.with_label("SPACE_COLON"))
+            .unwrap();
+        json_object.set_log(log.with_label("JSON_OBJECT")).unwrap();
+        json_value_union
+            .set_log(log.with_label("JSON_VALUE_UNION"))
+            .unwrap();
+        json_object_item
+            .set_log(log.with_label("JSON_OBJECT_ITEM"))
+            .unwrap();
+        json_array.set_log(log.with_label("JSON_ARRAY")).unwrap();
+        main_node.set_log(log.with_label("MAIN")).unwrap();
+    }
+
     let parser = LexerlessParser::new(main_node).unwrap();
 
     if cfg!(debug_assertions) {
@@ -2,11 +2,11 @@ use crate::production::ProductionBuilder;
 use crate::{
     lexeme::{Pattern, Punctuations},
     production::{Concat, EOFProd, Node, SeparatedList, TokenField, TokenFieldSet, Union},
-    DefaultParser, NodeImpl, TokenImpl, Tokenizer,
+    DefaultParser, Log, NodeImpl, Rc, TokenImpl, Tokenizer,
 };
-use std::rc::Rc;
 
 #[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 /// JSON token for the parsed document
 pub enum JSONToken {
     EOF,
@@ -23,6 +23,7 @@ pub enum JSONToken {
 }
 
 #[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum JSONNode {
     Key,
     String,
@@ -50,9 +51,16 @@ impl NodeImpl for JSONNode {
     fn null() -> Self {
         JSONNode::NULL
     }
+    fn is_null(&self) -> bool {
+        matches!(self, JSONNode::NULL)
+    }
 }
 
 pub fn json_tokenizer() -> Tokenizer<JSONToken> {
+    json_tokenizer_with_log(Log::None)
+}
+
+fn json_tokenizer_with_log(log: Log<()>) -> Tokenizer<JSONToken> {
     let punctuations = Rc::new(
         Punctuations::new(vec![
             ("{", JSONToken::OpenBrace),
@@ -79,6 +87,16 @@ pub fn json_tokenizer() -> Tokenizer<JSONToken> {
     );
     let const_literal = Rc::new(Pattern::new(JSONToken::Constant, r"^(true|false|null)").unwrap());
 
+    if log != Log::None {
+        punctuations
+            .set_log(log.with_label("punctuations"))
+            .unwrap();
+        dq_string.set_log(log.with_label("string")).unwrap();
+        lex_space.set_log(log.with_label("space")).unwrap();
+        number_literal.set_log(log.with_label("number")).unwrap();
+        const_literal.set_log(log.with_label("constant")).unwrap();
+    }
+
     Tokenizer::new(vec![
         lex_space,
         punctuations,
@@ -89,6 +107,11 @@ pub fn json_tokenizer() -> Tokenizer<JSONToken> {
 }
 
 pub fn json_grammar() -> DefaultParser<JSONNode, JSONToken> {
+    json_grammar_with_log(Log::None)
+}
+
+/// Create the JSON parser where the lexemes and the non terminal productions are logged with the level of the `log`.
+pub fn json_grammar_with_log(log: Log<()>) -> DefaultParser<JSONNode, JSONToken> {
     let eof = Rc::new(EOFProd::new(None));
 
     let json_key = Rc::new(TokenField::new(JSONToken::String, Some(JSONNode::Key)));
@@ -162,10 +185,24 @@ pub fn json_grammar() -> DefaultParser<JSONNode, JSONToken> {
         ])
         .unwrap();
 
-    let main = Rc::new(Concat::new("root", vec![json_value_union, eof]));
+    let main = Rc::new(Concat::new("root", vec![json_value_union.clone(), eof]));
     let main_node = Rc::new(Node::new(&main, JSONNode::Main));
 
-    let lexer = json_tokenizer();
+    if log != Log::None {
+        json_object.set_log(log.with_label("json_object")).unwrap();
+        json_value_union
+            .set_log(log.with_label("json_value_union"))
+            .unwrap();
+        json_object_item
+            .set_log(log.with_label("json_object_item"))
+            .unwrap();
+        json_array_node
+            .set_log(log.with_label("json_array"))
+            .unwrap();
+        main.set_log(log.with_label("root")).unwrap();
+    }
+
+    let lexer = json_tokenizer_with_log(log);
 
     let parser = DefaultParser::new(Rc::new(lexer), main_node).unwrap();
 
@@ -0,0 +1,454 @@
+use super::{
+    ChoiceConflict, Grammar, GrammarAnalysis, GrammarExpr, RuleSets, ShadowedAlternative, Terminal,
+};
+use regex::Regex;
+use std::{
+    collections::{BTreeSet, HashMap, HashSet},
+    fmt::{Display, Formatter},
+};
+
+impl Grammar {
+    /// Analyze the rules of the grammar.
+    ///
+    /// The analysis reports
+    /// * The FIRST and the FOLLOW sets of each rule.
+    /// * The alternatives of the choices which can start with the same terminals, where the parser may backtrack.
+    /// * The alternatives of the choices which never match because an earlier alternative always wins
+    ///   i.e. the earlier alternative is nullable or it matches a prefix of every input of the later alternative.
+    /// * The rules which are not reachable from the start rule.
+    /// * The structural tokens of the lexer rules which are not used by any rule.
+    ///
+    /// Two literals overlap if one of them is a prefix of the other, and a literal overlaps with a regular expression
+    /// if the regular expression matches a prefix of the literal.
+    pub fn analyze(&self) -> GrammarAnalysis {
+        let mut analyzer = Analyzer::new(self);
+        analyzer.compute_first_sets();
+        analyzer.compute_follow_sets();
+
+        let mut analysis = GrammarAnalysis::default();
+        for rule in &self.rules {
+            analysis.rules.push(RuleSets {
+                name: rule.name.clone(),
+                nullable: analyzer.nullable[rule.name.as_str()],
+                first: analyzer.first[rule.name.as_str()].clone(),
+                follow: analyzer.follow[rule.name.as_str()].clone(),
+            });
+            analyzer.check_choices(&rule.name, &rule.expr, &mut analysis);
+        }
+
+        let mut references = HashSet::new();
+        let mut tokens = HashSet::new();
+        let mut pending: Vec<&str> = self
+            .rules
+            .first()
+            .map(|r| r.name.as_str())
+            .into_iter()
+            .collect();
+        for rule in &self.rules {
+            collect_references(&rule.expr, &mut HashSet::new(), &mut tokens);
+        }
+        while let Some(name) = pending.pop() {
+            if references.insert(name) {
+                if let Some(rule) = self.get_rule(name) {
+                    let mut rules = HashSet::new();
+                    collect_references(&rule.expr, &mut rules, &mut HashSet::new());
+                    pending.extend(rules);
+                }
+            }
+        }
+        analysis.unreachable_rules = self
+            .rules
+            .iter()
+            .filter(|rule| !references.contains(rule.name.as_str()))
+            .map(|rule| rule.name.clone())
+            .collect();
+        analysis.unused_tokens = self
+            .lexer_rules
+            .iter()
+            .filter(|rule| rule.is_structural && !tokens.contains(rule.token.as_str()))
+            .map(|rule| rule.token.clone())
+            .collect();
+        analysis
+    }
+}
+
+/// Collect the names of the rules and the tokens referred by the expression.
+pub(super) fn collect_references<'g>(
+    expr: &'g GrammarExpr,
+    rules: &mut HashSet<&'g str>,
+    tokens: &mut HashSet<&'g str>,
+) {
+    match expr {
+        GrammarExpr::Rule(name) => {
+            rules.insert(name);
+        }
+        GrammarExpr::Token(name) => {
+            tokens.insert(name);
+        }
+        GrammarExpr::Sequence(items) | GrammarExpr::Choice(items) => items
+            .iter()
+            .for_each(|item| collect_references(item, rules, tokens)),
+        GrammarExpr::Repeat { expr, .. }
+        | GrammarExpr::Lookahead(expr)
+        | GrammarExpr::NotLookahead(expr)
+        | GrammarExpr::NonStructural(expr)
+        | GrammarExpr::Node { expr, .. } => collect_references(expr, rules, tokens),
+        _ => {}
+    }
+}
+
+/// Collect the regular expressions of the expression.
+fn collect_patterns<'g>(expr: &'g GrammarExpr, patterns: &mut HashMap<&'g str, Option<Regex>>) {
+    match expr {
+        GrammarExpr::Pattern(regex) => {
+            patterns
+                .entry(regex)
+                .or_insert_with(|| Regex::new(&format!("^(?:{})", regex)).ok());
+        }
+        GrammarExpr::Sequence(items) | GrammarExpr::Choice(items) => items
+            .iter()
+            .for_each(|item| collect_patterns(item, patterns)),
+        GrammarExpr::Repeat { expr, .. }
+        | GrammarExpr::Lookahead(expr)
+        | GrammarExpr::NotLookahead(expr)
+        | GrammarExpr::NonStructural(expr)
+        | GrammarExpr::Node { expr, .. } => collect_patterns(expr, patterns),
+        _ => {}
+    }
+}
+
+/// Unwrap the expression which does not change the matched input.
+fn unwrap_node(expr: &GrammarExpr) -> &GrammarExpr {
+    match expr {
+        GrammarExpr::Node { expr, .. } | GrammarExpr::NonStructural(expr) => unwrap_node(expr),
+        expr => expr,
+    }
+}
+
+/// The leading terminal of the expression which should be matched at the beginning of every input of the expression.
+fn leading_terminal(expr: &GrammarExpr) -> Option<&GrammarExpr> {
+    match unwrap_node(expr) {
+        GrammarExpr::Sequence(items) => items.first().and_then(leading_terminal),
+        GrammarExpr::Repeat { expr, min, .. } if *min > 0 => leading_terminal(expr),
+        expr @ (GrammarExpr::Token(_) | GrammarExpr::Literal(_)) => Some(expr),
+        _ => None,
+    }
+}
+
+struct Analyzer<'g> {
+    grammar: &'g Grammar,
+    patterns: HashMap<&'g str, Option<Regex>>,
+    nullable: HashMap<&'g str, bool>,
+    first: HashMap<&'g str, BTreeSet<Terminal>>,
+    follow: HashMap<&'g str, BTreeSet<Terminal>>,
+}
+
+impl<'g> Analyzer<'g> {
+    fn new(grammar: &'g Grammar) -> Self {
+        let mut patterns = HashMap::new();
+        for rule in &grammar.rules {
+            collect_patterns(&rule.expr, &mut patterns);
+        }
+        let names = grammar.rules.iter().map(|rule| rule.name.as_str());
+        Self {
+            grammar,
+            patterns,
+            nullable: names.clone().map(|name| (name, false)).collect(),
+            first: names.clone().map(|name| (name, BTreeSet::new())).collect(),
+            follow: names.map(|name| (name, BTreeSet::new())).collect(),
+        }
+    }
+
+    fn is_pattern_nullable(&self, regex: &str) -> bool {
+        self.patterns
+            .get(regex)
+            .and_then(|regex| regex.as_ref())
+            .is_some_and(|regex| regex.is_match(""))
+    }
+
+    /// Obtain the FIRST set and the nullability of the expression from the current sets of the rules.
+    fn first_of(&self, expr: &GrammarExpr) -> (BTreeSet<Terminal>, bool) {
+        let terminal = |terminal: Terminal| (BTreeSet::from([terminal]), false);
+        match expr {
+            GrammarExpr::Empty | GrammarExpr::Lookahead(_) | GrammarExpr::NotLookahead(_) => {
+                (BTreeSet::new(), true)
+            }
+            GrammarExpr::Literal(value) if value.is_empty() => (BTreeSet::new(), true),
+            GrammarExpr::EndOfFile => terminal(Terminal::EndOfFile),
+            GrammarExpr::Token(name) => terminal(Terminal::Token(name.clone())),
+            GrammarExpr::Literal(value) => terminal(Terminal::Literal(value.clone())),
+            GrammarExpr::Pattern(regex) => (
+                BTreeSet::from([Terminal::Pattern(regex.clone())]),
+                self.is_pattern_nullable(regex),
+            ),
+            GrammarExpr::CharSet { .. } => terminal(Terminal::Opaque(format!("{:?}", expr))),
+            GrammarExpr::Opaque(description) => terminal(Terminal::Opaque(description.clone())),
+            GrammarExpr::Rule(name) => (
+                self.first.get(name.as_str()).cloned().unwrap_or_default(),
+                self.nullable.get(name.as_str()).copied().unwrap_or(false),
+            ),
+            GrammarExpr::Sequence(items) => {
+                let mut first = BTreeSet::new();
+                for item in items {
+                    let (item_first, nullable) = self.first_of(item);
+                    first.extend(item_first);
+                    if !nullable {
+                        return (first, false);
+                    }
+                }
+                (first, true)
+            }
+            GrammarExpr::Choice(items) => {
+                let mut first = BTreeSet::new();
+                let mut is_nullable = false;
+                for item in items {
+                    let (item_first, nullable) = self.first_of(item);
+                    first.extend(item_first);
+                    is_nullable |= nullable;
+                }
+                (first, is_nullable)
+            }
+            GrammarExpr::Repeat { expr, min, .. } => {
+                let (first, nullable) = self.first_of(expr);
+                (first, nullable || *min == 0)
+            }
+            GrammarExpr::NonStructural(expr) | GrammarExpr::Node { expr, .. } => {
+                self.first_of(expr)
+            }
+        }
+    }
+
+    fn compute_first_sets(&mut self) {
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for rule in &self.grammar.rules {
+                let (first, nullable) = self.first_of(&rule.expr);
+                let name = rule.name.as_str();
+                if first != self.first[name] || nullable != self.nullable[name] {
+                    self.first.insert(name, first);
+                    self.nullable.insert(name, nullable);
+                    changed = true;
+                }
+            }
+        }
+    }
+
+    /// Add the terminals which can follow the expression to the FOLLOW sets of the referred rules.
+    fn follow_of(
+        &self,
+        expr: &'g GrammarExpr,
+        trailer: &BTreeSet<Terminal>,
+        follow: &mut HashMap<&'g str, BTreeSet<Terminal>>,
+    ) {
+        match expr {
+            GrammarExpr::Rule(name) => follow
+                .entry(name)
+                .or_default()
+                .extend(trailer.iter().cloned()),
+            GrammarExpr::Sequence(items) => {
+                let mut trailer = trailer.clone();
+                for item in items.iter().rev() {
+                    self.follow_of(item, &trailer, follow);
+                    let (first, nullable) = self.first_of(item);
+                    if !nullable {
+                        trailer.clear();
+                    }
+                    trailer.extend(first);
+                }
+            }
+            GrammarExpr::Choice(items) => items
+                .iter()
+                .for_each(|item| self.follow_of(item, trailer, follow)),
+            GrammarExpr::Repeat { expr, max, .. } => {
+                let mut trailer = trailer.clone();
+                if !matches!(max, Some(max) if *max <= 1) {
+                    trailer.extend(self.first_of(expr).0);
+                }
+                self.follow_of(expr, &trailer, follow);
+            }
+            GrammarExpr::Lookahead(expr)
+            | GrammarExpr::NotLookahead(expr)
+            | GrammarExpr::NonStructural(expr)
+            | GrammarExpr::Node { expr, .. } => self.follow_of(expr, trailer, follow),
+            _ => {}
+        }
+    }
+
+    fn compute_follow_sets(&mut self) {
+        let mut changed = true;
+        while changed {
+            changed = false;
+            let mut follow = HashMap::new();
+            for rule in &self.grammar.rules {
+                self.follow_of(&rule.expr, &self.follow[rule.name.as_str()], &mut follow);
+            }
+            for (name, terminals) in follow {
+                if let Some(current) = self.follow.get_mut(name) {
+                    let length = current.len();
+                    current.extend(terminals);
+                    changed |= current.len() != length;
+                }
+            }
+        }
+    }
+
+    fn overlaps(&self, a: &Terminal, b: &Terminal) -> bool {
+        let matches = |regex: &str, value: &str| {
+            self.patterns
+                .get(regex)
+                .and_then(|regex| regex.as_ref())
+                .is_some_and(|regex| regex.is_match(value))
+        };
+        match (a, b) {
+            (Terminal::Literal(a), Terminal::Literal(b)) => a.starts_with(b) || b.starts_with(a),
+            (Terminal::Literal(value), Terminal::Pattern(regex))
+            | (Terminal::Pattern(regex), Terminal::Literal(value)) => matches(regex, value),
+            (Terminal::Opaque(_), _) | (_, Terminal::Opaque(_)) => false,
+            (a, b) => a == b,
+        }
+    }
+
+    /// Whether the earlier alternative always wins before the later alternative of a choice.
+    fn is_shadowed(&self, earlier: &GrammarExpr, later: &GrammarExpr) -> bool {
+        if self.first_of(earlier).1 || unwrap_node(earlier) == unwrap_node(later) {
+            return true;
+        }
+        match (unwrap_node(earlier), leading_terminal(later)) {
+            (GrammarExpr::Token(a), Some(GrammarExpr::Token(b))) => a == b,
+            (GrammarExpr::Literal(a), Some(GrammarExpr::Literal(b))) => b.starts_with(a.as_str()),
+            _ => false,
+        }
+    }
+
+    /// Find the conflicting and the shadowed alternatives of the choices of the expression.
+    fn check_choices(&self, rule: &str, expr: &GrammarExpr, analysis: &mut GrammarAnalysis) {
+        match expr {
+            GrammarExpr::Choice(items) => {
+                let mut shadowed = vec![false; items.len()];
+                for (j, later) in items.iter().enumerate() {
+                    for (i, earlier) in items[..j].iter().enumerate() {
+                        if shadowed[i] {
+                            continue;
+                        }
+                        if self.is_shadowed(earlier, later) {
+                            shadowed[j] = true;
+                            analysis.shadowed_alternatives.push(ShadowedAlternative {
+                                rule: rule.to_string(),
+                                alternative: j,
+                                shadowed_by: i,
+                            });
+                            break;
+                        }
+                    }
+                    if shadowed[j] {
+                        continue;
+                    }
+                    let later_first = self.first_of(later).0;
+                    for (i, earlier) in items[..j].iter().enumerate() {
+                        if shadowed[i] {
+                            continue;
+                        }
+                        let terminals: BTreeSet<Terminal> = self
+                            .first_of(earlier)
+                            .0
+                            .into_iter()
+                            .filter(|a| later_first.iter().any(|b| self.overlaps(a, b)))
+                            .collect();
+                        if !terminals.is_empty() {
+                            analysis.conflicts.push(ChoiceConflict {
+                                rule: rule.to_string(),
+                                alternatives: (i, j),
+                                terminals,
+                            });
+                        }
+                    }
+                }
+                items
+                    .iter()
+                    .for_each(|item| self.check_choices(rule, item, analysis));
+            }
+            GrammarExpr::Sequence(items) => items
+                .iter()
+                .for_each(|item| self.check_choices(rule, item, analysis)),
+            GrammarExpr::Repeat { expr, .. }
+            | GrammarExpr::Lookahead(expr)
+            | GrammarExpr::NotLookahead(expr)
+            | GrammarExpr::NonStructural(expr)
+            | GrammarExpr::Node { expr, .. } => self.check_choices(rule, expr, analysis),
+            _ => {}
+        }
+    }
+}
+
+impl Display for Terminal {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Terminal::Token(name) => write!(f, "{}", name),
+            Terminal::Literal(value) => write!(f, "{:?}", value),
+            Terminal::Pattern(regex) => write!(f, "/{}/", regex.replace('/', "\\/")),
+            Terminal::EndOfFile => write!(f, "EOF"),
+            Terminal::Opaque(description) => write!(f, "<{}>", description),
+        }
+    }
+}
+
+fn write_set(f: &mut Formatter<'_>, terminals: &BTreeSet<Terminal>) -> std::fmt::Result {
+    let terminals: Vec<String> = terminals.iter().map(|t| t.to_string()).collect();
+    write!(f, "{{ {} }}", terminals.join(", "))
+}
+
+impl Display for GrammarAnalysis {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "Rules:")?;
+        for rule in &self.rules {
+            write!(
+                f,
+                "  {}{}\n    FIRST  = ",
+                rule.name,
+                if rule.nullable { " (nullable)" } else { "" }
+            )?;
+            write_set(f, &rule.first)?;
+            write!(f, "\n    FOLLOW = ")?;
+            write_set(f, &rule.follow)?;
+            writeln!(f)?;
+        }
+        if !self.conflicts.is_empty() {
+            writeln!(f, "Conflicts:")?;
+            for conflict in &self.conflicts {
+                write!(
+                    f,
+                    "  {}: alternatives #{} and #{} overlap on ",
+                    conflict.rule,
+                    conflict.alternatives.0 + 1,
+                    conflict.alternatives.1 + 1
+                )?;
+                write_set(f, &conflict.terminals)?;
+                writeln!(f)?;
+            }
+        }
+        if !self.shadowed_alternatives.is_empty() {
+            writeln!(f, "Shadowed alternatives:")?;
+            for shadowed in &self.shadowed_alternatives {
+                writeln!(
+                    f,
+                    "  {}: alternative #{} never matches because of alternative #{}",
+                    shadowed.rule,
+                    shadowed.alternative + 1,
+                    shadowed.shadowed_by + 1
+                )?;
+            }
+        }
+        if !self.unreachable_rules.is_empty() {
+            writeln!(
+                f,
+                "Unreachable rules: {}",
+                self.unreachable_rules.join(", ")
+            )?;
+        }
+        if !self.unused_tokens.is_empty() {
+            writeln!(f, "Unused tokens: {}", self.unused_tokens.join(", "))?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,319 @@
+use super::{
+    postfix_repetition, split_empty, wrap, Grammar, GrammarExpr, LexerPattern, ATOM, CHOICE,
+    POSTFIX, SEQUENCE,
+};
+use std::{
+    collections::{HashMap, HashSet},
+    fmt::Write,
+};
+
+const RESERVED: [&str; 14] = [
+    "catch", "channels", "finally", "fragment", "grammar", "import", "lexer", "locals", "mode",
+    "options", "parser", "returns", "throws", "tokens",
+];
+
+impl Grammar {
+    /// Write the grammar as an ANTLR4 combined grammar with the provided grammar name.
+    ///
+    /// The rule names are converted into lower case parser rule names and the token names into capitalized lexer rule names.
+    /// The non structural tokens are sent to the hidden channel.
+    /// The regular expressions of a lexerless grammar are added as the `PATTERN_<n>` lexer rules.
+    ///
+    /// ANTLR does not support the syntactic lookaheads, therefore the lookaheads are written as comments,
+    /// and the lexer rules which can not be translated into ANTLR are commented out.
+    pub fn to_antlr(&self, name: &str) -> String {
+        let mut writer = AntlrWriter::default();
+        for rule in &self.rules {
+            writer.rule_name(&rule.name);
+        }
+        for rule in &self.lexer_rules {
+            writer.token_name(&rule.token);
+        }
+
+        let mut antlr = String::new();
+        writeln!(antlr, "grammar {};", name).unwrap();
+        for rule in &self.rules {
+            writeln!(antlr).unwrap();
+            writeln!(antlr, "{}", writer.rule_name(&rule.name)).unwrap();
+            let alternatives = match &rule.expr {
+                GrammarExpr::Choice(items) => items.iter().collect(),
+                expr => vec![expr],
+            };
+            for (index, alternative) in alternatives.into_iter().enumerate() {
+                let alternative = wrap(writer.render(alternative, false), CHOICE);
+                let separator = if index == 0 { ":" } else { "|" };
+                writeln!(antlr, "    {} {}", separator, alternative).unwrap();
+            }
+            writeln!(antlr, "    ;").unwrap();
+        }
+
+        let mut lexer_rules: Vec<(String, Option<GrammarExpr>, String, bool)> = Vec::new();
+        for rule in &self.lexer_rules {
+            let expr = rule
+                .patterns
+                .iter()
+                .map(|pattern| match pattern.unconditional() {
+                    LexerPattern::Literal(value) => Some(GrammarExpr::Literal(value.clone())),
+                    LexerPattern::Regex(regex) => GrammarExpr::from_regex(regex),
+                    LexerPattern::Opaque(_) => None,
+                    LexerPattern::Conditional(_) => unreachable!(),
+                })
+                .collect::<Option<Vec<_>>>()
+                .map(GrammarExpr::choice);
+            let description: Vec<String> = rule
+                .patterns
+                .iter()
+                .map(|pattern| match pattern.unconditional() {
+                    LexerPattern::Literal(value) => quote(value),
+                    LexerPattern::Regex(regex) => format!("/{}/", regex.replace('/', "\\/")),
+                    LexerPattern::Opaque(description) => description.clone(),
+                    LexerPattern::Conditional(_) => unreachable!(),
+                })
+                .collect();
+            let name = writer.token_name(&rule.token);
+            lexer_rules.push((name, expr, description.join(" | "), rule.is_structural));
+        }
+        for pattern in &writer.patterns {
+            let description = format!("/{}/", pattern.regex.replace('/', "\\/"));
+            lexer_rules.push((
+                pattern.name.clone(),
+                pattern.expr.clone(),
+                description,
+                true,
+            ));
+        }
+
+        if !lexer_rules.is_empty() {
+            writeln!(antlr).unwrap();
+        }
+        for (name, expr, description, is_structural) in lexer_rules {
+            match expr {
+                Some(expr) => {
+                    let expr = writer.render(&expr, true).0;
+                    let channel = if is_structural {
+                        ""
+                    } else {
+                        " -> channel(HIDDEN)"
+                    };
+                    writeln!(antlr, "{} : {}{} ;", name, expr, channel).unwrap();
+                }
+                None => writeln!(antlr, "// {} : {} ;", name, description).unwrap(),
+            }
+        }
+        antlr
+    }
+}
+
+#[derive(Default)]
+struct AntlrWriter {
+    rule_names: HashMap<String, String>,
+    token_names: HashMap<String, String>,
+    used_names: HashSet<String>,
+    /// Implicit lexer rules of the regular expressions used in the parser rules.
+    patterns: Vec<ImplicitPattern>,
+}
+
+struct ImplicitPattern {
+    regex: String,
+    name: String,
+    /// Translated expression of the regular expression.
+    expr: Option<GrammarExpr>,
+    /// Whether the reference of the lexer rule is optional.
+    optional: bool,
+}
+
+impl AntlrWriter {
+    /// Replace the characters which are not allowed in an identifier.
+    fn sanitize(name: &str) -> String {
+        name.chars()
+            .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+            .collect()
+    }
+
+    fn unique_name(&mut self, mut name: String) -> String {
+        while !self.used_names.insert(name.clone()) {
+            name.push('_');
+        }
+        name
+    }
+
+    /// Parser rule names should start with a lower case letter.
+    fn rule_name(&mut self, name: &str) -> String {
+        if let Some(rule_name) = self.rule_names.get(name) {
+            return rule_name.clone();
+        }
+        let mut rule_name = Self::sanitize(name);
+        match rule_name.chars().next() {
+            Some(c) if c.is_ascii_uppercase() => rule_name = rule_name.to_lowercase(),
+            Some(c) if c.is_ascii_lowercase() => {}
+            _ => rule_name.insert_str(0, "r_"),
+        }
+        if RESERVED.contains(&rule_name.as_str()) {
+            rule_name.push('_');
+        }
+        let rule_name = self.unique_name(rule_name);
+        self.rule_names.insert(name.to_string(), rule_name.clone());
+        rule_name
+    }
+
+    /// Lexer rule names should start with an upper case letter.
+    fn token_name(&mut self, name: &str) -> String {
+        if let Some(token_name) = self.token_names.get(name) {
+            return token_name.clone();
+        }
+        let mut token_name = Self::sanitize(name);
+        match token_name.chars().next() {
+            Some(c) if c.is_ascii_lowercase() => {
+                token_name.replace_range(..1, &c.to_ascii_uppercase().to_string())
+            }
+            Some(c) if c.is_ascii_uppercase() => {}
+            _ => token_name.insert_str(0, "T_"),
+        }
+        if token_name == "EOF" {
+            token_name.push('_');
+        }
+        let token_name = self.unique_name(token_name);
+        self.token_names
+            .insert(name.to_string(), token_name.clone());
+        token_name
+    }
+
+    /// Reference of the implicit lexer rule of a regular expression used in a parser rule.
+    fn pattern_ref(&mut self, regex: &str) -> (String, u8) {
+        let index = match self.patterns.iter().position(|p| p.regex == regex) {
+            Some(index) => index,
+            None => {
+                let name = self.unique_name(format!("PATTERN_{}", self.patterns.len() + 1));
+                let (expr, optional) = match GrammarExpr::from_regex(regex) {
+                    // A lexer rule can not match the empty input, therefore the optional repetition is moved to the reference.
+                    Some(GrammarExpr::Repeat { expr, min: 0, max }) => {
+                        (Some(GrammarExpr::repeat(*expr, 1, max)), true)
+                    }
+                    expr => (expr, false),
+                };
+                self.patterns.push(ImplicitPattern {
+                    regex: regex.to_string(),
+                    name,
+                    expr,
+                    optional,
+                });
+                self.patterns.len() - 1
+            }
+        };
+        let pattern = &self.patterns[index];
+        match pattern.optional {
+            true => (format!("{}?", pattern.name), POSTFIX),
+            false => (pattern.name.clone(), ATOM),
+        }
+    }
+
+    fn render(&mut self, expr: &GrammarExpr, is_lexer: bool) -> (String, u8) {
+        match expr {
+            GrammarExpr::Empty => (String::new(), ATOM),
+            GrammarExpr::EndOfFile => ("EOF".into(), ATOM),
+            GrammarExpr::Rule(name) => (self.rule_name(name), ATOM),
+            GrammarExpr::Token(name) => (self.token_name(name), ATOM),
+            GrammarExpr::Literal(value) => (quote(value), ATOM),
+            GrammarExpr::Pattern(regex) => match (is_lexer, GrammarExpr::from_regex(regex)) {
+                (true, Some(expr)) => self.render(&expr, is_lexer),
+                (true, None) => comment(&format!("/{}/", regex.replace('/', "\\/"))),
+                (false, _) => self.pattern_ref(regex),
+            },
+            GrammarExpr::CharSet { ranges, negated } => (char_set(ranges, *negated), ATOM),
+            GrammarExpr::Sequence(items) => (
+                items
+                    .iter()
+                    .map(|item| wrap(self.render(item, is_lexer), SEQUENCE))
+                    .collect::<Vec<_>>()
+                    .join(" "),
+                SEQUENCE,
+            ),
+            GrammarExpr::Choice(items) => match split_empty(items) {
+                (rest, true) => self.render(&GrammarExpr::optional(rest), is_lexer),
+                (_, false) => (
+                    items
+                        .iter()
+                        .map(|item| wrap(self.render(item, is_lexer), CHOICE))
+                        .collect::<Vec<_>>()
+                        .join(" | "),
+                    CHOICE,
+                ),
+            },
+            GrammarExpr::Repeat { expr, min, max } => {
+                let operand = wrap(self.render(expr, is_lexer), ATOM);
+                let copies = postfix_repetition(*min, *max);
+                let level = if copies.len() == 1 { POSTFIX } else { SEQUENCE };
+                (
+                    copies
+                        .into_iter()
+                        .map(|operator| format!("{}{}", operand, operator))
+                        .collect::<Vec<_>>()
+                        .join(" "),
+                    level,
+                )
+            }
+            GrammarExpr::Lookahead(expr) => {
+                let expr = self.render(expr, is_lexer).0;
+                comment(&format!("followed by {}", expr))
+            }
+            GrammarExpr::NotLookahead(expr) => {
+                let expr = self.render(expr, is_lexer).0;
+                comment(&format!("not followed by {}", expr))
+            }
+            GrammarExpr::NonStructural(expr) => (
+                format!(
+                    "/* non-structural */ {}",
+                    wrap(self.render(expr, is_lexer), ATOM)
+                ),
+                ATOM,
+            ),
+            GrammarExpr::Node { expr, .. } => self.render(expr, is_lexer),
+            GrammarExpr::Opaque(description) => comment(description),
+        }
+    }
+}
+
+fn comment(text: &str) -> (String, u8) {
+    (format!("/* {} */", text.replace("*/", "*\\/")), ATOM)
+}
+
+fn escape(c: char) -> String {
+    match c {
+        '\n' => "\\n".into(),
+        '\r' => "\\r".into(),
+        '\t' => "\\t".into(),
+        '\\' => "\\\\".into(),
+        c if c.is_ascii_graphic() || c == ' ' => c.to_string(),
+        c if (c as u32) <= 0xFFFF => format!("\\u{:04X}", c as u32),
+        c => format!("\\u{{{:X}}}", c as u32),
+    }
+}
+
+fn quote(value: &str) -> String {
+    let mut quoted = String::from("'");
+    for c in value.chars() {
+        match c {
+            '\'' => quoted.push_str("\\'"),
+            c => quoted.push_str(&escape(c)),
+        }
+    }
+    quoted.push('\'');
+    quoted
+}
+
+fn char_set(ranges: &[(char, char)], negated: bool) -> String {
+    let escape = |c: char| match c {
+        ']' | '-' => format!("\\{}", c),
+        c => escape(c),
+    };
+    let mut set = String::from(if negated { "~[" } else { "[" });
+    for &(start, end) in ranges {
+        set.push_str(&escape(start));
+        if start != end {
+            set.push('-');
+            set.push_str(&escape(end));
+        }
+    }
+    set.push(']');
+    set
+}
@@ -0,0 +1,466 @@
+use super::{Grammar, GrammarExpr, LexerPattern, LexerRule, Rule};
+use crate::{Code, Expected, ParseError, ParseErrorKind};
+use std::{collections::HashSet, ops::Range};
+
+impl Grammar {
+    /// Load a grammar from a textual grammar definition.
+    ///
+    /// A grammar definition consists of the parser rules and the lexer rules, each terminated by `;`.
+    /// The first parser rule is the start rule of the grammar.
+    ///
+    /// ```text
+    /// // A parser rule.
+    /// value = object | array | Number | "true" | "false" ;
+    /// // A lexer rule creating the token `Number` from any of the literals and the regular expressions.
+    /// token Number = /-?[0-9]+/ ;
+    /// // A lexer rule creating a non structural token.
+    /// skip Space = /[ \t\r\n]+/ ;
+    /// ```
+    ///
+    /// The expressions of the parser rules are written as
+    /// * `a | b` - Ordered choice where the first matching alternative wins.
+    /// * `a b` - Sequence.
+    /// * `a?`, `a*`, `a+`, `a{2}`, `a{2,}` and `a{2,4}` - Repetition.
+    /// * `&a` and `!a` - Lookahead and negative lookahead.
+    /// * `( ... )` - Grouping, where `()` matches the empty input.
+    /// * `name` - Reference of a parser rule or a lexer rule.
+    /// * `"text"` or `'text'` - Literal with the escapes `\n`, `\r`, `\t`, `\0`, `\\`, `\"`, `\'` and `\u{..}`.
+    /// * `/regex/` - Regular expression anchored at the current position, where `\/` escapes the slash.
+    /// * `EOF` - The end of the input.
+    ///
+    /// Comments start with `//` and continue until the end of the line.
+    pub fn from_dsl(source: &str) -> Result<Self, ParseError> {
+        DslParser {
+            source,
+            pointer: 0,
+            references: Vec::new(),
+        }
+        .parse()
+    }
+}
+
+fn is_identifier_start(c: char) -> bool {
+    c.is_ascii_alphabetic() || c == '_'
+}
+
+fn is_identifier_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '_'
+}
+
+/// Anchor the regular expression at the current position of the input.
+fn anchor(regex: &str) -> String {
+    if regex.starts_with('^') {
+        regex.to_string()
+    } else {
+        format!("^(?:{})", regex)
+    }
+}
+
+/// Replace the references of the lexer rules with the [Token](GrammarExpr::Token) expressions.
+fn resolve_tokens(expr: &mut GrammarExpr, tokens: &HashSet<String>) {
+    match expr {
+        GrammarExpr::Rule(name) if tokens.contains(name) => {
+            *expr = GrammarExpr::Token(std::mem::take(name));
+        }
+        GrammarExpr::Sequence(items) | GrammarExpr::Choice(items) => {
+            items
+                .iter_mut()
+                .for_each(|item| resolve_tokens(item, tokens));
+        }
+        GrammarExpr::Repeat { expr, .. }
+        | GrammarExpr::Lookahead(expr)
+        | GrammarExpr::NotLookahead(expr)
+        | GrammarExpr::NonStructural(expr)
+        | GrammarExpr::Node { expr, .. } => resolve_tokens(expr, tokens),
+        _ => {}
+    }
+}
+
+struct DslParser<'s> {
+    source: &'s str,
+    pointer: usize,
+    /// References of the rules which are resolved after all the rules are parsed.
+    references: Vec<(String, Range<usize>)>,
+}
+
+impl<'s> DslParser<'s> {
+    fn error(&self, span: Range<usize>, message: String, expected: Vec<Expected>) -> ParseError {
+        let kind = if span.start == self.source.len() {
+            ParseErrorKind::UnexpectedEOF
+        } else {
+            ParseErrorKind::UnexpectedToken
+        };
+        let mut err = ParseError::new(kind, &Code::new(self.source.as_bytes()), span, message);
+        err.expected = expected;
+        err
+    }
+
+    /// Create an error at the next character of the input.
+    fn unexpected(&self, expected: Vec<Expected>) -> ParseError {
+        match self.rest().chars().next() {
+            Some(c) => self.error(
+                self.pointer..self.pointer + c.len_utf8(),
+                format!("Unexpected {:?}.", c),
+                expected,
+            ),
+            None => self.error(
+                self.pointer..self.pointer,
+                "Unexpected end of the grammar.".into(),
+                expected,
+            ),
+        }
+    }
+
+    fn rest(&self) -> &'s str {
+        &self.source[self.pointer..]
+    }
+
+    fn skip_trivia(&mut self) {
+        loop {
+            let rest = self.rest();
+            let trimmed = rest.trim_start();
+            self.pointer += rest.len() - trimmed.len();
+            if trimmed.starts_with("//") {
+                self.pointer += trimmed.find('\n').unwrap_or(trimmed.len());
+            } else {
+                break;
+            }
+        }
+    }
+
+    fn peek(&mut self) -> Option<char> {
+        self.skip_trivia();
+        self.rest().chars().next()
+    }
+
+    fn eat(&mut self, c: char) -> bool {
+        if self.peek() == Some(c) {
+            self.pointer += c.len_utf8();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, c: char) -> Result<(), ParseError> {
+        match self.eat(c) {
+            true => Ok(()),
+            false => Err(self.unexpected(vec![Expected::Literal(c.to_string())])),
+        }
+    }
+
+    fn identifier(&mut self) -> Option<(String, Range<usize>)> {
+        match self.peek() {
+            Some(c) if is_identifier_start(c) => {
+                let start = self.pointer;
+                let length = self
+                    .rest()
+                    .find(|c| !is_identifier_char(c))
+                    .unwrap_or(self.rest().len());
+                self.pointer += length;
+                Some((
+                    self.source[start..self.pointer].to_string(),
+                    start..self.pointer,
+                ))
+            }
+            _ => None,
+        }
+    }
+
+    fn expect_identifier(&mut self) -> Result<(String, Range<usize>), ParseError> {
+        self.identifier()
+            .ok_or_else(|| self.unexpected(vec![Expected::Pattern("identifier".into())]))
+    }
+
+    fn parse(mut self) -> Result<Grammar, ParseError> {
+        let mut grammar = Grammar::new();
+        let mut tokens = HashSet::new();
+        while self.peek().is_some() {
+            let (mut name, mut span) = self.expect_identifier()?;
+            let lexer_rule = match name.as_str() {
+                "token" | "skip" => match self.identifier() {
+                    Some(token) => {
+                        let is_structural = name == "token";
+                        (name, span) = token;
+                        Some(is_structural)
+                    }
+                    None => None,
+                },
+                _ => None,
+            };
+            if name == "EOF" {
+                return Err(self.error(
+                    span,
+                    "Rule name \"EOF\" is reserved for the end of the input.".into(),
+                    Vec::new(),
+                ));
+            }
+            if grammar.names.contains(&name) || tokens.contains(&name) {
+                return Err(self.error(
+                    span,
+                    format!("Rule {:?} is already defined.", name),
+                    Vec::new(),
+                ));
+            }
+            self.expect('=')?;
+            match lexer_rule {
+                Some(is_structural) => {
+                    let mut patterns = vec![self.lexer_pattern()?];
+                    while self.eat('|') {
+                        patterns.push(self.lexer_pattern()?);
+                    }
+                    tokens.insert(name.clone());
+                    grammar.lexer_rules.push(LexerRule {
+                        token: name,
+                        patterns,
+                        is_structural,
+                    });
+                }
+                None => {
+                    let expr = self.choice()?;
+                    grammar.names.insert(name.clone());
+                    grammar.rules.push(Rule { name, expr });
+                }
+            }
+            self.expect(';')?;
+        }
+
+        if grammar.rules.is_empty() {
+            return Err(self.error(
+                self.pointer..self.pointer,
+                "The grammar should define at least one parser rule.".into(),
+                Vec::new(),
+            ));
+        }
+        for (name, span) in &self.references {
+            if !grammar.names.contains(name) && !tokens.contains(name) {
+                return Err(self.error(
+                    span.clone(),
+                    format!("Rule {:?} is not defined.", name),
+                    Vec::new(),
+                ));
+            }
+        }
+        for rule in &mut grammar.rules {
+            resolve_tokens(&mut rule.expr, &tokens);
+        }
+        Ok(grammar)
+    }
+
+    fn lexer_pattern(&mut self) -> Result<LexerPattern, ParseError> {
+        let start = self.pointer;
+        match self.peek() {
+            Some('"') | Some('\'') => match self.string()? {
+                value if value.is_empty() => Err(self.error(
+                    start..self.pointer,
+                    "A lexer rule should not match an empty string.".into(),
+                    Vec::new(),
+                )),
+                value => Ok(LexerPattern::Literal(value)),
+            },
+            Some('/') => Ok(LexerPattern::Regex(self.regex()?)),
+            _ => Err(self.unexpected(vec![
+                Expected::Pattern("string".into()),
+                Expected::Pattern("regex".into()),
+            ])),
+        }
+    }
+
+    fn choice(&mut self) -> Result<GrammarExpr, ParseError> {
+        let mut items = vec![self.sequence()?];
+        while self.eat('|') {
+            items.push(self.sequence()?);
+        }
+        Ok(GrammarExpr::choice(items))
+    }
+
+    fn sequence(&mut self) -> Result<GrammarExpr, ParseError> {
+        let mut items = Vec::new();
+        while let Some(c) = self.peek() {
+            if !(is_identifier_start(c) || "&!(\"'/".contains(c)) {
+                break;
+            }
+            items.push(self.prefixed()?);
+        }
+        Ok(GrammarExpr::sequence(items))
+    }
+
+    fn prefixed(&mut self) -> Result<GrammarExpr, ParseError> {
+        if self.eat('&') {
+            Ok(GrammarExpr::Lookahead(Box::new(self.prefixed()?)))
+        } else if self.eat('!') {
+            Ok(GrammarExpr::NotLookahead(Box::new(self.prefixed()?)))
+        } else {
+            self.postfixed()
+        }
+    }
+
+    fn postfixed(&mut self) -> Result<GrammarExpr, ParseError> {
+        let mut expr = self.primary()?;
+        loop {
+            expr = if self.eat('?') {
+                GrammarExpr::optional(expr)
+            } else if self.eat('*') {
+                GrammarExpr::repeat(expr, 0, None)
+            } else if self.eat('+') {
+                GrammarExpr::repeat(expr, 1, None)
+            } else if self.peek() == Some('{') {
+                let start = self.pointer;
+                self.pointer += 1;
+                let min = self.number()?;
+                let max = match self.eat(',') {
+                    true if self.peek() == Some('}') => None,
+                    true => Some(self.number()?),
+                    false => Some(min),
+                };
+                self.expect('}')?;
+                if max.is_some_and(|max| max < min) {
+                    return Err(self.error(
+                        start..self.pointer,
+                        "The maximum repetition should not be less than the minimum repetition."
+                            .into(),
+                        Vec::new(),
+                    ));
+                }
+                GrammarExpr::repeat(expr, min, max)
+            } else {
+                return Ok(expr);
+            };
+        }
+    }
+
+    fn number(&mut self) -> Result<usize, ParseError> {
+        self.skip_trivia();
+        let length = self
+            .rest()
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(self.rest().len());
+        match self.rest()[..length].parse() {
+            Ok(number) => {
+                self.pointer += length;
+                Ok(number)
+            }
+            Err(_) => Err(self.unexpected(vec![Expected::Pattern("number".into())])),
+        }
+    }
+
+    fn primary(&mut self) -> Result<GrammarExpr, ParseError> {
+        match self.peek() {
+            Some('(') => {
+                self.pointer += 1;
+                let expr = self.choice()?;
+                self.expect(')')?;
+                Ok(expr)
+            }
+            Some('"') | Some('\'') => match self.string()? {
+                value if value.is_empty() => Ok(GrammarExpr::Empty),
+                value => Ok(GrammarExpr::Literal(value)),
+            },
+            Some('/') => Ok(GrammarExpr::Pattern(self.regex()?)),
+            _ => match self.identifier() {
+                Some((name, _)) if name == "EOF" => Ok(GrammarExpr::EndOfFile),
+                Some((name, span)) => {
+                    self.references.push((name.clone(), span));
+                    Ok(GrammarExpr::Rule(name))
+                }
+                None => Err(self.unexpected(vec![Expected::Pattern("expression".into())])),
+            },
+        }
+    }
+
+    fn string(&mut self) -> Result<String, ParseError> {
+        let start = self.pointer;
+        let mut chars = self.rest().char_indices();
+        let quote = chars.next().map(|(_, c)| c);
+        let mut value = String::new();
+        while let Some((index, c)) = chars.next() {
+            match c {
+                c if Some(c) == quote => {
+                    self.pointer += index + c.len_utf8();
+                    return Ok(value);
+                }
+                '\n' => break,
+                '\\' => {
+                    let escaped = match chars.next() {
+                        Some((_, 'n')) => Some('\n'),
+                        Some((_, 'r')) => Some('\r'),
+                        Some((_, 't')) => Some('\t'),
+                        Some((_, '0')) => Some('\0'),
+                        Some((_, c @ ('\\' | '"' | '\''))) => Some(c),
+                        Some((_, 'u')) if chars.next().map(|(_, c)| c) == Some('{') => {
+                            let hex: String = chars
+                                .by_ref()
+                                .map(|(_, c)| c)
+                                .take_while(|c| *c != '}')
+                                .collect();
+                            u32::from_str_radix(&hex, 16).ok().and_then(char::from_u32)
+                        }
+                        _ => None,
+                    };
+                    match escaped {
+                        Some(c) => value.push(c),
+                        None => {
+                            let end = self.pointer + index + 1;
+                            return Err(self.error(
+                                self.pointer + index..end,
+                                "Invalid escape sequence.".into(),
+                                Vec::new(),
+                            ));
+                        }
+                    }
+                }
+                c => value.push(c),
+            }
+        }
+        Err(self.error(
+            start..start + 1,
+            "Unterminated string literal.".into(),
+            Vec::new(),
+        ))
+    }
+
+    fn regex(&mut self) -> Result<String, ParseError> {
+        let start = self.pointer;
+        let mut regex = String::new();
+        let mut in_class = false;
+        let mut chars = self.rest().char_indices().skip(1);
+        while let Some((index, c)) = chars.next() {
+            match c {
+                '/' if !in_class => {
+                    self.pointer += index + 1;
+                    let regex = anchor(&regex);
+                    return match regex_syntax::parse(&regex) {
+                        Ok(_) => Ok(regex),
+                        Err(err) => Err(self.error(
+                            start..self.pointer,
+                            format!("Invalid regular expression. {}", err),
+                            Vec::new(),
+                        )),
+                    };
+                }
+                '\n' => break,
+                '\\' => match chars.next() {
+                    Some((_, '/')) => regex.push('/'),
+                    Some((_, c)) => {
+                        regex.push('\\');
+                        regex.push(c);
+                    }
+                    None => break,
+                },
+                c => {
+                    match c {
+                        '[' => in_class = true,
+                        ']' => in_class = false,
+                        _ => {}
+                    }
+                    regex.push(c);
+                }
+            }
+        }
+        Err(self.error(
+            start..start + 1,
+            "Unterminated regular expression.".into(),
+            Vec::new(),
+        ))
+    }
+}
@@ -0,0 +1,444 @@
+use super::{
+    analysis::collect_references, Grammar, GrammarExpr, LexerPattern, RoundTripError, Sentence,
+    SentenceGenerator,
+};
+use crate::{IProduction, ImplementationError};
+use regex_syntax::hir::{Class, Hir, HirKind};
+use std::{
+    collections::{HashMap, HashSet},
+    fmt::{Debug, Display, Formatter},
+};
+
+/// Height of a rule which can not finish any derivation.
+const UNPRODUCTIVE: usize = usize::MAX;
+/// Maximum number of the repetitions in addition to the minimum count of an unbounded repetition.
+const EXTRA_REPETITIONS: usize = 3;
+const DEFAULT_SEED: u64 = 0x2545_F491_4F6C_DD1D;
+
+impl SentenceGenerator {
+    /// Create a generator of the sentences of the start rule of the grammar.
+    ///
+    /// An error is returned if the grammar does not have any rule or any rule refers to an undefined rule.
+    pub fn new(grammar: Grammar) -> Result<Self, ImplementationError> {
+        if grammar.rules.is_empty() {
+            return Err(ImplementationError::new(
+                "EmptyGrammar".into(),
+                "The grammar does not have any rule to generate a sentence.".into(),
+            ));
+        }
+        for rule in &grammar.rules {
+            let mut references = HashSet::new();
+            collect_references(&rule.expr, &mut references, &mut HashSet::new());
+            if let Some(name) = references
+                .iter()
+                .find(|name| grammar.get_rule(name).is_none())
+            {
+                return Err(ImplementationError::new(
+                    "UndefinedRule".into(),
+                    format!(
+                        "Rule '{}' referred by '{}' is not defined.",
+                        name, rule.name
+                    ),
+                ));
+            }
+        }
+        let heights = rule_heights(&grammar);
+        Ok(Self {
+            grammar,
+            heights,
+            samples: HashMap::new(),
+            max_depth: 12,
+            max_size: 256,
+            separator: String::from(" "),
+            state: DEFAULT_SEED,
+        })
+    }
+
+    /// Create a generator of the sentences of the root production.
+    ///
+    /// The grammar does not include any lexer rule, therefore the samples should be added for the tokens of the productions.
+    pub fn from_production<TP: IProduction + ?Sized>(
+        root: &TP,
+    ) -> Result<Self, ImplementationError> {
+        Self::new(Grammar::from_production(root))
+    }
+
+    /// Add sample values of the token which are used as the text of the token in the sentences.
+    ///
+    /// The patterns of the lexer rule of the grammar are used for a token without any sample.
+    pub fn add_token_samples<TL: Debug>(&mut self, token: TL, samples: &[&str]) {
+        self.samples
+            .entry(format!("{:?}", token))
+            .or_default()
+            .extend(samples.iter().map(|sample| sample.to_string()));
+    }
+
+    /// Set the maximum nesting of the rules after which the shortest derivations are picked. The default depth is 12.
+    pub fn set_max_depth(&mut self, max_depth: usize) {
+        self.max_depth = max_depth;
+    }
+
+    /// Set the length of the sentence in bytes after which the shortest derivations are picked. The default size is 256.
+    pub fn set_max_size(&mut self, max_size: usize) {
+        self.max_size = max_size;
+    }
+
+    /// Set the text inserted between the tokens of the sentence. The default separator is a single space.
+    pub fn set_separator(&mut self, separator: &str) {
+        self.separator = separator.to_string();
+    }
+
+    /// Reset the pseudo random sequence with the seed to reproduce the sentences.
+    pub fn set_seed(&mut self, seed: u64) {
+        self.state = match seed ^ DEFAULT_SEED {
+            0 => DEFAULT_SEED,
+            state => state,
+        };
+    }
+
+    /// Get the grammar of the generator.
+    pub fn grammar(&self) -> &Grammar {
+        &self.grammar
+    }
+
+    /// Generate a random sentence of the start rule of the grammar.
+    pub fn generate(&mut self) -> Result<Sentence, ImplementationError> {
+        let mut expansion = Expansion {
+            generator: self,
+            state: self.state,
+            text: String::new(),
+            tokens: Vec::new(),
+            depth: 0,
+            non_structural: false,
+            region_start: 0,
+        };
+        let start = GrammarExpr::Rule(self.grammar.rules[0].name.clone());
+        let result = expansion.expand(&start);
+        let (state, text, tokens) = (expansion.state, expansion.text, expansion.tokens);
+        self.state = state;
+        result.map(|_| Sentence { text, tokens })
+    }
+}
+
+/// State of the generation of a single sentence.
+struct Expansion<'g> {
+    generator: &'g SentenceGenerator,
+    state: u64,
+    text: String,
+    tokens: Vec<String>,
+    depth: usize,
+    non_structural: bool,
+    /// Number of the tokens before the current non structural expression.
+    region_start: usize,
+}
+
+impl<'g> Expansion<'g> {
+    fn next(&mut self) -> u64 {
+        // xorshift64*
+        self.state ^= self.state >> 12;
+        self.state ^= self.state << 25;
+        self.state ^= self.state >> 27;
+        self.state.wrapping_mul(DEFAULT_SEED)
+    }
+
+    fn below(&mut self, bound: usize) -> usize {
+        (self.next() % bound as u64) as usize
+    }
+
+    /// Pick a random count of the repetition which is the minimum count once the limits are exceeded.
+    fn count(&mut self, min: usize, max: Option<usize>) -> usize {
+        if self.is_exhausted() {
+            return min;
+        }
+        let max = max
+            .unwrap_or(UNPRODUCTIVE)
+            .min(min.saturating_add(EXTRA_REPETITIONS));
+        min + self.below(max - min + 1)
+    }
+
+    fn is_exhausted(&self) -> bool {
+        self.depth >= self.generator.max_depth || self.text.len() >= self.generator.max_size
+    }
+
+    fn expand(&mut self, expr: &GrammarExpr) -> Result<(), ImplementationError> {
+        match expr {
+            GrammarExpr::Empty
+            | GrammarExpr::EndOfFile
+            | GrammarExpr::Lookahead(_)
+            | GrammarExpr::NotLookahead(_) => Ok(()),
+            GrammarExpr::Rule(name) => {
+                if self.is_exhausted() && self.generator.heights[name] == UNPRODUCTIVE {
+                    return Err(ImplementationError::new(
+                        "UnproductiveRule".into(),
+                        format!("Rule '{}' can not finish any derivation.", name),
+                    ));
+                }
+                let rule = self.generator.grammar.get_rule(name).unwrap();
+                self.depth += 1;
+                let result = self.expand(&rule.expr);
+                self.depth -= 1;
+                result
+            }
+            GrammarExpr::Token(name) => self.token(name),
+            GrammarExpr::Literal(value) => {
+                self.text.push_str(value);
+                Ok(())
+            }
+            GrammarExpr::Pattern(regex) => {
+                let mut text = String::new();
+                self.pattern(regex, &mut text)?;
+                self.text.push_str(&text);
+                Ok(())
+            }
+            GrammarExpr::CharSet { ranges, negated } => {
+                let ch = self.pick_char(ranges, *negated).ok_or_else(|| {
+                    ImplementationError::new(
+                        "UnsupportedPattern".into(),
+                        "No character can be generated for the negated character set.".into(),
+                    )
+                })?;
+                self.text.push(ch);
+                Ok(())
+            }
+            GrammarExpr::Sequence(items) => items.iter().try_for_each(|item| self.expand(item)),
+            GrammarExpr::Choice(alternatives) => {
+                let heights: Vec<usize> = alternatives
+                    .iter()
+                    .map(|alternative| expr_height(alternative, &self.generator.heights))
+                    .collect();
+                let limit = match self.is_exhausted() {
+                    true => *heights.iter().min().unwrap(),
+                    false => match heights.iter().any(|height| *height != UNPRODUCTIVE) {
+                        true => UNPRODUCTIVE - 1,
+                        false => UNPRODUCTIVE,
+                    },
+                };
+                let candidates: Vec<usize> = (0..alternatives.len())
+                    .filter(|index| heights[*index] <= limit)
+                    .collect();
+                let index = candidates[self.below(candidates.len())];
+                self.expand(&alternatives[index])
+            }
+            GrammarExpr::Repeat { expr, min, max } => {
+                let count = match expr_height(expr, &self.generator.heights) {
+                    UNPRODUCTIVE => *min,
+                    _ => self.count(*min, *max),
+                };
+                for index in 0..count {
+                    if index >= *min && self.is_exhausted() {
+                        break;
+                    }
+                    self.expand(expr)?;
+                }
+                Ok(())
+            }
+            GrammarExpr::NonStructural(expr) => {
+                let previous = (self.non_structural, self.region_start);
+                self.non_structural = true;
+                self.region_start = self.tokens.len();
+                let result = self.expand(expr);
+                (self.non_structural, self.region_start) = previous;
+                result
+            }
+            GrammarExpr::Node { expr, .. } => self.expand(expr),
+            GrammarExpr::Opaque(description) => Err(ImplementationError::new(
+                "OpaqueProduction".into(),
+                format!("No sentence can be generated for '{}'.", description),
+            )),
+        }
+    }
+
+    fn token(&mut self, name: &str) -> Result<(), ImplementationError> {
+        let generator = self.generator;
+        let lexer_rule = generator.grammar.get_lexer_rule(name);
+        let sample = match generator
+            .samples
+            .get(name)
+            .filter(|samples| !samples.is_empty())
+        {
+            Some(samples) => samples[self.below(samples.len())].clone(),
+            None => {
+                let patterns: Vec<&LexerPattern> = lexer_rule
+                    .iter()
+                    .flat_map(|rule| rule.patterns.iter().map(LexerPattern::unconditional))
+                    .filter(|pattern| !matches!(pattern, LexerPattern::Opaque(_)))
+                    .collect();
+                if patterns.is_empty() {
+                    return Err(ImplementationError::new(
+                        "MissingTokenSample".into(),
+                        format!("Token '{}' does not have any sample.", name),
+                    ));
+                }
+                match patterns[self.below(patterns.len())] {
+                    LexerPattern::Literal(value) => value.clone(),
+                    LexerPattern::Regex(regex) => {
+                        let mut sample = String::new();
+                        self.pattern(regex, &mut sample)?;
+                        sample
+                    }
+                    LexerPattern::Opaque(_) | LexerPattern::Conditional(_) => unreachable!(),
+                }
+            }
+        };
+        // The tokens of a non structural expression are matched without the separators.
+        if !self.text.is_empty() && (!self.non_structural || self.tokens.len() == self.region_start)
+        {
+            self.text.push_str(&generator.separator);
+        }
+        self.text.push_str(&sample);
+        if !matches!(lexer_rule, Some(rule) if !rule.is_structural) {
+            self.tokens.push(name.to_string());
+        }
+        Ok(())
+    }
+
+    fn pattern(&mut self, regex: &str, text: &mut String) -> Result<(), ImplementationError> {
+        let hir = regex_syntax::parse(regex).map_err(|err| {
+            ImplementationError::new(
+                "InvalidPattern".into(),
+                format!("Regular expression '{}' is invalid. {}", regex, err),
+            )
+        })?;
+        self.hir(&hir, text).ok_or_else(|| {
+            ImplementationError::new(
+                "UnsupportedPattern".into(),
+                format!(
+                    "No text can be generated for the regular expression '{}'.",
+                    regex
+                ),
+            )
+        })
+    }
+
+    /// Generate a random text matching the regular expression.
+    /// The assertions like the word boundaries are ignored.
+    fn hir(&mut self, hir: &Hir, text: &mut String) -> Option<()> {
+        match hir.kind() {
+            HirKind::Empty | HirKind::Look(_) => {}
+            HirKind::Literal(literal) => text.push_str(&String::from_utf8_lossy(&literal.0)),
+            HirKind::Class(Class::Unicode(class)) => {
+                let ranges: Vec<(char, char)> = class
+                    .iter()
+                    .map(|range| (range.start(), range.end()))
+                    .collect();
+                text.push(self.pick_char(&ranges, false)?);
+            }
+            HirKind::Class(Class::Bytes(class)) => {
+                let ranges: Vec<(char, char)> = class
+                    .iter()
+                    .filter(|range| range.start().is_ascii())
+                    .map(|range| (range.start() as char, range.end().min(0x7F) as char))
+                    .collect();
+                text.push(self.pick_char(&ranges, false)?);
+            }
+            HirKind::Repetition(repetition) => {
+                let count = self.count(
+                    repetition.min as usize,
+                    repetition.max.map(|max| max as usize),
+                );
+                for _ in 0..count {
+                    self.hir(&repetition.sub, text)?;
+                }
+            }
+            HirKind::Capture(capture) => self.hir(&capture.sub, text)?,
+            HirKind::Concat(hirs) => {
+                for hir in hirs {
+                    self.hir(hir, text)?;
+                }
+            }
+            HirKind::Alternation(hirs) => {
+                let index = self.below(hirs.len());
+                self.hir(&hirs[index], text)?;
+            }
+        }
+        Some(())
+    }
+
+    /// Pick a random character of the inclusive ranges where the printable ASCII characters are preferred.
+    fn pick_char(&mut self, ranges: &[(char, char)], negated: bool) -> Option<char> {
+        let contains = |ch: char| {
+            ranges
+                .iter()
+                .any(|(start, end)| (*start..=*end).contains(&ch))
+        };
+        let printable: Vec<char> = (' '..='~').filter(|ch| contains(*ch) != negated).collect();
+        if !printable.is_empty() {
+            return Some(printable[self.below(printable.len())]);
+        }
+        if negated || ranges.is_empty() {
+            return None;
+        }
+        let (start, end) = ranges[self.below(ranges.len())];
+        let code = start as usize + self.below(end as usize - start as usize + 1);
+        Some(char::from_u32(code as u32).unwrap_or(start))
+    }
+}
+
+/// Compute the minimum nesting of the rules to finish a derivation of each rule.
+fn rule_heights(grammar: &Grammar) -> HashMap<String, usize> {
+    let mut heights: HashMap<String, usize> = grammar
+        .rules
+        .iter()
+        .map(|rule| (rule.name.clone(), UNPRODUCTIVE))
+        .collect();
+    let mut changed = true;
+    while changed {
+        changed = false;
+        for rule in &grammar.rules {
+            let height = expr_height(&rule.expr, &heights);
+            if height < heights[&rule.name] {
+                heights.insert(rule.name.clone(), height);
+                changed = true;
+            }
+        }
+    }
+    heights
+}
+
+fn expr_height(expr: &GrammarExpr, heights: &HashMap<String, usize>) -> usize {
+    match expr {
+        GrammarExpr::Rule(name) => heights
+            .get(name)
+            .copied()
+            .unwrap_or(UNPRODUCTIVE)
+            .saturating_add(1),
+        GrammarExpr::Sequence(items) => items
+            .iter()
+            .map(|item| expr_height(item, heights))
+            .max()
+            .unwrap_or(0),
+        GrammarExpr::Choice(alternatives) => alternatives
+            .iter()
+            .map(|alternative| expr_height(alternative, heights))
+            .min()
+            .unwrap_or(0),
+        GrammarExpr::Repeat { min: 0, .. } => 0,
+        GrammarExpr::Repeat { expr, .. }
+        | GrammarExpr::NonStructural(expr)
+        | GrammarExpr::Node { expr, .. } => expr_height(expr, heights),
+        GrammarExpr::Opaque(_) => UNPRODUCTIVE,
+        _ => 0,
+    }
+}
+
+impl Display for RoundTripError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RoundTripError::Generation(err) => write!(f, "{}", err),
+            RoundTripError::Parse { sentence, error } => {
+                write!(f, "Failed to parse the sentence {:?}. {}", sentence, error)
+            }
+            RoundTripError::TokenMismatch {
+                sentence,
+                expected,
+                found,
+            } => write!(
+                f,
+                "The sentence {:?} is tokenized into [{}] instead of [{}].",
+                sentence,
+                found.join(", "),
+                expected.join(", ")
+            ),
+        }
+    }
+}
@@ -0,0 +1,139 @@
+use super::{split_empty, wrap, Grammar, GrammarExpr, ATOM, CHOICE, POSTFIX, SEQUENCE};
+use std::fmt::Write;
+
+impl Grammar {
+    /// Write the grammar in the ISO/IEC 14977 EBNF notation.
+    ///
+    /// The regular expressions are translated into the terminal strings where possible,
+    /// and the character sets, the lookaheads and the rest of the regular expressions are written as special sequences `? ... ?`.
+    /// The non structural expressions are marked with the `(* non-structural *)` comment.
+    pub fn to_iso_ebnf(&self) -> String {
+        let mut ebnf = String::new();
+        for rule in &self.rules {
+            writeln!(ebnf, "{} = {} ;", rule.name, render(&rule.expr).0).unwrap();
+        }
+        if !self.lexer_rules.is_empty() {
+            writeln!(ebnf).unwrap();
+        }
+        for rule in &self.lexer_rules {
+            write!(ebnf, "{} = {} ;", rule.token, render(&rule.expr()).0).unwrap();
+            if !rule.is_structural {
+                write!(ebnf, " (* non-structural *)").unwrap();
+            }
+            writeln!(ebnf).unwrap();
+        }
+        ebnf
+    }
+}
+
+fn special(text: &str) -> (String, u8) {
+    (format!("? {} ?", text.replace('?', "\\u{3f}")), ATOM)
+}
+
+fn terminal(value: &str) -> (String, u8) {
+    if value.is_empty() {
+        render(&GrammarExpr::Empty)
+    } else if !value.contains('"') {
+        (format!("\"{}\"", value), ATOM)
+    } else if !value.contains('\'') {
+        (format!("'{}'", value), ATOM)
+    } else {
+        special(&format!("{:?}", value))
+    }
+}
+
+fn char_set(ranges: &[(char, char)], negated: bool) -> String {
+    let escape = |c: char| match c {
+        ']' | '\\' | '^' | '-' => format!("\\{}", c),
+        '\n' => "\\n".into(),
+        '\r' => "\\r".into(),
+        '\t' => "\\t".into(),
+        c if c.is_ascii_graphic() || c == ' ' => c.to_string(),
+        c => format!("\\u{{{:x}}}", c as u32),
+    };
+    let mut set = String::from(if negated { "[^" } else { "[" });
+    for &(start, end) in ranges {
+        set.push_str(&escape(start));
+        if start != end {
+            set.push('-');
+            set.push_str(&escape(end));
+        }
+    }
+    set.push(']');
+    set
+}
+
+/// Repeat the expression `count` times as a factor like `3 * x`.
+fn factor(count: usize, expr: &GrammarExpr) -> (String, u8) {
+    match count {
+        1 => (wrap(render(expr), SEQUENCE), SEQUENCE),
+        _ => (format!("{} * {}", count, wrap(render(expr), ATOM)), POSTFIX),
+    }
+}
+
+fn render(expr: &GrammarExpr) -> (String, u8) {
+    match expr {
+        GrammarExpr::Empty => special("empty"),
+        GrammarExpr::EndOfFile => special("end of input"),
+        GrammarExpr::Rule(name) | GrammarExpr::Token(name) => (name.clone(), ATOM),
+        GrammarExpr::Literal(value) => terminal(value),
+        GrammarExpr::Pattern(regex) => match GrammarExpr::from_regex(regex) {
+            Some(expr) => render(&expr),
+            None => special(&format!("/{}/", regex.replace('/', "\\/"))),
+        },
+        GrammarExpr::CharSet { ranges, negated } => special(&char_set(ranges, *negated)),
+        GrammarExpr::Sequence(items) => (
+            items
+                .iter()
+                .map(|item| wrap(render(item), SEQUENCE))
+                .collect::<Vec<_>>()
+                .join(" , "),
+            SEQUENCE,
+        ),
+        GrammarExpr::Choice(items) => match split_empty(items) {
+            (rest, true) => render(&GrammarExpr::optional(rest)),
+            (_, false) => (
+                items
+                    .iter()
+                    .map(|item| wrap(render(item), CHOICE))
+                    .collect::<Vec<_>>()
+                    .join(" | "),
+                CHOICE,
+            ),
+        },
+        GrammarExpr::Repeat { expr, min, max } => {
+            let inner = wrap(render(expr), CHOICE);
+            let mut factors = Vec::new();
+            if *min > 0 {
+                factors.push(factor(*min, expr));
+            }
+            match max {
+                None => factors.push((format!("{{ {} }}", inner), ATOM)),
+                Some(max) if max > min => factors.push(match max - min {
+                    1 => (format!("[ {} ]", inner), ATOM),
+                    count => (format!("{} * [ {} ]", count, inner), POSTFIX),
+                }),
+                Some(_) => {}
+            }
+            match factors.len() {
+                1 => factors.pop().unwrap(),
+                _ => (
+                    factors
+                        .into_iter()
+                        .map(|factor| wrap(factor, SEQUENCE))
+                        .collect::<Vec<_>>()
+                        .join(" , "),
+                    SEQUENCE,
+                ),
+            }
+        }
+        GrammarExpr::Lookahead(expr) => special(&format!("followed by {}", render(expr).0)),
+        GrammarExpr::NotLookahead(expr) => special(&format!("not followed by {}", render(expr).0)),
+        GrammarExpr::NonStructural(expr) => (
+            format!("(* non-structural *) {}", wrap(render(expr), ATOM)),
+            ATOM,
+        ),
+        GrammarExpr::Node { expr, .. } => render(expr),
+        GrammarExpr::Opaque(description) => special(description),
+    }
+}
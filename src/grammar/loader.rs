@@ -0,0 +1,411 @@
+use super::{Grammar, GrammarExpr, LexerPattern, NodeName, TokenName};
+use crate::{
+    lexeme::{Pattern, Punctuations},
+    production::{
+        Concat, ConstantField, EOFProd, List, Lookahead, Node, NonStructural, NotLookahead,
+        NullProd, Nullable, RegexField, Repeat, TokenField, Union,
+    },
+    DefaultParser, ILexeme, IProduction, ImplementationError, LexerlessParser, Log, NodeImpl, Rc,
+    TokenImpl, Tokenizer,
+};
+use std::{
+    collections::{HashMap, HashSet},
+    fmt::{Debug, Formatter},
+};
+
+type Production<TN, TL> = Rc<dyn IProduction<Node = TN, Token = TL>>;
+
+impl Debug for NodeName {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl NodeImpl for NodeName {
+    fn null() -> Self {
+        NodeName("null")
+    }
+    fn is_null(&self) -> bool {
+        self.0 == "null"
+    }
+}
+
+impl Debug for TokenName {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name)
+    }
+}
+
+impl TokenImpl for TokenName {
+    fn eof() -> Self {
+        TokenName {
+            name: "EOF",
+            is_structural: true,
+        }
+    }
+
+    fn is_structural(&self) -> bool {
+        self.is_structural
+    }
+}
+
+/// The productions and the lexemes require static identifiers,
+/// therefore the names of a loaded grammar live as long as the program.
+fn leak(name: &str) -> &'static str {
+    Box::leak(name.to_string().into_boxed_str())
+}
+
+fn unsupported(expr: &GrammarExpr) -> ImplementationError {
+    ImplementationError::new(
+        "UnsupportedExpression".into(),
+        format!("{:?} can not be built into a production.", expr),
+    )
+}
+
+impl Grammar {
+    /// Build a [LexerlessParser] from the rules of the grammar where the first rule is the root production.
+    ///
+    /// The literals and the regular expressions of the rules are built into [ConstantField] and [RegexField],
+    /// therefore the grammar should not refer any lexer rule.
+    /// ### Arguments
+    /// * `node_value` - Node value of a rule name. The rule creates a node in the [AST](crate::ASTNode) if the value is available.
+    ///
+    /// The rule names are leaked to create the productions, therefore a grammar is expected to be built once for the program.
+    /// ## Example
+    /// ```
+    /// use lang_pt::grammar::{Grammar, NodeName};
+    ///
+    /// let grammar = Grammar::from_dsl(r#"
+    ///     list = "[" number ("," number)* "]" EOF ;
+    ///     number = /[0-9]+/ ;
+    /// "#).unwrap();
+    /// let parser = grammar.build_lexerless(|name| Some(NodeName(name))).unwrap();
+    /// let tree = parser.parse(b"[1,23]").unwrap();
+    /// assert_eq!(tree[0].to_sexp(None), "(list (number) (number))");
+    /// ```
+    pub fn build_lexerless<TN: NodeImpl + 'static, F: Fn(&'static str) -> Option<TN>>(
+        &self,
+        node_value: F,
+    ) -> Result<LexerlessParser<TN>, ImplementationError> {
+        self.build_lexerless_with_log(node_value, Log::None)
+    }
+
+    /// Build a [LexerlessParser] same as [build_lexerless](Grammar::build_lexerless)
+    /// where the productions of the rules are logged with the level of the `log`.
+    pub fn build_lexerless_with_log<TN: NodeImpl + 'static, F: Fn(&'static str) -> Option<TN>>(
+        &self,
+        node_value: F,
+        log: Log<()>,
+    ) -> Result<LexerlessParser<TN>, ImplementationError> {
+        let root = ProductionLoader::new(self, node_value, log, |expr| match expr {
+            GrammarExpr::Literal(value) => {
+                Ok(Rc::new(ConstantField::new(value, None)) as Production<TN, i8>)
+            }
+            GrammarExpr::Pattern(regex) => match RegexField::new(regex, None) {
+                Ok(field) => Ok(Rc::new(field) as Production<TN, i8>),
+                Err(err) => Err(ImplementationError::new("InvalidPattern".into(), err)),
+            },
+            GrammarExpr::Token(name) => Err(ImplementationError::new(
+                "UndefinedToken".into(),
+                format!(
+                    "Token {:?} requires a tokenizer. Use 'build_parser' to build the lexer rules.",
+                    name
+                ),
+            )),
+            expr => Err(unsupported(expr)),
+        })
+        .load()?;
+        LexerlessParser::new(root)
+    }
+
+    /// Build a [DefaultParser] from the lexer rules and the rules of the grammar where the first rule is the root production.
+    ///
+    /// Each lexer rule creates a [TokenName] from its literals and regular expressions in the order of the lexer rules,
+    /// where the adjacent literals are matched as [Punctuations] so that the longest literal wins.
+    /// The references of the lexer rules and the literals of the rules are built into [TokenField] of the corresponding tokens,
+    /// where a literal should be the only pattern of its lexer rule so that the token always matches the literal.
+    /// ### Arguments
+    /// * `node_value` - Node value of a rule name. The rule creates a node in the [AST](crate::ASTNode) if the value is available.
+    ///
+    /// The rule and the token names are leaked to create the productions, therefore a grammar is expected to be built once for the program.
+    /// ## Example
+    /// ```
+    /// use lang_pt::grammar::{Grammar, NodeName};
+    ///
+    /// let grammar = Grammar::from_dsl(r#"
+    ///     list = "[" Number ("," Number)* "]" EOF ;
+    ///     token OpenBracket = "[" ;
+    ///     token CloseBracket = "]" ;
+    ///     token Comma = "," ;
+    ///     token Number = /[0-9]+/ ;
+    ///     skip Space = /\s+/ ;
+    /// "#).unwrap();
+    /// let parser = grammar
+    ///     .build_parser(|name| (name == "list").then_some(NodeName(name)))
+    ///     .unwrap();
+    /// let tree = parser.parse(b"[1, 23]").unwrap();
+    /// assert_eq!(tree[0].to_sexp(None), "(list)");
+    /// ```
+    pub fn build_parser<TN: NodeImpl + 'static, F: Fn(&'static str) -> Option<TN>>(
+        &self,
+        node_value: F,
+    ) -> Result<DefaultParser<TN, TokenName>, ImplementationError> {
+        self.build_parser_with_log(node_value, Log::None)
+    }
+
+    /// Build a [DefaultParser] same as [build_parser](Grammar::build_parser)
+    /// where the lexemes and the productions of the rules are logged with the level of the `log`.
+    pub fn build_parser_with_log<TN: NodeImpl + 'static, F: Fn(&'static str) -> Option<TN>>(
+        &self,
+        node_value: F,
+        log: Log<()>,
+    ) -> Result<DefaultParser<TN, TokenName>, ImplementationError> {
+        let mut tokens: HashMap<&str, TokenName> = HashMap::new();
+        let mut literals: HashMap<&str, TokenName> = HashMap::new();
+        let mut lexemes: Vec<Rc<dyn ILexeme<Token = TokenName, State = u8>>> = Vec::new();
+        let mut punctuations: Vec<(&str, TokenName)> = Vec::new();
+
+        let flush = |punctuations: &mut Vec<(&str, TokenName)>,
+                     lexemes: &mut Vec<Rc<dyn ILexeme<Token = TokenName, State = u8>>>|
+         -> Result<(), ImplementationError> {
+            if !punctuations.is_empty() {
+                let names: Vec<&str> = punctuations.iter().map(|(_, token)| token.name).collect();
+                let label = leak(&names.join(" | "));
+                let lexeme = Punctuations::new(std::mem::take(punctuations))
+                    .map_err(|err| ImplementationError::new("InvalidLiteral".into(), err))?;
+                if log != Log::None {
+                    lexeme.set_log(log.with_label(label)).unwrap();
+                }
+                lexemes.push(Rc::new(lexeme));
+            }
+            Ok(())
+        };
+
+        for rule in &self.lexer_rules {
+            let token = TokenName {
+                name: leak(&rule.token),
+                is_structural: rule.is_structural,
+            };
+            tokens.insert(&rule.token, token);
+            for pattern in &rule.patterns {
+                match pattern.unconditional() {
+                    LexerPattern::Literal(value) => {
+                        literals.entry(value).or_insert(token);
+                        punctuations.push((value, token));
+                    }
+                    LexerPattern::Regex(regex) => {
+                        flush(&mut punctuations, &mut lexemes)?;
+                        let lexeme = Pattern::new(token, regex).map_err(|err| {
+                            ImplementationError::new("InvalidPattern".into(), err)
+                        })?;
+                        if log != Log::None {
+                            lexeme.set_log(log.with_label(token.name)).unwrap();
+                        }
+                        lexemes.push(Rc::new(lexeme));
+                    }
+                    LexerPattern::Opaque(description) => {
+                        return Err(ImplementationError::new(
+                            "UnsupportedPattern".into(),
+                            format!(
+                            "Lexer pattern {:?} of the token {:?} can not be built into a lexeme.",
+                            description, rule.token
+                        ),
+                        ))
+                    }
+                    LexerPattern::Conditional(_) => unreachable!(),
+                }
+            }
+        }
+        flush(&mut punctuations, &mut lexemes)?;
+
+        let root = ProductionLoader::new(self, node_value, log, |expr| match expr {
+            GrammarExpr::Token(name) => match tokens.get(name.as_str()) {
+                Some(token) => Ok(Rc::new(TokenField::new(*token, None)) as Production<TN, TokenName>),
+                None => Err(ImplementationError::new(
+                    "UndefinedToken".into(),
+                    format!("Lexer rule of the token {:?} is not defined.", name),
+                )),
+            },
+            GrammarExpr::Literal(value) => match literals.get(value.as_str()) {
+                Some(token) if self.get_lexer_rule(token.name).unwrap().patterns.len() > 1 => {
+                    Err(ImplementationError::new(
+                        "AmbiguousLiteral".into(),
+                        format!(
+                            "Token {:?} of the literal {:?} matches other patterns as well. Define a lexer rule for the literal.",
+                            token.name, value
+                        ),
+                    ))
+                }
+                Some(token) => Ok(Rc::new(TokenField::new(*token, None)) as Production<TN, TokenName>),
+                None => Err(ImplementationError::new(
+                    "UndefinedToken".into(),
+                    format!("Lexer rule of the literal {:?} is not defined.", value),
+                )),
+            },
+            expr => Err(unsupported(expr)),
+        })
+        .load()?;
+        DefaultParser::new(Rc::new(Tokenizer::new(lexemes)), root)
+    }
+}
+
+/// The production of a rule which is created before building its symbols to resolve the recursive references.
+enum RuleProduction<TN: NodeImpl, TL: TokenImpl> {
+    Concat(Rc<Concat<TN, TL>>),
+    Union(Rc<Union<TN, TL>>),
+}
+
+struct ProductionLoader<'g, TN: NodeImpl, TL: TokenImpl, FN, FT> {
+    grammar: &'g Grammar,
+    node_value: FN,
+    log: Log<()>,
+    /// Build a terminal expression into a production.
+    terminal: FT,
+    rules: HashMap<&'g str, Production<TN, TL>>,
+    names: HashSet<String>,
+}
+
+impl<'g, TN, TL, FN, FT> ProductionLoader<'g, TN, TL, FN, FT>
+where
+    TN: NodeImpl + 'static,
+    TL: TokenImpl + 'static,
+    FN: Fn(&'static str) -> Option<TN>,
+    FT: Fn(&GrammarExpr) -> Result<Production<TN, TL>, ImplementationError>,
+{
+    fn new(grammar: &'g Grammar, node_value: FN, log: Log<()>, terminal: FT) -> Self {
+        Self {
+            grammar,
+            node_value,
+            log,
+            terminal,
+            rules: HashMap::new(),
+            names: grammar.rules.iter().map(|rule| rule.name.clone()).collect(),
+        }
+    }
+
+    fn load(mut self) -> Result<Production<TN, TL>, ImplementationError> {
+        let grammar = self.grammar;
+        let mut productions = Vec::with_capacity(grammar.rules.len());
+        for rule in &grammar.rules {
+            let name = leak(&rule.name);
+            let (production, rule_production): (Production<TN, TL>, _) = match &rule.expr {
+                GrammarExpr::Choice(_) => {
+                    let union = Rc::new(Union::init(name));
+                    if self.log != Log::None {
+                        union.set_log(self.log.with_label(name)).unwrap();
+                    }
+                    (union.clone(), RuleProduction::Union(union))
+                }
+                _ => {
+                    let concat = Rc::new(Concat::init(name));
+                    if self.log != Log::None {
+                        concat.set_log(self.log.with_label(name)).unwrap();
+                    }
+                    (concat.clone(), RuleProduction::Concat(concat))
+                }
+            };
+            let production = match (self.node_value)(name) {
+                Some(value) => Rc::new(Node::new(&production, value)),
+                None => production,
+            };
+            self.rules.insert(&rule.name, production);
+            productions.push(rule_production);
+        }
+
+        for (rule, rule_production) in grammar.rules.iter().zip(productions) {
+            let result = match (rule_production, &rule.expr) {
+                (RuleProduction::Union(union), GrammarExpr::Choice(items)) => {
+                    union.set_symbols(self.build_all(&rule.name, items)?)
+                }
+                (RuleProduction::Concat(concat), GrammarExpr::Sequence(items)) => {
+                    concat.set_symbols(self.build_all(&rule.name, items)?)
+                }
+                (RuleProduction::Concat(concat), expr) => {
+                    concat.set_symbols(vec![self.build(&rule.name, expr)?])
+                }
+                (RuleProduction::Union(_), expr) => Err(format!("{:?} is not a choice.", expr)),
+            };
+            result.map_err(|err| ImplementationError::new("InitializationError".into(), err))?;
+        }
+
+        match grammar.rules.first() {
+            Some(rule) => Ok(self.rules[rule.name.as_str()].clone()),
+            None => Err(ImplementationError::new(
+                "InitializationError".into(),
+                "The grammar does not have any rule.".into(),
+            )),
+        }
+    }
+
+    /// Create an unique identifier for the nested sequence or choice of the rule.
+    fn anonymous_name(&mut self, rule: &str) -> &'static str {
+        let mut index = 1;
+        while self.names.contains(&format!("{}_{}", rule, index)) {
+            index += 1;
+        }
+        let name = format!("{}_{}", rule, index);
+        self.names.insert(name.clone());
+        leak(&name)
+    }
+
+    fn build_all(
+        &mut self,
+        rule: &str,
+        items: &[GrammarExpr],
+    ) -> Result<Vec<Production<TN, TL>>, ImplementationError> {
+        items.iter().map(|item| self.build(rule, item)).collect()
+    }
+
+    fn build(
+        &mut self,
+        rule: &str,
+        expr: &GrammarExpr,
+    ) -> Result<Production<TN, TL>, ImplementationError> {
+        Ok(match expr {
+            GrammarExpr::Empty => Rc::new(NullProd::new()),
+            GrammarExpr::Literal(value) if value.is_empty() => Rc::new(NullProd::new()),
+            GrammarExpr::EndOfFile => Rc::new(EOFProd::new(None)),
+            GrammarExpr::Rule(name) => match self.rules.get(name.as_str()) {
+                Some(production) => production.clone(),
+                None => {
+                    return Err(ImplementationError::new(
+                        "UndefinedRule".into(),
+                        format!("Rule {:?} is not defined.", name),
+                    ))
+                }
+            },
+            GrammarExpr::Sequence(items) => {
+                let symbols = self.build_all(rule, items)?;
+                Rc::new(Concat::new(self.anonymous_name(rule), symbols))
+            }
+            GrammarExpr::Choice(items) => {
+                let symbols = self.build_all(rule, items)?;
+                Rc::new(Union::new(self.anonymous_name(rule), symbols))
+            }
+            GrammarExpr::Repeat { expr, min, max } => {
+                let symbol = self.build(rule, expr)?;
+                match (min, max) {
+                    (0, Some(1)) => Rc::new(Nullable::new(&symbol)),
+                    (1, None) => Rc::new(List::new(&symbol)),
+                    (0, None) => Rc::new(Nullable::new(&Rc::new(List::new(&symbol)))),
+                    _ => Rc::new(Repeat::new(&symbol, *min, *max)),
+                }
+            }
+            GrammarExpr::Lookahead(expr) => Rc::new(Lookahead::new(&self.build(rule, expr)?, None)),
+            GrammarExpr::NotLookahead(expr) => {
+                Rc::new(NotLookahead::new(&self.build(rule, expr)?, None))
+            }
+            GrammarExpr::NonStructural(expr) => {
+                Rc::new(NonStructural::new(&self.build(rule, expr)?, false))
+            }
+            GrammarExpr::Node { value, expr } => {
+                let symbol = self.build(rule, expr)?;
+                match (self.node_value)(leak(value)) {
+                    Some(value) => Rc::new(Node::new(&symbol, value)),
+                    None => symbol,
+                }
+            }
+            expr => (self.terminal)(expr)?,
+        })
+    }
+}
@@ -0,0 +1,302 @@
+//! A module consists of a structured model of the grammar and the exporters of the model.
+//!
+//! The [Grammar] is built by walking the production graph from the root production with [Grammar::from_production],
+//! or from a parser with [DefaultParser::grammar_model](crate::DefaultParser::grammar_model)
+//! which also includes the lexer rules of the tokenizer.
+//! Each production describes itself as a [GrammarExpr] with [IProduction::grammar_expr](crate::IProduction::grammar_expr),
+//! where the named productions like [Concat](crate::production::Concat) and [Union](crate::production::Union)
+//! are added as the [rules](Rule) of the grammar and referred by their names.
+//!
+//! The model can be exported to
+//! * ISO EBNF with [to_iso_ebnf](Grammar::to_iso_ebnf).
+//! * W3C EBNF notation with [to_w3c_ebnf](Grammar::to_w3c_ebnf) to render railroad diagrams.
+//! * ANTLR4 grammar with [to_antlr](Grammar::to_antlr).
+//!
+//! The FIRST and FOLLOW sets of the rules, the conflicting and the shadowed alternatives of the choices
+//! and the unused lexer rules are reported by [analyze](Grammar::analyze).
+//!
+//! A grammar can also be loaded at runtime from a textual grammar definition with [Grammar::from_dsl],
+//! and built into a [LexerlessParser](crate::LexerlessParser) with [build_lexerless](Grammar::build_lexerless)
+//! or into a [DefaultParser](crate::DefaultParser) with [build_parser](Grammar::build_parser).
+//!
+//! The [SentenceGenerator] creates random valid sentences of the grammar within the depth and the size limits.
+//! The parsers check the generated sentences with [round_trip](crate::DefaultParser::round_trip)
+//! to catch the regressions of the grammar.
+//!
+//! # Example
+//! ```
+//! use lang_pt::examples::json::tokenized::json_grammar;
+//!
+//! let grammar = json_grammar().grammar_model();
+//! let antlr = grammar.to_antlr("JSON");
+//! assert!(antlr.starts_with("grammar JSON;"));
+//! assert!(antlr.contains("OpenBrace : '{' ;"));
+//! ```
+
+mod analysis;
+mod antlr;
+mod dsl;
+mod generator;
+mod iso_ebnf;
+mod loader;
+mod model;
+mod w3c_ebnf;
+
+use crate::{ImplementationError, ParseError};
+use std::collections::{BTreeSet, HashMap, HashSet};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// An expression of the right hand side of a grammar rule.
+pub enum GrammarExpr {
+    /// The empty string.
+    Empty,
+    /// The end of the input.
+    EndOfFile,
+    /// A reference to a [Rule] of the grammar.
+    Rule(String),
+    /// A token of the tokenizer which is defined by a [LexerRule].
+    Token(String),
+    /// A constant string.
+    Literal(String),
+    /// A regular expression which is matched with the input.
+    Pattern(String),
+    /// A set of inclusive character ranges obtained from a regular expression.
+    /// A negated set matches any character except the characters of the ranges.
+    CharSet {
+        ranges: Vec<(char, char)>,
+        negated: bool,
+    },
+    Sequence(Vec<GrammarExpr>),
+    /// Alternatives in the order of precedence.
+    Choice(Vec<GrammarExpr>),
+    /// Repetition of the expression with the minimum and the optional maximum count.
+    Repeat {
+        expr: Box<GrammarExpr>,
+        min: usize,
+        max: Option<usize>,
+    },
+    /// The expression should be matched at the position without consuming the input.
+    Lookahead(Box<GrammarExpr>),
+    /// The expression should not be matched at the position.
+    NotLookahead(Box<GrammarExpr>),
+    /// The expression is matched including the non structural tokens.
+    NonStructural(Box<GrammarExpr>),
+    /// The expression creates a node of the [AST](crate::ASTNode) with the value.
+    Node {
+        value: String,
+        expr: Box<GrammarExpr>,
+    },
+    /// A description of a production which can not be expressed in the model.
+    Opaque(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// A named production rule of the [Grammar].
+pub struct Rule {
+    pub name: String,
+    pub expr: GrammarExpr,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// A pattern of the lexeme to create a token.
+pub enum LexerPattern {
+    /// A constant string value of the token.
+    Literal(String),
+    /// A regular expression of the token.
+    Regex(String),
+    /// A description of a lexeme which can not be expressed as a literal or a regular expression.
+    Opaque(String),
+    /// A pattern of a wrapped lexeme which may not create the token even if the input matches the pattern.
+    Conditional(Box<LexerPattern>),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// A rule of the tokenizer to create a token from any of the patterns.
+pub struct LexerRule {
+    pub token: String,
+    /// Patterns in the order of the lexemes of the tokenizer.
+    pub patterns: Vec<LexerPattern>,
+    /// Whether the token is a [structural](crate::TokenImpl::is_structural) token.
+    pub is_structural: bool,
+}
+
+#[derive(Debug, Clone, Default)]
+/// A structured model of the grammar consisting of the production rules and the lexer rules.
+pub struct Grammar {
+    /// Production rules where the first rule is the start rule.
+    pub rules: Vec<Rule>,
+    /// Lexer rules in the order of the lexemes of the tokenizer.
+    pub lexer_rules: Vec<LexerRule>,
+    names: HashSet<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+/// A terminal symbol of the FIRST and the FOLLOW sets.
+pub enum Terminal {
+    /// A token of the tokenizer.
+    Token(String),
+    /// A constant string of a lexerless production.
+    Literal(String),
+    /// A regular expression of a lexerless production.
+    Pattern(String),
+    /// The end of the input.
+    EndOfFile,
+    /// A production which can not be expressed in the grammar model.
+    Opaque(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// The FIRST and the FOLLOW sets of a rule of the grammar.
+pub struct RuleSets {
+    pub name: String,
+    /// Whether the rule can be derived without consuming any input.
+    pub nullable: bool,
+    /// Terminals which can start the rule.
+    pub first: BTreeSet<Terminal>,
+    /// Terminals which can follow the rule.
+    pub follow: BTreeSet<Terminal>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// Two alternatives of a choice which can start with the same terminals,
+/// therefore the parser may backtrack from the first alternative to the second one.
+pub struct ChoiceConflict {
+    /// Name of the rule which contains the choice.
+    pub rule: String,
+    /// Indices of the conflicting alternatives in the choice.
+    pub alternatives: (usize, usize),
+    /// Terminals of the first alternative which overlap with the second alternative.
+    pub terminals: BTreeSet<Terminal>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// An alternative of a choice which can never match because an earlier alternative always wins.
+pub struct ShadowedAlternative {
+    /// Name of the rule which contains the choice.
+    pub rule: String,
+    /// Index of the alternative which never matches.
+    pub alternative: usize,
+    /// Index of the earlier alternative which always wins.
+    pub shadowed_by: usize,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+/// A report of the analysis of the [Grammar].
+///
+/// The report is displayed as a text where the alternatives are numbered from 1.
+pub struct GrammarAnalysis {
+    /// FIRST and FOLLOW sets of the rules in the order of the rules of the grammar.
+    pub rules: Vec<RuleSets>,
+    pub conflicts: Vec<ChoiceConflict>,
+    pub shadowed_alternatives: Vec<ShadowedAlternative>,
+    /// Rules which are not reachable from the start rule.
+    pub unreachable_rules: Vec<String>,
+    /// Structural tokens of the lexer rules which are not used by any rule.
+    pub unused_tokens: Vec<String>,
+}
+
+#[derive(Debug, Clone)]
+/// A generator of random sentences of the [Grammar].
+///
+/// The terminals are generated from
+/// * The constant strings of the [ConstantField](crate::production::ConstantField) and the [PunctuationsField](crate::production::PunctuationsField).
+/// * The samples of the tokens added with [add_token_samples](SentenceGenerator::add_token_samples),
+///   or the patterns of the lexer rules of the grammar for the tokens without any sample.
+/// * Random strings matching the regular expressions of the [RegexField](crate::production::RegexField)
+///   and the [Pattern](crate::lexeme::Pattern) lexemes.
+///
+/// Once the nesting of the rules exceeds the maximum depth or the sentence exceeds the maximum size,
+/// the generator picks the shortest derivations to finish the sentence.
+pub struct SentenceGenerator {
+    grammar: Grammar,
+    heights: HashMap<String, usize>,
+    samples: HashMap<String, Vec<String>>,
+    max_depth: usize,
+    max_size: usize,
+    separator: String,
+    state: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// A random sentence created by the [SentenceGenerator].
+pub struct Sentence {
+    pub text: String,
+    /// Names of the tokens in the order of the text, which is empty for a lexerless grammar.
+    pub tokens: Vec<String>,
+}
+
+#[derive(Debug)]
+/// A failure of the round trip check of a parser with the generated sentences.
+pub enum RoundTripError {
+    /// The sentence could not be generated from the grammar.
+    Generation(ImplementationError),
+    /// The parser failed to parse the generated sentence.
+    Parse {
+        sentence: String,
+        error: Box<ParseError>,
+    },
+    /// The tokenizer created different structural tokens from the generated ones.
+    TokenMismatch {
+        sentence: String,
+        expected: Vec<String>,
+        found: Vec<String>,
+    },
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+/// A string based node value named after a rule of a loaded grammar.
+pub struct NodeName(pub &'static str);
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+/// A string based token named after a lexer rule of a loaded grammar.
+pub struct TokenName {
+    pub name: &'static str,
+    pub is_structural: bool,
+}
+
+// Binding strength of the expressions to decide the parentheses in the exporters.
+const CHOICE: u8 = 0;
+const SEQUENCE: u8 = 1;
+const POSTFIX: u8 = 2;
+const ATOM: u8 = 3;
+
+/// Wrap the rendered expression with parentheses if it binds weaker than the required level.
+fn wrap((text, level): (String, u8), required: u8) -> String {
+    if level < required {
+        format!("({})", text)
+    } else {
+        text
+    }
+}
+
+/// Split the alternatives of a choice into the non empty alternatives and whether any alternative is empty,
+/// so that a choice with an empty alternative can be written as an optional choice.
+fn split_empty(items: &[GrammarExpr]) -> (GrammarExpr, bool) {
+    let has_empty = items.iter().any(|item| item == &GrammarExpr::Empty);
+    let rest = items
+        .iter()
+        .filter(|item| *item != &GrammarExpr::Empty)
+        .cloned()
+        .collect();
+    (GrammarExpr::choice(rest), has_empty)
+}
+
+/// Postfix operators of the copies of the expression to write a repetition
+/// in the notations which only support the `?`, `*` and `+` operators.
+fn postfix_repetition(min: usize, max: Option<usize>) -> Vec<&'static str> {
+    match max {
+        None if min == 0 => vec!["*"],
+        None => {
+            let mut copies = vec![""; min - 1];
+            copies.push("+");
+            copies
+        }
+        Some(max) => {
+            let mut copies = vec![""; min];
+            copies.extend(std::iter::repeat_n("?", max.saturating_sub(min)));
+            copies
+        }
+    }
+}
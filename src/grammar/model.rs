@@ -0,0 +1,254 @@
+use super::{Grammar, GrammarExpr, LexerPattern, LexerRule, Rule};
+use crate::{IProduction, TokenImpl};
+use regex_syntax::hir::{Class, Hir, HirKind, Look};
+use std::fmt::Debug;
+
+impl Grammar {
+    /// Create an empty grammar.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create the grammar model by walking the production graph from the root production.
+    ///
+    /// The rule of the root production is the start rule of the grammar.
+    /// A `start` rule is added if the root production is not a named production.
+    pub fn from_production<TP: IProduction + ?Sized>(root: &TP) -> Self {
+        let mut grammar = Self::new();
+        let expr = root.grammar_expr(&mut grammar);
+        if !matches!(expr, GrammarExpr::Rule(_)) {
+            let mut name = String::from("start");
+            while grammar.names.contains(&name) {
+                name.push('_');
+            }
+            grammar.names.insert(name.clone());
+            grammar.rules.insert(0, Rule { name, expr });
+        }
+        grammar
+    }
+
+    /// Add a named rule to the grammar and return the reference of the rule.
+    ///
+    /// The rule is added before building its expression so that the recursive references of the rule are not expanded.
+    /// The expression is not built again if the rule is already added to the grammar.
+    pub fn add_rule<F: FnOnce(&mut Self) -> GrammarExpr>(
+        &mut self,
+        name: &str,
+        build: F,
+    ) -> GrammarExpr {
+        if self.names.insert(name.to_string()) {
+            let index = self.rules.len();
+            self.rules.push(Rule {
+                name: name.to_string(),
+                expr: GrammarExpr::Empty,
+            });
+            self.rules[index].expr = build(self);
+        }
+        GrammarExpr::Rule(name.to_string())
+    }
+
+    /// Get the rule of the grammar by name.
+    pub fn get_rule(&self, name: &str) -> Option<&Rule> {
+        self.rules.iter().find(|rule| rule.name == name)
+    }
+
+    /// Get the lexer rule of the token by name.
+    pub fn get_lexer_rule(&self, token: &str) -> Option<&LexerRule> {
+        self.lexer_rules.iter().find(|rule| rule.token == token)
+    }
+}
+
+impl GrammarExpr {
+    /// Create a sequence of the expressions where the nested sequences are flattened and the empty expressions are removed.
+    pub fn sequence(exprs: Vec<GrammarExpr>) -> Self {
+        let mut items = Vec::with_capacity(exprs.len());
+        for expr in exprs {
+            match expr {
+                GrammarExpr::Empty => {}
+                GrammarExpr::Sequence(nested) => items.extend(nested),
+                expr => items.push(expr),
+            }
+        }
+        match items.len() {
+            0 => GrammarExpr::Empty,
+            1 => items.pop().unwrap(),
+            _ => GrammarExpr::Sequence(items),
+        }
+    }
+
+    /// Create a choice of the expressions where the nested choices are flattened.
+    pub fn choice(exprs: Vec<GrammarExpr>) -> Self {
+        let mut items = Vec::with_capacity(exprs.len());
+        for expr in exprs {
+            match expr {
+                GrammarExpr::Choice(nested) => items.extend(nested),
+                expr => items.push(expr),
+            }
+        }
+        match items.len() {
+            0 => GrammarExpr::Empty,
+            1 => items.pop().unwrap(),
+            _ => GrammarExpr::Choice(items),
+        }
+    }
+
+    /// Create a repetition of the expression.
+    pub fn repeat(expr: GrammarExpr, min: usize, max: Option<usize>) -> Self {
+        match (min, max) {
+            (1, Some(1)) => expr,
+            (_, Some(0)) => GrammarExpr::Empty,
+            _ => GrammarExpr::Repeat {
+                expr: Box::new(expr),
+                min,
+                max,
+            },
+        }
+    }
+
+    /// Create an optional expression.
+    pub fn optional(expr: GrammarExpr) -> Self {
+        Self::repeat(expr, 0, Some(1))
+    }
+
+    /// Wrap the expression with a [Node](GrammarExpr::Node) if the node value is available.
+    pub fn with_node<TN: Debug>(node_value: Option<&TN>, expr: GrammarExpr) -> Self {
+        match node_value {
+            Some(value) => GrammarExpr::Node {
+                value: format!("{:?}", value),
+                expr: Box::new(expr),
+            },
+            None => expr,
+        }
+    }
+
+    /// Translate a regular expression into an expression of the literals and the character sets.
+    ///
+    /// The start of the text anchor `^` is ignored because the patterns are always matched at the current position.
+    /// [None] is returned if the regular expression contains any lazy repetition or any other assertion.
+    pub fn from_regex(pattern: &str) -> Option<Self> {
+        let hir = regex_syntax::parse(pattern).ok()?;
+        Self::from_hir(&hir)
+    }
+
+    fn from_hir(hir: &Hir) -> Option<Self> {
+        match hir.kind() {
+            HirKind::Empty | HirKind::Look(Look::Start) => Some(GrammarExpr::Empty),
+            HirKind::Look(_) => None,
+            HirKind::Literal(literal) => Some(GrammarExpr::Literal(
+                String::from_utf8(literal.0.to_vec()).ok()?,
+            )),
+            HirKind::Class(Class::Unicode(class)) => {
+                let mut class = class.clone();
+                let ranges = class.ranges();
+                // Describe the classes like `[^"]` or `.` by the excluded characters.
+                let negated = ranges.first().is_some_and(|range| range.start() == '\0')
+                    && ranges.last().is_some_and(|range| range.end() == char::MAX);
+                if negated {
+                    class.negate();
+                }
+                Some(GrammarExpr::CharSet {
+                    ranges: class
+                        .iter()
+                        .map(|range| (range.start(), range.end()))
+                        .collect(),
+                    negated,
+                })
+            }
+            HirKind::Class(Class::Bytes(_)) => None,
+            HirKind::Repetition(repetition) => {
+                if !repetition.greedy {
+                    return None;
+                }
+                Some(Self::repeat(
+                    Self::from_hir(&repetition.sub)?,
+                    repetition.min as usize,
+                    repetition.max.map(|max| max as usize),
+                ))
+            }
+            HirKind::Capture(capture) => Self::from_hir(&capture.sub),
+            HirKind::Concat(hirs) => Some(Self::sequence(
+                hirs.iter().map(Self::from_hir).collect::<Option<_>>()?,
+            )),
+            HirKind::Alternation(hirs) => Some(Self::choice(
+                hirs.iter().map(Self::from_hir).collect::<Option<_>>()?,
+            )),
+        }
+    }
+}
+
+impl LexerPattern {
+    /// Wrap the pattern as a [Conditional](LexerPattern::Conditional) pattern.
+    pub fn conditional(self) -> Self {
+        match self {
+            LexerPattern::Conditional(_) => self,
+            pattern => LexerPattern::Conditional(Box::new(pattern)),
+        }
+    }
+
+    /// The pattern matched by the lexeme regardless of the condition.
+    pub fn unconditional(&self) -> &Self {
+        match self {
+            LexerPattern::Conditional(pattern) => pattern.unconditional(),
+            pattern => pattern,
+        }
+    }
+}
+
+impl LexerRule {
+    /// Expression of the rule as a choice of the patterns.
+    pub fn expr(&self) -> GrammarExpr {
+        GrammarExpr::choice(
+            self.patterns
+                .iter()
+                .map(|pattern| match pattern.unconditional() {
+                    LexerPattern::Literal(value) => GrammarExpr::Literal(value.clone()),
+                    LexerPattern::Regex(regex) => GrammarExpr::Pattern(regex.clone()),
+                    LexerPattern::Opaque(description) => GrammarExpr::Opaque(description.clone()),
+                    LexerPattern::Conditional(_) => unreachable!(),
+                })
+                .collect(),
+        )
+    }
+
+    /// The text of the token if every pattern of the rule is the same literal string
+    /// e.g. `,` of a `Comma` token of the [Punctuations](crate::lexeme::Punctuations).
+    pub fn literal_text(&self) -> Option<&str> {
+        let mut texts = self
+            .patterns
+            .iter()
+            .map(|pattern| match pattern.unconditional() {
+                LexerPattern::Literal(value) => Some(value.as_str()),
+                _ => None,
+            });
+        let text = texts.next()??;
+        texts.all(|t| t == Some(text)).then_some(text)
+    }
+
+    /// Group the patterns of the lexemes by the token in the order of the first occurrence of the token.
+    pub(crate) fn group<TL: TokenImpl>(
+        patterns: impl IntoIterator<Item = (TL, LexerPattern)>,
+    ) -> Vec<Self> {
+        let mut rules: Vec<(TL, LexerRule)> = Vec::new();
+        for (token, pattern) in patterns {
+            let index = match rules.iter().position(|(t, _)| *t == token) {
+                Some(index) => index,
+                None => {
+                    rules.push((
+                        token,
+                        LexerRule {
+                            token: format!("{:?}", token),
+                            patterns: Vec::new(),
+                            is_structural: token.is_structural(),
+                        },
+                    ));
+                    rules.len() - 1
+                }
+            };
+            let rule = &mut rules[index].1;
+            if !rule.patterns.contains(&pattern) {
+                rule.patterns.push(pattern);
+            }
+        }
+        rules.into_iter().map(|(_, rule)| rule).collect()
+    }
+}
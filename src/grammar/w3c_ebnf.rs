@@ -0,0 +1,128 @@
+use super::{
+    postfix_repetition, split_empty, wrap, Grammar, GrammarExpr, ATOM, CHOICE, POSTFIX, SEQUENCE,
+};
+use std::fmt::Write;
+
+impl Grammar {
+    /// Write the grammar in the EBNF notation of the W3C XML specification,
+    /// which can be used to render the railroad diagrams of the grammar.
+    ///
+    /// The regular expressions are translated into the strings and the character classes where possible.
+    /// The lookaheads and the untranslatable expressions are written as comments `/* ... */`,
+    /// and the end of the input is referred as `EOF`.
+    pub fn to_w3c_ebnf(&self) -> String {
+        let mut ebnf = String::new();
+        for rule in &self.rules {
+            writeln!(ebnf, "{} ::= {}", rule.name, render(&rule.expr).0).unwrap();
+        }
+        if !self.lexer_rules.is_empty() {
+            writeln!(ebnf).unwrap();
+        }
+        for rule in &self.lexer_rules {
+            write!(ebnf, "{} ::= {}", rule.token, render(&rule.expr()).0).unwrap();
+            if !rule.is_structural {
+                write!(ebnf, " /* non-structural */").unwrap();
+            }
+            writeln!(ebnf).unwrap();
+        }
+        ebnf
+    }
+}
+
+fn comment(text: &str) -> (String, u8) {
+    (format!("/* {} */", text.replace("*/", "*\\/")), ATOM)
+}
+
+fn string(value: &str) -> (String, u8) {
+    if !value.contains('"') {
+        (format!("\"{}\"", value), ATOM)
+    } else if !value.contains('\'') {
+        (format!("'{}'", value), ATOM)
+    } else {
+        // A string can not contain both of the quotes, so that the string is split at the double quotes.
+        let mut parts = Vec::new();
+        for (index, part) in value.split('"').enumerate() {
+            if index > 0 {
+                parts.push("'\"'".to_string());
+            }
+            if !part.is_empty() {
+                parts.push(format!("\"{}\"", part));
+            }
+        }
+        (parts.join(" "), SEQUENCE)
+    }
+}
+
+fn char_set(ranges: &[(char, char)], negated: bool) -> String {
+    let escape = |c: char| match c {
+        c if c.is_ascii_alphanumeric() => c.to_string(),
+        c => format!("#x{:X}", c as u32),
+    };
+    let mut set = String::from(if negated { "[^" } else { "[" });
+    for &(start, end) in ranges {
+        set.push_str(&escape(start));
+        if start != end {
+            set.push('-');
+            set.push_str(&escape(end));
+        }
+    }
+    set.push(']');
+    set
+}
+
+fn render(expr: &GrammarExpr) -> (String, u8) {
+    match expr {
+        GrammarExpr::Empty => ("()".into(), ATOM),
+        GrammarExpr::EndOfFile => ("EOF".into(), ATOM),
+        GrammarExpr::Rule(name) | GrammarExpr::Token(name) => (name.clone(), ATOM),
+        GrammarExpr::Literal(value) => match value.is_empty() {
+            true => render(&GrammarExpr::Empty),
+            false => string(value),
+        },
+        GrammarExpr::Pattern(regex) => match GrammarExpr::from_regex(regex) {
+            Some(expr) => render(&expr),
+            None => comment(&format!("/{}/", regex.replace('/', "\\/"))),
+        },
+        GrammarExpr::CharSet { ranges, negated } => (char_set(ranges, *negated), ATOM),
+        GrammarExpr::Sequence(items) => (
+            items
+                .iter()
+                .map(|item| wrap(render(item), SEQUENCE))
+                .collect::<Vec<_>>()
+                .join(" "),
+            SEQUENCE,
+        ),
+        GrammarExpr::Choice(items) => match split_empty(items) {
+            (rest, true) => render(&GrammarExpr::optional(rest)),
+            (_, false) => (
+                items
+                    .iter()
+                    .map(|item| wrap(render(item), CHOICE))
+                    .collect::<Vec<_>>()
+                    .join(" | "),
+                CHOICE,
+            ),
+        },
+        GrammarExpr::Repeat { expr, min, max } => {
+            let operand = wrap(render(expr), ATOM);
+            let copies = postfix_repetition(*min, *max);
+            let level = if copies.len() == 1 { POSTFIX } else { SEQUENCE };
+            (
+                copies
+                    .into_iter()
+                    .map(|operator| format!("{}{}", operand, operator))
+                    .collect::<Vec<_>>()
+                    .join(" "),
+                level,
+            )
+        }
+        GrammarExpr::Lookahead(expr) => comment(&format!("followed by {}", render(expr).0)),
+        GrammarExpr::NotLookahead(expr) => comment(&format!("not followed by {}", render(expr).0)),
+        GrammarExpr::NonStructural(expr) => (
+            format!("/* non-structural */ {}", wrap(render(expr), ATOM)),
+            ATOM,
+        ),
+        GrammarExpr::Node { expr, .. } => render(expr),
+        GrammarExpr::Opaque(description) => comment(description),
+    }
+}
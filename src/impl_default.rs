@@ -29,14 +29,23 @@ impl NodeImpl for u8 {
     fn null() -> Self {
         0
     }
+    fn is_null(&self) -> bool {
+        *self == 0
+    }
 }
 impl NodeImpl for usize {
     fn null() -> Self {
         0
     }
+    fn is_null(&self) -> bool {
+        *self == 0
+    }
 }
 impl NodeImpl for u16 {
     fn null() -> Self {
         0
     }
+    fn is_null(&self) -> bool {
+        *self == 0
+    }
 }
@@ -0,0 +1,203 @@
+use crate::{
+    ASTNode, FltrPtr, Lex, Memo, ParsedDocument, ParsedResult, ProductionError, ReusableMemo,
+    SuccessData, TextEdit, TokenImpl, TokenPtr,
+};
+use std::ops::Range;
+
+fn shift(value: usize, delta: isize) -> usize {
+    (value as isize + delta) as usize
+}
+
+impl TextEdit {
+    /// Create a new [TextEdit].
+    /// ## Arguments
+    /// * `range` - Range of the bytes of the previous text to be replaced.
+    /// * `text` - Replacement text.
+    pub fn new(range: Range<usize>, text: &[u8]) -> Self {
+        Self {
+            range,
+            text: text.to_vec(),
+        }
+    }
+
+    /// Difference of the length of the text after the edit.
+    pub fn shift(&self) -> isize {
+        self.text.len() as isize - self.range.len() as isize
+    }
+
+    /// Ending code point of the replaced text in the edited text.
+    pub fn new_end(&self) -> usize {
+        self.range.start + self.text.len()
+    }
+}
+
+impl<TN, TL> ParsedDocument<TN, TL> {
+    pub(crate) fn new(text: &[u8]) -> Self {
+        Self {
+            text: text.to_vec(),
+            tokens: Vec::new(),
+            checkpoints: Vec::new(),
+            tree: Vec::new(),
+            memo: Memo::new(),
+        }
+    }
+
+    /// Current text of the document.
+    pub fn text(&self) -> &[u8] {
+        &self.text
+    }
+
+    /// Tokens of the current text.
+    /// The tokens will be empty if the last tokenization has been failed.
+    pub fn tokens(&self) -> &Vec<Lex<TL>> {
+        &self.tokens
+    }
+
+    /// Parsed tree of the current text.
+    /// The tree will be empty if the last parsing has been failed.
+    pub fn tree(&self) -> &Vec<ASTNode<TN>> {
+        &self.tree
+    }
+
+    pub(crate) fn apply(&mut self, edit: &TextEdit) {
+        self.text
+            .splice(edit.range.clone(), edit.text.iter().copied());
+    }
+
+    pub(crate) fn update(
+        &mut self,
+        tokens: Vec<Lex<TL>>,
+        checkpoints: Vec<usize>,
+        tree: Vec<ASTNode<TN>>,
+        memo: Memo<FltrPtr, TN>,
+    ) {
+        self.tokens = tokens;
+        self.checkpoints = checkpoints;
+        self.tree = tree;
+        self.memo = memo;
+    }
+
+    pub(crate) fn take(&mut self) -> (Vec<Lex<TL>>, Vec<usize>, Memo<FltrPtr, TN>) {
+        self.tree.clear();
+        (
+            std::mem::take(&mut self.tokens),
+            std::mem::take(&mut self.checkpoints),
+            std::mem::take(&mut self.memo),
+        )
+    }
+}
+
+impl<TNode: Clone> ReusableMemo<TNode> {
+    /// Compare the previous and the new tokens to find the changed tokens,
+    /// and create the memo to reuse the parsed results outside of the changed tokens.
+    pub(crate) fn new<TL: TokenImpl>(
+        memo: Memo<FltrPtr, TNode>,
+        previous: &[Lex<TL>],
+        tokens: &[Lex<TL>],
+        byte_shift: isize,
+    ) -> Option<Self> {
+        if memo.is_empty() || previous.is_empty() || tokens.is_empty() {
+            return None;
+        }
+        let prefix_len = previous
+            .iter()
+            .zip(tokens)
+            .take_while(|(old, new)| old == new)
+            .count();
+
+        let max_suffix_len = std::cmp::min(previous.len(), tokens.len()) - prefix_len;
+        let suffix_len = previous
+            .iter()
+            .rev()
+            .zip(tokens.iter().rev())
+            .take(max_suffix_len)
+            .take_while(|(old, new)| {
+                old.token == new.token
+                    && shift(old.start, byte_shift) == new.start
+                    && shift(old.end, byte_shift) == new.end
+            })
+            .count();
+
+        let structural_len =
+            |stream: &[Lex<TL>]| stream.iter().filter(|l| l.token.is_structural()).count();
+
+        Some(Self {
+            memo,
+            changed_token: TokenPtr(prefix_len),
+            changed_point: previous.get(prefix_len).map_or(usize::MAX, |lex| lex.start),
+            suffix_token: TokenPtr(tokens.len() - suffix_len),
+            byte_shift,
+            token_shift: tokens.len() as isize - previous.len() as isize,
+            fltr_shift: structural_len(tokens) as isize - structural_len(previous) as isize,
+        })
+    }
+
+    /// Get the reusable result of the previous parse before the changed tokens.
+    pub(crate) fn find_before(
+        &self,
+        key: (crate::CacheKey, usize),
+        consumed_token: impl Fn(FltrPtr) -> TokenPtr,
+    ) -> Option<(ParsedResult<FltrPtr, TNode>, usize)> {
+        let (result, examined_point) = self.memo.get(&key)?;
+        if *examined_point > self.changed_point {
+            return None;
+        }
+        if let Ok(data) = result {
+            // The end of the parsed tree is the start of the next structural token.
+            if consumed_token(data.consumed_index) >= self.changed_token {
+                return None;
+            }
+        }
+        Some((result.clone(), *examined_point))
+    }
+
+    /// Get the reusable result of the previous parse after the changed tokens.
+    /// The pointer is the starting code point of the result in the edited text.
+    pub(crate) fn find_after(
+        &self,
+        key: crate::CacheKey,
+        pointer: usize,
+    ) -> Option<(ParsedResult<FltrPtr, TNode>, usize)> {
+        let (result, examined_point) = self.memo.get(&(key, shift(pointer, -self.byte_shift)))?;
+        let result = match result {
+            Ok(data) => Ok(SuccessData::new(
+                FltrPtr(shift(data.consumed_index.0, self.fltr_shift)),
+                data.children
+                    .iter()
+                    .map(|tree| self.shift_tree(tree))
+                    .collect(),
+            )),
+            Err(ProductionError::Unparsed) => Err(ProductionError::Unparsed),
+            Err(ProductionError::Validation(pointer, message)) => Err(ProductionError::Validation(
+                shift(*pointer, self.byte_shift),
+                message.clone(),
+            )),
+        };
+        Some((result, shift(*examined_point, self.byte_shift)))
+    }
+
+    /// Whether the parsed results starting after the token can be reused.
+    pub(crate) fn is_after(&self, token: TokenPtr) -> bool {
+        token >= self.suffix_token
+    }
+
+    /// Whether the parsed results starting before the code point can be reused.
+    pub(crate) fn is_before(&self, pointer: usize) -> bool {
+        pointer < self.changed_point
+    }
+
+    fn shift_tree(&self, tree: &ASTNode<TNode>) -> ASTNode<TNode> {
+        ASTNode::new(
+            tree.node.clone(),
+            shift(tree.start, self.byte_shift),
+            shift(tree.end, self.byte_shift),
+            tree.bound.map(|(start, end)| {
+                (
+                    TokenPtr(shift(start.0, self.token_shift)),
+                    TokenPtr(shift(end.0, self.token_shift)),
+                )
+            }),
+            tree.children.iter().map(|c| self.shift_tree(c)).collect(),
+        )
+    }
+}
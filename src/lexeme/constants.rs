@@ -1,9 +1,5 @@
 use super::{Constants, LexemeLogger};
-use crate::{
-    Code, Log,
-    ILexeme, Lex,
-};
-use once_cell::unsync::OnceCell;
+use crate::{grammar::LexerPattern, Code, ILexeme, Lex, Log, OnceCell, Shareable};
 use std::{fmt::Debug, marker::PhantomData};
 
 impl<TToken: Debug + Copy, TState> Constants<TToken, TState> {
@@ -37,8 +33,8 @@ impl<TToken, TState> LexemeLogger for Constants<TToken, TState> {
 
 impl<TToken, TState> ILexeme for Constants<TToken, TState>
 where
-    TToken: Copy + Debug + Eq + Ord,
-    TState: Copy + Debug + Eq + Ord,
+    TToken: Copy + Debug + Eq + Ord + Shareable,
+    TState: Copy + Debug + Eq + Ord + Shareable,
 {
     type Token = TToken;
 
@@ -51,7 +47,7 @@ where
         _: &Vec<Lex<Self::Token>>,
         _: &mut Vec<Self::State>,
     ) -> Option<Lex<Self::Token>> {
-        self.log_enter();
+        self.log_enter(pointer);
         let result = self.values.iter().rev().find_map(|(value, token)| {
             let lex = Lex::new(token.clone(), pointer, pointer + value.len());
             Some(lex)
@@ -66,4 +62,11 @@ where
             .map(|(s, t)| (*t, format!("{:?}", s)))
             .collect()
     }
+
+    fn lexer_patterns(&self) -> Vec<(TToken, LexerPattern)> {
+        self.values
+            .iter()
+            .map(|(s, t)| (*t, LexerPattern::Literal(s.clone())))
+            .collect()
+    }
 }
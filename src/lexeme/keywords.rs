@@ -0,0 +1,174 @@
+use super::{Keywords, LexemeLogger};
+use crate::{grammar::LexerPattern, Code, ILexeme, Lex, Log, OnceCell, Shareable};
+use regex::bytes::Regex;
+use std::{borrow::Cow, collections::HashMap, fmt::Debug, marker::PhantomData};
+
+impl<TToken: Debug + Copy, TState> Keywords<TToken, TState> {
+    /// Create a new [Keywords] lexeme utility with the default identifier character classes `[_$a-zA-Z]` and `[_$\w]`.
+    /// ## Arguments
+    /// * `identifier` - Token of the identifier which is not a keyword.
+    /// * `keywords` - A [Vec] of tuples of keywords, and their associated token.
+    pub fn new(identifier: TToken, keywords: Vec<(&str, TToken)>) -> Result<Self, String> {
+        let regexp = Self::identifier_regex(r"[_$a-zA-Z]", r"[_$\w]")?;
+        let keywords: Vec<(String, TToken)> =
+            keywords.iter().map(|(s, t)| (s.to_string(), *t)).collect();
+        Ok(Self {
+            identifier,
+            fields: Self::fields(&regexp, &keywords, true)?,
+            regexp,
+            keywords,
+            case_sensitive: true,
+            log: OnceCell::new(),
+            _state: PhantomData,
+        })
+    }
+
+    fn identifier_regex(start: &str, rest: &str) -> Result<Regex, String> {
+        let regexp = Regex::new(&format!("^(?:{})(?:{})*", start, rest)).map_err(|err| {
+            format!(
+                "Identifier character classes should be valid regex expressions.{:?}",
+                err
+            )
+        })?;
+        match regexp.is_match(b"") {
+            true => Err(format!(
+                "Identifier start class '{}' should not be nullable.",
+                start
+            )),
+            false => Ok(regexp),
+        }
+    }
+
+    /// Add the keywords which should be matched as a whole identifier.
+    pub fn extend_keywords(&mut self, keywords: Vec<(&str, TToken)>) -> Result<(), String> {
+        let mut extended = self.keywords.clone();
+        extended.extend(keywords.iter().map(|(s, t)| (s.to_string(), *t)));
+        self.fields = Self::fields(&self.regexp, &extended, self.case_sensitive)?;
+        self.keywords = extended;
+        Ok(())
+    }
+
+    /// Set the character classes of the first character and the rest characters of the identifier.
+    /// ## Arguments
+    /// * `start` - A regex expression to match the first character of the identifier e.g. `[_a-zA-Z]`.
+    /// * `rest` - A regex expression to match each of the rest characters of the identifier e.g. `[_a-zA-Z0-9]`.
+    pub fn set_identifier_class(&mut self, start: &str, rest: &str) -> Result<(), String> {
+        let regexp = Self::identifier_regex(start, rest)?;
+        self.fields = Self::fields(&regexp, &self.keywords, self.case_sensitive)?;
+        self.regexp = regexp;
+        Ok(())
+    }
+
+    /// Set whether the keywords are matched with the case of the identifier. The keywords are case-sensitive by default.
+    pub fn set_case_sensitive(&mut self, case_sensitive: bool) -> Result<(), String> {
+        self.fields = Self::fields(&self.regexp, &self.keywords, case_sensitive)?;
+        self.case_sensitive = case_sensitive;
+        Ok(())
+    }
+
+    /// Set a log label to debug the lexeme.
+    /// Based on the level of the [Log], the lexeme will debug the lexeme result.
+    pub fn set_log(&self, log: Log<&'static str>) -> Result<(), String> {
+        self.log
+            .set(log)
+            .map_err(|err| format!("Log label {} is already assigned.", err))
+    }
+
+    fn normalize(value: &[u8], case_sensitive: bool) -> Cow<'_, [u8]> {
+        match case_sensitive {
+            true => Cow::Borrowed(value),
+            false => Cow::Owned(String::from_utf8_lossy(value).to_lowercase().into_bytes()),
+        }
+    }
+
+    /// Map the normalized keywords to their tokens after validating that each keyword is an identifier.
+    fn fields(
+        regexp: &Regex,
+        keywords: &[(String, TToken)],
+        case_sensitive: bool,
+    ) -> Result<HashMap<Vec<u8>, TToken>, String> {
+        let mut fields = HashMap::new();
+        for (keyword, token) in keywords {
+            match regexp.find(keyword.as_bytes()) {
+                Some(m) if m.end() == keyword.len() => {}
+                _ => {
+                    return Err(format!(
+                        "Keyword {:?} is not matched by the identifier regex '{}'.",
+                        keyword,
+                        regexp.as_str()
+                    ))
+                }
+            }
+            let key = Self::normalize(keyword.as_bytes(), case_sensitive).into_owned();
+            if let Some(token) = fields.insert(key, *token) {
+                return Err(format!(
+                    "{:?} is already been used with token {:?}",
+                    keyword, token
+                ));
+            }
+        }
+        Ok(fields)
+    }
+}
+
+impl<TToken, TState> LexemeLogger for Keywords<TToken, TState> {
+    fn log_cell(&self) -> &OnceCell<crate::Log<&'static str>> {
+        &self.log
+    }
+}
+
+impl<TToken, TState> ILexeme for Keywords<TToken, TState>
+where
+    TToken: Copy + Debug + Eq + Ord + Shareable,
+    TState: Copy + Debug + Eq + Ord + Shareable,
+{
+    type Token = TToken;
+    type State = TState;
+
+    fn consume(
+        &self,
+        code: &Code,
+        pointer: usize,
+        _: &Vec<Lex<Self::Token>>,
+        _: &mut Vec<Self::State>,
+    ) -> Option<Lex<Self::Token>> {
+        self.log_enter(pointer);
+        let result = self.regexp.find(&code.value[pointer..]).map(|m| {
+            let end = pointer + m.end();
+            let token = self
+                .fields
+                .get(Self::normalize(&code.value[pointer..end], self.case_sensitive).as_ref())
+                .map_or(self.identifier, |token| *token);
+            Lex::new(token, pointer, end)
+        });
+        self.log_result(pointer, code, &result);
+        result
+    }
+
+    fn get_grammar_field(&self) -> Vec<(TToken, String)> {
+        let mut fields: Vec<(TToken, String)> = self
+            .keywords
+            .iter()
+            .map(|(s, t)| (*t, format!("{:?}", s)))
+            .collect();
+        fields.push((
+            self.identifier,
+            format!("/{}/", self.regexp.as_str().replace('/', "\\/")),
+        ));
+        fields
+    }
+
+    /// The keywords are written before the identifier pattern so that the keywords take precedence over the identifier.
+    fn lexer_patterns(&self) -> Vec<(TToken, LexerPattern)> {
+        let mut patterns: Vec<(TToken, LexerPattern)> = self
+            .keywords
+            .iter()
+            .map(|(s, t)| (*t, LexerPattern::Literal(s.clone())))
+            .collect();
+        patterns.push((
+            self.identifier,
+            LexerPattern::Regex(self.regexp.as_str().to_string()),
+        ));
+        patterns
+    }
+}
@@ -1,9 +1,5 @@
 use super::{LexemeLogger, Mapper, ThunkMapper};
-use crate::{
-    Code, Log,
-    ILexeme, Lex,
-};
-use once_cell::unsync::OnceCell;
+use crate::{grammar::LexerPattern, Code, ILexeme, Lex, Log, OnceCell, ParseError, Shareable};
 use std::collections::HashMap;
 
 impl<TS: ILexeme> Mapper<TS> {
@@ -61,16 +57,29 @@ impl<TLexer: ILexeme> ILexeme for Mapper<TLexer> {
         tokenized_stream: &Vec<Lex<Self::Token>>,
         info: &mut Vec<Self::State>,
     ) -> Option<Lex<Self::Token>> {
-        let result = self.lexeme.consume(code, pointer, tokenized_stream, info);
+        self.try_consume(code, pointer, tokenized_stream, info)
+            .unwrap_or(None)
+    }
+
+    fn try_consume(
+        &self,
+        code: &Code,
+        pointer: usize,
+        tokenized_stream: &Vec<Lex<Self::Token>>,
+        info: &mut Vec<Self::State>,
+    ) -> Result<Option<Lex<Self::Token>>, ParseError> {
+        let result = self
+            .lexeme
+            .try_consume(code, pointer, tokenized_stream, info)?;
         self.log_result(pointer, code, &result);
 
-        result.map(|mut lex_data| {
+        Ok(result.map(|mut lex_data| {
             let code_part = &code.value[lex_data.start..lex_data.end];
             if let Some(token) = self.fields.get(code_part) {
                 lex_data.token = *token;
             }
             lex_data
-        })
+        }))
     }
     fn get_grammar_field(&self) -> Vec<(<TLexer as ILexeme>::Token, String)> {
         let mut v: Vec<(TLexer::Token, String)> = self
@@ -86,6 +95,22 @@ impl<TLexer: ILexeme> ILexeme for Mapper<TLexer> {
         v.extend(self.lexeme.get_grammar_field().into_iter());
         v
     }
+
+    /// The mapped values are written before the patterns of the lexeme in the order of the values,
+    /// so that the mapped values take precedence over the patterns of the lexeme.
+    fn lexer_patterns(&self) -> Vec<(TLexer::Token, LexerPattern)> {
+        let mut fields: Vec<(&Vec<u8>, &TLexer::Token)> = self.fields.iter().collect();
+        fields.sort();
+        let mut v: Vec<(TLexer::Token, LexerPattern)> = fields
+            .into_iter()
+            .map(|(s, t)| {
+                let s = String::from_utf8_lossy(s).into_owned();
+                (*t, LexerPattern::Literal(s))
+            })
+            .collect();
+        v.extend(self.lexeme.lexer_patterns());
+        v
+    }
 }
 
 impl<TL: ILexeme, TF: Fn(&Lex<TL::Token>, &[u8], &Vec<Lex<TL::Token>>) -> Option<TL::Token>>
@@ -112,8 +137,10 @@ impl<TL: ILexeme, TF: Fn(&Lex<TL::Token>, &[u8], &Vec<Lex<TL::Token>>) -> Option
         &self.log
     }
 }
-impl<TL: ILexeme, TF: Fn(&Lex<TL::Token>, &[u8], &Vec<Lex<TL::Token>>) -> Option<TL::Token>> ILexeme
-    for ThunkMapper<TL, TF>
+impl<
+        TL: ILexeme,
+        TF: Fn(&Lex<TL::Token>, &[u8], &Vec<Lex<TL::Token>>) -> Option<TL::Token> + Shareable,
+    > ILexeme for ThunkMapper<TL, TF>
 {
     type Token = TL::Token;
     type State = TL::State;
@@ -125,9 +152,22 @@ impl<TL: ILexeme, TF: Fn(&Lex<TL::Token>, &[u8], &Vec<Lex<TL::Token>>) -> Option
         tokenized_stream: &Vec<Lex<Self::Token>>,
         info: &mut Vec<Self::State>,
     ) -> Option<Lex<Self::Token>> {
-        let result = self.lexeme.consume(code, pointer, tokenized_stream, info);
+        self.try_consume(code, pointer, tokenized_stream, info)
+            .unwrap_or(None)
+    }
+
+    fn try_consume(
+        &self,
+        code: &Code,
+        pointer: usize,
+        tokenized_stream: &Vec<Lex<Self::Token>>,
+        info: &mut Vec<Self::State>,
+    ) -> Result<Option<Lex<Self::Token>>, ParseError> {
+        let result = self
+            .lexeme
+            .try_consume(code, pointer, tokenized_stream, info)?;
         self.log_result(pointer, code, &result);
-        result.map(|mut lex| {
+        Ok(result.map(|mut lex| {
             match (self.thunk)(&lex, &code.value, tokenized_stream) {
                 Some(token) => {
                     lex.token = token;
@@ -135,10 +175,19 @@ impl<TL: ILexeme, TF: Fn(&Lex<TL::Token>, &[u8], &Vec<Lex<TL::Token>>) -> Option
                 None => {}
             };
             lex
-        })
+        }))
     }
 
     fn get_grammar_field(&self) -> Vec<(TL::Token, String)> {
         self.lexeme.get_grammar_field()
     }
+
+    /// The patterns are [conditional](LexerPattern::Conditional) as the thunk may change the token.
+    fn lexer_patterns(&self) -> Vec<(TL::Token, LexerPattern)> {
+        self.lexeme
+            .lexer_patterns()
+            .into_iter()
+            .map(|(token, pattern)| (token, pattern.conditional()))
+            .collect()
+    }
 }
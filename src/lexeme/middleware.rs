@@ -1,9 +1,5 @@
 use super::{LexemeLogger, Middleware};
-use crate::{
-    Code, Log,
-    ILexeme, Lex,
-};
-use once_cell::unsync::OnceCell;
+use crate::{grammar::LexerPattern, Code, ILexeme, Lex, Log, OnceCell, ParseError, Shareable};
 
 impl<TS: ILexeme, TMiddleware: Fn(&[u8], &Vec<Lex<TS::Token>>) -> bool>
     Middleware<TS, TMiddleware>
@@ -36,7 +32,7 @@ impl<TL: ILexeme, TMiddleware: Fn(&[u8], &Vec<Lex<TL::Token>>) -> bool> LexemeLo
         &self.log_label
     }
 }
-impl<TL: ILexeme, TMiddleware: Fn(&[u8], &Vec<Lex<TL::Token>>) -> bool> ILexeme
+impl<TL: ILexeme, TMiddleware: Fn(&[u8], &Vec<Lex<TL::Token>>) -> bool + Shareable> ILexeme
     for Middleware<TL, TMiddleware>
 {
     type Token = TL::Token;
@@ -49,19 +45,39 @@ impl<TL: ILexeme, TMiddleware: Fn(&[u8], &Vec<Lex<TL::Token>>) -> bool> ILexeme
         tokenized_stream: &Vec<Lex<Self::Token>>,
         info: &mut Vec<Self::State>,
     ) -> Option<Lex<Self::Token>> {
-        #[cfg(debug_assertions)]
-        self.log_enter();
+        self.try_consume(code, pointer, tokenized_stream, info)
+            .unwrap_or(None)
+    }
+
+    fn try_consume(
+        &self,
+        code: &Code,
+        pointer: usize,
+        tokenized_stream: &Vec<Lex<Self::Token>>,
+        info: &mut Vec<Self::State>,
+    ) -> Result<Option<Lex<Self::Token>>, ParseError> {
+        self.log_enter(pointer);
         if (self.middleware)(&code.value, tokenized_stream) {
-            let result = self.lexeme.consume(code, pointer, tokenized_stream, info);
-            #[cfg(debug_assertions)]
+            let result = self
+                .lexeme
+                .try_consume(code, pointer, tokenized_stream, info)?;
             self.log_result(pointer, code, &result);
-            result
+            Ok(result)
         } else {
-            None
+            Ok(None)
         }
     }
 
     fn get_grammar_field(&self) -> Vec<(TL::Token, String)> {
         self.lexeme.get_grammar_field()
     }
+
+    /// The patterns are [conditional](LexerPattern::Conditional) as the middleware may reject the input.
+    fn lexer_patterns(&self) -> Vec<(TL::Token, LexerPattern)> {
+        self.lexeme
+            .lexer_patterns()
+            .into_iter()
+            .map(|(token, pattern)| (token, pattern.conditional()))
+            .collect()
+    }
 }
@@ -1,5 +1,4 @@
-use crate::{Code, ILexeme, Lex, Log};
-use once_cell::unsync::OnceCell;
+use crate::{grammar::LexerPattern, Code, ILexeme, Lex, Log, OnceCell, ParseError, Shareable};
 use std::fmt::Debug;
 
 use super::{Action, LexemeLogger, StateMixin, ThunkStateMixin};
@@ -85,9 +84,22 @@ impl<TL: ILexeme> ILexeme for StateMixin<TL> {
         tokenized_stream: &Vec<Lex<Self::Token>>,
         info: &mut Vec<Self::State>,
     ) -> Option<Lex<Self::Token>> {
-        let result = self.lexeme.consume(code, pointer, tokenized_stream, info);
+        self.try_consume(code, pointer, tokenized_stream, info)
+            .unwrap_or(None)
+    }
+
+    fn try_consume(
+        &self,
+        code: &Code,
+        pointer: usize,
+        tokenized_stream: &Vec<Lex<Self::Token>>,
+        info: &mut Vec<Self::State>,
+    ) -> Result<Option<Lex<Self::Token>>, ParseError> {
+        let result = self
+            .lexeme
+            .try_consume(code, pointer, tokenized_stream, info)?;
         self.log_result(pointer, code, &result);
-        match result {
+        Ok(match result {
             Some(lexical_data) => {
                 match self
                     .actions
@@ -104,12 +116,21 @@ impl<TL: ILexeme> ILexeme for StateMixin<TL> {
                 }
             }
             None => None,
-        }
+        })
     }
 
     fn get_grammar_field(&self) -> Vec<(TL::Token, String)> {
         self.lexeme.get_grammar_field()
     }
+
+    /// The patterns are [conditional](LexerPattern::Conditional) as the state actions may discard the token.
+    fn lexer_patterns(&self) -> Vec<(TL::Token, LexerPattern)> {
+        self.lexeme
+            .lexer_patterns()
+            .into_iter()
+            .map(|(token, pattern)| (token, pattern.conditional()))
+            .collect()
+    }
 }
 
 impl<TL: ILexeme, TF: Fn(&Lex<TL::Token>, &[u8], &Vec<Lex<TL::Token>>) -> Action<TL::State>>
@@ -145,8 +166,10 @@ impl<TL: ILexeme, TF: Fn(&Lex<TL::Token>, &[u8], &Vec<Lex<TL::Token>>) -> Action
         &self.log
     }
 }
-impl<TL: ILexeme, TF: Fn(&Lex<TL::Token>, &[u8], &Vec<Lex<TL::Token>>) -> Action<TL::State>> ILexeme
-    for ThunkStateMixin<TL, TF>
+impl<
+        TL: ILexeme,
+        TF: Fn(&Lex<TL::Token>, &[u8], &Vec<Lex<TL::Token>>) -> Action<TL::State> + Shareable,
+    > ILexeme for ThunkStateMixin<TL, TF>
 {
     type Token = TL::Token;
     type State = TL::State;
@@ -158,22 +181,42 @@ impl<TL: ILexeme, TF: Fn(&Lex<TL::Token>, &[u8], &Vec<Lex<TL::Token>>) -> Action
         tokenized_stream: &Vec<Lex<Self::Token>>,
         state_stack: &mut Vec<Self::State>,
     ) -> Option<Lex<Self::Token>> {
+        self.try_consume(code, pointer, tokenized_stream, state_stack)
+            .unwrap_or(None)
+    }
+
+    fn try_consume(
+        &self,
+        code: &Code,
+        pointer: usize,
+        tokenized_stream: &Vec<Lex<Self::Token>>,
+        state_stack: &mut Vec<Self::State>,
+    ) -> Result<Option<Lex<Self::Token>>, ParseError> {
         // console_log!("Regex pointer {}", pointer);
         let result = self
             .lexeme
-            .consume(code, pointer, tokenized_stream, state_stack);
+            .try_consume(code, pointer, tokenized_stream, state_stack)?;
         self.log_result(pointer, code, &result);
-        match result {
+        Ok(match result {
             Some(lexical_data) => {
                 let action = (self.thunk_action)(&lexical_data, &code.value, tokenized_stream);
 
                 perform_state_action(lexical_data, action, state_stack, pointer, code)
             }
             None => None,
-        }
+        })
     }
 
     fn get_grammar_field(&self) -> Vec<(TL::Token, String)> {
         self.lexeme.get_grammar_field()
     }
+
+    /// The patterns are [conditional](LexerPattern::Conditional) as the state actions may discard the token.
+    fn lexer_patterns(&self) -> Vec<(TL::Token, LexerPattern)> {
+        self.lexeme
+            .lexer_patterns()
+            .into_iter()
+            .map(|(token, pattern)| (token, pattern.conditional()))
+            .collect()
+    }
 }
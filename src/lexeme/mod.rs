@@ -24,7 +24,7 @@
 //!     TokenImpl, Tokenizer,
 //! };
 //! use lang_pt::{ITokenization, Lex};
-//! use std::rc::Rc;
+//! use lang_pt::Rc;
 //!
 //! #[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy)]
 //! enum JSONToken {
@@ -110,56 +110,58 @@
 mod action;
 mod builder;
 mod constants;
+mod keywords;
 mod mapper;
 mod middleware;
 mod mixin;
+mod nested_comment;
 mod pattern;
 mod punctuation;
-use crate::{Code, FieldTree, ILexeme, Lex, Log};
-use once_cell::unsync::OnceCell;
+pub(crate) mod unicode;
+use crate::{trace, TraceEvent};
+use crate::{Code, FieldTree, ILexeme, Lex, Log, OnceCell};
 use regex::bytes::Regex;
-use std::{collections::HashMap, fmt::Debug, marker::PhantomData};
+use std::{borrow::Cow, collections::HashMap, fmt::Debug, marker::PhantomData};
 
 trait LexemeLogger {
     fn log_cell(&self) -> &OnceCell<Log<&'static str>>;
-    fn log_enter(&self) {
-        #[cfg(debug_assertions)]
-        if let Some(l) = self.log_cell().get() {
-            println!("Entering {}", l)
+
+    /// Label of the event of the level, when the [Log] label of the lexeme selects the event.
+    fn event_label(&self, level: Log<()>) -> Option<Cow<'static, str>> {
+        self.log_cell()
+            .get()
+            .filter(|log_label| log_label.order() >= level.order())
+            .map(|log_label| Cow::Borrowed(log_label.label()))
+    }
+
+    fn log_enter(&self, pointer: usize) {
+        if let Some(label) = self.event_label(Log::Default(())) {
+            trace::emit(TraceEvent::Enter { label, pointer })
         }
     }
 
-    fn log_result<T: Debug>(&self, _pointer: usize, _code: &Code, _result: &Option<Lex<T>>) {
-        #[cfg(debug_assertions)]
-        match _result {
-            Some(lex) => self.log_success(_code, lex),
-            None => self.log_failure(_pointer, _code),
+    fn log_result<T: Debug>(&self, pointer: usize, code: &Code, result: &Option<Lex<T>>) {
+        match result {
+            Some(lex) => self.log_success(code, lex),
+            None => self.log_failure(pointer, code),
         }
     }
-    fn log_success<T: Debug>(&self, _code: &Code, _lex: &Lex<T>) {
-        #[cfg(debug_assertions)]
-        if let Some(log_label) = self.log_cell().get() {
-            if log_label.order() >= Log::Success(()).order() {
-                println!(
-                    "Lexeme Success for {} : token: {:?} from {} to {}.",
-                    log_label,
-                    _lex.token,
-                    _code.obtain_position(_lex.start),
-                    _code.obtain_position(_lex.end)
-                )
-            }
+    fn log_success<T: Debug>(&self, _code: &Code, lex: &Lex<T>) {
+        if let Some(label) = self.event_label(Log::Success(())) {
+            trace::emit(TraceEvent::Token {
+                label,
+                token: format!("{:?}", lex.token),
+                range: lex.start..lex.end,
+            })
         }
     }
-    fn log_failure(&self, _pointer: usize, _code: &Code) {
-        #[cfg(debug_assertions)]
-        if let Some(log_label) = self.log_cell().get() {
-            if log_label.order() >= Log::Result(()).order() {
-                println!(
-                    "Lexeme error for {} : at {}",
-                    log_label,
-                    _code.obtain_position(_pointer)
-                )
-            }
+    fn log_failure(&self, pointer: usize, _code: &Code) {
+        if let Some(label) = self.event_label(Log::Result(())) {
+            trace::emit(TraceEvent::Failure {
+                label,
+                pointer,
+                message: None,
+            })
         }
     }
 }
@@ -184,7 +186,7 @@ pub enum Action<T> {
 /// # Example
 /// ```
 /// use lang_pt::{lexeme::Pattern, Code, ITokenization, Lex, TokenImpl, Tokenizer};
-/// use std::rc::Rc;
+/// use lang_pt::Rc;
 /// #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
 /// enum Token {
 ///     ID,
@@ -230,7 +232,7 @@ pub struct Pattern<TToken, TState = u8> {
 ///     Code,
 ///     ITokenization, Lex, TokenImpl, Tokenizer,
 /// };
-/// use std::rc::Rc;
+/// use lang_pt::Rc;
 ///
 /// #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 /// enum Token {
@@ -304,6 +306,127 @@ pub struct Constants<TToken, TState = u8> {
     _state: PhantomData<TState>,
 }
 
+/// A lexer utility to match keywords only when the whole identifier at the position is a keyword.
+///
+/// Unlike [Constants] and [Punctuations] which match the prefix of the input, [Keywords] first matches an identifier
+/// from the identifier character classes and then looks up the keyword table for the identifier.
+/// The identifier token is returned if the identifier is not a keyword.
+/// Thus, `interface` is tokenized as an identifier even if `in` is a keyword.
+/// The keywords can be matched case-insensitively with [set_case_sensitive](Keywords::set_case_sensitive).
+/// # Example
+/// ```
+/// use lang_pt::{
+///     lexeme::{Keywords, Pattern},
+///     Code,
+///     ITokenization, Lex, TokenImpl, Tokenizer,
+/// };
+/// use lang_pt::Rc;
+///
+/// #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+/// enum Token {
+///     ID,
+///     In,
+///     Interface,
+///     Space,
+///     EOF,
+/// }
+///
+/// impl TokenImpl for Token {
+///     fn eof() -> Self { Self::EOF }
+///     fn is_structural(&self) -> bool { *self != Self::EOF }
+/// }
+/// let mut keywords: Keywords<Token> =
+///     Keywords::new(Token::ID, vec![("in", Token::In), ("interface", Token::Interface)]).unwrap();
+/// let space = Pattern::new(Token::Space, r#"^\s+"#).unwrap();
+///
+/// keywords.set_identifier_class(r"[a-zA-Z]", r"\w").unwrap();
+/// keywords.set_case_sensitive(false).unwrap();
+///
+/// let tokenizer = Tokenizer::new(vec![Rc::new(keywords), Rc::new(space)]);
+/// let lex_stream = tokenizer.tokenize(&Code::from("in inner INTERFACE")).unwrap();
+///
+/// assert_eq!(
+///     lex_stream,
+///     vec![
+///         Lex { token: Token::In, start: 0, end: 2 },
+///         Lex { token: Token::Space, start: 2, end: 3 },
+///         Lex { token: Token::ID, start: 3, end: 8 },
+///         Lex { token: Token::Space, start: 8, end: 9 },
+///         Lex { token: Token::Interface, start: 9, end: 18 },
+///         Lex { token: Token::EOF, start: 18, end: 18 }
+///     ]
+/// );
+/// ```
+pub struct Keywords<TToken, TState = u8> {
+    identifier: TToken,
+    regexp: Regex,
+    keywords: Vec<(String, TToken)>,
+    fields: HashMap<Vec<u8>, TToken>,
+    case_sensitive: bool,
+    log: OnceCell<Log<&'static str>>,
+    _state: PhantomData<TState>,
+}
+
+/// A lexer utility to match the block comments which can be nested like `/* /* */ */` of Rust, Swift and OCaml.
+///
+/// The lexeme tracks the nesting depth of the opening and the closing delimiters and creates one token for the whole comment.
+/// Alternatively, the delimiters and the text between them can be created as separate tokens with
+/// [set_delimiter_tokens](NestedComment::set_delimiter_tokens).
+/// An unterminated comment stops the tokenization with an error at the position of the opening delimiter.
+/// # Example
+/// ```
+/// use lang_pt::{
+///     lexeme::{NestedComment, Pattern, Punctuations},
+///     Code,
+///     ITokenization, Lex, TokenImpl, Tokenizer,
+/// };
+/// use lang_pt::Rc;
+///
+/// #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+/// enum Token {
+///     ID,
+///     Comment,
+///     Div,
+///     Mul,
+///     EOF,
+/// }
+///
+/// impl TokenImpl for Token {
+///     fn eof() -> Self { Self::EOF }
+///     fn is_structural(&self) -> bool { *self != Self::Comment }
+/// }
+/// let comment: NestedComment<Token> = NestedComment::new(Token::Comment, "/*", "*/").unwrap();
+/// let identifier = Pattern::new(Token::ID, r#"^[_$a-zA-Z][_$\w]*"#).unwrap();
+/// let punctuations = Punctuations::new(vec![("/", Token::Div), ("*", Token::Mul)]).unwrap();
+///
+/// let tokenizer = Tokenizer::new(vec![
+///     Rc::new(comment),
+///     Rc::new(identifier),
+///     Rc::new(punctuations),
+/// ]);
+/// let lex_stream = tokenizer.tokenize(&Code::from("a/*b/*c*/*/*d")).unwrap();
+/// assert_eq!(
+///     lex_stream,
+///     vec![
+///         Lex { token: Token::ID, start: 0, end: 1 },
+///         Lex { token: Token::Comment, start: 1, end: 11 },
+///         Lex { token: Token::Mul, start: 11, end: 12 },
+///         Lex { token: Token::ID, start: 12, end: 13 },
+///         Lex { token: Token::EOF, start: 13, end: 13 }
+///     ]
+/// );
+///
+/// let err = tokenizer.tokenize(&Code::from("a/*b/*c*/")).unwrap_err();
+/// assert_eq!(err.span, 1..3);
+/// ```
+pub struct NestedComment<TToken, TState = u8> {
+    token: TToken,
+    open: String,
+    close: String,
+    delimiters: Option<(TToken, TToken, TState)>,
+    log: OnceCell<Log<&'static str>>,
+}
+
 /// A lexical utility that transforms tokenized data based on the mapped string fields.
 ///
 /// The associated lexeme utility will first be matched with the input string.
@@ -317,7 +440,7 @@ pub struct Constants<TToken, TState = u8> {
 ///     Code,
 ///     ITokenization, Lex, TokenImpl, Tokenizer,
 /// };
-/// use std::rc::Rc;
+/// use lang_pt::Rc;
 ///
 /// #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 /// enum Token {
@@ -391,7 +514,8 @@ pub struct Mapper<TLexer: ILexeme> {
 ///     Code,
 ///     ITokenization, Lex, TokenImpl, Tokenizer,
 /// };
-/// use std::{io::BufRead, rc::Rc};
+/// use lang_pt::Rc;
+/// use std::io::BufRead;
 ///
 /// #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 /// enum Token {
@@ -453,7 +577,7 @@ pub struct ThunkMapper<
 ///     Code,
 ///     ITokenization, Lex, TokenImpl, Tokenizer,
 /// };
-/// use std::rc::Rc;
+/// use lang_pt::Rc;
 /// #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 /// enum Token {
 ///     RegexLiteral,
@@ -544,7 +668,7 @@ pub struct Middleware<TLexeme: ILexeme, TMiddleware: Fn(&[u8], &Vec<Lex<TLexeme:
 ///     Code,
 ///     CombinedTokenizer, ITokenization, Lex, TokenImpl,
 /// };
-/// use std::rc::Rc;
+/// use lang_pt::Rc;
 /// #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 /// enum Token {
 ///     ID,
@@ -657,7 +781,7 @@ pub struct StateMixin<TLexeme: ILexeme> {
 ///     Code,
 ///     ITokenization, Lex, TokenImpl, Tokenizer,
 /// };
-/// use std::rc::Rc;
+/// use lang_pt::Rc;
 /// #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 /// enum Token {
 ///     RegexLiteral,
@@ -0,0 +1,198 @@
+use super::{LexemeLogger, NestedComment};
+use crate::{
+    grammar::LexerPattern, Code, ILexeme, Lex, Log, OnceCell, ParseError, ParseErrorKind, Shareable,
+};
+use std::fmt::Debug;
+
+impl<TToken: Debug + Copy + Eq, TState> NestedComment<TToken, TState> {
+    /// Create a new [NestedComment] lexeme utility which creates one token for the whole comment.
+    /// ## Arguments
+    /// * `token` - Token of the comment.
+    /// * `open` - Opening delimiter of the comment e.g. `/*`.
+    /// * `close` - Closing delimiter of the comment e.g. `*/`.
+    pub fn new(token: TToken, open: &str, close: &str) -> Result<Self, String> {
+        if open.is_empty() || close.is_empty() {
+            return Err(String::from("Comment delimiters should not be empty."));
+        }
+        if open == close {
+            return Err(format!(
+                "Opening and closing delimiter '{}' should be different to nest the comments.",
+                open
+            ));
+        }
+        Ok(Self {
+            token,
+            open: open.to_string(),
+            close: close.to_string(),
+            delimiters: None,
+            log: OnceCell::new(),
+        })
+    }
+
+    /// Create separate tokens for the delimiters where the text between the delimiters is created with the comment token.
+    ///
+    /// The nesting depth is tracked with the state stack of the tokenizer,
+    /// where the lexeme pushes the state at each opening delimiter and pops it at each closing delimiter.
+    /// Add the lexeme to the state of a [CombinedTokenizer](crate::CombinedTokenizer) as well,
+    /// so that the text within the comment is not matched by the other lexemes.
+    /// ## Arguments
+    /// * `open` - Token of the opening delimiter.
+    /// * `close` - Token of the closing delimiter.
+    /// * `state` - State of the tokenizer within the comment.
+    pub fn set_delimiter_tokens(
+        &mut self,
+        open: TToken,
+        close: TToken,
+        state: TState,
+    ) -> Result<(), String> {
+        if open == close || open == self.token || close == self.token {
+            return Err(format!(
+                "Delimiter tokens {:?} and {:?} should be different from each other and the comment token {:?}.",
+                open, close, self.token
+            ));
+        }
+        self.delimiters = Some((open, close, state));
+        Ok(())
+    }
+
+    /// Set a log label to debug the lexeme.
+    /// Based on the level of the [Log], the lexeme will debug the lexeme result.
+    pub fn set_log(&self, log: Log<&'static str>) -> Result<(), String> {
+        self.log
+            .set(log)
+            .map_err(|err| format!("Log label {} is already assigned.", err))
+    }
+
+    /// Find the end of the comment which starts with the opening delimiter at the pointer.
+    fn comment_end(&self, value: &[u8], pointer: usize) -> Option<usize> {
+        if !value[pointer..].starts_with(self.open.as_bytes()) {
+            return None;
+        }
+        let mut depth: usize = 1;
+        let mut index = pointer + self.open.len();
+        while index < value.len() {
+            if value[index..].starts_with(self.open.as_bytes()) {
+                depth += 1;
+                index += self.open.len();
+            } else if value[index..].starts_with(self.close.as_bytes()) {
+                depth -= 1;
+                index += self.close.len();
+                if depth == 0 {
+                    return Some(index);
+                }
+            } else {
+                index += 1;
+            }
+        }
+        None
+    }
+
+    fn unterminated(&self, code: &Code, pointer: usize) -> ParseError {
+        ParseError::new(
+            ParseErrorKind::Tokenization,
+            code,
+            pointer..pointer + self.open.len(),
+            format!(
+                "Unterminated comment: '{}' is not closed with '{}'.",
+                self.open, self.close
+            ),
+        )
+    }
+}
+
+impl<TToken, TState> LexemeLogger for NestedComment<TToken, TState> {
+    fn log_cell(&self) -> &OnceCell<crate::Log<&'static str>> {
+        &self.log
+    }
+}
+
+impl<TToken, TState> ILexeme for NestedComment<TToken, TState>
+where
+    TToken: Copy + Debug + Eq + Ord + Shareable,
+    TState: Copy + Debug + Eq + Ord + Shareable,
+{
+    type Token = TToken;
+    type State = TState;
+
+    /// An unterminated comment is not consumed. Use [try_consume](ILexeme::try_consume) to obtain the error.
+    fn consume(
+        &self,
+        code: &Code,
+        pointer: usize,
+        tokenized_stream: &Vec<Lex<Self::Token>>,
+        state_stack: &mut Vec<Self::State>,
+    ) -> Option<Lex<Self::Token>> {
+        self.try_consume(code, pointer, tokenized_stream, state_stack)
+            .unwrap_or(None)
+    }
+
+    fn try_consume(
+        &self,
+        code: &Code,
+        pointer: usize,
+        _: &Vec<Lex<Self::Token>>,
+        state_stack: &mut Vec<Self::State>,
+    ) -> Result<Option<Lex<Self::Token>>, ParseError> {
+        self.log_enter(pointer);
+        let value = &code.value[pointer..];
+        let result = match self.delimiters {
+            // The delimiters are created separately within the comment which is known to be terminated.
+            Some((open, close, state)) if state_stack.last() == Some(&state) => {
+                if value.starts_with(self.open.as_bytes()) {
+                    state_stack.push(state);
+                    Some(Lex::new(open, pointer, pointer + self.open.len()))
+                } else if value.starts_with(self.close.as_bytes()) {
+                    state_stack.pop();
+                    Some(Lex::new(close, pointer, pointer + self.close.len()))
+                } else {
+                    let end = (1..value.len())
+                        .find(|index| {
+                            value[*index..].starts_with(self.open.as_bytes())
+                                || value[*index..].starts_with(self.close.as_bytes())
+                        })
+                        .unwrap_or(value.len());
+                    Some(Lex::new(self.token, pointer, pointer + end))
+                }
+            }
+            _ if !value.starts_with(self.open.as_bytes()) => None,
+            _ => {
+                let end = self
+                    .comment_end(code.value, pointer)
+                    .ok_or_else(|| self.unterminated(code, pointer))?;
+                Some(match self.delimiters {
+                    Some((open, _, state)) => {
+                        state_stack.push(state);
+                        Lex::new(open, pointer, pointer + self.open.len())
+                    }
+                    None => Lex::new(self.token, pointer, end),
+                })
+            }
+        };
+        self.log_result(pointer, code, &result);
+        Ok(result)
+    }
+
+    fn get_grammar_field(&self) -> Vec<(TToken, String)> {
+        let comment = format!("nested {:?} ... {:?}", self.open, self.close);
+        match self.delimiters {
+            Some((open, close, _)) => vec![
+                (open, format!("{:?}", self.open)),
+                (self.token, comment),
+                (close, format!("{:?}", self.close)),
+            ],
+            None => vec![(self.token, comment)],
+        }
+    }
+
+    fn lexer_patterns(&self) -> Vec<(TToken, LexerPattern)> {
+        let comment = format!("nested {:?} ... {:?}", self.open, self.close);
+        match self.delimiters {
+            Some((open, close, _)) => vec![
+                (open, LexerPattern::Literal(self.open.clone())),
+                (self.token, LexerPattern::Opaque(comment)),
+                (close, LexerPattern::Literal(self.close.clone())),
+            ],
+            None => vec![(self.token, LexerPattern::Opaque(comment))],
+        }
+    }
+}
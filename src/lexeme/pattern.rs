@@ -1,7 +1,6 @@
 use super::{LexemeLogger, Pattern};
-use crate::{Code, Log};
+use crate::{grammar::LexerPattern, Code, Log, OnceCell, Shareable};
 use crate::{ILexeme, Lex};
-use once_cell::unsync::OnceCell;
 use regex::bytes::Regex;
 use std::fmt::Debug;
 use std::marker::PhantomData;
@@ -50,8 +49,8 @@ impl<TToken, TState> LexemeLogger for Pattern<TToken, TState> {
 
 impl<TToken, TState> ILexeme for Pattern<TToken, TState>
 where
-    TToken: Copy + Debug + Eq + Ord,
-    TState: Copy + Debug + Eq + Ord,
+    TToken: Copy + Debug + Eq + Ord + Shareable,
+    TState: Copy + Debug + Eq + Ord + Shareable,
 {
     type Token = TToken;
     type State = TState;
@@ -63,7 +62,7 @@ where
         _: &Vec<Lex<Self::Token>>,
         _: &mut Vec<Self::State>,
     ) -> Option<Lex<Self::Token>> {
-        self.log_enter();
+        self.log_enter(pointer);
         if let Some(m) = self.regexp.find(&&code.value[pointer..]) {
             debug_assert_eq!(m.start(), 0);
             let consumed_ptr = pointer + m.end();
@@ -83,4 +82,19 @@ where
             format!("/{}/", self.regexp.as_str().replace('/', "\\/")),
         )]
     }
+
+    fn lexer_patterns(&self) -> Vec<(TToken, LexerPattern)> {
+        vec![(
+            self.token,
+            LexerPattern::Regex(self.regexp.as_str().to_string()),
+        )]
+    }
+
+    fn scan_pattern(&self) -> Option<(TToken, &str)> {
+        // A logged pattern consumes the input by itself to debug the result.
+        match self.log.get() {
+            Some(_) => None,
+            None => Some((self.token, self.regexp.as_str())),
+        }
+    }
 }
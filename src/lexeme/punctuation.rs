@@ -1,6 +1,5 @@
 use super::{LexemeLogger, Punctuations};
-use crate::{Code, FieldTree, ILexeme, Lex, Log};
-use once_cell::unsync::OnceCell;
+use crate::{grammar::LexerPattern, Code, FieldTree, ILexeme, Lex, Log, OnceCell, Shareable};
 use std::{fmt::Debug, marker::PhantomData};
 
 impl<TToken: Debug + Copy, TState> Punctuations<TToken, TState> {
@@ -54,8 +53,8 @@ impl<TToken, TState> LexemeLogger for Punctuations<TToken, TState> {
 
 impl<TToken, TState> ILexeme for Punctuations<TToken, TState>
 where
-    TToken: Copy + Debug + Eq + Ord,
-    TState: Copy + Debug + Eq + Ord,
+    TToken: Copy + Debug + Eq + Ord + Shareable,
+    TState: Copy + Debug + Eq + Ord + Shareable,
 {
     type Token = TToken;
 
@@ -68,7 +67,7 @@ where
         _: &Vec<Lex<Self::Token>>,
         _: &mut Vec<Self::State>,
     ) -> Option<Lex<Self::Token>> {
-        self.log_enter();
+        self.log_enter(pointer);
         match self.field_tree.find(&code.value[pointer..]) {
             Some((token, index)) => {
                 let lex = Lex::new(token, pointer, pointer + index);
@@ -88,4 +87,11 @@ where
             .map(|(s, t)| (*t, format!("{:?}", s)))
             .collect()
     }
+
+    fn lexer_patterns(&self) -> Vec<(TToken, LexerPattern)> {
+        self.punctuations
+            .iter()
+            .map(|(s, t)| (*t, LexerPattern::Literal(s.clone())))
+            .collect()
+    }
 }
@@ -0,0 +1,35 @@
+use super::Pattern;
+
+/// An identifier of the default identifier syntax of UAX #31 where `_` is also allowed at the start like Rust and Python.
+pub(crate) const XID_IDENTIFIER: &str = r"^[\p{XID_Start}_]\p{XID_Continue}*";
+/// A sequence of the characters of the Unicode `White_Space` property including the line terminators.
+pub(crate) const UNICODE_WHITESPACE: &str = r"^\p{White_Space}+";
+/// A sequence of the Unicode whitespace characters except the line terminators.
+pub(crate) const INLINE_WHITESPACE: &str = r"^[\p{White_Space}--[\r\n\x{2028}\x{2029}]]+";
+/// A line terminator of Javascript and Python which is one of CRLF, CR, LF, U+2028 and U+2029.
+pub(crate) const LINE_TERMINATOR: &str = r"^(?:\r\n|[\r\n\x{2028}\x{2029}])";
+
+impl<TToken, TState> Pattern<TToken, TState> {
+    /// Create a [Pattern] of the Unicode identifier which starts with a `XID_Start` character or `_`
+    /// followed by the `XID_Continue` characters according to [UAX #31](https://www.unicode.org/reports/tr31/).
+    pub fn xid_identifier(token: TToken) -> Self {
+        Self::new(token, XID_IDENTIFIER).unwrap()
+    }
+
+    /// Create a [Pattern] of a sequence of the Unicode `White_Space` characters including the line terminators.
+    pub fn unicode_whitespace(token: TToken) -> Self {
+        Self::new(token, UNICODE_WHITESPACE).unwrap()
+    }
+
+    /// Create a [Pattern] of a sequence of the Unicode `White_Space` characters except the line terminators
+    /// CR, LF, U+2028 and U+2029.
+    pub fn inline_whitespace(token: TToken) -> Self {
+        Self::new(token, INLINE_WHITESPACE).unwrap()
+    }
+
+    /// Create a [Pattern] of a single line terminator of Javascript and Python
+    /// where CRLF is matched as one terminator alongside CR, LF, U+2028 and U+2029.
+    pub fn line_terminator(token: TToken) -> Self {
+        Self::new(token, LINE_TERMINATOR).unwrap()
+    }
+}
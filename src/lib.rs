@@ -20,8 +20,8 @@
 //! This parsing tool is also equipped with utilities like [Lookahead](crate::production::Lookahead), [Validator](crate::production::Validator),
 //! and [NonStructural](crate::production::NonStructural) to support custom validation, precedence-based parsing, etc.
 //! This parsing library can be used to parse a wide range of languages which often require custom functionality to be injected into the grammar.
-//! Moreover, the library also includes production utilities like [SeparatedList](crate::production::SeparatedList), and [Suffixes](crate::production::Suffixes),
-//! to ease writing grammar for a language.
+//! Moreover, the library also includes production utilities like [SeparatedList](crate::production::SeparatedList), [Suffixes](crate::production::Suffixes),
+//! and [PrecedenceClimb](crate::production::PrecedenceClimb) to ease writing grammar for a language.
 //!
 //! # Example
 //!
@@ -35,7 +35,7 @@
 //!     production::{Concat, EOFProd, Node, SeparatedList, TokenField, TokenFieldSet, Union},
 //!     DefaultParser, NodeImpl, TokenImpl, Tokenizer,
 //! };
-//! use std::rc::Rc;
+//! use lang_pt::Rc;
 //!
 //! #[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy)]
 //! // JSON token
@@ -144,7 +144,7 @@
 //!     ],
 //! ));
 //!
-//! let json_object_item_node = Rc::new(Node::new(&json_object_item, Some(JSONNode::Item)));
+//! let json_object_item_node = Rc::new(Node::new(&json_object_item, JSONNode::Item));
 //! let json_object_item_list =
 //!     Rc::new(SeparatedList::new(&json_object_item_node, &hidden_comma, true).into_nullable());
 //! let json_array_item_list =
@@ -158,10 +158,10 @@
 //!             hidden_close_bracket.clone(),
 //!         ],
 //!     )
-//!     .into_node(Some(JSONNode::Array)),
+//!     .into_node(JSONNode::Array),
 //! );
 //!
-//! let json_object_node = Rc::new(Node::new(&json_object, Some(JSONNode::Object)));
+//! let json_object_node = Rc::new(Node::new(&json_object, JSONNode::Object));
 //!
 //! json_value_union
 //!     .set_symbols(vec![
@@ -180,7 +180,7 @@
 //!     .unwrap();
 //!
 //! let main = Rc::new(Concat::new("root", vec![json_value_union, eof]));
-//! let main_node = Rc::new(Node::new(&main, Some(JSONNode::Main)));
+//! let main_node = Rc::new(Node::new(&main, JSONNode::Main));
 //! let parser = DefaultParser::new(Rc::new(tokenizer), main_node).unwrap();
 //!
 //! ```
@@ -190,27 +190,78 @@
 mod ast_node;
 mod cache;
 mod code;
+mod cst;
 mod doc;
 mod error;
 pub mod examples;
 mod field_tree;
 mod filtered_stream;
+pub mod grammar;
 mod impl_default;
+mod incremental;
 mod lex;
+mod match_policy;
 pub mod lexeme;
 mod logger;
 mod parsing;
+mod pattern_scan;
 mod position;
 pub mod production;
+mod profile;
 mod success_data;
 mod tokenization;
+mod trace;
+mod typed_node;
+mod visitor;
 mod wrapper_index;
 
+#[cfg(feature = "sync")]
+use once_cell::sync::OnceCell;
+#[cfg(not(feature = "sync"))]
 use once_cell::unsync::OnceCell;
+use std::borrow::Cow;
 use std::collections::{HashMap, HashSet};
 use std::fmt::{Debug, Display, Write};
 use std::hash::Hash;
-use std::rc::Rc;
+
+#[cfg(feature = "derive")]
+pub use lang_pt_derive::TypedNode;
+#[cfg(feature = "profile")]
+pub use profile::with_profile;
+pub use trace::with_trace_sink;
+
+/// Reference counted pointer to share the [productions](IProduction), the [lexemes](ILexeme) and the [tokenizers](ITokenization).
+///
+/// The pointer is [std::rc::Rc] by default and [std::sync::Arc] when the `sync` feature is enabled.
+/// Build the grammars with this re-export instead of [std::rc::Rc] so that they compile with either pointer.
+#[cfg(not(feature = "sync"))]
+pub use std::rc::Rc;
+/// Reference counted pointer to share the [productions](IProduction), the [lexemes](ILexeme) and the [tokenizers](ITokenization).
+///
+/// The pointer is [std::rc::Rc] by default and [std::sync::Arc] when the `sync` feature is enabled.
+/// Build the grammars with this re-export instead of [std::rc::Rc] so that they compile with either pointer.
+#[cfg(feature = "sync")]
+pub use std::sync::Arc as Rc;
+
+/// A marker trait for the types which are shared by a parser.
+///
+/// When the `sync` feature is enabled, the trait requires [Send] and [Sync]
+/// so that a parser can be stored in a `static` and used from multiple threads.
+/// Otherwise, the trait is implemented for all types.
+#[cfg(feature = "sync")]
+pub trait Shareable: Send + Sync {}
+#[cfg(feature = "sync")]
+impl<T: Send + Sync + ?Sized> Shareable for T {}
+
+/// A marker trait for the types which are shared by a parser.
+///
+/// When the `sync` feature is enabled, the trait requires [Send] and [Sync]
+/// so that a parser can be stored in a `static` and used from multiple threads.
+/// Otherwise, the trait is implemented for all types.
+#[cfg(not(feature = "sync"))]
+pub trait Shareable {}
+#[cfg(not(feature = "sync"))]
+impl<T: ?Sized> Shareable for T {}
 
 /// A trait implementation to generate default tokens to assign token values to the associated [ASTNode].
 ///
@@ -220,7 +271,7 @@ use std::rc::Rc;
 ///
 /// The non structural tokens like whitespace, line break, in javascript language do not provide any grammatical meaning.
 /// Therefore these tokens can be omitted from the tokes stream to simplify the grammar and optimize the parser performance.
-pub trait TokenImpl: Copy + Debug + Eq + Hash + Ord {
+pub trait TokenImpl: Copy + Debug + Eq + Hash + Ord + Shareable {
     fn eof() -> Self;
     fn is_structural(&self) -> bool;
 }
@@ -229,16 +280,25 @@ pub trait TokenImpl: Copy + Debug + Eq + Hash + Ord {
 ///
 /// When the tokenizer or the parser encounter null production or end of file production during lexical analysis and parsing phase,
 /// self implementation will create and assign corresponding token in the token stream and [ASTNode].  
-pub trait NodeImpl: Debug + Clone {
+pub trait NodeImpl: Debug + Clone + Shareable {
     /// Default token placeholder for null production.
     fn null() -> Self;
+    /// Whether the node value is the [null](NodeImpl::null) placeholder.
+    ///
+    /// The [NodeCursor] skips the placeholder trees of the null productions with this method,
+    /// so that it should be implemented to convert the trees of the nullable productions into the [TypedNode] views.
+    fn is_null(&self) -> bool {
+        false
+    }
 }
 
 #[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 /// A wrapper to indicate the index of the tokenized data in the [TokenStream].
 pub struct TokenPtr(usize);
 
-#[derive(Clone)]
+#[derive(Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 /// Abstract Syntax tree (AST) of the parsed input.
 pub struct ASTNode<TNode> {
     pub node: TNode,
@@ -248,7 +308,125 @@ pub struct ASTNode<TNode> {
     pub children: Vec<ASTNode<TNode>>, // Children of the abstract syntax tree
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// An action returned by the callbacks of [Visitor] and [VisitorMut] to control the traversal.
+pub enum VisitAction {
+    /// Continue the traversal.
+    Continue,
+    /// Skip the children of the entered tree and continue with its next sibling.
+    /// The tree is still [left](Visitor::leave).
+    SkipChildren,
+    /// Stop the traversal.
+    Stop,
+}
+
+/// A visitor of the [ASTNode] which is walked in depth first order by [walk](ASTNode::walk).
+///
+/// The callbacks receive every tree of the walked [ASTNode],
+/// so that the visitor can match the [node](ASTNode::node) value of the tree to handle each kind of tree.
+/// The traversal does not recurse on the call stack, therefore deeply nested trees can be visited.
+pub trait Visitor<TNode> {
+    /// Called before visiting the children of the tree.
+    fn enter(&mut self, _tree: &ASTNode<TNode>) -> VisitAction {
+        VisitAction::Continue
+    }
+    /// Called after visiting the children of the tree.
+    fn leave(&mut self, _tree: &ASTNode<TNode>) -> VisitAction {
+        VisitAction::Continue
+    }
+}
+
+/// A visitor which can modify the [ASTNode] walked by [walk_mut](ASTNode::walk_mut).
+///
+/// The children of a tree are visited after the [enter](VisitorMut::enter) callback of the tree,
+/// therefore the children replaced in the callback are visited.
+pub trait VisitorMut<TNode> {
+    /// Called before visiting the children of the tree.
+    fn enter(&mut self, _tree: &mut ASTNode<TNode>) -> VisitAction {
+        VisitAction::Continue
+    }
+    /// Called after visiting the children of the tree.
+    fn leave(&mut self, _tree: &mut ASTNode<TNode>) -> VisitAction {
+        VisitAction::Continue
+    }
+}
+
+/// An iterator over the nested trees of an [ASTNode] in pre-order created by [pre_order](ASTNode::pre_order).
+pub struct PreOrder<'tree, TNode> {
+    stack: Vec<&'tree ASTNode<TNode>>,
+}
+
+/// An iterator over the nested trees of an [ASTNode] in post-order created by [post_order](ASTNode::post_order).
+pub struct PostOrder<'tree, TNode> {
+    stack: Vec<(&'tree ASTNode<TNode>, usize)>,
+}
+
+/// A typed view over an [ASTNode] whose children are checked against the declared shape of the view.
+///
+/// The trait is usually implemented with the `TypedNode` derive macro of the `lang_pt_derive` crate,
+/// which is re-exported with the `derive` feature.
+/// A derived struct annotated with `#[node(Node::Variant)]` matches the trees of the node value,
+/// and its fields consume the children of the tree in order.
+/// The fields of type [Option] and [Vec] consume zero or one and zero or more matching children respectively,
+/// and a field annotated with `#[tree]` refers to the tree itself.
+/// A derived enum of single field variants matches the trees of any of its variants.
+///
+/// ```
+/// use lang_pt::{ASTNode, NodeCursor, ShapeError, TypedNode};
+///
+/// #[derive(Debug, Clone, PartialEq)]
+/// enum Node {
+///     Number,
+///     List,
+///     Null,
+/// }
+/// impl lang_pt::NodeImpl for Node {
+///     fn null() -> Self {
+///         Node::Null
+///     }
+///     fn is_null(&self) -> bool {
+///         matches!(self, Node::Null)
+///     }
+/// }
+///
+/// struct Number<'a>(&'a ASTNode<Node>);
+/// impl<'a> TypedNode<'a, Node> for Number<'a> {
+///     fn is_match(tree: &ASTNode<Node>) -> bool {
+///         tree.node == Node::Number
+///     }
+///     fn from_tree(tree: &'a ASTNode<Node>) -> Result<Self, ShapeError> {
+///         if !Self::is_match(tree) {
+///             return Err(ShapeError::mismatch(tree, "Node::Number"));
+///         }
+///         NodeCursor::new(tree).finish()?;
+///         Ok(Number(tree))
+///     }
+/// }
+///
+/// let numbers = vec![ASTNode::leaf(Node::Number, 0, 1, None), ASTNode::leaf(Node::Number, 2, 3, None)];
+/// let list = ASTNode::new(Node::List, 0, 3, None, numbers);
+///
+/// let mut cursor = NodeCursor::new(&list);
+/// let items: Vec<Number> = cursor.many().unwrap();
+/// cursor.finish().unwrap();
+/// assert_eq!(items.len(), 2);
+/// assert!(Number::from_tree(&list).is_err());
+/// ```
+pub trait TypedNode<'tree, TNode>: Sized {
+    /// Whether the tree can be converted into the view.
+    fn is_match(tree: &ASTNode<TNode>) -> bool;
+    /// Convert the tree into the view after checking the shape of the tree.
+    fn from_tree(tree: &'tree ASTNode<TNode>) -> Result<Self, ShapeError>;
+}
+
+/// A cursor over the children of an [ASTNode] to build a [TypedNode] view.
+pub struct NodeCursor<'tree, TNode> {
+    tree: &'tree ASTNode<TNode>,
+    index: usize,
+}
+
 #[derive(Debug, Hash, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 /// Element of the tokenized data.
 pub struct Lex<TToken> {
     pub token: TToken,
@@ -257,9 +435,9 @@ pub struct Lex<TToken> {
 }
 
 /// An interface implemented by all lexeme utilities which are primary element of a tokenizer.   
-pub trait ILexeme {
-    type Token: Copy + Debug + Eq + Ord;
-    type State: Copy + Debug + Eq + Ord;
+pub trait ILexeme: Shareable {
+    type Token: Copy + Debug + Eq + Ord + Shareable;
+    type State: Copy + Debug + Eq + Ord + Shareable;
 
     /// Primary tokenization method implemented by each lexeme utility.
     /// The analyzer will call this method for all the lexeme at the incremental locations of the input to create tokens.
@@ -271,16 +449,87 @@ pub trait ILexeme {
         state_stack: &mut Vec<Self::State>,
     ) -> Option<Lex<Self::Token>>;
 
+    /// Consume the token like [consume](ILexeme::consume) or return an error which stops the tokenization,
+    /// e.g. an unterminated comment.
+    ///
+    /// The tokenizers call this method and the default implementation never fails.
+    fn try_consume(
+        &self,
+        code: &Code,
+        pointer: usize,
+        tokenized_stream: &Vec<Lex<Self::Token>>,
+        state_stack: &mut Vec<Self::State>,
+    ) -> Result<Option<Lex<Self::Token>>, ParseError> {
+        Ok(self.consume(code, pointer, tokenized_stream, state_stack))
+    }
+
     fn get_grammar_field(&self) -> Vec<(Self::Token, String)>;
+
+    /// Patterns of the tokens created by the lexeme to write the [lexer rules](grammar::LexerRule) of the grammar.
+    fn lexer_patterns(&self) -> Vec<(Self::Token, grammar::LexerPattern)> {
+        self.get_grammar_field()
+            .into_iter()
+            .map(|(token, field)| (token, grammar::LexerPattern::Opaque(field)))
+            .collect()
+    }
+
+    /// Regular expression of the token which can be merged into the multi-pattern scan of the tokenizer.
+    ///
+    /// The lexeme should create the token for the match of the regular expression at the pointer
+    /// without any other condition or change of the state stack.
+    fn scan_pattern(&self) -> Option<(Self::Token, &str)> {
+        None
+    }
 }
 
 /// A trait consists of [tokenize](ITokenization::tokenize) method which takes input utf-8 string bytes and produces a tokens stream.
 ///
 /// This interface implemented by [Tokenizer] and [CombinedTokenizer].
-pub trait ITokenization {
+pub trait ITokenization: Shareable {
     type Token;
     fn tokenize(&self, code: &Code) -> Result<Vec<Lex<Self::Token>>, ParseError>;
     fn build_grammar(&self) -> Result<String, std::fmt::Error>;
+
+    /// Lexer rules of the tokens created by the tokenizer.
+    fn lexer_rules(&self) -> Vec<grammar::LexerRule> {
+        Vec::new()
+    }
+
+    /// Lexemes which never create a token under the [MatchPolicy] of the tokenizer.
+    fn shadowed_lexemes(&self) -> Vec<ShadowedLexeme> {
+        Vec::new()
+    }
+
+    /// Tokenize the code and return the tokens with the checkpoints.
+    ///
+    /// A checkpoint is an index of the token stream where the tokenizer is at its default state with an empty state stack.
+    /// The tokenization can be safely restarted from a checkpoint with [retokenize](ITokenization::retokenize).
+    fn tokenize_with_checkpoints(
+        &self,
+        code: &Code,
+    ) -> Result<CheckpointedTokens<Self::Token>, ParseError> {
+        Ok((self.tokenize(code)?, vec![0]))
+    }
+
+    /// Re-tokenize the edited code by reusing the tokens of the previous code.
+    ///
+    /// The tokenization restarts from the last checkpoint before the edit and stops as soon as
+    /// the new tokens are synchronized with the previous tokens after the edit.
+    /// The default implementation tokenizes the whole code.
+    /// ## Arguments
+    /// * `code` - The edited code.
+    /// * `tokens` - Tokens of the code before the edit.
+    /// * `checkpoints` - Checkpoints of the previous tokens returned from [tokenize_with_checkpoints](ITokenization::tokenize_with_checkpoints).
+    /// * `edit` - The edit applied to the previous code.
+    fn retokenize(
+        &self,
+        code: &Code,
+        _tokens: &[Lex<Self::Token>],
+        _checkpoints: &[usize],
+        _edit: &TextEdit,
+    ) -> Result<CheckpointedTokens<Self::Token>, ParseError> {
+        self.tokenize_with_checkpoints(code)
+    }
 }
 
 /// Base tokenization structure for lexical analysis.
@@ -289,9 +538,12 @@ pub trait ITokenization {
 /// from this trait will split the input string into a token stream and return the result.
 /// The [Tokenizer] object consists of lexeme utilities.
 /// During tokenization, each lexeme utility will be called sequentially to get split tokens input.
+/// The [Pattern](crate::lexeme::Pattern) lexemes are merged into a single multi-pattern regex which is scanned once at each position.
 ///
 pub struct Tokenizer<TToken = i8, TState = u8> {
     lexers: Vec<Rc<dyn ILexeme<Token = TToken, State = TState>>>,
+    policy: MatchPolicy,
+    scan: OnceCell<pattern_scan::PatternScan<TToken>>,
 }
 
 /// A state-based tokenizer for lexical analysis.
@@ -310,6 +562,39 @@ pub struct CombinedTokenizer<TT = i8, TS = u8> {
     analyzers: Vec<(TS, Vec<Rc<dyn ILexeme<Token = TT, State = TS>>>)>,
     default_state: TS,
     debug: OnceCell<Log<&'static str>>,
+    policy: MatchPolicy,
+    scans: OnceCell<Vec<pattern_scan::PatternScan<TT>>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+/// A policy to select the token among the lexemes which match the input at the same location.
+///
+/// The policy is assigned to a [Tokenizer] or a [CombinedTokenizer] with its `set_match_policy` method.
+pub enum MatchPolicy {
+    /// The first lexeme that matches the input creates the token.
+    #[default]
+    FirstMatch,
+    /// The lexeme that consumes the longest input creates the token.
+    /// The earlier lexeme wins if multiple lexemes consume the same length of input.
+    LongestMatch,
+    /// The lexemes are split into groups at the given indices of the lexemes of each state.
+    /// The first group with any matching lexeme creates the longest token within the group.
+    PriorityGroups(Vec<usize>),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// A lexeme which never creates a token because the lexemes of a higher priority always match the same input.
+///
+/// The shadowed lexemes are reported from [shadowed_lexemes](ITokenization::shadowed_lexemes).
+pub struct ShadowedLexeme {
+    /// The tokenization state of the lexeme.
+    pub state: String,
+    /// Index of the lexeme in the lexemes of the state.
+    pub index: usize,
+    /// Patterns of the tokens created by the lexeme.
+    pub patterns: Vec<grammar::LexerPattern>,
+    /// Indices of the lexemes which shadow the lexeme.
+    pub shadowed_by: Vec<usize>,
 }
 
 #[derive(Debug)]
@@ -329,11 +614,60 @@ pub enum ProductionError {
     Validation(usize, String),
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 /// An error returned when the parser failed to parse the input because of the language syntax error.
+///
+/// The error can be printed as a source snippet with [render](ParseError::render).
 pub struct ParseError {
+    pub kind: ParseErrorKind,
+    /// Starting code point of the error i.e. same as the start of the [span](ParseError::span).
     pub pointer: usize,
+    /// Range of the code points of the input where the error occurred.
+    pub span: std::ops::Range<usize>,
+    /// Line and column position at the start of the span.
+    pub start: Position,
+    /// Line and column position at the end of the span.
+    pub end: Position,
     pub message: String,
+    /// Terminals which have been tried at the failed position.
+    pub expected: Vec<Expected>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// The reason of a [ParseError].
+pub enum ParseErrorKind {
+    /// The parser could not consume the token at the failed position.
+    UnexpectedToken,
+    /// The parser reached the end of the input before completing the derivation.
+    UnexpectedEOF,
+    /// A [Validation](crate::ProductionError::Validation) error returned by a production.
+    Validation,
+    /// The tokenizer could not split the input into tokens.
+    Tokenization,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// An error returned when an [AST](crate::ASTNode) does not match the shape of a [TypedNode] view.
+pub struct ShapeError {
+    /// Starting code point of the mismatched tree.
+    pub pointer: usize,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// A terminal which was expected by the parser at the failed position of the input.
+pub enum Expected {
+    /// A token of the tokenized input which is not created from a single literal text.
+    Token(String),
+    /// A constant string field or the literal text of an expected token.
+    Literal(String),
+    /// A regular expression field or its rule name.
+    Pattern(String),
+    /// The end of the input.
+    EndOfFile,
 }
 
 #[derive(Debug, Clone)]
@@ -362,14 +696,113 @@ pub struct CacheKey(usize);
 /// A result returned from [Production](IProduction) when it try to [consume][IProduction::advance_token_ptr] inputs.
 pub type ParsedResult<I, TToken> = Result<SuccessData<I, TToken>, ProductionError>;
 
+/// Tokens along with the indices of the tokens where the tokenization can be restarted.
+pub type CheckpointedTokens<TToken> = (Vec<Lex<TToken>>, Vec<usize>);
+
+/// Parsed results saved at the code points along with the furthest code point examined to obtain the result.
+type Memo<TP, TToken> = HashMap<(CacheKey, usize), (ParsedResult<TP, TToken>, usize)>;
+
+/// Seed parsed results of the left recursive productions along with the errors recovered to obtain the seeds.
+type Seeds<TP, TToken> = HashMap<(CacheKey, usize), (ParsedResult<TP, TToken>, Vec<ParseError>)>;
+
 /// An object structure to store maximum successful parse position and parsed result for Packrat parsing technique.   
 pub struct Cache<TP, TToken> {
-    parsed_result_cache: HashMap<(CacheKey, usize), ParsedResult<TP, TToken>>,
+    parsed_result_cache: Memo<TP, TToken>,
     max_parsed_point: usize,
+    /// Furthest code point examined by the productions, which is kept when the max parsed point is restored.
+    examined_point: usize,
+    recovered_errors: Option<Vec<ParseError>>,
+    expected: Vec<Expected>,
+    expected_point: usize,
+    reusable: Option<Box<ReusableMemo<TToken>>>,
+    /// Code points of the growing left recursive productions
+    /// along with the keys of the results and the seeds saved at the code points.
+    growing_seeds: Vec<(usize, Vec<CacheKey>, Vec<CacheKey>)>,
+    /// The seeds are saved separately so that the keys of the left recursive productions
+    /// do not conflict with the keys of the [Cacheable](crate::production::Cacheable) productions.
+    seed_cache: Seeds<TP, TToken>,
+    /// Seeds of the left recursive productions which consume the non structural tokens.
+    token_seed_cache: Seeds<TokenPtr, TToken>,
+    /// Errors recovered to obtain the parsed results saved at the code points, which are added again when the results are reused.
+    recovered_memo: HashMap<(CacheKey, usize), Vec<ParseError>>,
+}
+
+/// Parsed results of the previous parse which are reused by the incremental parsing after a [TextEdit].
+struct ReusableMemo<TNode> {
+    memo: Memo<FltrPtr, TNode>,
+    /// Index of the first changed token.
+    changed_token: TokenPtr,
+    /// Starting code point of the first changed token.
+    changed_point: usize,
+    /// Index of the first unchanged token after the edit in the new token stream.
+    suffix_token: TokenPtr,
+    byte_shift: isize,
+    token_shift: isize,
+    fltr_shift: isize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// An edit of the text which replaces the bytes in the `range` with the `text`.
+///
+/// The edit is used to update a [ParsedDocument] with [reparse](DefaultParser::reparse).
+pub struct TextEdit {
+    pub range: std::ops::Range<usize>,
+    pub text: Vec<u8>,
+}
+
+/// Tokens and parsed tree of a text which can be updated after [TextEdit]s with [reparse](DefaultParser::reparse).
+///
+/// The document keeps the parsed results of the [Cacheable](crate::production::Cacheable) productions
+/// so that the unchanged parts of the text are not parsed again.
+pub struct ParsedDocument<TN, TL> {
+    text: Vec<u8>,
+    tokens: Vec<Lex<TL>>,
+    checkpoints: Vec<usize>,
+    tree: Vec<ASTNode<TN>>,
+    memo: Memo<FltrPtr, TN>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+/// A lossless concrete syntax tree (CST) of a text parsed by [parse_cst](DefaultParser::parse_cst).
+///
+/// Unlike the [AST](ASTNode), the tree contains all the structural tokens including the hidden ones,
+/// and the non structural tokens (trivia) like whitespace and comments are attached to the structural tokens.
+/// Therefore, the text can be reproduced byte for byte from the tree with [to_text](ConcreteSyntaxTree::to_text).
+pub struct ConcreteSyntaxTree<TN, TL> {
+    text: Vec<u8>,
+    children: Vec<CSTElement<TN, TL>>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+/// An element of the [ConcreteSyntaxTree].
+pub enum CSTElement<TN, TL> {
+    Node(CSTNode<TN, TL>),
+    Token(CSTToken<TL>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+/// A node of the [ConcreteSyntaxTree] created from an [ASTNode].
+pub struct CSTNode<TN, TL> {
+    pub node: TN,
+    /// Starting code point of the first token of the node excluding the trivia.
+    pub start: usize,
+    /// End code point of the last token of the node excluding the trivia.
+    pub end: usize,
+    pub children: Vec<CSTElement<TN, TL>>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+/// A structural token of the [ConcreteSyntaxTree] with the attached trivia.
+pub struct CSTToken<TL> {
+    pub lex: Lex<TL>,
+    /// Trivia before the token which are not trailing trivia of the previous token.
+    pub leading: Vec<Lex<TL>>,
+    /// Trivia after the token until the first trivia containing a line break.
+    pub trailing: Vec<Lex<TL>>,
 }
 
 /// A trait implemented by production utilities which are used to write the various production rule for writing the grammar.
-pub trait IProduction: Display {
+pub trait IProduction: Display + Shareable {
     type Node: NodeImpl;
     type Token: TokenImpl;
     /// Whether the production is nullable.
@@ -433,6 +866,13 @@ pub trait IProduction: Display {
         self.impl_grammar(&mut writer, &mut HashSet::new())?;
         Ok(writer)
     }
+
+    /// Describe the production as an expression of the [Grammar](grammar::Grammar) model.
+    ///
+    /// A named production adds its rule to the grammar and returns the reference of the rule.
+    fn grammar_expr(&self, _grammar: &mut grammar::Grammar) -> grammar::GrammarExpr {
+        grammar::GrammarExpr::Opaque(self.to_string())
+    }
 }
 
 /// A parser structure to construct a tokenized based parsing program.
@@ -457,6 +897,7 @@ struct FieldTree<T> {
 }
 
 #[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 /// The line and column information at code point.
 pub struct Position {
     pub line: usize,
@@ -466,7 +907,7 @@ pub struct Position {
 /// A wrapper for the input language to be parsed with lines information.
 pub struct Code<'c> {
     pub value: &'c [u8],
-    line_breaks: OnceCell<Vec<usize>>,
+    line_breaks: once_cell::unsync::OnceCell<Vec<usize>>,
 }
 
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
@@ -478,3 +919,107 @@ pub enum Log<T> {
     Result(T),
     Verbose(T),
 }
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// A structured event created by the lexeme, the production and the tokenizer utilities.
+///
+/// The pointers and the ranges are the byte positions of the input.
+/// The events are sent to the [TraceSink] installed by [with_trace_sink] in any build.
+/// A utility with a [Log] label creates the events selected by the level of the log.
+/// While a sink is installed, the productions and the tokenizers without a label create all of their events
+/// and the events are labeled with the identifier or the description of the utility.
+pub enum TraceEvent {
+    /// A production or a lexeme starts to parse the input at the pointer.
+    Enter { label: Cow<'static, str>, pointer: usize },
+    /// A production or a tokenizer successfully parsed the range of the input.
+    Success {
+        label: Cow<'static, str>,
+        range: std::ops::Range<usize>,
+    },
+    /// A lexeme created the token for the range of the input.
+    Token {
+        label: Cow<'static, str>,
+        token: String,
+        range: std::ops::Range<usize>,
+    },
+    /// A production, a lexeme or a tokenizer failed at the pointer with an optional validation message.
+    Failure {
+        label: Cow<'static, str>,
+        pointer: usize,
+        message: Option<String>,
+    },
+    /// A [Cacheable](crate::production::Cacheable) production reused the cached result at the pointer.
+    CacheHit {
+        label: Cow<'static, str>,
+        pointer: usize,
+        success: bool,
+    },
+    /// A [CombinedTokenizer] switched the tokenization state at the pointer.
+    StateSwitch {
+        label: Cow<'static, str>,
+        from: String,
+        to: String,
+        pointer: usize,
+    },
+}
+
+/// A receiver of the [TraceEvent] of the lexemes, the productions and the tokenizers.
+///
+/// The sink is installed with [with_trace_sink] or passed to
+/// [parse_with_trace](DefaultParser::parse_with_trace) and [tokenize_with_trace](DefaultParser::tokenize_with_trace).
+/// Any closure `FnMut(TraceEvent)` can be used as a sink.
+pub trait TraceSink {
+    fn receive(&mut self, event: TraceEvent);
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+/// A [TraceSink] which prints the events to the standard output.
+///
+/// The events are printed to the standard output when no other sink is installed.
+pub struct StdoutSink;
+
+#[derive(Debug, Clone, Default)]
+/// A [TraceSink] which records the events so that they can be inspected by the tests or the other tools.
+pub struct TraceRecorder {
+    pub events: Vec<TraceEvent>,
+}
+
+#[cfg(feature = "log")]
+#[derive(Debug, Clone, Copy, Default)]
+/// A [TraceSink] which forwards the events to the [log](https://docs.rs/log) crate with the `lang_pt` target.
+pub struct LogSink;
+
+#[cfg(feature = "tracing")]
+#[derive(Debug, Clone, Copy, Default)]
+/// A [TraceSink] which forwards the events to the [tracing](https://docs.rs/tracing) crate with the `lang_pt` target.
+pub struct TracingSink;
+
+#[derive(Debug, Clone, PartialEq)]
+/// Parsing statistics of a production which is labeled with the identifier of the non-terminal production or the terminal name.
+///
+/// Each production has its own profile even if the label is shared with other productions.
+///
+/// The time includes the time spent by the nested productions.
+pub struct ProductionProfile {
+    pub name: String,
+    /// Number of times the production was tried at any position of the input.
+    pub attempts: usize,
+    pub successes: usize,
+    /// Number of failed attempts after which the parser backtracked to another alternative.
+    pub failures: usize,
+    /// Total number of the structural tokens consumed by the successful attempts,
+    /// or the number of bytes for the [LexerlessParser].
+    pub consumed: usize,
+    pub time: std::time::Duration,
+    /// Number of results reused from the memory of the [Cacheable](crate::production::Cacheable) wrapper.
+    pub cache_hits: usize,
+    pub cache_misses: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+/// A report of the [ProductionProfile] of the productions which are parsed by `with_profile` of the `profile` feature.
+///
+/// The productions are sorted in the descending order of the time spent by default.
+pub struct ProfileReport {
+    pub productions: Vec<ProductionProfile>,
+}
@@ -23,3 +23,26 @@ impl<T> Log<T> {
         }
     }
 }
+
+impl Log<()> {
+    /// Create a log of the same level with the label.
+    pub fn with_label<T>(&self, label: T) -> Log<T> {
+        match self {
+            Log::None => Log::None,
+            Log::Default(_) => Log::Default(label),
+            Log::Success(_) => Log::Success(label),
+            Log::Result(_) => Log::Result(label),
+            Log::Verbose(_) => Log::Verbose(label),
+        }
+    }
+}
+
+impl Log<&'static str> {
+    /// Label of the log which is empty for [Log::None].
+    pub(crate) fn label(&self) -> &'static str {
+        match self {
+            Log::None => "",
+            Log::Default(s) | Log::Success(s) | Log::Result(s) | Log::Verbose(s) => s,
+        }
+    }
+}
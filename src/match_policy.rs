@@ -0,0 +1,237 @@
+use crate::grammar::LexerPattern;
+use crate::pattern_scan::PatternScan;
+use crate::{Code, ILexeme, Lex, MatchPolicy, ParseError, Rc, ShadowedLexeme, Shareable};
+use regex::bytes::Regex;
+use std::fmt::{Debug, Display, Formatter};
+use std::ops::Range;
+
+type Lexemes<TToken, TState> = [Rc<dyn ILexeme<Token = TToken, State = TState>>];
+
+/// Bounds of the token and the state of an [ILexeme].
+pub(crate) trait LexemeType: Copy + Debug + Eq + Ord + Shareable {}
+
+impl<T: Copy + Debug + Eq + Ord + Shareable> LexemeType for T {}
+
+impl MatchPolicy {
+    /// Ranges of the lexemes of each priority group in the order of the priority.
+    fn groups(&self, len: usize) -> Vec<Range<usize>> {
+        match self {
+            MatchPolicy::FirstMatch | MatchPolicy::LongestMatch => {
+                std::iter::once(0..len).collect()
+            }
+            MatchPolicy::PriorityGroups(starts) => {
+                let mut bounds: Vec<usize> = starts
+                    .iter()
+                    .copied()
+                    .filter(|start| *start > 0 && *start < len)
+                    .collect();
+                bounds.sort_unstable();
+                bounds.dedup();
+                bounds.push(len);
+                let mut start = 0;
+                bounds
+                    .into_iter()
+                    .map(|end| {
+                        let range = start..end;
+                        start = end;
+                        range
+                    })
+                    .collect()
+            }
+        }
+    }
+
+    /// Consume the token at the pointer with the lexeme selected by the policy.
+    ///
+    /// The merged [Pattern](crate::lexeme::Pattern) lexemes are matched with a single scan of the [PatternScan].
+    pub(crate) fn consume<TToken: LexemeType, TState: LexemeType>(
+        &self,
+        lexemes: &Lexemes<TToken, TState>,
+        scan: &PatternScan<TToken>,
+        code: &Code,
+        pointer: usize,
+        tokenized_stream: &Vec<Lex<TToken>>,
+        state_stack: &mut Vec<TState>,
+    ) -> Result<Option<Lex<TToken>>, ParseError> {
+        match self {
+            MatchPolicy::FirstMatch => {
+                let mut scanned = None;
+                for (index, lexeme) in lexemes.iter().enumerate() {
+                    if scan.is_merged(index) {
+                        let scanned = scanned.get_or_insert_with(|| scan.scan(code, pointer));
+                        if let Some(result) = scan.first_match(scanned, index, pointer) {
+                            if result.is_some() {
+                                return Ok(result);
+                            }
+                            continue;
+                        }
+                    }
+                    if let Some(lex) =
+                        lexeme.try_consume(code, pointer, tokenized_stream, state_stack)?
+                    {
+                        return Ok(Some(lex));
+                    }
+                }
+                Ok(None)
+            }
+            _ => {
+                let scanned = scan.scan(code, pointer);
+                for range in self.groups(lexemes.len()) {
+                    let lex = longest_match(
+                        lexemes,
+                        range.filter(|index| scan.may_match(&scanned, *index)),
+                        code,
+                        pointer,
+                        tokenized_stream,
+                        state_stack,
+                    )?;
+                    if lex.is_some() {
+                        return Ok(lex);
+                    }
+                }
+                Ok(None)
+            }
+        }
+    }
+
+    /// Find the lexemes whose patterns are always matched by the lexemes of higher priority.
+    ///
+    /// Only the literal and the regular expression [patterns](crate::ILexeme::lexer_patterns) are compared.
+    /// A lexeme with an opaque pattern is never reported,
+    /// and a [conditional](LexerPattern::Conditional) pattern never shadows the other lexemes.
+    pub(crate) fn shadowed_lexemes<TToken: LexemeType, TState: LexemeType>(
+        &self,
+        state: &dyn Debug,
+        lexemes: &Lexemes<TToken, TState>,
+    ) -> Vec<ShadowedLexeme> {
+        let patterns: Vec<Vec<LexerPattern>> = lexemes
+            .iter()
+            .map(|lexeme| {
+                lexeme
+                    .lexer_patterns()
+                    .into_iter()
+                    .map(|(_, pattern)| pattern)
+                    .collect()
+            })
+            .collect();
+        let groups = self.groups(lexemes.len());
+        let group_of = |index: usize| groups.iter().position(|g| g.contains(&index));
+
+        let mut shadowed = Vec::new();
+        for (index, lexeme_patterns) in patterns.iter().enumerate() {
+            if lexeme_patterns.is_empty() {
+                continue;
+            }
+            let mut shadowed_by: Vec<usize> = Vec::new();
+            for pattern in lexeme_patterns {
+                let shadowing = (0..index).find(|earlier| {
+                    // A lexeme of a higher priority group wins with any match like the first match policy.
+                    let longest = match self {
+                        MatchPolicy::FirstMatch => false,
+                        _ => group_of(*earlier) == group_of(index),
+                    };
+                    patterns[*earlier]
+                        .iter()
+                        .any(|earlier_pattern| shadows(earlier_pattern, pattern, longest))
+                });
+                match shadowing {
+                    Some(earlier) => shadowed_by.push(earlier),
+                    None => {
+                        shadowed_by.clear();
+                        break;
+                    }
+                }
+            }
+            if !shadowed_by.is_empty() {
+                shadowed_by.sort_unstable();
+                shadowed_by.dedup();
+                shadowed.push(ShadowedLexeme {
+                    state: format!("{:?}", state),
+                    index,
+                    patterns: lexeme_patterns.clone(),
+                    shadowed_by,
+                });
+            }
+        }
+        shadowed
+    }
+}
+
+/// Consume the token with the lexemes of the indices and select the longest one where the earlier lexeme wins the tie.
+///
+/// Each lexeme consumes the token with a copy of the state stack so that only the selected lexeme changes the state.
+fn longest_match<TToken: LexemeType, TState: LexemeType>(
+    lexemes: &Lexemes<TToken, TState>,
+    indices: impl Iterator<Item = usize>,
+    code: &Code,
+    pointer: usize,
+    tokenized_stream: &Vec<Lex<TToken>>,
+    state_stack: &mut Vec<TState>,
+) -> Result<Option<Lex<TToken>>, ParseError> {
+    let mut selected: Option<(Lex<TToken>, Vec<TState>)> = None;
+    for index in indices {
+        let mut stack = state_stack.clone();
+        if let Some(lex) =
+            lexemes[index].try_consume(code, pointer, tokenized_stream, &mut stack)?
+        {
+            let is_longer = match &selected {
+                Some((s, _)) => lex.end > s.end,
+                None => true,
+            };
+            if is_longer {
+                selected = Some((lex, stack));
+            }
+        }
+    }
+    Ok(selected.map(|(lex, stack)| {
+        *state_stack = stack;
+        lex
+    }))
+}
+
+/// Whether the earlier pattern always wins over the pattern at the input matched by the pattern.
+///
+/// A conditional earlier pattern may not create the token, so it never wins for certain.
+fn shadows(earlier: &LexerPattern, pattern: &LexerPattern, longest: bool) -> bool {
+    match (earlier, pattern.unconditional()) {
+        (LexerPattern::Literal(earlier), LexerPattern::Literal(literal)) => match longest {
+            true => earlier == literal,
+            false => !earlier.is_empty() && literal.starts_with(earlier.as_str()),
+        },
+        (LexerPattern::Regex(regex), LexerPattern::Literal(literal)) => {
+            match Regex::new(regex)
+                .ok()
+                .and_then(|r| r.find(literal.as_bytes()))
+            {
+                Some(m) if m.start() == 0 && m.end() > 0 => !longest || m.end() == literal.len(),
+                _ => false,
+            }
+        }
+        (LexerPattern::Regex(earlier), LexerPattern::Regex(regex)) => earlier == regex,
+        _ => false,
+    }
+}
+
+fn describe(pattern: &LexerPattern) -> String {
+    match pattern {
+        LexerPattern::Literal(s) => format!("{:?}", s),
+        LexerPattern::Regex(s) => format!("/{}/", s),
+        LexerPattern::Opaque(s) => s.clone(),
+        LexerPattern::Conditional(pattern) => format!("{} (conditional)", describe(pattern)),
+    }
+}
+
+impl Display for ShadowedLexeme {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let patterns: Vec<String> = self.patterns.iter().map(describe).collect();
+        let shadowed_by: Vec<String> = self.shadowed_by.iter().map(|i| i.to_string()).collect();
+        write!(
+            f,
+            "Lexeme {} ({}) of state {} never wins over the lexemes {}.",
+            self.index,
+            patterns.join(" | "),
+            self.state,
+            shadowed_by.join(", ")
+        )
+    }
+}
@@ -1,9 +1,14 @@
-use super::{Cache, DefaultParser, IProduction, ImplementationError, LexerlessParser, ParseError};
-use crate::{ASTNode, Code, FltrPtr, ITokenization, Lex, NodeImpl, TokenImpl, TokenStream};
-use std::{
-    collections::{HashMap, HashSet},
-    rc::Rc,
+use super::{
+    Cache, DefaultParser, Expected, IProduction, ImplementationError, LexerlessParser, ParseError,
 };
+use crate::{
+    grammar::{Grammar, GrammarAnalysis, RoundTripError, SentenceGenerator},
+    with_trace_sink, ASTNode, Code, ConcreteSyntaxTree, FltrPtr, ITokenization, Lex, NodeImpl,
+    ParsedDocument, Rc, ReusableMemo, ShadowedLexeme, TextEdit, TokenImpl, TokenStream, TraceSink,
+};
+#[cfg(feature = "profile")]
+use crate::{with_profile, ProfileReport};
+use std::collections::{HashMap, HashSet};
 
 impl<TN: NodeImpl, TL: TokenImpl> DefaultParser<TN, TL> {
     pub fn new(
@@ -25,12 +30,82 @@ impl<TN: NodeImpl, TL: TokenImpl> DefaultParser<TN, TL> {
         g.push_str(&self.tokenizer.build_grammar()?);
         Ok(g)
     }
+
+    /// Create the [Grammar] model of the productions and the lexer rules of the tokenizer.
+    pub fn grammar_model(&self) -> Grammar {
+        let mut grammar = Grammar::from_production(self.root.as_ref());
+        grammar.lexer_rules = self.tokenizer.lexer_rules();
+        grammar
+    }
+
+    /// Report the FIRST and FOLLOW sets of the rules, the conflicting and the shadowed alternatives
+    /// and the tokens of the tokenizer which are not used by the productions.
+    /// See [Grammar::analyze] for the details.
+    pub fn analyze_grammar(&self) -> GrammarAnalysis {
+        self.grammar_model().analyze()
+    }
+
+    /// Report the lexemes of the tokenizer which never create a token under its [MatchPolicy](crate::MatchPolicy).
+    pub fn shadowed_lexemes(&self) -> Vec<ShadowedLexeme> {
+        self.tokenizer.shadowed_lexemes()
+    }
+
+    /// Create a [SentenceGenerator] of the productions
+    /// where the tokens without any sample are generated from the lexer rules of the tokenizer.
+    pub fn sentence_generator(&self) -> Result<SentenceGenerator, ImplementationError> {
+        SentenceGenerator::new(self.grammar_model())
+    }
+
+    /// Parse the sentences created by the generator and compare the structural tokens of each sentence with the generated tokens.
+    ///
+    /// The first sentence which fails to be tokenized into the generated tokens or fails to parse is returned as an error.
+    /// ## Arguments
+    /// * `generator` - A generator of the sentences of the grammar of the parser.
+    /// * `count` - Number of the sentences to check.
+    pub fn round_trip(
+        &self,
+        generator: &mut SentenceGenerator,
+        count: usize,
+    ) -> Result<(), RoundTripError> {
+        for _ in 0..count {
+            let sentence = generator.generate().map_err(RoundTripError::Generation)?;
+            let parse_error = |error| RoundTripError::Parse {
+                sentence: sentence.text.clone(),
+                error: Box::new(error),
+            };
+            let found: Vec<String> = self
+                .tokenize(&Code::new(sentence.text.as_bytes()))
+                .map_err(parse_error)?
+                .iter()
+                .filter(|lex| lex.token.is_structural() && lex.token != TL::eof())
+                .map(|lex| format!("{:?}", lex.token))
+                .collect();
+            if found != sentence.tokens {
+                return Err(RoundTripError::TokenMismatch {
+                    sentence: sentence.text,
+                    expected: sentence.tokens,
+                    found,
+                });
+            }
+            self.parse(sentence.text.as_bytes()).map_err(parse_error)?;
+        }
+        Ok(())
+    }
 }
 
 impl<TN: NodeImpl, TL: TokenImpl> DefaultParser<TN, TL> {
     pub fn tokenize(&self, code: &Code) -> Result<Vec<Lex<TL>>, ParseError> {
         self.tokenizer.tokenize(code)
     }
+
+    /// Tokenize the code while the [TraceEvent](crate::TraceEvent) of the lexemes and the tokenizer are sent to the sink.
+    pub fn tokenize_with_trace(
+        &self,
+        code: &Code,
+        sink: &mut dyn TraceSink,
+    ) -> Result<Vec<Lex<TL>>, ParseError> {
+        with_trace_sink(sink, || self.tokenize(code))
+    }
     pub fn parse_stream<'lex>(
         &self,
         code: &Code,
@@ -44,7 +119,44 @@ impl<TN: NodeImpl, TL: TokenImpl> DefaultParser<TN, TL> {
             .advance_fltr_ptr(code, index, &filtered_stream, &mut cached_data)
         {
             Ok(sd) => Ok(sd.children),
-            Err(err) => Err(cached_data.create_error(code, &filtered_stream, err)),
+            Err(err) => {
+                Err(self.describe_expected(cached_data.create_error(code, &filtered_stream, err)))
+            }
+        }
+    }
+
+    /// Parse the token stream in the error recovery mode.
+    ///
+    /// Productions with a recovery point i.e. [Concat](crate::production::Concat::set_recovery),
+    /// [List](crate::production::List::set_recovery) and [SeparatedList](crate::production::SeparatedList::set_recovery)
+    /// will skip the tokens after a syntax error and add an error node into the tree.
+    /// The partial tree is returned with all the recovered syntax errors.
+    /// An [Err] result is returned only if the error can not be recovered by any production.
+    pub fn parse_stream_with_recovery<'lex>(
+        &self,
+        code: &Code,
+        filtered_stream: TokenStream<'lex, TL>,
+    ) -> Result<(Vec<ASTNode<TN>>, Vec<ParseError>), ParseError> {
+        let mut cached_data: Cache<FltrPtr, TN> = Cache::recovery_root();
+
+        let index = FltrPtr::default();
+        match self
+            .root
+            .advance_fltr_ptr(code, index, &filtered_stream, &mut cached_data)
+        {
+            Ok(sd) => {
+                let errors = cached_data.take_recovered_errors();
+                Ok((
+                    sd.children,
+                    errors
+                        .into_iter()
+                        .map(|err| self.describe_expected(err))
+                        .collect(),
+                ))
+            }
+            Err(err) => {
+                Err(self.describe_expected(cached_data.create_error(code, &filtered_stream, err)))
+            }
         }
     }
 
@@ -52,6 +164,39 @@ impl<TN: NodeImpl, TL: TokenImpl> DefaultParser<TN, TL> {
         self.root.validate(HashMap::new(), &mut HashSet::new())
     }
 
+    /// Replace the expected tokens of the error with the literal text of their lexemes
+    /// e.g. `,` instead of the token name `Comma`.
+    fn describe_expected(&self, mut err: ParseError) -> ParseError {
+        if !err.expected.iter().any(|e| matches!(e, Expected::Token(_))) {
+            return err;
+        }
+        let rules = self.tokenizer.lexer_rules();
+        for expected in &mut err.expected {
+            if let Expected::Token(token) = expected {
+                let text = rules
+                    .iter()
+                    .find(|rule| rule.token == *token)
+                    .and_then(|rule| rule.literal_text());
+                if let Some(text) = text {
+                    *expected = Expected::Literal(text.to_string());
+                }
+            }
+        }
+        err
+    }
+
+    /// Tokenize and parse the input in the error recovery mode.
+    /// See [parse_stream_with_recovery](DefaultParser::parse_stream_with_recovery).
+    pub fn parse_with_recovery(
+        &self,
+        text: &[u8],
+    ) -> Result<(Vec<ASTNode<TN>>, Vec<ParseError>), ParseError> {
+        let code = Code::new(text);
+        let lexical_stream = self.tokenize(&code)?;
+        let filtered_stream = TokenStream::from(&lexical_stream);
+        self.parse_stream_with_recovery(&code, filtered_stream)
+    }
+
     pub fn tokenize_n_parse(
         &self,
         text: &[u8],
@@ -69,6 +214,38 @@ impl<TN: NodeImpl, TL: TokenImpl> DefaultParser<TN, TL> {
         self.parse_stream(&code, filtered_stream)
     }
 
+    /// Tokenize and parse the input while the [TraceEvent](crate::TraceEvent) of the lexemes,
+    /// the productions and the tokenizer are sent to the sink.
+    ///
+    /// The productions and the tokenizer without a [Log](crate::Log) label are traced with the `trace` feature.
+    pub fn parse_with_trace(
+        &self,
+        text: &[u8],
+        sink: &mut dyn TraceSink,
+    ) -> Result<Vec<ASTNode<TN>>, ParseError> {
+        with_trace_sink(sink, || self.parse(text))
+    }
+
+    /// Tokenize and parse the input while the productions are profiled.
+    ///
+    /// The [ProfileReport] counts the attempts, the successes, the failures, the consumed tokens,
+    /// the time spent and the cache hits of each production to find the productions which backtrack the most.
+    /// The method is available with the `profile` feature.
+    #[cfg(feature = "profile")]
+    pub fn parse_with_profile(
+        &self,
+        text: &[u8],
+    ) -> (Result<Vec<ASTNode<TN>>, ParseError>, ProfileReport) {
+        with_profile(|| self.parse(text))
+    }
+
+    /// Tokenize and parse the text into a lossless [ConcreteSyntaxTree]
+    /// which keeps all the tokens of the text including the non structural tokens.
+    pub fn parse_cst(&self, text: &[u8]) -> Result<ConcreteSyntaxTree<TN, TL>, ParseError> {
+        let (lexical_stream, tree_list) = self.tokenize_n_parse(text)?;
+        Ok(ConcreteSyntaxTree::new(text, &tree_list, lexical_stream))
+    }
+
     pub fn add_debug_production<T: IProduction<Node = TN, Token = TL> + 'static>(
         &mut self,
         _id: &'static str,
@@ -79,6 +256,83 @@ impl<TN: NodeImpl, TL: TokenImpl> DefaultParser<TN, TL> {
     }
 }
 
+impl<TN: NodeImpl, TL: TokenImpl> DefaultParser<TN, TL> {
+    /// Tokenize and parse the text into a [ParsedDocument] which can be updated with [reparse](DefaultParser::reparse).
+    pub fn parse_incremental(&self, text: &[u8]) -> Result<ParsedDocument<TN, TL>, ParseError> {
+        let mut document = ParsedDocument::new(text);
+        self.parse_document(&mut document, None)?;
+        Ok(document)
+    }
+
+    /// Apply the edit to the document and parse the edited text.
+    ///
+    /// Only the changed region of the text is tokenized again,
+    /// and the parsed results of the [Cacheable](crate::production::Cacheable) productions outside the changed tokens are reused.
+    /// The updated tokens and the tree are same as the result of parsing the edited text from the beginning.
+    ///
+    /// If the edited text fails to parse, the document keeps the edited text with an empty tree,
+    /// so that it can be reparsed after the next edit.
+    pub fn reparse(
+        &self,
+        document: &mut ParsedDocument<TN, TL>,
+        edit: &TextEdit,
+    ) -> Result<(), ParseError> {
+        document.apply(edit);
+        self.parse_document(document, Some(edit))
+    }
+
+    fn parse_document(
+        &self,
+        document: &mut ParsedDocument<TN, TL>,
+        edit: Option<&TextEdit>,
+    ) -> Result<(), ParseError> {
+        let (previous_tokens, previous_checkpoints, memo) = document.take();
+        let code = Code::new(document.text());
+
+        let (tokens, checkpoints) = match edit {
+            Some(edit) if !previous_tokens.is_empty() => {
+                self.tokenizer
+                    .retokenize(&code, &previous_tokens, &previous_checkpoints, edit)?
+            }
+            _ => self.tokenizer.tokenize_with_checkpoints(&code)?,
+        };
+
+        let byte_shift = edit.map_or(0, |e| e.shift());
+        let reusable = ReusableMemo::new(memo, &previous_tokens, &tokens, byte_shift);
+
+        let stream = TokenStream::from(&tokens);
+        let mut cached_data: Cache<FltrPtr, TN> = Cache::reuse_root(reusable);
+        let result =
+            self.root
+                .advance_fltr_ptr(&code, FltrPtr::default(), &stream, &mut cached_data);
+
+        let (tree, memo, result) = match result {
+            Ok(sd) => (sd.children, cached_data.into_memo(), Ok(())),
+            Err(_) => {
+                // Parse again without reused results to report the same error as a full parse,
+                // or to keep the tree of the full parse if the reused results fail to derive the edited text.
+                let mut cached_data: Cache<FltrPtr, TN> = Cache::root();
+                let result = self.root.advance_fltr_ptr(
+                    &code,
+                    FltrPtr::default(),
+                    &stream,
+                    &mut cached_data,
+                );
+                match result {
+                    Ok(sd) => (sd.children, cached_data.into_memo(), Ok(())),
+                    Err(err) => {
+                        let err =
+                            self.describe_expected(cached_data.create_error(&code, &stream, err));
+                        (Vec::new(), cached_data.into_memo(), Err(err))
+                    }
+                }
+            }
+        };
+        document.update(tokens, checkpoints, tree, memo);
+        result
+    }
+}
+
 #[cfg(debug_assertions)]
 impl<TN: NodeImpl, TL: TokenImpl> DefaultParser<TN, TL> {
     pub fn get_production(&self, id: &str) -> Option<&Rc<dyn IProduction<Node = TN, Token = TL>>> {
@@ -93,16 +347,19 @@ impl<TN: NodeImpl, TL: TokenImpl> DefaultParser<TN, TL> {
         text: &[u8],
         pointer: usize,
     ) -> Result<Vec<ASTNode<TN>>, ParseError> {
+        let code = Code::new(text);
+
         let production = match self.get_production(id) {
             Some(p) => p.clone(),
             None => {
                 return Err(ParseError::new(
-                    0,
+                    crate::ParseErrorKind::Validation,
+                    &code,
+                    0..0,
                     format!("Production {} is not added for debugging.", id),
                 ));
             }
         };
-        let code = Code::new(text);
 
         let tokens = self.tokenize(&code)?;
 
@@ -118,7 +375,7 @@ impl<TN: NodeImpl, TL: TokenImpl> DefaultParser<TN, TL> {
 
         let success_data = production
             .advance_fltr_ptr(&code, index, &stream, &mut cached_data)
-            .map_err(|err| cached_data.create_error(&code, &stream, err))?;
+            .map_err(|err| self.describe_expected(cached_data.create_error(&code, &stream, err)))?;
         Ok(success_data.children)
     }
 }
@@ -138,6 +395,45 @@ impl<TN: NodeImpl, TL: TokenImpl> LexerlessParser<TN, TL> {
     pub fn grammar(&self) -> Result<String, std::fmt::Error> {
         self.root.build_grammar()
     }
+
+    /// Create the [Grammar] model of the productions.
+    pub fn grammar_model(&self) -> Grammar {
+        Grammar::from_production(self.root.as_ref())
+    }
+
+    /// Report the FIRST and FOLLOW sets of the rules and the conflicting and the shadowed alternatives.
+    /// See [Grammar::analyze] for the details.
+    pub fn analyze_grammar(&self) -> GrammarAnalysis {
+        self.grammar_model().analyze()
+    }
+
+    /// Create a [SentenceGenerator] of the productions.
+    pub fn sentence_generator(&self) -> Result<SentenceGenerator, ImplementationError> {
+        SentenceGenerator::new(self.grammar_model())
+    }
+
+    /// Parse the sentences created by the generator.
+    ///
+    /// The first sentence which fails to parse is returned as an error.
+    /// ## Arguments
+    /// * `generator` - A generator of the sentences of the grammar of the parser.
+    /// * `count` - Number of the sentences to check.
+    pub fn round_trip(
+        &self,
+        generator: &mut SentenceGenerator,
+        count: usize,
+    ) -> Result<(), RoundTripError> {
+        for _ in 0..count {
+            let sentence = generator.generate().map_err(RoundTripError::Generation)?;
+            if let Err(error) = self.parse(sentence.text.as_bytes()) {
+                return Err(RoundTripError::Parse {
+                    sentence: sentence.text,
+                    error: Box::new(error),
+                });
+            }
+        }
+        Ok(())
+    }
 }
 
 impl<TN: NodeImpl, TL: TokenImpl> LexerlessParser<TN, TL> {
@@ -152,6 +448,27 @@ impl<TN: NodeImpl, TL: TokenImpl> LexerlessParser<TN, TL> {
         }
     }
 
+    /// Parse the input while the [TraceEvent](crate::TraceEvent) of the productions are sent to the sink.
+    ///
+    /// The productions without a [Log](crate::Log) label are traced with the `trace` feature.
+    pub fn parse_with_trace(
+        &self,
+        text: &[u8],
+        sink: &mut dyn TraceSink,
+    ) -> Result<Vec<ASTNode<TN>>, ParseError> {
+        with_trace_sink(sink, || self.parse(text))
+    }
+
+    /// Parse the input while the productions are profiled.
+    /// See [parse_with_profile](DefaultParser::parse_with_profile).
+    #[cfg(feature = "profile")]
+    pub fn parse_with_profile(
+        &self,
+        text: &[u8],
+    ) -> (Result<Vec<ASTNode<TN>>, ParseError>, ProfileReport) {
+        with_profile(|| self.parse(text))
+    }
+
     pub fn validate(&self) -> Result<(), ImplementationError> {
         self.root.validate(HashMap::new(), &mut HashSet::new())
     }
@@ -185,7 +502,9 @@ impl<TN: NodeImpl, TL: TokenImpl> LexerlessParser<TN, TL> {
             Some(p) => p.clone(),
             None => {
                 return Err(ParseError::new(
-                    0,
+                    crate::ParseErrorKind::Validation,
+                    &code,
+                    0..0,
                     format!("Production {} is not added for debugging.", id),
                 ));
             }
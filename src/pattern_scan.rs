@@ -0,0 +1,131 @@
+use crate::match_policy::LexemeType;
+use crate::{Code, ILexeme, Lex, MatchPolicy, Rc};
+use regex_automata::{
+    meta::{self, Regex},
+    util::syntax,
+    Anchored, Input, MatchKind, PatternID, PatternSet,
+};
+
+/// The [scan patterns](ILexeme::scan_pattern) of the lexemes of a tokenizer state which are compiled into a
+/// single multi-pattern regex, so that the matching patterns are found in one pass at each pointer.
+pub(crate) struct PatternScan<TToken> {
+    /// Pattern ID of each lexeme if the lexeme is merged into the regex.
+    pattern_ids: Vec<Option<usize>>,
+    /// Token of each pattern ID.
+    tokens: Vec<TToken>,
+    regex: Option<Regex>,
+}
+
+/// Result of the multi-pattern scan at a pointer.
+pub(crate) enum Scanned {
+    /// The scan is not available and the merged lexemes should consume the input by themselves.
+    Unknown,
+    /// The first matching pattern and the length of its match.
+    First(Option<(usize, usize)>),
+    /// All the patterns which match the input.
+    All(PatternSet),
+}
+
+impl<TToken: LexemeType> PatternScan<TToken> {
+    /// Merge the scan patterns of the lexemes where the match kind of the regex is selected for the policy.
+    ///
+    /// The lexemes are not merged if there are less than two patterns or the regex cannot be compiled.
+    pub(crate) fn new<TState: LexemeType>(
+        lexemes: &[Rc<dyn ILexeme<Token = TToken, State = TState>>],
+        policy: &MatchPolicy,
+    ) -> Self {
+        let mut pattern_ids = Vec::with_capacity(lexemes.len());
+        let mut tokens = Vec::new();
+        let mut patterns = Vec::new();
+        for lexeme in lexemes {
+            match lexeme.scan_pattern() {
+                Some((token, pattern)) => {
+                    pattern_ids.push(Some(patterns.len()));
+                    tokens.push(token);
+                    patterns.push(pattern);
+                }
+                None => pattern_ids.push(None),
+            }
+        }
+        let match_kind = match policy {
+            MatchPolicy::FirstMatch => MatchKind::LeftmostFirst,
+            _ => MatchKind::All,
+        };
+        // Same syntax as the byte oriented regex of the Pattern lexeme.
+        let regex = match patterns.len() {
+            0 | 1 => None,
+            _ => Regex::builder()
+                .configure(meta::Config::new().match_kind(match_kind).utf8_empty(false))
+                .syntax(syntax::Config::new().utf8(false))
+                .build_many(&patterns)
+                .ok(),
+        };
+        match regex {
+            Some(regex) => Self {
+                pattern_ids,
+                tokens,
+                regex: Some(regex),
+            },
+            None => Self {
+                pattern_ids: vec![None; lexemes.len()],
+                tokens: Vec::new(),
+                regex: None,
+            },
+        }
+    }
+
+    /// Scan the input at the pointer with the anchored multi-pattern regex.
+    pub(crate) fn scan(&self, code: &Code, pointer: usize) -> Scanned {
+        let regex = match &self.regex {
+            Some(regex) => regex,
+            None => return Scanned::Unknown,
+        };
+        // The patterns are anchored with '^' at the pointer like the Pattern lexeme.
+        let input = Input::new(&code.value[pointer..]).anchored(Anchored::Yes);
+        match regex.get_config().get_match_kind() {
+            MatchKind::All => {
+                let mut patterns = PatternSet::new(regex.pattern_len());
+                regex.which_overlapping_matches(&input, &mut patterns);
+                Scanned::All(patterns)
+            }
+            _ => match regex.search(&input) {
+                // An empty match does not create a token, therefore the later patterns may still match.
+                Some(m) if m.is_empty() => Scanned::Unknown,
+                m => Scanned::First(m.map(|m| (m.pattern().as_usize(), m.end()))),
+            },
+        }
+    }
+
+    /// Create the token of the lexeme from the scan result.
+    ///
+    /// Returns [None] if the lexeme is not merged or the scan result is unknown,
+    /// therefore the lexeme should consume the input by itself.
+    pub(crate) fn first_match(
+        &self,
+        scanned: &Scanned,
+        index: usize,
+        pointer: usize,
+    ) -> Option<Option<Lex<TToken>>> {
+        let id = self.pattern_ids[index]?;
+        match scanned {
+            Scanned::First(Some((matched, end))) if *matched == id => {
+                Some(Some(Lex::new(self.tokens[id], pointer, pointer + end)))
+            }
+            Scanned::First(_) => Some(None),
+            _ => None,
+        }
+    }
+
+    /// Whether the lexeme may match the input according to the scan result.
+    pub(crate) fn may_match(&self, scanned: &Scanned, index: usize) -> bool {
+        match (self.pattern_ids[index], scanned) {
+            (Some(id), Scanned::All(patterns)) => patterns.contains(PatternID::new_unchecked(id)),
+            _ => true,
+        }
+    }
+
+    /// Whether the lexeme is merged into the regex.
+    pub(crate) fn is_merged(&self, index: usize) -> bool {
+        self.pattern_ids[index].is_some()
+    }
+}
@@ -0,0 +1,124 @@
+use crate::{
+    lexeme::{Pattern, Punctuations},
+    production::{Concat, EOFProd, List, ProductionBuilder, TokenField},
+    CSTElement, CSTNode, DefaultParser, NodeImpl, Rc, TokenImpl, Tokenizer,
+};
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, PartialOrd, Ord)]
+enum Token {
+    ID,
+    Number,
+    Assign,
+    Semicolon,
+    Space,
+    Comment,
+    Eof,
+}
+impl TokenImpl for Token {
+    fn eof() -> Self {
+        Token::Eof
+    }
+
+    fn is_structural(&self) -> bool {
+        !matches!(self, Token::Space | Token::Comment)
+    }
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+enum NodeValue {
+    ID,
+    Number,
+    Statement,
+    Root,
+    Null,
+}
+
+impl NodeImpl for NodeValue {
+    fn null() -> Self {
+        Self::Null
+    }
+}
+
+fn parser() -> DefaultParser<NodeValue, Token> {
+    let punctuations =
+        Rc::new(Punctuations::new(vec![("=", Token::Assign), (";", Token::Semicolon)]).unwrap());
+    let space = Rc::new(Pattern::new(Token::Space, r"^\s+").unwrap());
+    let comment = Rc::new(Pattern::new(Token::Comment, r"^(//[^\n]*|/\*.*?\*/)").unwrap());
+    let id = Rc::new(Pattern::new(Token::ID, r"^[a-z]+").unwrap());
+    let number = Rc::new(Pattern::new(Token::Number, r"^[0-9]+").unwrap());
+    let tokenizer = Tokenizer::new(vec![space, comment, punctuations, id, number]);
+
+    let statement = Rc::new(
+        Concat::new(
+            "statement",
+            vec![
+                Rc::new(TokenField::new(Token::ID, Some(NodeValue::ID))),
+                Rc::new(TokenField::new(Token::Assign, None)),
+                Rc::new(TokenField::new(Token::Number, Some(NodeValue::Number))),
+                Rc::new(TokenField::new(Token::Semicolon, None)),
+            ],
+        )
+        .into_node(NodeValue::Statement),
+    );
+    let root = Concat::new(
+        "root",
+        vec![Rc::new(List::new(&statement)), Rc::new(EOFProd::new(None))],
+    );
+    DefaultParser::new(Rc::new(tokenizer), Rc::new(root.into_node(NodeValue::Root))).unwrap()
+}
+
+fn as_node<TL>(element: &CSTElement<NodeValue, TL>) -> &CSTNode<NodeValue, TL> {
+    match element {
+        CSTElement::Node(node) => node,
+        CSTElement::Token(_) => panic!("Expected a node."),
+    }
+}
+
+#[test]
+fn lossless_text_test() {
+    let parser = parser();
+    for text in [
+        "a = 1; // one\n  b = 22; /* two */\n",
+        "  /* start */ a=1;b=2;",
+        "a=1;",
+        "a = 1;\n\n// end",
+    ] {
+        let cst = parser.parse_cst(text.as_bytes()).unwrap();
+        assert_eq!(cst.to_text(), text.as_bytes());
+    }
+}
+
+#[test]
+fn trivia_test() {
+    let parser = parser();
+    let text = "a = 1; // one\n  b = 22; /* two */\n";
+    let cst = parser.parse_cst(text.as_bytes()).unwrap();
+    let source = |lexes: &[crate::Lex<Token>]| -> Vec<&str> {
+        lexes.iter().map(|lex| &text[lex.start..lex.end]).collect()
+    };
+
+    let root = as_node(&cst.children()[0]);
+    assert_eq!(root.node, NodeValue::Root);
+    let statements: Vec<_> = root.children[..2].iter().map(as_node).collect();
+    assert_eq!((statements[0].start, statements[0].end), (0, 6));
+    assert_eq!((statements[1].start, statements[1].end), (16, 23));
+
+    // Hidden tokens are included in the tree.
+    let first = statements[0].children[3].first_token().unwrap();
+    assert_eq!(first.lex.token, Token::Semicolon);
+    assert_eq!(source(&first.trailing), vec![" ", "// one"]);
+
+    let id = as_node(&statements[1].children[0]);
+    assert_eq!(id.node, NodeValue::ID);
+    let second = id.children[0].first_token().unwrap();
+    assert_eq!(source(&second.leading), vec!["\n  "]);
+    assert_eq!(source(&second.trailing), vec![" "]);
+
+    let last = root.children[1].last_token().unwrap();
+    assert_eq!(source(&last.trailing), vec![" ", "/* two */"]);
+    assert_eq!((last.full_start(), last.full_end()), (22, 33));
+
+    let eof = cst.children().last().unwrap().last_token().unwrap();
+    assert_eq!(eof.lex.token, Token::Eof);
+    assert_eq!(source(&eof.leading), vec!["\n"]);
+}
@@ -0,0 +1,282 @@
+use crate::{
+    grammar::{Grammar, GrammarExpr},
+    lexeme::{Pattern, Punctuations},
+    production::{
+        Concat, EOFProd, List, Lookahead, Node, NonStructural, NotLookahead, Nullable,
+        ProductionBuilder, RegexField, SeparatedList, TokenField, Union,
+    },
+    DefaultParser, LexerlessParser, NodeImpl, Rc, TokenImpl, Tokenizer,
+};
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, PartialOrd, Ord)]
+enum Token {
+    ID,
+    Number,
+    OpenParen,
+    CloseParen,
+    Comma,
+    Space,
+    Eof,
+}
+impl TokenImpl for Token {
+    fn eof() -> Self {
+        Token::Eof
+    }
+
+    fn is_structural(&self) -> bool {
+        *self != Token::Space
+    }
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+enum NodeValue {
+    Call,
+    Name,
+    Number,
+    Null,
+}
+
+impl NodeImpl for NodeValue {
+    fn null() -> Self {
+        Self::Null
+    }
+}
+
+fn call_grammar() -> DefaultParser<NodeValue, Token> {
+    let punctuations = Rc::new(
+        Punctuations::new(vec![
+            ("(", Token::OpenParen),
+            (")", Token::CloseParen),
+            (",", Token::Comma),
+        ])
+        .unwrap(),
+    );
+    let space = Rc::new(Pattern::new(Token::Space, r"^[ \t\n]+").unwrap());
+    let id = Rc::new(Pattern::new(Token::ID, r"^[a-z_]+").unwrap());
+    let number = Rc::new(Pattern::new(Token::Number, r"^[0-9]+(\.[0-9]+)?").unwrap());
+    let tokenizer = Rc::new(Tokenizer::new(vec![space, punctuations, id, number]));
+
+    let name = Rc::new(TokenField::new(Token::ID, Some(NodeValue::Name)));
+    let open_paren = Rc::new(TokenField::new(Token::OpenParen, None));
+    let close_paren = Rc::new(TokenField::new(Token::CloseParen, None));
+    let comma = Rc::new(TokenField::new(Token::Comma, None));
+    let eof = Rc::new(EOFProd::new(None));
+
+    let value = Rc::new(Union::init("value"));
+    let args = Rc::new(SeparatedList::new(&value, &comma, false));
+    args.set_rule_name("args").unwrap();
+    let call = Rc::new(
+        Concat::new(
+            "call",
+            vec![
+                name.clone(),
+                Rc::new(Lookahead::new(&open_paren, None)),
+                open_paren.clone(),
+                Rc::new(Nullable::new(&args)),
+                close_paren,
+            ],
+        )
+        .into_node(NodeValue::Call),
+    );
+    let number = Rc::new(TokenField::new(Token::Number, Some(NodeValue::Number)));
+    let variable = Rc::new(Concat::new(
+        "variable",
+        vec![name, Rc::new(NotLookahead::new(&open_paren, None))],
+    ));
+    value
+        .set_symbols(vec![number, call.clone(), variable])
+        .unwrap();
+    let root = Rc::new(Concat::new("root", vec![Rc::new(List::new(&call)), eof]));
+    DefaultParser::new(tokenizer, root).unwrap()
+}
+
+#[test]
+fn grammar_model_test() {
+    let grammar = call_grammar().grammar_model();
+    let names: Vec<&str> = grammar.rules.iter().map(|r| r.name.as_str()).collect();
+    assert_eq!(names, vec!["root", "call", "args", "value", "variable"]);
+    assert_eq!(
+        grammar.get_rule("variable").unwrap().expr,
+        GrammarExpr::Sequence(vec![
+            GrammarExpr::Node {
+                value: "Name".into(),
+                expr: Box::new(GrammarExpr::Token("ID".into()))
+            },
+            GrammarExpr::NotLookahead(Box::new(GrammarExpr::Token("OpenParen".into()))),
+        ])
+    );
+    let tokens: Vec<(&str, bool)> = grammar
+        .lexer_rules
+        .iter()
+        .map(|r| (r.token.as_str(), r.is_structural))
+        .collect();
+    assert_eq!(
+        tokens,
+        vec![
+            ("Space", false),
+            ("OpenParen", true),
+            ("CloseParen", true),
+            ("Comma", true),
+            ("ID", true),
+            ("Number", true)
+        ]
+    );
+}
+
+#[test]
+fn iso_ebnf_test() {
+    assert_eq!(
+        call_grammar().grammar_model().to_iso_ebnf(),
+        r#"root = call , { call } , ? end of input ? ;
+call = ID , ? followed by OpenParen ? , OpenParen , [ args ] , CloseParen ;
+args = value , { Comma , value } , [ Comma ] ;
+value = Number | call | variable ;
+variable = ID , ? not followed by OpenParen ? ;
+
+Space = ? [\t-\n ] ? , { ? [\t-\n ] ? } ; (* non-structural *)
+OpenParen = "(" ;
+CloseParen = ")" ;
+Comma = "," ;
+ID = ? [_a-z] ? , { ? [_a-z] ? } ;
+Number = ? [0-9] ? , { ? [0-9] ? } , [ "." , ? [0-9] ? , { ? [0-9] ? } ] ;
+"#
+    );
+}
+
+#[test]
+fn w3c_ebnf_test() {
+    assert_eq!(
+        call_grammar().grammar_model().to_w3c_ebnf(),
+        r#"root ::= call+ EOF
+call ::= ID /* followed by OpenParen */ OpenParen args? CloseParen
+args ::= value (Comma value)* Comma?
+value ::= Number | call | variable
+variable ::= ID /* not followed by OpenParen */
+
+Space ::= [#x9-#xA#x20]+ /* non-structural */
+OpenParen ::= "("
+CloseParen ::= ")"
+Comma ::= ","
+ID ::= [#x5Fa-z]+
+Number ::= [0-9]+ ("." [0-9]+)?
+"#
+    );
+}
+
+#[test]
+fn antlr_test() {
+    assert_eq!(
+        call_grammar().grammar_model().to_antlr("Call"),
+        r#"grammar Call;
+
+root
+    : call+ EOF
+    ;
+
+call
+    : ID /* followed by OpenParen */ OpenParen args? CloseParen
+    ;
+
+args
+    : value (Comma value)* Comma?
+    ;
+
+value
+    : Number
+    | call
+    | variable
+    ;
+
+variable
+    : ID /* not followed by OpenParen */
+    ;
+
+Space : [\t-\n ]+ -> channel(HIDDEN) ;
+OpenParen : '(' ;
+CloseParen : ')' ;
+Comma : ',' ;
+ID : [_a-z]+ ;
+Number : [0-9]+ ('.' [0-9]+)? ;
+"#
+    );
+}
+
+#[test]
+fn lexerless_grammar_test() {
+    let space = Rc::new(RegexField::new(r"^[ \t]*", None).unwrap());
+    let word = Rc::new(RegexField::new(r"^[a-z]+", Some(NodeValue::Name)).unwrap());
+    let lazy = Rc::new(RegexField::new(r"^/\*.*?\*/", None).unwrap());
+    let eof = Rc::new(EOFProd::new(None));
+    let item = Rc::new(Union::new("item", vec![word, lazy]));
+    let items = Rc::new(SeparatedList::new(&item, &space, true));
+    let root = Rc::new(Node::new(
+        &Rc::new(Concat::new(
+            "Main",
+            vec![Rc::new(NonStructural::new(&items, false)), eof],
+        )),
+        NodeValue::Call,
+    ));
+    let grammar = LexerlessParser::new(root).unwrap().grammar_model();
+    assert_eq!(grammar.rules[0].name, "start");
+    assert!(grammar.lexer_rules.is_empty());
+
+    assert_eq!(
+        grammar.to_antlr("Words"),
+        r#"grammar Words;
+
+start
+    : main
+    ;
+
+main
+    : /* non-structural */ (item (PATTERN_1? item)*) EOF
+    ;
+
+item
+    : PATTERN_2
+    | PATTERN_3
+    ;
+
+PATTERN_1 : [\t ]+ ;
+PATTERN_2 : [a-z]+ ;
+// PATTERN_3 : /^\/\*.*?\*\// ;
+"#
+    );
+}
+
+#[test]
+fn regex_translation_test() {
+    assert_eq!(
+        GrammarExpr::from_regex(r"^(true|false)"),
+        Some(GrammarExpr::Choice(vec![
+            GrammarExpr::Literal("true".into()),
+            GrammarExpr::Literal("false".into())
+        ]))
+    );
+    assert_eq!(
+        GrammarExpr::from_regex(r#"^[^"]{2,3}"#),
+        Some(GrammarExpr::Repeat {
+            expr: Box::new(GrammarExpr::CharSet {
+                ranges: vec![('"', '"')],
+                negated: true
+            }),
+            min: 2,
+            max: Some(3)
+        })
+    );
+    assert_eq!(GrammarExpr::from_regex(r"^[a-z]+\b"), None);
+
+    let mut grammar = Grammar::new();
+    grammar.rules.push(crate::grammar::Rule {
+        name: "digits".into(),
+        expr: GrammarExpr::repeat(GrammarExpr::Token("Digit".into()), 2, Some(4)),
+    });
+    assert_eq!(
+        grammar.to_iso_ebnf(),
+        "digits = 2 * Digit , 2 * [ Digit ] ;\n"
+    );
+    assert_eq!(
+        grammar.to_w3c_ebnf(),
+        "digits ::= Digit Digit Digit? Digit?\n"
+    );
+}
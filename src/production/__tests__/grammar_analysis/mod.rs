@@ -0,0 +1,203 @@
+use crate::{
+    grammar::{ChoiceConflict, Grammar, NodeName, ShadowedAlternative, Terminal},
+    lexeme::{Pattern, Punctuations},
+    production::{Concat, EOFProd, List, TokenField, Union},
+    DefaultParser, NodeImpl, Rc, TokenImpl, Tokenizer,
+};
+use std::collections::BTreeSet;
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, PartialOrd, Ord)]
+enum Token {
+    ID,
+    Number,
+    Assign,
+    Plus,
+    Semicolon,
+    Colon,
+    Space,
+    Eof,
+}
+
+impl TokenImpl for Token {
+    fn eof() -> Self {
+        Token::Eof
+    }
+
+    fn is_structural(&self) -> bool {
+        *self != Token::Space
+    }
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+enum NodeValue {
+    Null,
+}
+
+impl NodeImpl for NodeValue {
+    fn null() -> Self {
+        Self::Null
+    }
+}
+
+fn token_set(tokens: &[&str]) -> BTreeSet<Terminal> {
+    tokens
+        .iter()
+        .map(|token| match *token {
+            "EOF" => Terminal::EndOfFile,
+            token => Terminal::Token(token.to_string()),
+        })
+        .collect()
+}
+
+#[test]
+fn tokenized_analysis_test() {
+    let punctuations = Rc::new(
+        Punctuations::new(vec![
+            ("=", Token::Assign),
+            ("+", Token::Plus),
+            (";", Token::Semicolon),
+            (":", Token::Colon),
+        ])
+        .unwrap(),
+    );
+    let id = Rc::new(Pattern::new(Token::ID, r"^[a-z]+").unwrap());
+    let number = Rc::new(Pattern::new(Token::Number, r"^[0-9]+").unwrap());
+    let space = Rc::new(Pattern::new(Token::Space, r"^\s+").unwrap());
+    let tokenizer = Rc::new(Tokenizer::new(vec![space, punctuations, id, number]));
+
+    let id = Rc::new(TokenField::new(Token::ID, None));
+    let number = Rc::new(TokenField::new(Token::Number, None));
+    let assign = Rc::new(TokenField::new(Token::Assign, None));
+    let plus = Rc::new(TokenField::new(Token::Plus, None));
+    let semicolon = Rc::new(TokenField::new(Token::Semicolon, None));
+
+    let value = Rc::new(Union::new("value", vec![number.clone(), id.clone()]));
+    let sum = Rc::new(Concat::new("sum", vec![value.clone(), plus, value.clone()]));
+    let expr = Rc::new(Union::new(
+        "expr",
+        vec![value.clone(), sum.clone(), value.clone()],
+    ));
+    let statement = Rc::new(Union::new(
+        "statement",
+        vec![
+            Rc::new(Concat::new("assignment", vec![id.clone(), assign, expr])),
+            id,
+        ],
+    ));
+    let statements = Rc::new(Concat::new("statements", vec![statement, semicolon]));
+    let root = Rc::new(Concat::new(
+        "root",
+        vec![Rc::new(List::new(&statements)), Rc::new(EOFProd::new(None))],
+    ));
+    let parser: DefaultParser<NodeValue, Token> = DefaultParser::new(tokenizer, root).unwrap();
+
+    let analysis = parser.analyze_grammar();
+    let rules: Vec<&str> = analysis.rules.iter().map(|r| r.name.as_str()).collect();
+    assert_eq!(
+        rules,
+        vec![
+            "root",
+            "statements",
+            "statement",
+            "assignment",
+            "expr",
+            "value",
+            "sum"
+        ]
+    );
+    let sets = |name: &str| analysis.rules.iter().find(|r| r.name == name).unwrap();
+    assert_eq!(sets("root").first, token_set(&["ID"]));
+    assert_eq!(sets("expr").first, token_set(&["ID", "Number"]));
+    assert_eq!(sets("value").follow, token_set(&["Plus", "Semicolon"]));
+    assert_eq!(sets("statements").follow, token_set(&["EOF", "ID"]));
+    assert_eq!(sets("expr").follow, token_set(&["Semicolon"]));
+    assert!(!sets("expr").nullable);
+
+    assert_eq!(
+        analysis.conflicts,
+        vec![
+            ChoiceConflict {
+                rule: "statement".into(),
+                alternatives: (0, 1),
+                terminals: token_set(&["ID"]),
+            },
+            ChoiceConflict {
+                rule: "expr".into(),
+                alternatives: (0, 1),
+                terminals: token_set(&["ID", "Number"]),
+            },
+        ]
+    );
+    assert_eq!(
+        analysis.shadowed_alternatives,
+        vec![ShadowedAlternative {
+            rule: "expr".into(),
+            alternative: 2,
+            shadowed_by: 0,
+        }]
+    );
+    assert!(analysis.unreachable_rules.is_empty());
+    assert_eq!(analysis.unused_tokens, vec!["Colon"]);
+
+    let report = analysis.to_string();
+    assert!(report.contains("  expr\n    FIRST  = { ID, Number }\n    FOLLOW = { Semicolon }\n"));
+    assert!(report.contains("  expr: alternatives #1 and #2 overlap on { ID, Number }\n"));
+    assert!(report.contains("  expr: alternative #3 never matches because of alternative #1\n"));
+    assert!(report.ends_with("Unused tokens: Colon\n"));
+}
+
+#[test]
+fn lexerless_analysis_test() {
+    let grammar = Grammar::from_dsl(
+        r#"
+        main = (statement ";")* EOF ;
+        statement = "let" name "=" value | name "==" value | name ;
+        operator = "=" | "==" ;
+        value = /[0-9]+/ | "0x" /[0-9a-f]+/ | ("-")? | "+" ;
+        name = /[a-z]+/ ;
+        "#,
+    )
+    .unwrap();
+    let analysis = grammar.analyze();
+    assert_eq!(analysis.unreachable_rules, vec!["operator"]);
+
+    let value = analysis.rules.iter().find(|r| r.name == "value").unwrap();
+    assert!(value.nullable);
+    assert_eq!(
+        value.follow,
+        BTreeSet::from([Terminal::Literal(";".into())])
+    );
+
+    let conflicts: Vec<(&str, (usize, usize))> = analysis
+        .conflicts
+        .iter()
+        .map(|c| (c.rule.as_str(), c.alternatives))
+        .collect();
+    assert_eq!(
+        conflicts,
+        vec![
+            ("statement", (0, 1)),
+            ("statement", (0, 2)),
+            ("statement", (1, 2)),
+            ("value", (0, 1)),
+        ]
+    );
+    let shadowed: Vec<(&str, usize, usize)> = analysis
+        .shadowed_alternatives
+        .iter()
+        .map(|s| (s.rule.as_str(), s.alternative, s.shadowed_by))
+        .collect();
+    assert_eq!(shadowed, vec![("operator", 1, 0), ("value", 3, 2)]);
+
+    let parser = grammar
+        .build_lexerless(|name| Some(NodeName(name)))
+        .unwrap();
+    assert_eq!(
+        parser.analyze_grammar().rules[0].first,
+        BTreeSet::from([
+            Terminal::EndOfFile,
+            Terminal::Literal("let".into()),
+            Terminal::Pattern("^(?:[a-z]+)".into())
+        ])
+    );
+}
@@ -0,0 +1,183 @@
+use crate::{
+    grammar::{Grammar, GrammarExpr, LexerPattern, NodeName, TokenName},
+    Code, NodeImpl, ParseErrorKind,
+};
+
+const ASSIGNMENTS: &str = r#"
+// Parser rules.
+program = (assignment ";")* EOF ;
+assignment = ID "=" expr ;
+expr = term (Operator term)* ;
+term = Number | ID | "(" expr ")" ;
+
+// Lexer rules.
+token Assign = "=" ;
+token Semicolon = ";" ;
+token Operator = "+" | "-" ;
+token OpenParen = "(" ;
+token CloseParen = ")" ;
+token Number = /[0-9]+/ ;
+token ID = /[a-z_][a-z0-9_]*/ ;
+skip Space = /[ \t\n]+/ ;
+"#;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum NodeValue {
+    Assignment,
+    Expr,
+    Null,
+}
+
+impl NodeImpl for NodeValue {
+    fn null() -> Self {
+        Self::Null
+    }
+}
+
+#[test]
+fn dsl_model_test() {
+    let grammar = Grammar::from_dsl(ASSIGNMENTS).unwrap();
+    let names: Vec<&str> = grammar.rules.iter().map(|r| r.name.as_str()).collect();
+    assert_eq!(names, vec!["program", "assignment", "expr", "term"]);
+    assert_eq!(
+        grammar.get_rule("assignment").unwrap().expr,
+        GrammarExpr::Sequence(vec![
+            GrammarExpr::Token("ID".into()),
+            GrammarExpr::Literal("=".into()),
+            GrammarExpr::Rule("expr".into()),
+        ])
+    );
+    let space = grammar.get_lexer_rule("Space").unwrap();
+    assert!(!space.is_structural);
+    assert_eq!(
+        space.patterns,
+        vec![LexerPattern::Regex(r"^(?:[ \t\n]+)".into())]
+    );
+    assert_eq!(
+        grammar.to_w3c_ebnf().lines().next(),
+        Some(r#"program ::= (assignment ";")* EOF"#)
+    );
+}
+
+#[test]
+fn dsl_parser_test() {
+    let grammar = Grammar::from_dsl(ASSIGNMENTS).unwrap();
+    let parser = grammar
+        .build_parser(|name| match name {
+            "assignment" => Some(NodeValue::Assignment),
+            "expr" => Some(NodeValue::Expr),
+            _ => None,
+        })
+        .unwrap();
+
+    let tokens = parser.tokenize(&Code::new(b"a = 1;")).unwrap();
+    let names: Vec<&str> = tokens.iter().map(|lex| lex.token.name).collect();
+    assert_eq!(
+        names,
+        vec![
+            "ID",
+            "Space",
+            "Assign",
+            "Space",
+            "Number",
+            "Semicolon",
+            "EOF"
+        ]
+    );
+
+    let tree = parser.parse(b"a = 1 + (b - 2);\nc = a;").unwrap();
+    assert_eq!(tree.len(), 2);
+    assert_eq!(tree[0].node, NodeValue::Assignment);
+    assert_eq!(tree[0].children[0].node, NodeValue::Expr);
+    assert_eq!(tree[0].children[0].children[0].node, NodeValue::Expr);
+    assert_eq!(tree[1].start, 17);
+
+    let err = parser.parse(b"a = 1 +;").unwrap_err();
+    assert_eq!(err.kind, ParseErrorKind::UnexpectedToken);
+    assert_eq!(err.pointer, 7);
+    assert!(parser.parse(b"a = 1 $ 2;").is_err());
+
+    let err = Grammar::from_dsl("main = '+' ; token Operator = '+' | '-' ;")
+        .unwrap()
+        .build_parser(|_| None::<NodeValue>)
+        .err()
+        .unwrap();
+    assert_eq!(err.what, "AmbiguousLiteral");
+}
+
+#[test]
+fn dsl_lexerless_test() {
+    let grammar = Grammar::from_dsl(
+        r#"
+        main = list EOF ;
+        list = "[" _ (item (_ "," _ item)*)? _ "]" ;
+        item = number | word | list ;
+        number = /-?[0-9]+/ ;
+        word = '\u{27}' /[^'\/]*/ "'" ;
+        _ = /[ ]*/ ;
+        "#,
+    )
+    .unwrap();
+    let parser = grammar
+        .build_lexerless(|name| (!["main", "item", "_"].contains(&name)).then_some(NodeName(name)))
+        .unwrap();
+    let tree = parser.parse(b"[1, 'a', [-2]]").unwrap();
+    assert_eq!(
+        tree[0].to_sexp(Some(&Code::new(b"[1, 'a', [-2]]"))),
+        r#"(list (number "1") (word "'a'") (list (number "-2") (null "")))"#
+    );
+    assert!(parser.parse(b"[1 2]").is_err());
+
+    let err = Grammar::from_dsl("main = Word ; token Word = /[a-z]+/ ;")
+        .unwrap()
+        .build_lexerless(|name| Some(NodeName(name)))
+        .err()
+        .unwrap();
+    assert_eq!(err.what, "UndefinedToken");
+
+    let parser = Grammar::from_dsl("main = &'a' !'ab' /[a-z]{1,2}/ { 2 , 3 } EOF ;")
+        .unwrap()
+        .build_lexerless(|_| None::<NodeName>)
+        .unwrap();
+    assert!(parser.parse(b"aaaa").is_ok());
+    assert!(parser.parse(b"abab").is_err());
+    assert!(parser.parse(b"aa").is_err());
+}
+
+#[test]
+fn dsl_error_test() {
+    let err = Grammar::from_dsl("main = item ;\nitem = Number ;").unwrap_err();
+    assert_eq!(err.message, r#"Rule "Number" is not defined."#);
+    assert_eq!((err.start.line, err.start.column), (2, 8));
+
+    let err = Grammar::from_dsl("main = 'a' ;\nmain = 'b' ;").unwrap_err();
+    assert_eq!(err.message, r#"Rule "main" is already defined."#);
+    assert_eq!(err.span, 13..17);
+
+    let err = Grammar::from_dsl("main = 'a' 'b'").unwrap_err();
+    assert_eq!(err.kind, ParseErrorKind::UnexpectedEOF);
+
+    let err = Grammar::from_dsl("main = 'a\n;").unwrap_err();
+    assert_eq!(err.message, "Unterminated string literal.");
+
+    let err = Grammar::from_dsl("main = /(a/ ;").unwrap_err();
+    assert!(err.message.starts_with("Invalid regular expression."));
+
+    let err = Grammar::from_dsl("main = 'a'{3,2} ;").unwrap_err();
+    assert_eq!(err.span, 10..15);
+
+    let err = Grammar::from_dsl("token Space = '' ;").unwrap_err();
+    assert_eq!(
+        err.message,
+        "A lexer rule should not match an empty string."
+    );
+
+    assert!(Grammar::from_dsl("// comment only\n").is_err());
+    assert!(Grammar::from_dsl("token = skip ; skip = 'a' ;").is_ok());
+
+    let token = TokenName {
+        name: "Number",
+        is_structural: true,
+    };
+    assert_eq!(format!("{:?}", token), "Number");
+}
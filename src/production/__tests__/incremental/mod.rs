@@ -0,0 +1,285 @@
+use crate::{
+    lexeme::{Action, Pattern, Punctuations, StateMixin},
+    production::{
+        Cacheable, Concat, EOFProd, List, Node, ProductionBuilder, TokenField, TokenFieldSet, Union,
+    },
+    CacheKey, Code, CombinedTokenizer, DefaultParser, ITokenization, NodeImpl, Rc, TextEdit,
+    TokenImpl,
+};
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, PartialOrd, Ord)]
+enum Token {
+    ID,
+    Number,
+    Assign,
+    Semicolon,
+    OpenBrace,
+    CloseBrace,
+    Quote,
+    Text,
+    Space,
+    Eof,
+}
+impl TokenImpl for Token {
+    fn eof() -> Self {
+        Token::Eof
+    }
+
+    fn is_structural(&self) -> bool {
+        *self != Token::Space
+    }
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+enum NodeValue {
+    ID,
+    Number,
+    Text,
+    Statement,
+    Block,
+    Root,
+    Null,
+}
+
+impl NodeImpl for NodeValue {
+    fn null() -> Self {
+        Self::Null
+    }
+}
+
+const MAIN: u8 = 0;
+const STRING: u8 = 1;
+
+fn tokenizer() -> Rc<CombinedTokenizer<Token, u8>> {
+    let punctuations = Punctuations::new(vec![
+        ("=", Token::Assign),
+        (";", Token::Semicolon),
+        ("{", Token::OpenBrace),
+        ("}", Token::CloseBrace),
+        ("\"", Token::Quote),
+    ])
+    .unwrap();
+    let punctuation_mixin = Rc::new(StateMixin::new(
+        punctuations,
+        vec![(Token::Quote, Action::append(STRING, false))],
+    ));
+    let space = Rc::new(Pattern::new(Token::Space, r"^\s+").unwrap());
+    let id = Rc::new(Pattern::new(Token::ID, r"^[a-z]+").unwrap());
+    let number = Rc::new(Pattern::new(Token::Number, r"^[0-9]+").unwrap());
+
+    let quote_mixin = Rc::new(StateMixin::new(
+        Punctuations::new(vec![("\"", Token::Quote)]).unwrap(),
+        vec![(Token::Quote, Action::remove(false))],
+    ));
+    let text = Rc::new(Pattern::new(Token::Text, r#"^[^"]+"#).unwrap());
+
+    let mut tokenizer = CombinedTokenizer::new(MAIN, vec![space, punctuation_mixin, id, number]);
+    tokenizer.add_state(STRING, vec![quote_mixin, text]);
+    Rc::new(tokenizer)
+}
+
+fn parser() -> DefaultParser<NodeValue, Token> {
+    let id = Rc::new(TokenField::new(Token::ID, Some(NodeValue::ID)));
+    let number = Rc::new(TokenField::new(Token::Number, Some(NodeValue::Number)));
+    let quote = Rc::new(TokenField::new(Token::Quote, None));
+    let text = Rc::new(TokenField::new(Token::Text, Some(NodeValue::Text)));
+    let assign = Rc::new(TokenField::new(Token::Assign, None));
+    let semicolon = Rc::new(TokenField::new(Token::Semicolon, None));
+    let open_brace = Rc::new(TokenField::new(Token::OpenBrace, None));
+    let close_brace = Rc::new(TokenField::new(Token::CloseBrace, None));
+    let eof = Rc::new(EOFProd::new(None));
+
+    let string = Rc::new(Concat::new("string", vec![quote.clone(), text, quote]));
+    let value = Rc::new(Union::new(
+        "value",
+        vec![
+            Rc::new(TokenFieldSet::new(vec![(Token::ID, Some(NodeValue::ID))])),
+            number,
+            string,
+        ],
+    ));
+    let statement = Rc::new(Concat::new("statement", vec![id, assign, value, semicolon]));
+
+    let block = Rc::new(Concat::init("block"));
+    let item = Rc::new(Union::new(
+        "item",
+        vec![
+            Rc::new(Node::new(&statement, NodeValue::Statement)),
+            Rc::new(Node::new(&block, NodeValue::Block)),
+        ],
+    ));
+    let cached_item = Rc::new(Cacheable::new(CacheKey::from(0), &item));
+    let item_list = Rc::new(List::new(&cached_item));
+    block
+        .set_symbols(vec![open_brace, item_list.clone(), close_brace])
+        .unwrap();
+
+    let root = Rc::new(Concat::new("root", vec![item_list, eof]).into_node(NodeValue::Root));
+    DefaultParser::new(tokenizer(), root).unwrap()
+}
+
+/// A parser of the items where two identifiers followed by a number are parsed as a statement,
+/// so that a cached identifier depends on the second following token.
+fn triple_parser() -> DefaultParser<NodeValue, Token> {
+    let id = Rc::new(TokenField::new(Token::ID, Some(NodeValue::ID)));
+    let number = Rc::new(TokenField::new(Token::Number, Some(NodeValue::Number)));
+    let triple = Rc::new(
+        Concat::new("triple", vec![id.clone(), id.clone(), number.clone()])
+            .into_node(NodeValue::Statement),
+    );
+    let item = Rc::new(Union::new("item", vec![triple, id, number]));
+    let cached_item = Rc::new(Cacheable::new(CacheKey::from(0), &item));
+    let root = Rc::new(
+        Concat::new(
+            "root",
+            vec![
+                Rc::new(List::new(&cached_item)),
+                Rc::new(EOFProd::new(None)),
+            ],
+        )
+        .into_node(NodeValue::Root),
+    );
+    DefaultParser::new(tokenizer(), root).unwrap()
+}
+
+fn assert_reparse(parser: &DefaultParser<NodeValue, Token>, text: &[u8], edits: &[TextEdit]) {
+    let mut document = parser.parse_incremental(text).unwrap();
+    for edit in edits {
+        let result = parser.reparse(&mut document, edit);
+        match parser.tokenize_n_parse(document.text()) {
+            Ok((tokens, tree)) => {
+                assert!(result.is_ok());
+                assert_eq!(document.tokens(), &tokens);
+                assert_eq!(document.tree(), &tree);
+            }
+            Err(err) => {
+                let reparse_err = result.unwrap_err();
+                assert_eq!(reparse_err.pointer, err.pointer);
+                assert_eq!(reparse_err.message, err.message);
+                assert!(document.tree().is_empty());
+            }
+        }
+    }
+}
+
+#[test]
+fn reparse_test() {
+    let parser = parser();
+    let text = b"a = 1; { b = \"x y\"; { c = d; } } e = 22;";
+    assert_reparse(
+        &parser,
+        text,
+        &[
+            // Rename an identifier at the beginning.
+            TextEdit::new(0..1, b"abc"),
+            // Change the value inside the nested block.
+            TextEdit::new(33..34, b"100"),
+            // Insert a statement in the middle.
+            TextEdit::new(9..9, b"x = 5; "),
+            // Remove the inserted statement.
+            TextEdit::new(9..16, b""),
+            // Append a statement at the end.
+            TextEdit::new(44..44, b" f = g;"),
+        ],
+    );
+}
+
+#[test]
+fn reparse_string_test() {
+    let parser = parser();
+    let text = b"a = \"one\"; b = \"two\"; c = 3;";
+    assert_reparse(
+        &parser,
+        text,
+        &[
+            // Edit the text inside the string.
+            TextEdit::new(5..8, b"first ; x = 1"),
+            // Open a new string which consumes the following statements.
+            TextEdit::new(0..0, b"z = \""),
+            // Close the string again.
+            TextEdit::new(5..5, b"\"; "),
+            // Replace the whole text.
+            TextEdit::new(0..39, b"{ }"),
+        ],
+    );
+}
+
+#[test]
+fn reparse_error_test() {
+    let parser = parser();
+    let text = b"a = 1; b = 2;";
+    let mut document = parser.parse_incremental(text).unwrap();
+
+    let err = parser
+        .reparse(&mut document, &TextEdit::new(4..5, b";"))
+        .unwrap_err();
+    assert_eq!(err.pointer, 4);
+    assert_eq!(document.text(), b"a = ;; b = 2;");
+    assert!(document.tree().is_empty());
+
+    parser
+        .reparse(&mut document, &TextEdit::new(4..5, b"7"))
+        .unwrap();
+    assert_eq!(document.tree(), &parser.parse(b"a = 7; b = 2;").unwrap());
+}
+
+#[test]
+fn retokenize_test() {
+    let tokenizer = tokenizer();
+    let text = b"a = \"x\"; { b = 2; } c = \"y z\";".to_vec();
+    let (tokens, checkpoints) = tokenizer
+        .tokenize_with_checkpoints(&Code::new(&text))
+        .unwrap();
+    assert_eq!(tokens, tokenizer.tokenize(&Code::new(&text)).unwrap());
+
+    for (range, replacement) in [
+        (0..1, &b"abc"[..]),
+        (5..6, b"\" d = \""),
+        (11..17, b""),
+        (24..24, b"\""),
+        (text.len()..text.len(), b" d = 4;"),
+    ] {
+        let edit = TextEdit::new(range.clone(), replacement);
+        let mut edited = text.clone();
+        edited.splice(range, replacement.iter().copied());
+        let code = Code::new(&edited);
+
+        let (retokenized, _) = tokenizer
+            .retokenize(&code, &tokens, &checkpoints, &edit)
+            .unwrap();
+        assert_eq!(retokenized, tokenizer.tokenize(&code).unwrap());
+    }
+}
+
+#[cfg(feature = "profile")]
+#[test]
+fn reuse_cache_hits_test() {
+    let parser = triple_parser();
+    // The cached items before and after the edited number are reused except the identifier 'b' which examined the number.
+    let mut document = parser.parse_incremental(b"a 1 b 2 c 3").unwrap();
+    let (result, report) =
+        crate::with_profile(|| parser.reparse(&mut document, &TextEdit::new(6..7, b"22")));
+    result.unwrap();
+    assert_eq!(document.tree(), &parser.parse(b"a 1 b 22 c 3").unwrap());
+    assert_eq!(report.get("item").unwrap().cache_hits, 4);
+}
+
+#[test]
+fn reuse_cached_results_test() {
+    let parser = triple_parser();
+    assert_reparse(
+        &parser,
+        b"a b c 1 d",
+        &[
+            // The item 'a' ends before the edit but examined the edited token,
+            // so that it is parsed again as a statement instead of reusing the identifier.
+            TextEdit::new(4..5, b"2"),
+            // The statement is split again and the following items are reused after the shifted checkpoints.
+            TextEdit::new(4..5, b"x y"),
+            // The edit fails to parse.
+            TextEdit::new(0..1, b"="),
+            // The failed document is parsed again from the beginning.
+            TextEdit::new(0..1, b"z"),
+        ],
+    );
+}
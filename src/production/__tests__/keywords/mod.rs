@@ -0,0 +1,99 @@
+use crate::{
+    grammar::LexerPattern,
+    lexeme::{Keywords, Punctuations},
+    Code, ILexeme, ITokenization, Lex, MatchPolicy, Rc, TokenImpl, Tokenizer,
+};
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, PartialOrd, Ord)]
+enum Token {
+    ID,
+    In,
+    If,
+    Dot,
+    Eof,
+}
+impl TokenImpl for Token {
+    fn eof() -> Self {
+        Token::Eof
+    }
+
+    fn is_structural(&self) -> bool {
+        true
+    }
+}
+
+#[test]
+fn keyword_boundary_test() {
+    let keywords: Keywords<Token> =
+        Keywords::new(Token::ID, vec![("in", Token::In), ("if", Token::If)]).unwrap();
+    let dot = Punctuations::new(vec![(".", Token::Dot)]).unwrap();
+    let tokenizer = Tokenizer::new(vec![Rc::new(keywords), Rc::new(dot)]);
+    assert_eq!(
+        tokenizer
+            .tokenize(&Code::from("interface.in.if_.If"))
+            .unwrap(),
+        vec![
+            Lex::new(Token::ID, 0, 9),
+            Lex::new(Token::Dot, 9, 10),
+            Lex::new(Token::In, 10, 12),
+            Lex::new(Token::Dot, 12, 13),
+            Lex::new(Token::ID, 13, 16),
+            Lex::new(Token::Dot, 16, 17),
+            Lex::new(Token::ID, 17, 19),
+            Lex::new(Token::Eof, 19, 19),
+        ]
+    );
+    assert!(tokenizer.shadowed_lexemes().is_empty());
+}
+
+#[test]
+fn case_insensitive_test() {
+    let mut keywords: Keywords<Token> = Keywords::new(Token::ID, vec![("IN", Token::In)]).unwrap();
+    keywords.set_case_sensitive(false).unwrap();
+    let tokenizer = Tokenizer::new(vec![Rc::new(keywords)]);
+    assert_eq!(
+        tokenizer.tokenize(&Code::from("In")).unwrap(),
+        vec![Lex::new(Token::In, 0, 2), Lex::new(Token::Eof, 2, 2)]
+    );
+
+    let mut keywords: Keywords<Token> =
+        Keywords::new(Token::ID, vec![("if", Token::If), ("IF", Token::In)]).unwrap();
+    assert!(keywords.set_case_sensitive(false).is_err());
+    // The lexeme is unchanged after the failed update.
+    let tokenizer = Tokenizer::new(vec![Rc::new(keywords)]);
+    assert_eq!(
+        tokenizer.tokenize(&Code::from("IF")).unwrap(),
+        vec![Lex::new(Token::In, 0, 2), Lex::new(Token::Eof, 2, 2)]
+    );
+}
+
+#[test]
+fn identifier_class_test() {
+    let mut keywords: Keywords<Token> = Keywords::new(Token::ID, vec![("in", Token::In)]).unwrap();
+    assert_eq!(
+        keywords.set_identifier_class(r"[A-Z]", r"[A-Z]"),
+        Err(String::from(
+            "Keyword \"in\" is not matched by the identifier regex '^(?:[A-Z])(?:[A-Z])*'."
+        ))
+    );
+    assert!(keywords.set_identifier_class(r"\d?", r"\w").is_err());
+    keywords.set_identifier_class(r"[a-z]", r"[a-z.]").unwrap();
+    assert_eq!(
+        keywords.lexer_patterns(),
+        vec![
+            (Token::In, LexerPattern::Literal(String::from("in"))),
+            (
+                Token::ID,
+                LexerPattern::Regex(String::from("^(?:[a-z])(?:[a-z.])*"))
+            ),
+        ]
+    );
+
+    let mut tokenizer = Tokenizer::new(vec![Rc::new(keywords)]);
+    tokenizer.set_match_policy(MatchPolicy::LongestMatch);
+    assert_eq!(
+        tokenizer.tokenize(&Code::from("in.if")).unwrap(),
+        vec![Lex::new(Token::ID, 0, 5), Lex::new(Token::Eof, 5, 5)]
+    );
+    assert!(Keywords::<Token>::new(Token::ID, vec![("in", Token::In), ("in", Token::If)]).is_err());
+}
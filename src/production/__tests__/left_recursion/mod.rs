@@ -0,0 +1,225 @@
+use crate::{
+    lexeme::{Pattern, Punctuations},
+    production::{
+        Cacheable, Concat, ConstantField, EOFProd, Node, NonStructural, ProductionBuilder,
+        RegexField, TokenField, Union,
+    },
+    ASTNode, CacheKey, DefaultParser, LexerlessParser, NodeImpl, Rc, TokenImpl, Tokenizer,
+};
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, PartialOrd, Ord)]
+enum Token {
+    ID,
+    Number,
+    Minus,
+    Dot,
+    Space,
+    Eof,
+}
+impl TokenImpl for Token {
+    fn eof() -> Self {
+        Token::Eof
+    }
+
+    fn is_structural(&self) -> bool {
+        *self != Token::Space
+    }
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+enum NodeValue {
+    ID,
+    Number,
+    Sub,
+    Access,
+    Root,
+    Null,
+}
+
+impl NodeImpl for NodeValue {
+    fn null() -> Self {
+        Self::Null
+    }
+}
+
+fn tokenizer() -> Rc<Tokenizer<Token>> {
+    let punctuations =
+        Rc::new(Punctuations::new(vec![("-", Token::Minus), (".", Token::Dot)]).unwrap());
+    let space = Rc::new(Pattern::new(Token::Space, r"^\s+").unwrap());
+    let id = Rc::new(Pattern::new(Token::ID, r"^[a-z]+").unwrap());
+    let number = Rc::new(Pattern::new(Token::Number, r"^[0-9]+").unwrap());
+    Rc::new(Tokenizer::new(vec![space, punctuations, id, number]))
+}
+
+/// Format the parsed tree as a nested list of node values.
+fn format_tree(tree: &ASTNode<NodeValue>, text: &[u8]) -> String {
+    if tree.children.is_empty() {
+        String::from_utf8_lossy(&text[tree.start..tree.end]).to_string()
+    } else {
+        let children: Vec<String> = tree.children.iter().map(|c| format_tree(c, text)).collect();
+        format!("{:?}({})", tree.node, children.join(", "))
+    }
+}
+
+#[test]
+fn direct_left_recursion_test() {
+    let number = Rc::new(TokenField::new(Token::Number, Some(NodeValue::Number)));
+    let minus = Rc::new(TokenField::new(Token::Minus, None));
+    let eof = Rc::new(EOFProd::new(None));
+
+    // expr : expr '-' Number | Number
+    let expr = Rc::new(Union::init("expr"));
+    let sub = Rc::new(Concat::new(
+        "sub",
+        vec![expr.clone(), minus, number.clone()],
+    ));
+    expr.set_symbols(vec![Rc::new(Node::new(&sub, NodeValue::Sub)), number])
+        .unwrap();
+    expr.set_left_recursive(CacheKey::from(0)).unwrap();
+
+    let root = Rc::new(Concat::new("root", vec![expr, eof]).into_node(NodeValue::Root));
+    let parser = DefaultParser::new(tokenizer(), root).unwrap();
+
+    let text = b"1 - 2 - 3";
+    let tree = parser.parse(text).unwrap();
+    assert_eq!(format_tree(&tree[0], text), "Root(Sub(Sub(1, 2), 3))");
+
+    let text = b"1";
+    let tree = parser.parse(text).unwrap();
+    assert_eq!(format_tree(&tree[0], text), "Root(1)");
+
+    assert_eq!(parser.parse(b"1 - 2 -").unwrap_err().pointer, 7);
+}
+
+#[test]
+fn shared_cache_key_test() {
+    let number = Rc::new(TokenField::new(Token::Number, Some(NodeValue::Number)));
+    let minus = Rc::new(TokenField::new(Token::Minus, None));
+    let eof = Rc::new(EOFProd::new(None));
+
+    // The seeds of the left recursive production are not found by the cached production with the same key.
+    let cached_number = Rc::new(Cacheable::new(CacheKey::from(0), &number));
+    let expr = Rc::new(Union::init("expr"));
+    let sub = Rc::new(Concat::new("sub", vec![expr.clone(), minus, number]));
+    expr.set_symbols(vec![
+        Rc::new(Node::new(&sub, NodeValue::Sub)),
+        cached_number,
+    ])
+    .unwrap();
+    expr.set_left_recursive(CacheKey::from(0)).unwrap();
+
+    let root = Rc::new(Concat::new("root", vec![expr, eof]).into_node(NodeValue::Root));
+    let parser = DefaultParser::new(tokenizer(), root).unwrap();
+
+    let text = b"1 - 2 - 3";
+    let tree = parser.parse(text).unwrap();
+    assert_eq!(format_tree(&tree[0], text), "Root(Sub(Sub(1, 2), 3))");
+}
+
+#[test]
+fn indirect_left_recursion_test() {
+    let id = Rc::new(TokenField::new(Token::ID, Some(NodeValue::ID)));
+    let dot = Rc::new(TokenField::new(Token::Dot, None));
+    let eof = Rc::new(EOFProd::new(None));
+
+    // primary : access | ID
+    // access : primary '.' ID
+    let primary = Rc::new(Union::init("primary"));
+    let access = Rc::new(Concat::new(
+        "access",
+        vec![primary.clone(), dot, id.clone()],
+    ));
+    primary
+        .set_symbols(vec![Rc::new(Node::new(&access, NodeValue::Access)), id])
+        .unwrap();
+    primary.set_left_recursive(CacheKey::from(0)).unwrap();
+
+    let root = Rc::new(Concat::new("root", vec![primary, eof]).into_node(NodeValue::Root));
+    let parser = DefaultParser::new(tokenizer(), root).unwrap();
+
+    let text = b"a.b . c";
+    let tree = parser.parse(text).unwrap();
+    assert_eq!(format_tree(&tree[0], text), "Root(Access(Access(a, b), c))");
+}
+
+#[test]
+fn non_structural_left_recursion_test() {
+    // The seed of either production of the cycle is grown while consuming the non structural tokens.
+    for is_concat_recursive in [false, true] {
+        let space = Rc::new(Pattern::new(Token::Space, r"^ ").unwrap());
+        let id = Rc::new(Pattern::new(Token::ID, r"^[a-z]+").unwrap());
+        let tokenizer = Rc::new(Tokenizer::new(vec![space, id]));
+
+        let id = Rc::new(TokenField::new(Token::ID, Some(NodeValue::ID)));
+        let space = Rc::new(TokenField::new(Token::Space, Some(NodeValue::Number)));
+        let eof = Rc::new(EOFProd::new(None));
+
+        // spaces : spaces Space | Space
+        let spaces = Rc::new(Union::init("spaces"));
+        let access = Rc::new(Concat::new("access", vec![spaces.clone(), space.clone()]));
+        spaces
+            .set_symbols(vec![Rc::new(Node::new(&access, NodeValue::Access)), space])
+            .unwrap();
+        if is_concat_recursive {
+            access.set_left_recursive(CacheKey::from(0)).unwrap();
+        } else {
+            spaces.set_left_recursive(CacheKey::from(0)).unwrap();
+        }
+
+        let spaces = Rc::new(NonStructural::new(&spaces, true));
+        let root = Rc::new(
+            Concat::new("root", vec![id.clone(), spaces, id, eof]).into_node(NodeValue::Root),
+        );
+        let parser = DefaultParser::new(tokenizer, root).unwrap();
+
+        let text = b"a   b";
+        let tree = parser.parse(text).unwrap();
+        assert_eq!(
+            format_tree(&tree[0], text),
+            "Root(a, Access(Access( ,  ),  ), b)"
+        );
+    }
+}
+
+#[test]
+fn lexerless_left_recursion_test() {
+    let number = Rc::new(RegexField::new(r"^[0-9]+", Some(NodeValue::Number)).unwrap());
+    let minus = Rc::new(ConstantField::new("-", None));
+    let eof = Rc::new(EOFProd::new(None));
+
+    // expr : expr '-' Number | Number
+    let expr = Rc::new(Concat::init("expr"));
+    let sub = Rc::new(Union::new(
+        "sub",
+        vec![Rc::new(Node::new(&expr, NodeValue::Sub)), number.clone()],
+    ));
+    expr.set_symbols(vec![sub.clone(), minus, number]).unwrap();
+    sub.set_left_recursive(CacheKey::from(0)).unwrap();
+
+    let root = Rc::new(Concat::new("root", vec![sub, eof]).into_node(NodeValue::Root));
+    let parser = LexerlessParser::new(root).unwrap();
+
+    let text = b"10-2-3-4";
+    let tree = parser.parse(text).unwrap();
+    assert_eq!(
+        format_tree(&tree[0], text),
+        "Root(Sub(Sub(Sub(10, 2), 3), 4))"
+    );
+}
+
+#[test]
+fn unmarked_left_recursion_test() {
+    let number = Rc::new(TokenField::new(Token::Number, Some(NodeValue::Number)));
+    let minus = Rc::new(TokenField::new(Token::Minus, None));
+    let eof = Rc::new(EOFProd::new(None));
+
+    let expr = Rc::new(Union::init("expr"));
+    let sub = Rc::new(Concat::new(
+        "sub",
+        vec![expr.clone(), minus, number.clone()],
+    ));
+    expr.set_symbols(vec![sub, number]).unwrap();
+
+    let root = Rc::new(Concat::new("root", vec![expr, eof]));
+    assert!(DefaultParser::new(tokenizer(), root).is_err());
+}
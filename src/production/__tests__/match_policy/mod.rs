@@ -0,0 +1,181 @@
+use crate::{
+    grammar::LexerPattern,
+    lexeme::{Middleware, Pattern, Punctuations},
+    Code, CombinedTokenizer, ITokenization, Lex, MatchPolicy, Rc, ShadowedLexeme, TokenImpl,
+    Tokenizer,
+};
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, PartialOrd, Ord)]
+enum Token {
+    If,
+    ID,
+    Assign,
+    EQ,
+    Space,
+    Eof,
+}
+impl TokenImpl for Token {
+    fn eof() -> Self {
+        Token::Eof
+    }
+
+    fn is_structural(&self) -> bool {
+        *self != Token::Space
+    }
+}
+
+fn tokenizer(policy: MatchPolicy) -> Tokenizer<Token> {
+    let keyword: Pattern<Token> = Pattern::new(Token::If, r"^if").unwrap();
+    let identifier = Pattern::new(Token::ID, r"^[_$a-zA-Z][_$\w]*").unwrap();
+    let assign = Punctuations::new(vec![("=", Token::Assign)]).unwrap();
+    let eq = Punctuations::new(vec![("==", Token::EQ)]).unwrap();
+    let space = Pattern::new(Token::Space, r"^\s+").unwrap();
+    let mut tokenizer = Tokenizer::new(vec![
+        Rc::new(keyword),
+        Rc::new(identifier),
+        Rc::new(assign),
+        Rc::new(eq),
+        Rc::new(space),
+    ]);
+    tokenizer.set_match_policy(policy);
+    tokenizer
+}
+
+#[test]
+fn first_match_test() {
+    let tokens = tokenizer(MatchPolicy::FirstMatch)
+        .tokenize(&Code::from("ifx==if"))
+        .unwrap();
+    assert_eq!(
+        tokens,
+        vec![
+            Lex::new(Token::If, 0, 2),
+            Lex::new(Token::ID, 2, 3),
+            Lex::new(Token::Assign, 3, 4),
+            Lex::new(Token::Assign, 4, 5),
+            Lex::new(Token::If, 5, 7),
+            Lex::new(Token::Eof, 7, 7),
+        ]
+    );
+}
+
+#[test]
+fn longest_match_test() {
+    let tokens = tokenizer(MatchPolicy::LongestMatch)
+        .tokenize(&Code::from("ifx==if"))
+        .unwrap();
+    assert_eq!(
+        tokens,
+        vec![
+            Lex::new(Token::ID, 0, 3),
+            Lex::new(Token::EQ, 3, 5),
+            Lex::new(Token::If, 5, 7),
+            Lex::new(Token::Eof, 7, 7),
+        ]
+    );
+}
+
+#[test]
+fn priority_groups_test() {
+    // The keyword wins over the identifier while the punctuations are matched by the longest match.
+    let tokens = tokenizer(MatchPolicy::PriorityGroups(vec![1, 2]))
+        .tokenize(&Code::from("ifx==if"))
+        .unwrap();
+    assert_eq!(
+        tokens,
+        vec![
+            Lex::new(Token::If, 0, 2),
+            Lex::new(Token::ID, 2, 3),
+            Lex::new(Token::EQ, 3, 5),
+            Lex::new(Token::If, 5, 7),
+            Lex::new(Token::Eof, 7, 7),
+        ]
+    );
+}
+
+#[test]
+fn shadowed_lexemes_test() {
+    let first_match = tokenizer(MatchPolicy::FirstMatch);
+    assert_eq!(
+        first_match.shadowed_lexemes(),
+        vec![ShadowedLexeme {
+            state: String::from("0"),
+            index: 3,
+            patterns: vec![LexerPattern::Literal(String::from("=="))],
+            shadowed_by: vec![2],
+        }]
+    );
+    assert_eq!(
+        first_match.shadowed_lexemes()[0].to_string(),
+        "Lexeme 3 (\"==\") of state 0 never wins over the lexemes 2."
+    );
+    assert!(tokenizer(MatchPolicy::LongestMatch)
+        .shadowed_lexemes()
+        .is_empty());
+
+    let keyword: Pattern<Token> = Pattern::new(Token::If, r"^if").unwrap();
+    let identifier = Pattern::new(Token::ID, r"^[_$a-zA-Z][_$\w]*").unwrap();
+    let if_punctuation = Punctuations::new(vec![("if", Token::If)]).unwrap();
+    let mut combined_tokenizer: CombinedTokenizer<Token> =
+        CombinedTokenizer::new(0, vec![Rc::new(identifier), Rc::new(keyword)]);
+    combined_tokenizer.add_state(1, vec![Rc::new(if_punctuation)]);
+    combined_tokenizer.set_match_policy(MatchPolicy::LongestMatch);
+
+    // The regular expressions of the identifier and the keyword are not compared.
+    assert!(combined_tokenizer.shadowed_lexemes().is_empty());
+
+    let keyword = Punctuations::new(vec![("if", Token::If)]).unwrap();
+    let identifier = Pattern::new(Token::ID, r"^[_$a-zA-Z][_$\w]*").unwrap();
+    let mut tokenizer: Tokenizer<Token> =
+        Tokenizer::new(vec![Rc::new(identifier), Rc::new(keyword)]);
+    tokenizer.set_match_policy(MatchPolicy::LongestMatch);
+    assert_eq!(
+        tokenizer.shadowed_lexemes(),
+        vec![ShadowedLexeme {
+            state: String::from("0"),
+            index: 1,
+            patterns: vec![LexerPattern::Literal(String::from("if"))],
+            shadowed_by: vec![0],
+        }]
+    );
+}
+
+#[test]
+fn conditional_shadowed_lexemes_test() {
+    // The identifier rejected by the middleware does not shadow the keyword.
+    let identifier = Pattern::new(Token::ID, r"^[_$a-zA-Z][_$\w]*").unwrap();
+    let keyword = Punctuations::new(vec![("if", Token::If)]).unwrap();
+    let mut tokenizer: Tokenizer<Token> = Tokenizer::new(vec![
+        Rc::new(Middleware::new(identifier, |_, _| false)),
+        Rc::new(keyword),
+    ]);
+    assert!(tokenizer.shadowed_lexemes().is_empty());
+    assert_eq!(
+        tokenizer.tokenize(&Code::from("if")).unwrap(),
+        vec![Lex::new(Token::If, 0, 2), Lex::new(Token::Eof, 2, 2)]
+    );
+    tokenizer.set_match_policy(MatchPolicy::LongestMatch);
+    assert!(tokenizer.shadowed_lexemes().is_empty());
+
+    // A conditional lexeme is still shadowed by an unconditional lexeme.
+    let identifier = Pattern::new(Token::ID, r"^[_$a-zA-Z][_$\w]*").unwrap();
+    let keyword = Punctuations::new(vec![("if", Token::If)]).unwrap();
+    let tokenizer: Tokenizer<Token> = Tokenizer::new(vec![
+        Rc::new(identifier),
+        Rc::new(Middleware::new(keyword, |_, _| true)),
+    ]);
+    let shadowed = tokenizer.shadowed_lexemes();
+    assert_eq!(
+        shadowed,
+        vec![ShadowedLexeme {
+            state: String::from("0"),
+            index: 1,
+            patterns: vec![LexerPattern::Literal(String::from("if")).conditional()],
+            shadowed_by: vec![0],
+        }]
+    );
+    assert_eq!(
+        shadowed[0].to_string(),
+        "Lexeme 1 (\"if\" (conditional)) of state 0 never wins over the lexemes 0."
+    );
+}
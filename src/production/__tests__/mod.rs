@@ -1 +1,22 @@
+mod cst;
+mod grammar;
+mod grammar_analysis;
+mod grammar_dsl;
+mod incremental;
+mod keywords;
+mod left_recursion;
+mod match_policy;
+mod nested_comment;
+mod not_lookahead;
+mod pattern_scan;
+mod precedence;
+#[cfg(feature = "profile")]
+mod profile;
+mod recovery;
+mod repeat;
+mod sentence_generator;
+mod serialization;
+mod trace;
+mod unicode;
 mod validations;
+mod visitor;
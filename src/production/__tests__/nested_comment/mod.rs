@@ -0,0 +1,249 @@
+use crate::{
+    lexeme::{
+        Action, Mapper, Middleware, NestedComment, Pattern, Punctuations, StateMixin, ThunkMapper,
+        ThunkStateMixin,
+    },
+    Code, CombinedTokenizer, ILexeme, ITokenization, Lex, MatchPolicy, ParseErrorKind, Rc,
+    TextEdit, TokenImpl, Tokenizer,
+};
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, PartialOrd, Ord)]
+enum Token {
+    ID,
+    Comment,
+    CommentOpen,
+    CommentClose,
+    OpenParen,
+    Mul,
+    CloseParen,
+    Space,
+    Eof,
+}
+impl TokenImpl for Token {
+    fn eof() -> Self {
+        Token::Eof
+    }
+
+    fn is_structural(&self) -> bool {
+        !matches!(
+            self,
+            Token::Space | Token::Comment | Token::CommentOpen | Token::CommentClose
+        )
+    }
+}
+
+fn tokenizer(comment: NestedComment<Token>) -> Tokenizer<Token> {
+    let punctuations = Punctuations::new(vec![
+        ("(", Token::OpenParen),
+        ("*", Token::Mul),
+        (")", Token::CloseParen),
+    ])
+    .unwrap();
+    Tokenizer::new(vec![
+        Rc::new(comment),
+        Rc::new(Pattern::new(Token::ID, r"^[a-z]+").unwrap()),
+        Rc::new(Pattern::new(Token::Space, r"^\s+").unwrap()),
+        Rc::new(punctuations),
+    ])
+}
+
+#[test]
+fn single_token_test() {
+    let tokenizer = tokenizer(NestedComment::new(Token::Comment, "(*", "*)").unwrap());
+    assert_eq!(
+        tokenizer
+            .tokenize(&Code::from("a (* b (* c *) d *) (*)*) e"))
+            .unwrap(),
+        vec![
+            Lex::new(Token::ID, 0, 1),
+            Lex::new(Token::Space, 1, 2),
+            Lex::new(Token::Comment, 2, 19),
+            Lex::new(Token::Space, 19, 20),
+            Lex::new(Token::Comment, 20, 25),
+            Lex::new(Token::Space, 25, 26),
+            Lex::new(Token::ID, 26, 27),
+            Lex::new(Token::Eof, 27, 27),
+        ]
+    );
+}
+
+#[test]
+fn separate_tokens_test() {
+    let mut comment = NestedComment::new(Token::Comment, "(*", "*)").unwrap();
+    assert!(comment
+        .set_delimiter_tokens(Token::Comment, Token::CommentClose, 1)
+        .is_err());
+    comment
+        .set_delimiter_tokens(Token::CommentOpen, Token::CommentClose, 1)
+        .unwrap();
+    let tokenizer = tokenizer(comment);
+    assert_eq!(
+        tokenizer
+            .tokenize(&Code::from("(* b (* c *)*)(**)a"))
+            .unwrap(),
+        vec![
+            Lex::new(Token::CommentOpen, 0, 2),
+            Lex::new(Token::Comment, 2, 5),
+            Lex::new(Token::CommentOpen, 5, 7),
+            Lex::new(Token::Comment, 7, 10),
+            Lex::new(Token::CommentClose, 10, 12),
+            Lex::new(Token::CommentClose, 12, 14),
+            Lex::new(Token::CommentOpen, 14, 16),
+            Lex::new(Token::CommentClose, 16, 18),
+            Lex::new(Token::ID, 18, 19),
+            Lex::new(Token::Eof, 19, 19),
+        ]
+    );
+    // A closing delimiter outside of the comment is not a comment token.
+    assert_eq!(
+        tokenizer.tokenize(&Code::from("*)")).unwrap(),
+        vec![
+            Lex::new(Token::Mul, 0, 1),
+            Lex::new(Token::CloseParen, 1, 2),
+            Lex::new(Token::Eof, 2, 2),
+        ]
+    );
+}
+
+#[test]
+fn separate_tokens_order_test() {
+    // The lexemes before the comment lexeme may match the text within the comment.
+    let separate_comment = || {
+        let mut comment = NestedComment::new(Token::Comment, "/*", "*/").unwrap();
+        comment
+            .set_delimiter_tokens(Token::CommentOpen, Token::CommentClose, 1)
+            .unwrap();
+        Rc::new(comment)
+    };
+    let tokenizer: Tokenizer<Token> = Tokenizer::new(vec![
+        Rc::new(Pattern::new(Token::Space, r"^\s+").unwrap()),
+        separate_comment(),
+        Rc::new(Pattern::new(Token::ID, r"^[a-z]+").unwrap()),
+    ]);
+    assert_eq!(
+        tokenizer
+            .tokenize(&Code::from("a /* x /* y */ z */ b"))
+            .unwrap(),
+        vec![
+            Lex::new(Token::ID, 0, 1),
+            Lex::new(Token::Space, 1, 2),
+            Lex::new(Token::CommentOpen, 2, 4),
+            Lex::new(Token::Space, 4, 5),
+            Lex::new(Token::Comment, 5, 7),
+            Lex::new(Token::CommentOpen, 7, 9),
+            Lex::new(Token::Space, 9, 10),
+            Lex::new(Token::Comment, 10, 12),
+            Lex::new(Token::CommentClose, 12, 14),
+            Lex::new(Token::Space, 14, 15),
+            Lex::new(Token::Comment, 15, 17),
+            Lex::new(Token::CommentClose, 17, 19),
+            Lex::new(Token::Space, 19, 20),
+            Lex::new(Token::ID, 20, 21),
+            Lex::new(Token::Eof, 21, 21),
+        ]
+    );
+    let err = tokenizer.tokenize(&Code::from("/* a */ /* b")).unwrap_err();
+    assert_eq!(err.span, 8..10);
+    assert_eq!(
+        err.message,
+        "Unterminated comment: '/*' is not closed with '*/'."
+    );
+
+    // The comment state of a combined tokenizer matches the text within the comment only with the comment lexeme.
+    let mut combined_tokenizer = CombinedTokenizer::new(
+        0,
+        vec![
+            Rc::new(Pattern::new(Token::Space, r"^\s+").unwrap()),
+            separate_comment(),
+            Rc::new(Pattern::new(Token::ID, r"^[a-z]+").unwrap()),
+        ],
+    );
+    combined_tokenizer.add_state(1, vec![separate_comment()]);
+    assert_eq!(
+        combined_tokenizer
+            .tokenize(&Code::from("/* x /**/ */b"))
+            .unwrap(),
+        vec![
+            Lex::new(Token::CommentOpen, 0, 2),
+            Lex::new(Token::Comment, 2, 5),
+            Lex::new(Token::CommentOpen, 5, 7),
+            Lex::new(Token::CommentClose, 7, 9),
+            Lex::new(Token::Comment, 9, 10),
+            Lex::new(Token::CommentClose, 10, 12),
+            Lex::new(Token::ID, 12, 13),
+            Lex::new(Token::Eof, 13, 13),
+        ]
+    );
+}
+
+#[test]
+fn unterminated_comment_test() {
+    let mut tokenizer = tokenizer(NestedComment::new(Token::Comment, "(*", "*)").unwrap());
+    for policy in [MatchPolicy::FirstMatch, MatchPolicy::LongestMatch] {
+        tokenizer.set_match_policy(policy);
+        let err = tokenizer
+            .tokenize(&Code::from("a\n (* b (* c *) d"))
+            .unwrap_err();
+        assert_eq!(err.kind, ParseErrorKind::Tokenization);
+        assert_eq!(err.span, 3..5);
+        assert_eq!((err.start.line, err.start.column), (2, 2));
+        assert_eq!(
+            err.message,
+            "Unterminated comment: '(*' is not closed with '*)'."
+        );
+    }
+
+    let comment: NestedComment<Token> = NestedComment::new(Token::Comment, "(*", "*)").unwrap();
+    let space = Pattern::new(Token::Space, r"^\s+").unwrap();
+    let combined_tokenizer = CombinedTokenizer::new(0, vec![Rc::new(space), Rc::new(comment)]);
+    let (tokens, checkpoints) = combined_tokenizer
+        .tokenize_with_checkpoints(&Code::from(" (* *)"))
+        .unwrap();
+    let err = combined_tokenizer
+        .retokenize(
+            &Code::from(" (* *"),
+            &tokens,
+            &checkpoints,
+            &TextEdit::new(5..6, b""),
+        )
+        .unwrap_err();
+    assert_eq!(err.span, 1..3);
+
+    assert!(NestedComment::<Token>::new(Token::Comment, "\"\"\"", "\"\"\"").is_err());
+    assert!(NestedComment::<Token>::new(Token::Comment, "", "*)").is_err());
+}
+
+#[test]
+fn wrapped_unterminated_comment_test() {
+    let comment = || NestedComment::new(Token::Comment, "(*", "*)").unwrap();
+    let wrapped: Vec<Rc<dyn ILexeme<Token = Token, State = u8>>> = vec![
+        Rc::new(Middleware::new(comment(), |_, _| true)),
+        Rc::new(StateMixin::new(
+            comment(),
+            vec![(Token::Comment, Action::None { discard: false })],
+        )),
+        Rc::new(ThunkStateMixin::new(comment(), |_, _, _| Action::None {
+            discard: false,
+        })),
+        Rc::new(Mapper::new(comment(), vec![("(**)", Token::ID)]).unwrap()),
+        Rc::new(ThunkMapper::new(comment(), |_, _, _| None)),
+    ];
+    for lexeme in wrapped {
+        let punctuations = Punctuations::new(vec![
+            ("(", Token::OpenParen),
+            ("*", Token::Mul),
+            (")", Token::CloseParen),
+        ])
+        .unwrap();
+        let space = Pattern::new(Token::Space, r"^\s+").unwrap();
+        let tokenizer =
+            CombinedTokenizer::new(0, vec![lexeme, Rc::new(space), Rc::new(punctuations)]);
+        let err = tokenizer.tokenize(&Code::from(" (* (* *)")).unwrap_err();
+        assert_eq!(err.kind, ParseErrorKind::Tokenization);
+        assert_eq!(err.span, 1..3);
+        assert_eq!(
+            err.message,
+            "Unterminated comment: '(*' is not closed with '*)'."
+        );
+    }
+}
@@ -0,0 +1,186 @@
+use crate::{
+    lexeme::{Pattern, Punctuations},
+    production::{
+        Concat, ConstantField, EOFProd, List, NotLookahead, ProductionBuilder, RegexField,
+        TokenField, Union,
+    },
+    DefaultParser, IProduction, LexerlessParser, NodeImpl, Rc, TokenImpl, Tokenizer,
+};
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, PartialOrd, Ord)]
+enum Token {
+    ID,
+    OpenParen,
+    CloseParen,
+    Semicolon,
+    Space,
+    Eof,
+}
+impl TokenImpl for Token {
+    fn eof() -> Self {
+        Token::Eof
+    }
+
+    fn is_structural(&self) -> bool {
+        *self != Token::Space
+    }
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+enum NodeValue {
+    ID,
+    Call,
+    Variable,
+    Keyword,
+    NotCall,
+    Null,
+}
+
+impl NodeImpl for NodeValue {
+    fn null() -> Self {
+        Self::Null
+    }
+}
+
+fn tokenizer() -> Rc<Tokenizer<Token>> {
+    let punctuations = Rc::new(
+        Punctuations::new(vec![
+            ("(", Token::OpenParen),
+            (")", Token::CloseParen),
+            (";", Token::Semicolon),
+        ])
+        .unwrap(),
+    );
+    let space = Rc::new(Pattern::new(Token::Space, r"^\s+").unwrap());
+    let id = Rc::new(Pattern::new(Token::ID, r"^[a-z]+").unwrap());
+    Rc::new(Tokenizer::new(vec![space, punctuations, id]))
+}
+
+#[test]
+fn not_followed_by_test() {
+    let id = Rc::new(TokenField::new(Token::ID, Some(NodeValue::ID)));
+    let open_paren = Rc::new(TokenField::new(Token::OpenParen, None));
+    let close_paren = Rc::new(TokenField::new(Token::CloseParen, None));
+    let semicolon = Rc::new(TokenField::new(Token::Semicolon, None));
+    let eof = Rc::new(EOFProd::new(None));
+
+    // An identifier which is not followed by `(`.
+    let variable = Rc::new(
+        Concat::new(
+            "variable",
+            vec![
+                id.clone(),
+                Rc::new(NotLookahead::new(&open_paren, Some(NodeValue::NotCall))),
+            ],
+        )
+        .into_node(NodeValue::Variable),
+    );
+    let call =
+        Rc::new(Concat::new("call", vec![id, open_paren, close_paren]).into_node(NodeValue::Call));
+    let statement = Rc::new(Concat::new(
+        "statement",
+        vec![
+            Rc::new(Union::new("expression", vec![variable, call])),
+            semicolon,
+        ],
+    ));
+    let root = Rc::new(Concat::new(
+        "root",
+        vec![Rc::new(List::new(&statement)), eof],
+    ));
+    let parser = DefaultParser::new(tokenizer(), root).unwrap();
+
+    let tree = parser.parse(b"a; b(); c ;").unwrap();
+    let nodes: Vec<NodeValue> = tree.iter().map(|t| t.node).collect();
+    assert_eq!(
+        nodes,
+        vec![NodeValue::Variable, NodeValue::Call, NodeValue::Variable]
+    );
+    let not_call = &tree[2].children[1];
+    assert_eq!(not_call.node, NodeValue::NotCall);
+    assert_eq!((not_call.start, not_call.end), (10, 10));
+
+    // The `(` is not reported as expected after an identifier.
+    let err = parser.parse(b"a b").unwrap_err();
+    assert_eq!(err.pointer, 2);
+    assert!(!format!("{:?}", err.expected).contains("OpenParen"));
+}
+
+#[test]
+fn rejected_error_pointer_test() {
+    let id = Rc::new(TokenField::new(Token::ID, Some(NodeValue::ID)));
+    let eof = Rc::new(EOFProd::new(None));
+
+    // The statement should not start with three identifiers.
+    let three_ids = Rc::new(Concat::new(
+        "three_ids",
+        vec![id.clone(), id.clone(), id.clone()],
+    ));
+    let root = Rc::new(Concat::new(
+        "root",
+        vec![
+            Rc::new(NotLookahead::new(&three_ids, None)),
+            Rc::new(List::new(&id)),
+            eof,
+        ],
+    ));
+    let parser = DefaultParser::new(tokenizer(), root).unwrap();
+
+    assert_eq!(parser.parse(b"a b").unwrap().len(), 2);
+    // The error is reported at the rejected identifiers rather than the end of them.
+    assert_eq!(parser.parse(b"a b c").unwrap_err().pointer, 0);
+}
+
+#[test]
+fn lexerless_keyword_test() {
+    let id = Rc::new(RegexField::new(r"^[a-z]+", Some(NodeValue::ID)).unwrap());
+    let space = Rc::new(RegexField::new(r"^\s+", None).unwrap());
+    let eof = Rc::new(EOFProd::new(None));
+
+    let keyword = Rc::new(Concat::new(
+        "keyword",
+        vec![
+            Rc::new(ConstantField::new("let", Some(NodeValue::Keyword))),
+            Rc::new(
+                RegexField::new(r"^[a-z]", None)
+                    .unwrap()
+                    .into_not_lookahead(None),
+            ),
+        ],
+    ));
+    let word = Rc::new(Concat::new(
+        "word",
+        vec![
+            Rc::new(Union::new("word_type", vec![keyword, id])),
+            space.clone(),
+        ],
+    ));
+    let root = Rc::new(Concat::new("root", vec![Rc::new(List::new(&word)), eof]));
+    let parser = LexerlessParser::new(root).unwrap();
+
+    let tree = parser.parse(b"let letter lets let ").unwrap();
+    let nodes: Vec<NodeValue> = tree.iter().map(|t| t.node).collect();
+    assert_eq!(
+        nodes,
+        vec![
+            NodeValue::Keyword,
+            NodeValue::ID,
+            NodeValue::ID,
+            NodeValue::Keyword
+        ]
+    );
+}
+
+#[test]
+fn not_lookahead_first_set_test() {
+    let id = Rc::new(TokenField::new(Token::ID, Some(NodeValue::ID)));
+    let open_paren = Rc::new(TokenField::new(Token::OpenParen, None));
+    let not_paren = Rc::new(NotLookahead::new(&open_paren, None));
+    assert!(not_paren.is_nullable());
+
+    let variable = Concat::new("variable", vec![not_paren.clone(), id]);
+    let mut first_set = std::collections::HashSet::new();
+    variable.impl_first_set(&mut first_set);
+    assert_eq!(first_set.into_iter().collect::<Vec<_>>(), vec![Token::ID]);
+    assert_eq!(format!("{}", not_paren), "?![&OpenParen; ]");
+}
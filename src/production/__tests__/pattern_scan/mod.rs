@@ -0,0 +1,118 @@
+use crate::{
+    lexeme::{Pattern, Punctuations},
+    Code, CombinedTokenizer, ILexeme, ITokenization, Lex, Log, MatchPolicy, Rc, TokenImpl,
+    Tokenizer,
+};
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, PartialOrd, Ord)]
+enum Token {
+    If,
+    ID,
+    Number,
+    Float,
+    Assign,
+    EQ,
+    Space,
+    Eof,
+}
+impl TokenImpl for Token {
+    fn eof() -> Self {
+        Token::Eof
+    }
+
+    fn is_structural(&self) -> bool {
+        *self != Token::Space
+    }
+}
+
+fn lexemes(logged: bool) -> Vec<Rc<dyn ILexeme<Token = Token, State = u8>>> {
+    let keyword: Pattern<Token> = Pattern::new(Token::If, r"^if").unwrap();
+    let identifier = Pattern::new(Token::ID, r"^[_$a-zA-Z][_$\w]*").unwrap();
+    let number = Pattern::new(Token::Number, r"^\d+").unwrap();
+    let float = Pattern::new(Token::Float, r"^\d+\.\d+").unwrap();
+    let punctuations = Punctuations::new(vec![("=", Token::Assign), ("==", Token::EQ)]).unwrap();
+    let space = Pattern::new(Token::Space, r"^\s+").unwrap();
+    if logged {
+        // A logged pattern is not merged into the scan of the tokenizer.
+        identifier.set_log(Log::None).unwrap();
+        number.set_log(Log::None).unwrap();
+    }
+    vec![
+        Rc::new(keyword),
+        Rc::new(identifier),
+        Rc::new(punctuations),
+        Rc::new(float),
+        Rc::new(number),
+        Rc::new(space),
+    ]
+}
+
+const CODE: &str = "ifx == 12.5 if y=3";
+
+#[test]
+fn merged_first_match_test() {
+    let expected = vec![
+        Lex::new(Token::If, 0, 2),
+        Lex::new(Token::ID, 2, 3),
+        Lex::new(Token::Space, 3, 4),
+        Lex::new(Token::EQ, 4, 6),
+        Lex::new(Token::Space, 6, 7),
+        Lex::new(Token::Float, 7, 11),
+        Lex::new(Token::Space, 11, 12),
+        Lex::new(Token::If, 12, 14),
+        Lex::new(Token::Space, 14, 15),
+        Lex::new(Token::ID, 15, 16),
+        Lex::new(Token::Assign, 16, 17),
+        Lex::new(Token::Number, 17, 18),
+        Lex::new(Token::Eof, 18, 18),
+    ];
+    let merged = Tokenizer::new(lexemes(false));
+    assert_eq!(merged.tokenize(&Code::from(CODE)).unwrap(), expected);
+
+    let separate = Tokenizer::new(lexemes(true));
+    assert_eq!(separate.tokenize(&Code::from(CODE)).unwrap(), expected);
+}
+
+#[test]
+fn merged_longest_match_test() {
+    let mut tokenizer = Tokenizer::new(lexemes(false));
+    tokenizer.set_match_policy(MatchPolicy::LongestMatch);
+    assert_eq!(
+        tokenizer.tokenize(&Code::from(CODE)).unwrap(),
+        vec![
+            Lex::new(Token::ID, 0, 3),
+            Lex::new(Token::Space, 3, 4),
+            Lex::new(Token::EQ, 4, 6),
+            Lex::new(Token::Space, 6, 7),
+            Lex::new(Token::Float, 7, 11),
+            Lex::new(Token::Space, 11, 12),
+            Lex::new(Token::If, 12, 14),
+            Lex::new(Token::Space, 14, 15),
+            Lex::new(Token::ID, 15, 16),
+            Lex::new(Token::Assign, 16, 17),
+            Lex::new(Token::Number, 17, 18),
+            Lex::new(Token::Eof, 18, 18),
+        ]
+    );
+}
+
+#[test]
+fn merged_states_test() {
+    let digits: Pattern<Token> = Pattern::new(Token::Number, r"^\d").unwrap();
+    let letters = Pattern::new(Token::ID, r"^[a-z]").unwrap();
+    let mut tokenizer = CombinedTokenizer::new(0, lexemes(false));
+    tokenizer.add_state(1, vec![Rc::new(digits), Rc::new(letters)]);
+    let (tokens, checkpoints) = tokenizer
+        .tokenize_with_checkpoints(&Code::from("if 12"))
+        .unwrap();
+    assert_eq!(
+        tokens,
+        vec![
+            Lex::new(Token::If, 0, 2),
+            Lex::new(Token::Space, 2, 3),
+            Lex::new(Token::Number, 3, 5),
+            Lex::new(Token::Eof, 5, 5),
+        ]
+    );
+    assert_eq!(checkpoints, vec![0, 1, 2]);
+}
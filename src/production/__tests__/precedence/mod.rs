@@ -0,0 +1,200 @@
+use crate::{
+    lexeme::{Pattern, Punctuations},
+    production::{
+        Associativity, Concat, ConstantField, EOFProd, Nullable, Operator, PrecedenceClimb,
+        ProductionBuilder, RegexField, TokenField, Union,
+    },
+    ASTNode, DefaultParser, LexerlessParser, NodeImpl, Rc, TokenImpl, Tokenizer,
+};
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, PartialOrd, Ord)]
+enum Token {
+    Number,
+    Add,
+    Sub,
+    Mul,
+    Pow,
+    Not,
+    OpenParen,
+    CloseParen,
+    Space,
+    Eof,
+}
+impl TokenImpl for Token {
+    fn eof() -> Self {
+        Token::Eof
+    }
+
+    fn is_structural(&self) -> bool {
+        *self != Token::Space
+    }
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+enum NodeValue {
+    Number,
+    Add,
+    Sub,
+    Mul,
+    Pow,
+    Neg,
+    Fact,
+    Root,
+    Null,
+}
+
+impl NodeImpl for NodeValue {
+    fn null() -> Self {
+        Self::Null
+    }
+}
+
+fn tokenizer() -> Rc<Tokenizer<Token>> {
+    let punctuations = Rc::new(
+        Punctuations::new(vec![
+            ("+", Token::Add),
+            ("-", Token::Sub),
+            ("*", Token::Mul),
+            ("^", Token::Pow),
+            ("!", Token::Not),
+            ("(", Token::OpenParen),
+            (")", Token::CloseParen),
+        ])
+        .unwrap(),
+    );
+    let space = Rc::new(Pattern::new(Token::Space, r"^\s+").unwrap());
+    let number = Rc::new(Pattern::new(Token::Number, r"^[0-9]+").unwrap());
+    Rc::new(Tokenizer::new(vec![space, punctuations, number]))
+}
+
+/// Format the parsed tree as a nested list of node values.
+fn format_tree(tree: &ASTNode<NodeValue>, text: &[u8]) -> String {
+    if tree.children.is_empty() {
+        String::from_utf8_lossy(&text[tree.start..tree.end]).to_string()
+    } else {
+        let children: Vec<String> = tree.children.iter().map(|c| format_tree(c, text)).collect();
+        format!("{:?}({})", tree.node, children.join(", "))
+    }
+}
+
+fn parser() -> DefaultParser<NodeValue, Token> {
+    let number = Rc::new(TokenField::new(Token::Number, Some(NodeValue::Number)));
+    let open_paren = Rc::new(TokenField::new(Token::OpenParen, None));
+    let close_paren = Rc::new(TokenField::new(Token::CloseParen, None));
+    let add = Rc::new(TokenField::new(Token::Add, None));
+    let sub = Rc::new(TokenField::new(Token::Sub, None));
+    let mul = Rc::new(TokenField::new(Token::Mul, None));
+    let pow = Rc::new(TokenField::new(Token::Pow, None));
+    let not = Rc::new(TokenField::new(Token::Not, None));
+    let eof = Rc::new(EOFProd::new(None));
+
+    let parenthesis = Rc::new(Concat::init("parenthesis"));
+    let operand = Rc::new(Union::new("operand", vec![number, parenthesis.clone()]));
+    let expression = Rc::new(PrecedenceClimb::new(
+        "expression",
+        &operand,
+        vec![Operator::new(
+            sub.clone(),
+            3,
+            Associativity::Right,
+            NodeValue::Neg,
+        )],
+        vec![
+            Operator::new(add, 1, Associativity::Left, NodeValue::Add),
+            Operator::new(sub, 1, Associativity::Left, NodeValue::Sub),
+            Operator::new(mul, 2, Associativity::Left, NodeValue::Mul),
+            Operator::new(pow, 4, Associativity::Right, NodeValue::Pow),
+        ],
+        vec![Operator::new(not, 5, Associativity::Left, NodeValue::Fact)],
+    ));
+    parenthesis
+        .set_symbols(vec![open_paren, expression.clone(), close_paren])
+        .unwrap();
+
+    let root = Rc::new(Concat::new("root", vec![expression, eof]).into_node(NodeValue::Root));
+    DefaultParser::new(tokenizer(), root).unwrap()
+}
+
+#[test]
+fn precedence_test() {
+    let parser = parser();
+    for (text, expected) in [
+        ("1", "Root(1)"),
+        ("1 + 2 * 3", "Root(Add(1, Mul(2, 3)))"),
+        ("1 * 2 + 3", "Root(Add(Mul(1, 2), 3))"),
+        ("(1 + 2) * 3", "Root(Mul(Add(1, 2), 3))"),
+        ("1 - 2 + 3", "Root(Add(Sub(1, 2), 3))"),
+        ("2 ^ 3 ^ 4", "Root(Pow(2, Pow(3, 4)))"),
+        ("-2 ^ 3", "Root(Neg(Pow(2, 3)))"),
+        ("-2 * 3", "Root(Mul(Neg(2), 3))"),
+        ("- -2!", "Root(Neg(Neg(Fact(2))))"),
+        ("1 - -2! * 3", "Root(Sub(1, Mul(Neg(Fact(2)), 3)))"),
+        ("2 ^ 3!", "Root(Pow(2, Fact(3)))"),
+    ] {
+        let tree = parser.parse(text.as_bytes()).unwrap();
+        assert_eq!(format_tree(&tree[0], text.as_bytes()), expected, "{}", text);
+    }
+}
+
+#[test]
+fn precedence_error_test() {
+    let parser = parser();
+    let err = parser.parse(b"1 + * 2").unwrap_err();
+    assert_eq!(err.pointer, 4);
+
+    let err = parser.parse(b"(1 + 2").unwrap_err();
+    assert_eq!(err.pointer, 6);
+}
+
+#[test]
+fn lexerless_precedence_test() {
+    let number = Rc::new(RegexField::new(r"^[0-9]+", Some(NodeValue::Number)).unwrap());
+    let add = Rc::new(ConstantField::new("+", None));
+    let mul = Rc::new(ConstantField::new("*", None));
+    let pow = Rc::new(ConstantField::new("^", None));
+    let eof = Rc::new(EOFProd::new(None));
+
+    let expression = Rc::new(PrecedenceClimb::init("expression", &number));
+    expression
+        .set_operators(
+            vec![],
+            vec![
+                Operator::new(add, 1, Associativity::Left, NodeValue::Add),
+                Operator::new(mul, 2, Associativity::Left, NodeValue::Mul),
+                Operator::new(pow, 3, Associativity::Right, NodeValue::Pow),
+            ],
+            vec![],
+        )
+        .unwrap();
+    let root = Rc::new(Concat::new("root", vec![expression, eof]).into_node(NodeValue::Root));
+    let parser = LexerlessParser::new(root).unwrap();
+
+    let text = b"1+2*3^4^5+6";
+    let tree = parser.parse(text).unwrap();
+    assert_eq!(
+        format_tree(&tree[0], text),
+        "Root(Add(Add(1, Mul(2, Pow(3, Pow(4, 5)))), 6))"
+    );
+}
+
+#[test]
+fn nullable_operator_test() {
+    let number = Rc::new(TokenField::new(Token::Number, Some(NodeValue::Number)));
+    let add = Rc::new(TokenField::new(Token::Add, None));
+    let eof = Rc::new(EOFProd::new(None));
+
+    let expression = Rc::new(PrecedenceClimb::new(
+        "expression",
+        &number,
+        vec![],
+        vec![Operator::new(
+            Rc::new(Nullable::new(&add)),
+            1,
+            Associativity::Left,
+            NodeValue::Add,
+        )],
+        vec![],
+    ));
+    let root = Rc::new(Concat::new("root", vec![expression, eof]));
+    assert!(DefaultParser::new(tokenizer(), root).is_err());
+}
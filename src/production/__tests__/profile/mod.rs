@@ -0,0 +1,168 @@
+use crate::{
+    lexeme::{Pattern, Punctuations},
+    production::{Cacheable, Concat, EOFProd, List, TokenField, Union},
+    CacheKey, DefaultParser, NodeImpl, Rc, TokenImpl, Tokenizer,
+};
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, PartialOrd, Ord)]
+enum Token {
+    ID,
+    Number,
+    OpenParen,
+    CloseParen,
+    Assign,
+    Semicolon,
+    Space,
+    Eof,
+}
+impl TokenImpl for Token {
+    fn eof() -> Self {
+        Token::Eof
+    }
+
+    fn is_structural(&self) -> bool {
+        *self != Token::Space
+    }
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+enum NodeValue {
+    ID,
+    Number,
+    Null,
+}
+
+impl NodeImpl for NodeValue {
+    fn null() -> Self {
+        Self::Null
+    }
+}
+
+fn parser() -> DefaultParser<NodeValue, Token> {
+    let punctuations = Rc::new(
+        Punctuations::new(vec![
+            ("(", Token::OpenParen),
+            (")", Token::CloseParen),
+            ("=", Token::Assign),
+            (";", Token::Semicolon),
+        ])
+        .unwrap(),
+    );
+    let space = Rc::new(Pattern::new(Token::Space, r"^\s+").unwrap());
+    let id = Rc::new(Pattern::new(Token::ID, r"^[a-z]+").unwrap());
+    let number = Rc::new(Pattern::new(Token::Number, r"^[0-9]+").unwrap());
+    let tokenizer = Rc::new(Tokenizer::new(vec![space, punctuations, id, number]));
+
+    let id = Rc::new(TokenField::new(Token::ID, Some(NodeValue::ID)));
+    let number = Rc::new(TokenField::new(Token::Number, Some(NodeValue::Number)));
+    let target = Rc::new(Concat::new("target", vec![id]));
+    let cached_target = Rc::new(Cacheable::new(CacheKey::from(0), &target));
+
+    let call = Rc::new(Concat::new(
+        "call",
+        vec![
+            cached_target.clone(),
+            Rc::new(TokenField::new(Token::OpenParen, None)),
+            Rc::new(TokenField::new(Token::CloseParen, None)),
+        ],
+    ));
+    let assignment = Rc::new(Concat::new(
+        "assignment",
+        vec![
+            cached_target.clone(),
+            Rc::new(TokenField::new(Token::Assign, None)),
+            number,
+        ],
+    ));
+    let variable = Rc::new(Concat::new("variable", vec![cached_target]));
+    let statement = Rc::new(Concat::new(
+        "statement",
+        vec![
+            Rc::new(Union::new("expression", vec![call, assignment, variable])),
+            Rc::new(TokenField::new(Token::Semicolon, None)),
+        ],
+    ));
+    let root = Rc::new(Concat::new(
+        "root",
+        vec![Rc::new(List::new(&statement)), Rc::new(EOFProd::new(None))],
+    ));
+    DefaultParser::new(tokenizer, root).unwrap()
+}
+
+#[test]
+fn profile_report_test() {
+    let parser = parser();
+    let (result, mut report) = parser.parse_with_profile(b"a; b(); c = 1;");
+    assert_eq!(result.unwrap().len(), 4);
+
+    let counts = |name: &str| {
+        let profile = report.get(name).unwrap();
+        (
+            profile.attempts,
+            profile.successes,
+            profile.failures,
+            profile.consumed,
+        )
+    };
+    assert_eq!(counts("expression"), (4, 3, 1, 7));
+    assert_eq!(counts("call"), (3, 1, 2, 3));
+    assert_eq!(counts("assignment"), (2, 1, 1, 3));
+    assert_eq!(counts("variable"), (1, 1, 0, 1));
+    assert_eq!(counts("statement"), (4, 3, 1, 10));
+    assert_eq!(counts("root"), (1, 1, 0, 10));
+
+    // The target is parsed once at each position and reused by the other alternatives.
+    let target = report.get("target").unwrap();
+    assert_eq!(target.attempts, 3);
+    assert_eq!((target.cache_hits, target.cache_misses), (3, 3));
+    assert_eq!(target.cache_hit_ratio(), Some(0.5));
+    assert_eq!(report.get("call").unwrap().cache_hit_ratio(), None);
+
+    assert_eq!(report.productions[0].name, "root");
+    report.sort_by_failures();
+    let names: Vec<&str> = report.productions[..2]
+        .iter()
+        .map(|profile| profile.name.as_str())
+        .collect();
+    assert_eq!(names, vec!["[&OpenParen; ]", "call"]);
+    report.sort_by_attempts();
+    assert_eq!(report.productions[0].name, "expression");
+    assert!(report.to_string().starts_with("Production"));
+
+    // The productions are not profiled outside of the profiled parsing.
+    let (_, report) = crate::with_profile(|| ());
+    assert!(report.productions.is_empty());
+}
+
+#[test]
+fn same_label_profile_test() {
+    let punctuations = Rc::new(Punctuations::new(vec![(";", Token::Semicolon)]).unwrap());
+    let space = Rc::new(Pattern::new(Token::Space, r"^\s+").unwrap());
+    let id = Rc::new(Pattern::new(Token::ID, r"^[a-z]+").unwrap());
+    let tokenizer = Rc::new(Tokenizer::new(vec![space, punctuations, id]));
+
+    // The productions with the same label are profiled separately.
+    let id = Rc::new(TokenField::new(Token::ID, Some(NodeValue::ID)));
+    let root = Rc::new(Concat::new(
+        "root",
+        vec![
+            id.clone(),
+            Rc::new(TokenField::new(Token::Semicolon, None)),
+            id,
+            Rc::new(TokenField::new(Token::Semicolon, None)),
+            Rc::new(EOFProd::new(None)),
+        ],
+    ));
+    let parser = DefaultParser::new(tokenizer, root).unwrap();
+    let (result, report) = parser.parse_with_profile(b"a; b;");
+    assert_eq!(result.unwrap().len(), 2);
+
+    let semicolons: Vec<_> = report
+        .productions
+        .iter()
+        .filter(|profile| profile.name == "[&Semicolon; ]")
+        .map(|profile| (profile.attempts, profile.successes))
+        .collect();
+    assert_eq!(semicolons, vec![(1, 1), (1, 1)]);
+    assert_eq!(report.get("[&ID; ID]").unwrap().attempts, 2);
+}
@@ -0,0 +1,370 @@
+use crate::{
+    lexeme::{Pattern, Punctuations},
+    production::{
+        Cacheable, Concat, EOFProd, List, Lookahead, Node, NotLookahead, ProductionBuilder,
+        SeparatedList, Suffixes, TokenField, TokenFieldSet, Union,
+    },
+    Cache, CacheKey, Code, DefaultParser, Expected, FltrPtr, IProduction, ITokenization, NodeImpl,
+    Rc, TokenImpl, TokenStream, Tokenizer,
+};
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, PartialOrd, Ord)]
+enum Token {
+    ID,
+    Number,
+    Assign,
+    Comma,
+    Semicolon,
+    OpenBrace,
+    CloseBrace,
+    OpenBracket,
+    CloseBracket,
+    Space,
+    Eof,
+}
+impl TokenImpl for Token {
+    fn eof() -> Self {
+        Token::Eof
+    }
+
+    fn is_structural(&self) -> bool {
+        *self != Token::Space
+    }
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+enum NodeValue {
+    ID,
+    Number,
+    Statement,
+    Block,
+    Array,
+    Error,
+    Root,
+    Null,
+}
+
+impl NodeImpl for NodeValue {
+    fn null() -> Self {
+        Self::Null
+    }
+}
+
+fn tokenizer() -> Rc<Tokenizer<Token>> {
+    let punctuations = Rc::new(
+        Punctuations::new(vec![
+            ("=", Token::Assign),
+            (",", Token::Comma),
+            (";", Token::Semicolon),
+            ("{", Token::OpenBrace),
+            ("}", Token::CloseBrace),
+            ("[", Token::OpenBracket),
+            ("]", Token::CloseBracket),
+        ])
+        .unwrap(),
+    );
+    let space = Rc::new(Pattern::new(Token::Space, r"^\s+").unwrap());
+    let id = Rc::new(Pattern::new(Token::ID, r"^[a-z]+").unwrap());
+    let number = Rc::new(Pattern::new(Token::Number, r"^[0-9]+").unwrap());
+    Rc::new(Tokenizer::new(vec![space, punctuations, id, number]))
+}
+
+fn statement_parser() -> DefaultParser<NodeValue, Token> {
+    let id = Rc::new(TokenField::new(Token::ID, Some(NodeValue::ID)));
+    let value = Rc::new(TokenFieldSet::new(vec![
+        (Token::ID, Some(NodeValue::ID)),
+        (Token::Number, Some(NodeValue::Number)),
+    ]));
+    let assign = Rc::new(TokenField::new(Token::Assign, None));
+    let semicolon = Rc::new(TokenField::new(Token::Semicolon, None));
+    let open_brace = Rc::new(TokenField::new(Token::OpenBrace, None));
+    let close_brace = Rc::new(TokenField::new(Token::CloseBrace, None));
+    let eof = Rc::new(EOFProd::new(None));
+
+    let statement = Rc::new(Concat::new("statement", vec![id, assign, value, semicolon]));
+    statement
+        .set_recovery(vec![Token::Semicolon, Token::CloseBrace], NodeValue::Error)
+        .unwrap();
+
+    let block = Rc::new(Concat::init("block"));
+    let item = Rc::new(Union::new(
+        "item",
+        vec![
+            Rc::new(Node::new(&statement, NodeValue::Statement)),
+            Rc::new(Node::new(&block, NodeValue::Block)),
+        ],
+    ));
+    let item_list = Rc::new(List::new(&item));
+    item_list
+        .set_recovery(vec![Token::CloseBrace], NodeValue::Error)
+        .unwrap();
+
+    block
+        .set_symbols(vec![open_brace, item_list.clone(), close_brace])
+        .unwrap();
+
+    let root = Rc::new(Concat::new("root", vec![item_list, eof]).into_node(NodeValue::Root));
+    DefaultParser::new(tokenizer(), root).unwrap()
+}
+
+#[test]
+fn concat_recovery_test() {
+    let parser = statement_parser();
+    let code = b"a = 1; b = = 2; c = 3;";
+    assert!(parser.parse(code).is_err());
+
+    let (tree_list, errors) = parser.parse_with_recovery(code).unwrap();
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].pointer, 11);
+
+    let statements = &tree_list[0].children;
+    assert_eq!(statements.len(), 3);
+    let error_node = statements[1].get_child(&NodeValue::Error).unwrap();
+    assert_eq!(&code[error_node.start..error_node.end], b"= 2");
+    assert!(!statements[2].contains(&NodeValue::Error));
+}
+
+#[test]
+fn concat_skipped_symbols_test() {
+    let parser = statement_parser();
+    let code = b"{ a 1 }";
+    let (tree_list, errors) = parser.parse_with_recovery(code).unwrap();
+
+    // The value and the semicolon missing before the closing brace are reported separately.
+    let pointers: Vec<usize> = errors.iter().map(|err| err.pointer).collect();
+    assert_eq!(pointers, vec![4, 6, 6]);
+    assert_eq!(errors[0].expected, vec![Expected::Literal("=".into())]);
+    assert_eq!(
+        errors[1].expected,
+        vec![
+            Expected::Token("ID".into()),
+            Expected::Token("Number".into())
+        ]
+    );
+    assert_eq!(errors[2].expected, vec![Expected::Literal(";".into())]);
+
+    let statement = tree_list[0].children[0]
+        .get_child(&NodeValue::Statement)
+        .unwrap();
+    let error_node = statement.get_child(&NodeValue::Error).unwrap();
+    assert_eq!(&code[error_node.start..error_node.end], b"1 ");
+}
+
+#[test]
+fn concat_recovery_position_test() {
+    let id = Rc::new(TokenField::new(Token::ID, Some(NodeValue::ID)));
+    let value = Rc::new(TokenFieldSet::new(vec![
+        (Token::ID, Some(NodeValue::ID)),
+        (Token::Number, Some(NodeValue::Number)),
+    ]));
+    let assign = Rc::new(TokenField::new(Token::Assign, None));
+    let semicolon = Rc::new(TokenField::new(Token::Semicolon, None));
+    let open_bracket = Rc::new(TokenField::new(Token::OpenBracket, None));
+    let eof = Rc::new(EOFProd::new(None));
+
+    // The first alternative parses further than the statement before failing.
+    let indexed_values = Rc::new(Concat::new(
+        "indexed_values",
+        vec![
+            id.clone(),
+            assign.clone(),
+            assign.clone(),
+            value.clone(),
+            value.clone(),
+            open_bracket,
+        ],
+    ));
+    let statement = Rc::new(Concat::new("statement", vec![id, assign, value, semicolon]));
+    statement
+        .set_recovery(vec![Token::Semicolon], NodeValue::Error)
+        .unwrap();
+    let item = Rc::new(Union::new(
+        "item",
+        vec![
+            indexed_values,
+            Rc::new(Node::new(&statement, NodeValue::Statement)),
+        ],
+    ));
+    let root = Rc::new(Concat::new("root", vec![Rc::new(List::new(&item)), eof]));
+    let parser = DefaultParser::new(tokenizer(), root).unwrap();
+
+    let code = b"a = = b c; e = = 2;";
+    let (tree_list, errors) = parser.parse_with_recovery(code).unwrap();
+    assert_eq!(tree_list.len(), 2);
+    assert_eq!(errors.len(), 2);
+    let value_expected = vec![
+        Expected::Token("ID".into()),
+        Expected::Token("Number".into()),
+    ];
+    assert_eq!(errors[0].pointer, 4);
+    assert_eq!(errors[0].expected, value_expected);
+    assert_eq!(errors[1].pointer, 15);
+    assert_eq!(errors[1].expected, value_expected);
+}
+
+#[test]
+fn list_recovery_test() {
+    let parser = statement_parser();
+    let code = b"a = 1; { x = 0; 5 5; b = 2; } c = ; d = 4;";
+    let (tree_list, errors) = parser.parse_with_recovery(code).unwrap();
+    assert_eq!(errors.len(), 2);
+
+    let items = &tree_list[0].children;
+    assert_eq!(items.len(), 4);
+    assert_eq!(items[1].node, NodeValue::Block);
+    let error_node = &items[1].children[1];
+    assert_eq!(error_node.node, NodeValue::Error);
+    assert_eq!(&code[error_node.start..error_node.end], b"5 5; ");
+    assert_eq!(items[1].children[2].node, NodeValue::Statement);
+
+    let error_node = items[2].get_child(&NodeValue::Error).unwrap();
+    assert_eq!(error_node.start, error_node.end);
+}
+
+#[test]
+fn separated_list_recovery_test() {
+    let value = Rc::new(TokenFieldSet::new(vec![
+        (Token::ID, Some(NodeValue::ID)),
+        (Token::Number, Some(NodeValue::Number)),
+    ]));
+    let comma = Rc::new(TokenField::new(Token::Comma, None));
+    let open_bracket = Rc::new(TokenField::new(Token::OpenBracket, None));
+    let close_bracket = Rc::new(TokenField::new(Token::CloseBracket, None));
+    let eof = Rc::new(EOFProd::new(None));
+
+    let items = Rc::new(SeparatedList::new(&value, &comma, false));
+    items
+        .set_recovery(vec![Token::CloseBracket], NodeValue::Error)
+        .unwrap();
+    let array = Rc::new(
+        Concat::new("array", vec![open_bracket, items, close_bracket]).into_node(NodeValue::Array),
+    );
+    let root = Rc::new(Concat::new("root", vec![array, eof]).into_node(NodeValue::Root));
+    let parser = DefaultParser::new(tokenizer(), root).unwrap();
+
+    let code = b"[1, ; =, a, {]";
+    let (tree_list, errors) = parser.parse_with_recovery(code).unwrap();
+    assert_eq!(errors.len(), 2);
+    let values: Vec<NodeValue> = tree_list[0].children[0]
+        .children
+        .iter()
+        .map(|tree| tree.node)
+        .collect();
+    assert_eq!(
+        values,
+        vec![
+            NodeValue::Number,
+            NodeValue::Error,
+            NodeValue::ID,
+            NodeValue::Error
+        ]
+    );
+}
+
+#[test]
+fn cached_recovery_test() {
+    let id = Rc::new(TokenField::new(Token::ID, Some(NodeValue::ID)));
+    let value = Rc::new(TokenFieldSet::new(vec![
+        (Token::ID, Some(NodeValue::ID)),
+        (Token::Number, Some(NodeValue::Number)),
+    ]));
+    let assign = Rc::new(TokenField::new(Token::Assign, None));
+    let semicolon = Rc::new(TokenField::new(Token::Semicolon, None));
+    let open_bracket = Rc::new(TokenField::new(Token::OpenBracket, None));
+    let eof = Rc::new(EOFProd::new(None));
+
+    let statement = Rc::new(Concat::new("statement", vec![id, assign, value, semicolon]));
+    statement
+        .set_recovery(vec![Token::Semicolon], NodeValue::Error)
+        .unwrap();
+    let cached_statement = Rc::new(Cacheable::new(CacheKey::from(0), &statement));
+
+    // The first alternative recovers the statement and fails afterward,
+    // so that the second alternative reuses the recovered statement from the cache.
+    let item = Rc::new(Union::new(
+        "item",
+        vec![
+            Rc::new(Concat::new(
+                "indexed_statement",
+                vec![cached_statement.clone(), open_bracket],
+            )),
+            Rc::new(Node::new(&cached_statement, NodeValue::Statement)),
+        ],
+    ));
+    let root = Rc::new(Concat::new("root", vec![item, eof]).into_node(NodeValue::Root));
+    let parser = DefaultParser::new(tokenizer(), root).unwrap();
+
+    let code = b"a = = 2;";
+    let (tree_list, errors) = parser.parse_with_recovery(code).unwrap();
+    assert!(tree_list[0].contains(&NodeValue::Error));
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].pointer, 4);
+}
+
+#[test]
+fn discarded_recovery_test() {
+    let id = Rc::new(TokenField::new(Token::ID, Some(NodeValue::ID)));
+    let value = Rc::new(TokenFieldSet::new(vec![
+        (Token::ID, Some(NodeValue::ID)),
+        (Token::Number, Some(NodeValue::Number)),
+    ]));
+    let assign = Rc::new(TokenField::new(Token::Assign, None));
+    let semicolon = Rc::new(TokenField::new(Token::Semicolon, None));
+    let open_bracket = Rc::new(TokenField::new(Token::OpenBracket, None));
+    let eof = Rc::new(EOFProd::new(None));
+    let code = b"a = = 2;";
+
+    let assignment = Rc::new(Concat::new("assignment", vec![assign, value, semicolon]));
+    assignment
+        .set_recovery(vec![Token::Semicolon], NodeValue::Error)
+        .unwrap();
+
+    // The errors recovered by the lookahead are recorded again by the statement.
+    let statement = Rc::new(Concat::new(
+        "statement",
+        vec![id.clone(), assignment.clone()],
+    ));
+    let root = Rc::new(Concat::new(
+        "root",
+        vec![
+            Rc::new(Lookahead::new(&statement, None)),
+            statement,
+            eof.clone(),
+        ],
+    ));
+    let parser = DefaultParser::new(tokenizer(), root).unwrap();
+    let (_, errors) = parser.parse_with_recovery(code).unwrap();
+    assert_eq!(errors.len(), 1);
+
+    // The first suffix is discarded after recovering the assignment.
+    let indexed_assignment = Rc::new(Concat::new(
+        "indexed_assignment",
+        vec![assignment.clone(), open_bracket],
+    ));
+    let statement = Rc::new(Suffixes::new(
+        "statement",
+        &id,
+        false,
+        vec![
+            (indexed_assignment, NodeValue::Array),
+            (assignment.clone(), NodeValue::Statement),
+        ],
+    ));
+    let root = Rc::new(Concat::new("root", vec![statement, eof]));
+    let parser = DefaultParser::new(tokenizer(), root).unwrap();
+    let (tree_list, errors) = parser.parse_with_recovery(code).unwrap();
+    assert_eq!(tree_list[0].node, NodeValue::Statement);
+    assert_eq!(errors.len(), 1);
+
+    // The errors recovered by the statement rejected by the negative lookahead are discarded.
+    let statement = Concat::new("statement", vec![id, assignment]);
+    let not_statement = NotLookahead::new(&Rc::new(statement), None);
+    let code = Code::new(code);
+    let tokens = tokenizer().tokenize(&code).unwrap();
+    let stream = TokenStream::from(&tokens);
+    let mut cache = Cache::recovery_root();
+    assert!(not_statement
+        .advance_fltr_ptr(&code, FltrPtr::default(), &stream, &mut cache)
+        .is_err());
+    assert!(cache.take_recovered_errors().is_empty());
+}
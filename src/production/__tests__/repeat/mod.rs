@@ -0,0 +1,95 @@
+use crate::{
+    production::{
+        Concat, ConstantField, EOFProd, ProductionBuilder, RegexField, Repeat, SeparatedList,
+    },
+    IProduction, LexerlessParser, NodeImpl, Rc,
+};
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+enum NodeValue {
+    Digit,
+    Item,
+    Null,
+}
+
+impl NodeImpl for NodeValue {
+    fn null() -> Self {
+        Self::Null
+    }
+}
+
+#[test]
+fn repeat_test() {
+    let digit = Rc::new(RegexField::new(r"^[0-9a-f]", Some(NodeValue::Digit)).unwrap());
+    let eof = Rc::new(EOFProd::new(None));
+
+    let hex = Rc::new(Repeat::new(&digit, 2, Some(4)));
+    let root = Rc::new(Concat::new("root", vec![hex, eof]));
+    let parser = LexerlessParser::new(root).unwrap();
+
+    assert!(parser.parse(b"a").is_err());
+    assert_eq!(parser.parse(b"ab").unwrap().len(), 2);
+    assert_eq!(parser.parse(b"abc").unwrap().len(), 3);
+    assert_eq!(parser.parse(b"abcd").unwrap().len(), 4);
+    assert_eq!(parser.parse(b"abcde").unwrap_err().pointer, 4);
+
+    assert_eq!(parser.grammar().unwrap().lines().next(), Some("root"));
+    assert!(parser.grammar().unwrap().contains("{2,4}"));
+}
+
+#[test]
+fn optional_repeat_test() {
+    let digit = Rc::new(RegexField::new(r"^[0-9]", Some(NodeValue::Digit)).unwrap());
+    let dot = Rc::new(ConstantField::new(".", None));
+    let eof = Rc::new(EOFProd::new(None));
+
+    let digits = Rc::new(
+        RegexField::new(r"^[0-9]", Some(NodeValue::Digit))
+            .unwrap()
+            .into_repeat(0, None),
+    );
+    assert!(digits.is_nullable_n_hidden());
+    assert_eq!(format!("{}", digits), format!("{}{{0,}}", digit));
+
+    let root = Rc::new(Concat::new("root", vec![digits, dot, eof]));
+    let parser = LexerlessParser::new(root).unwrap();
+    assert!(parser.parse(b".").unwrap().is_empty());
+    assert_eq!(parser.parse(b"123.").unwrap().len(), 3);
+}
+
+#[test]
+fn bounded_separated_list_test() {
+    let item = Rc::new(RegexField::new(r"^[a-z]+", Some(NodeValue::Item)).unwrap());
+    let comma = Rc::new(ConstantField::new(",", None));
+    let eof = Rc::new(EOFProd::new(None));
+
+    let list = Rc::new(SeparatedList::bounded(&item, &comma, true, 2, Some(3)));
+    assert_eq!(
+        format!("{}", list),
+        format!("{i} ({c} {i}){{1,2}}", i = item, c = comma)
+    );
+    let root = Rc::new(Concat::new("root", vec![list, eof.clone()]));
+    let parser = LexerlessParser::new(root).unwrap();
+
+    assert!(parser.parse(b"a").is_err());
+    assert_eq!(parser.parse(b"a,b").unwrap().len(), 2);
+    assert_eq!(parser.parse(b"a,b,c,").unwrap().len(), 3);
+    assert_eq!(parser.parse(b"a,b,c,d").unwrap_err().pointer, 6);
+
+    let empty_list = Rc::new(SeparatedList::bounded(&item, &comma, false, 0, Some(2)));
+    let root = Rc::new(Concat::new("root", vec![empty_list, eof]));
+    let parser = LexerlessParser::new(root).unwrap();
+    assert!(parser.parse(b"").unwrap().is_empty());
+    assert_eq!(parser.parse(b"a,b").unwrap().len(), 2);
+    assert!(parser.parse(b"a,b,").is_err());
+}
+
+#[test]
+fn invalid_repeat_test() {
+    let digit = Rc::new(RegexField::new(r"^[0-9]", Some(NodeValue::Digit)).unwrap());
+    let eof = Rc::new(EOFProd::new(None));
+
+    let digits = Rc::new(Repeat::new(&digit, 3, Some(2)));
+    let root = Rc::new(Concat::new("root", vec![digits, eof]));
+    assert!(LexerlessParser::new(root).is_err());
+}
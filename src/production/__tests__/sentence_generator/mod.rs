@@ -0,0 +1,149 @@
+use crate::{
+    examples::json::{lexerless::json_lexerless_grammar, tokenized::json_grammar},
+    grammar::{Grammar, RoundTripError, SentenceGenerator},
+    production::{Concat, ConstantField, EOFProd, List, PunctuationsField, TokenField, Union},
+    LexerlessParser, NodeImpl, Rc, TokenImpl,
+};
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, PartialOrd, Ord)]
+enum Token {
+    ID,
+    Number,
+    Assign,
+    Eof,
+}
+
+impl TokenImpl for Token {
+    fn eof() -> Self {
+        Token::Eof
+    }
+
+    fn is_structural(&self) -> bool {
+        true
+    }
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+enum NodeValue {
+    Null,
+}
+
+impl NodeImpl for NodeValue {
+    fn null() -> Self {
+        Self::Null
+    }
+}
+
+#[test]
+fn token_samples_test() {
+    let id: Rc<TokenField<NodeValue, Token>> = Rc::new(TokenField::new(Token::ID, None));
+    let number = Rc::new(TokenField::new(Token::Number, None));
+    let assign = Rc::new(TokenField::new(Token::Assign, None));
+    let value = Rc::new(Union::new("value", vec![id.clone(), number]));
+    let assignment = Rc::new(Concat::new("assignment", vec![id, assign, value]));
+    let root = Rc::new(Concat::new(
+        "root",
+        vec![Rc::new(List::new(&assignment)), Rc::new(EOFProd::new(None))],
+    ));
+
+    let mut generator = SentenceGenerator::from_production(root.as_ref()).unwrap();
+    let err = generator.generate().unwrap_err();
+    assert_eq!(err.what, "MissingTokenSample");
+
+    generator.add_token_samples(Token::ID, &["a", "b"]);
+    generator.add_token_samples(Token::Number, &["1"]);
+    generator.add_token_samples(Token::Assign, &["="]);
+    generator.set_separator("\t");
+    generator.set_seed(7);
+    let sentence = generator.generate().unwrap();
+    assert!(sentence
+        .tokens
+        .chunks(3)
+        .all(|tokens| tokens.len() == 3 && tokens[..2] == ["ID", "Assign"]));
+    assert_eq!(
+        sentence.text.split('\t').count(),
+        sentence.tokens.len(),
+        "{:?}",
+        sentence
+    );
+
+    generator.set_seed(7);
+    assert_eq!(generator.generate().unwrap(), sentence);
+}
+
+#[test]
+fn generation_limit_test() {
+    let grammar = Grammar::from_dsl(
+        r#"
+        main = list EOF ;
+        list = "[" (item ("," item)*)? "]" ;
+        item = list | /[0-9]{1,3}/ | "'" /[a-z]*/ "'" ;
+        "#,
+    )
+    .unwrap();
+    let mut generator = SentenceGenerator::new(grammar.clone()).unwrap();
+    generator.set_max_depth(3);
+    generator.set_max_size(40);
+    for _ in 0..50 {
+        let sentence = generator.generate().unwrap();
+        assert!(sentence.tokens.is_empty());
+        assert!(sentence.text.starts_with('['), "{}", sentence.text);
+        assert!(!sentence.text.contains("[[[["), "{}", sentence.text);
+    }
+
+    let parser = grammar.build_lexerless(|_| None::<NodeValue>).unwrap();
+    assert!(parser.round_trip(&mut generator, 100).is_ok());
+
+    let err = SentenceGenerator::new(Grammar::from_dsl("main = main 'a' ;").unwrap())
+        .unwrap()
+        .generate()
+        .unwrap_err();
+    assert_eq!(err.what, "UnproductiveRule");
+}
+
+#[test]
+fn round_trip_test() {
+    let parser = json_grammar();
+    let mut generator = parser.sentence_generator().unwrap();
+    generator.set_seed(42);
+    assert!(parser.round_trip(&mut generator, 200).is_ok());
+
+    let parser = json_lexerless_grammar();
+    let mut generator = parser.sentence_generator().unwrap();
+    generator.set_max_depth(8);
+    assert!(parser.round_trip(&mut generator, 200).is_ok());
+
+    let mut generator = json_grammar().sentence_generator().unwrap();
+    generator.add_token_samples(
+        crate::examples::json::tokenized::JSONToken::Number,
+        &["1 2"],
+    );
+    match json_grammar().round_trip(&mut generator, 50).unwrap_err() {
+        RoundTripError::TokenMismatch {
+            expected, found, ..
+        } => assert_eq!(found.len(), expected.len() + 1),
+        err => panic!("Unexpected error {}", err),
+    }
+
+    // The second alternative never matches because the first one matches its prefix.
+    let operator = Rc::new(PunctuationsField::new(vec![("=", None), ("+", None)]).unwrap());
+    let comparison = Rc::new(Union::new(
+        "comparison",
+        vec![
+            Rc::new(ConstantField::new("=", None)),
+            Rc::new(ConstantField::new("==", None)),
+        ],
+    ));
+    let root = Rc::new(Concat::new(
+        "root",
+        vec![operator, comparison, Rc::new(EOFProd::new(None))],
+    ));
+    let parser: LexerlessParser<NodeValue> = LexerlessParser::new(root).unwrap();
+    let mut generator = parser.sentence_generator().unwrap();
+    let err = parser.round_trip(&mut generator, 100).unwrap_err();
+    assert!(err.to_string().starts_with("Failed to parse the sentence"));
+    match err {
+        RoundTripError::Parse { sentence, .. } => assert!(sentence.contains("==")),
+        err => panic!("Unexpected error {}", err),
+    }
+}
@@ -0,0 +1,42 @@
+use crate::{examples::json::tokenized::json_grammar, Code};
+
+#[test]
+fn sexp_test() {
+    let parser = json_grammar();
+    let text = br#"{"a": [1, true], "b\"c": {}}"#;
+    let tree = parser.parse(text).unwrap();
+
+    assert_eq!(
+        tree[0].to_sexp(None),
+        "(Main (Object (Item (Key) (Array (Number) (Constant))) (Item (Key) (Object (NULL)))))"
+    );
+    assert_eq!(
+        tree[0].to_sexp(Some(&Code::new(text))),
+        r#"(Main (Object (Item (Key "\"a\"") (Array (Number "1") (Constant "true"))) (Item (Key "\"b\\\"c\"") (Object (NULL "")))))"#
+    );
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn serde_test() {
+    use crate::{ASTNode, Lex, ParseError};
+
+    let parser = json_grammar();
+    let (tokens, tree) = parser.tokenize_n_parse(b"[1]").unwrap();
+
+    let json = serde_json::to_string(&tree[0]).unwrap();
+    assert_eq!(serde_json::from_str::<ASTNode<_>>(&json).unwrap(), tree[0]);
+    let json = serde_json::to_value(&tokens[1]).unwrap();
+    assert_eq!(
+        json,
+        serde_json::json!({"token": "Number", "start": 1, "end": 2})
+    );
+    assert_eq!(serde_json::from_value::<Lex<_>>(json).unwrap(), tokens[1]);
+
+    let err = parser.parse(b"[1 2]").unwrap_err();
+    let json = serde_json::to_value(&err).unwrap();
+    assert_eq!(json["kind"], "UnexpectedToken");
+    assert_eq!(json["start"], serde_json::json!({"line": 1, "column": 4}));
+    let err: ParseError = serde_json::from_value(json).unwrap();
+    assert_eq!(err.pointer, 3);
+}
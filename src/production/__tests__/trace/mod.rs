@@ -0,0 +1,276 @@
+use crate::{
+    lexeme::{Action, Pattern, Punctuations, StateMixin},
+    production::{Cacheable, Concat, EOFProd, List, TokenField, Union},
+    CacheKey, Code, CombinedTokenizer, DefaultParser, Log, NodeImpl, Rc, TokenImpl, TraceEvent,
+    TraceRecorder,
+};
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, PartialOrd, Ord)]
+enum Token {
+    ID,
+    Number,
+    Assign,
+    Semicolon,
+    Quote,
+    Text,
+    Space,
+    Eof,
+}
+impl TokenImpl for Token {
+    fn eof() -> Self {
+        Token::Eof
+    }
+
+    fn is_structural(&self) -> bool {
+        *self != Token::Space
+    }
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+enum NodeValue {
+    ID,
+    Number,
+    Text,
+    Null,
+}
+
+impl NodeImpl for NodeValue {
+    fn null() -> Self {
+        Self::Null
+    }
+}
+
+const MAIN: u8 = 0;
+const STRING: u8 = 1;
+
+fn parser(labeled: bool) -> DefaultParser<NodeValue, Token> {
+    let punctuations = Punctuations::new(vec![
+        ("=", Token::Assign),
+        (";", Token::Semicolon),
+        ("\"", Token::Quote),
+    ])
+    .unwrap();
+    let punctuation_mixin = Rc::new(StateMixin::new(
+        punctuations,
+        vec![(Token::Quote, Action::append(STRING, false))],
+    ));
+    let space = Rc::new(Pattern::new(Token::Space, r"^\s+").unwrap());
+    let id = Rc::new(Pattern::new(Token::ID, r"^[a-z]+").unwrap());
+    let number = Rc::new(Pattern::new(Token::Number, r"^[0-9]+").unwrap());
+    if labeled {
+        number.set_log(Log::Result("number")).unwrap();
+    }
+
+    let quote_mixin = Rc::new(StateMixin::new(
+        Punctuations::new(vec![("\"", Token::Quote)]).unwrap(),
+        vec![(Token::Quote, Action::remove(false))],
+    ));
+    let text = Rc::new(Pattern::new(Token::Text, r#"^[^"]+"#).unwrap());
+
+    let mut tokenizer = CombinedTokenizer::new(MAIN, vec![space, punctuation_mixin, id, number]);
+    tokenizer.add_state(STRING, vec![quote_mixin, text]);
+    if labeled {
+        tokenizer.set_log(Log::Default("tokenizer")).unwrap();
+    }
+
+    let id = Rc::new(TokenField::new(Token::ID, Some(NodeValue::ID)));
+    let number = Rc::new(TokenField::new(Token::Number, Some(NodeValue::Number)));
+    let quote = Rc::new(TokenField::new(Token::Quote, None));
+    let text = Rc::new(TokenField::new(Token::Text, Some(NodeValue::Text)));
+    let string = Rc::new(Concat::new("string", vec![quote.clone(), text, quote]));
+    let value = Rc::new(Union::new("value", vec![number, string]));
+    let cached_value = Rc::new(Cacheable::new(CacheKey::from(0), &value));
+    if labeled {
+        value.set_log(Log::Result("value")).unwrap();
+        cached_value
+            .assign_debugger(Log::Verbose("cached-value"))
+            .unwrap();
+    }
+
+    // Both alternatives parse the value so that the second one reuses the cached result.
+    let assignment = Rc::new(Union::new(
+        "assignment",
+        vec![
+            Rc::new(Concat::new(
+                "declaration",
+                vec![
+                    id.clone(),
+                    Rc::new(TokenField::new(Token::Assign, None)),
+                    cached_value.clone(),
+                    id,
+                ],
+            )),
+            Rc::new(Concat::new(
+                "statement",
+                vec![
+                    Rc::new(TokenField::new(Token::ID, Some(NodeValue::ID))),
+                    Rc::new(TokenField::new(Token::Assign, None)),
+                    cached_value,
+                    Rc::new(TokenField::new(Token::Semicolon, None)),
+                ],
+            )),
+        ],
+    ));
+    let root = Rc::new(Concat::new(
+        "root",
+        vec![Rc::new(List::new(&assignment)), Rc::new(EOFProd::new(None))],
+    ));
+    DefaultParser::new(Rc::new(tokenizer), root).unwrap()
+}
+
+#[test]
+fn parse_trace_test() {
+    let parser = parser(true);
+    let mut recorder = TraceRecorder::new();
+    parser
+        .parse_with_trace(b"a=12; b=\"x\";", &mut recorder)
+        .unwrap();
+
+    assert_eq!(
+        recorder.events.first(),
+        Some(&TraceEvent::Enter {
+            label: "number".into(),
+            pointer: 2
+        })
+    );
+    assert!(recorder.events.contains(&TraceEvent::Token {
+        label: "number".into(),
+        token: String::from("Number"),
+        range: 2..4,
+    }));
+    assert!(recorder.events.contains(&TraceEvent::StateSwitch {
+        label: "tokenizer".into(),
+        from: String::from("0"),
+        to: String::from("1"),
+        pointer: 9,
+    }));
+    assert!(recorder.events.contains(&TraceEvent::StateSwitch {
+        label: "tokenizer".into(),
+        from: String::from("1"),
+        to: String::from("0"),
+        pointer: 11,
+    }));
+
+    let cached_events: Vec<&TraceEvent> = recorder
+        .events
+        .iter()
+        .filter(|event| event.label() == "cached-value")
+        .take(4)
+        .collect();
+    assert_eq!(
+        cached_events,
+        vec![
+            &TraceEvent::Enter {
+                label: "cached-value".into(),
+                pointer: 2
+            },
+            &TraceEvent::Success {
+                label: "cached-value".into(),
+                range: 2..4
+            },
+            &TraceEvent::Enter {
+                label: "cached-value".into(),
+                pointer: 2
+            },
+            &TraceEvent::CacheHit {
+                label: "cached-value".into(),
+                pointer: 2,
+                success: true
+            },
+        ]
+    );
+    assert!(recorder.events.contains(&TraceEvent::Success {
+        label: "value".into(),
+        range: 8..11,
+    }));
+}
+
+#[test]
+fn closure_sink_test() {
+    let parser = parser(true);
+    let mut failures = Vec::new();
+    let mut sink = |event: TraceEvent| {
+        if let TraceEvent::Failure {
+            label,
+            pointer,
+            message,
+        } = event
+        {
+            failures.push((label.into_owned(), pointer, message));
+        }
+    };
+    let err = parser
+        .tokenize_with_trace(&Code::new(b"a = #"), &mut sink)
+        .unwrap_err();
+    assert_eq!(err.pointer, 4);
+    assert_eq!(
+        failures,
+        vec![
+            (String::from("number"), 4, None),
+            (
+                String::from("tokenizer"),
+                4,
+                Some(String::from("Tokenization failed in state 0."))
+            )
+        ]
+    );
+
+    // The events are not sent to the sink after the traced function returns.
+    parser.parse(b"a=1;").unwrap();
+    assert_eq!(failures.len(), 2);
+}
+
+#[cfg(not(feature = "trace"))]
+#[test]
+fn unlabeled_trace_disabled_test() {
+    // The unlabeled utilities are traced only with the `trace` feature.
+    let parser = parser(false);
+    let mut recorder = TraceRecorder::new();
+    parser
+        .parse_with_trace(b"a=12; b=\"x\";", &mut recorder)
+        .unwrap();
+    assert!(recorder.events.is_empty());
+}
+
+#[cfg(feature = "trace")]
+#[test]
+fn unlabeled_trace_test() {
+    let parser = parser(false);
+    let mut recorder = TraceRecorder::new();
+    parser
+        .parse_with_trace(b"a=12; b=\"x\";", &mut recorder)
+        .unwrap();
+
+    // The unlabeled lexemes do not create the events.
+    assert_eq!(
+        recorder.events.first(),
+        Some(&TraceEvent::Enter {
+            label: "tokenizer".into(),
+            pointer: 0
+        })
+    );
+    assert!(recorder.events.contains(&TraceEvent::StateSwitch {
+        label: "tokenizer".into(),
+        from: String::from("0"),
+        to: String::from("1"),
+        pointer: 9,
+    }));
+    assert!(recorder.events.contains(&TraceEvent::Enter {
+        label: "assignment".into(),
+        pointer: 0,
+    }));
+    assert!(recorder.events.contains(&TraceEvent::Success {
+        label: "value".into(),
+        range: 8..11,
+    }));
+    assert!(recorder.events.contains(&TraceEvent::CacheHit {
+        label: "<value>".into(),
+        pointer: 2,
+        success: true,
+    }));
+    assert!(recorder.events.contains(&TraceEvent::Failure {
+        label: "declaration".into(),
+        pointer: 0,
+        message: None,
+    }));
+}
@@ -0,0 +1,145 @@
+use crate::{
+    lexeme::{Keywords, Pattern},
+    production::{Concat, EOFProd, List, RegexField, Union},
+    Code, ITokenization, Lex, LexerlessParser, NodeImpl, Rc, TokenImpl, Tokenizer,
+};
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, PartialOrd, Ord)]
+enum Token {
+    ID,
+    Def,
+    Space,
+    LineBreak,
+    Eof,
+}
+impl TokenImpl for Token {
+    fn eof() -> Self {
+        Token::Eof
+    }
+
+    fn is_structural(&self) -> bool {
+        *self != Token::Space
+    }
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+enum NodeValue {
+    ID,
+    LineBreak,
+    Null,
+}
+
+impl NodeImpl for NodeValue {
+    fn null() -> Self {
+        Self::Null
+    }
+}
+
+const CODE: &str = "größe\u{3000}_naïve2\r\nδ\u{2028}名前\u{2029}\nx";
+
+#[test]
+fn unicode_tokenizer_test() {
+    let tokenizer = Tokenizer::new(vec![
+        Rc::new(Pattern::inline_whitespace(Token::Space)),
+        Rc::new(Pattern::line_terminator(Token::LineBreak)),
+        Rc::new(Pattern::xid_identifier(Token::ID)),
+    ]);
+    let code = Code::from(CODE);
+    let tokens: Vec<(Token, &str)> = tokenizer
+        .tokenize(&code)
+        .unwrap()
+        .iter()
+        .map(|lex| (lex.token, &CODE[lex.start..lex.end]))
+        .collect();
+    assert_eq!(
+        tokens,
+        vec![
+            (Token::ID, "größe"),
+            (Token::Space, "\u{3000}"),
+            (Token::ID, "_naïve2"),
+            (Token::LineBreak, "\r\n"),
+            (Token::ID, "δ"),
+            (Token::LineBreak, "\u{2028}"),
+            (Token::ID, "名前"),
+            (Token::LineBreak, "\u{2029}"),
+            (Token::LineBreak, "\n"),
+            (Token::ID, "x"),
+            (Token::Eof, ""),
+        ]
+    );
+
+    let whitespace_tokenizer = Tokenizer::new(vec![
+        Rc::new(Pattern::unicode_whitespace(Token::Space)),
+        Rc::new(Pattern::xid_identifier(Token::ID)),
+    ]);
+    assert_eq!(
+        whitespace_tokenizer
+            .tokenize(&Code::from("a\u{00A0}\r\n\u{2028}b"))
+            .unwrap(),
+        vec![
+            Lex::new(Token::ID, 0, 1),
+            Lex::new(Token::Space, 1, 8),
+            Lex::new(Token::ID, 8, 9),
+            Lex::new(Token::Eof, 9, 9),
+        ]
+    );
+
+    // A digit is not a start of the identifier.
+    assert!(tokenizer.tokenize(&Code::from("2x")).is_err());
+}
+
+#[test]
+fn unicode_keywords_test() {
+    let mut keywords: Keywords<Token> =
+        Keywords::new(Token::ID, vec![("def", Token::Def)]).unwrap();
+    keywords
+        .set_identifier_class(r"[\p{XID_Start}_]", r"\p{XID_Continue}")
+        .unwrap();
+    let tokenizer = Tokenizer::new(vec![
+        Rc::new(keywords),
+        Rc::new(Pattern::inline_whitespace(Token::Space)),
+    ]);
+    assert_eq!(
+        tokenizer.tokenize(&Code::from("def défaut")).unwrap(),
+        vec![
+            Lex::new(Token::Def, 0, 3),
+            Lex::new(Token::Space, 3, 4),
+            Lex::new(Token::ID, 4, 11),
+            Lex::new(Token::Eof, 11, 11),
+        ]
+    );
+}
+
+#[test]
+fn unicode_lexerless_test() {
+    let id = Rc::new(RegexField::xid_identifier(Some(NodeValue::ID)));
+    let space = Rc::new(RegexField::inline_whitespace(None));
+    let line_break = Rc::new(RegexField::line_terminator(Some(NodeValue::LineBreak)));
+    let eof = Rc::new(EOFProd::new(None));
+    let item = Rc::new(Union::new("item", vec![id, space, line_break]));
+    let root = Rc::new(Concat::new("root", vec![Rc::new(List::new(&item)), eof]));
+    let parser = LexerlessParser::new(root).unwrap();
+
+    let tree = parser.parse(CODE.as_bytes()).unwrap();
+    let nodes: Vec<(NodeValue, &str)> = tree
+        .iter()
+        .map(|t| (t.node, &CODE[t.start..t.end]))
+        .collect();
+    assert_eq!(
+        nodes,
+        vec![
+            (NodeValue::ID, "größe"),
+            (NodeValue::ID, "_naïve2"),
+            (NodeValue::LineBreak, "\r\n"),
+            (NodeValue::ID, "δ"),
+            (NodeValue::LineBreak, "\u{2028}"),
+            (NodeValue::ID, "名前"),
+            (NodeValue::LineBreak, "\u{2029}"),
+            (NodeValue::LineBreak, "\n"),
+            (NodeValue::ID, "x"),
+        ]
+    );
+
+    let whitespace: RegexField<NodeValue> = RegexField::unicode_whitespace(None);
+    assert_eq!(whitespace.to_string(), r"/^\p{White_Space}+/");
+}
@@ -1,12 +1,11 @@
 use std::{
     fmt::{Debug, Display},
-    rc::Rc,
     time::Instant,
 };
 
 use crate::{
     production::{Concat, Node, Nullable, TokenField},
-    LexerlessParser, NodeImpl, TokenImpl,
+    LexerlessParser, NodeImpl, Rc, TokenImpl,
 };
 
 #[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, PartialOrd, Ord)]
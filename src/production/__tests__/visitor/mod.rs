@@ -0,0 +1,215 @@
+use crate::{ASTNode, VisitAction, Visitor, VisitorMut};
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+enum NodeValue {
+    Number,
+    Add,
+    Mul,
+    Group,
+}
+
+fn leaf(start: usize) -> ASTNode<NodeValue> {
+    ASTNode::leaf(NodeValue::Number, start, start + 1, None)
+}
+
+fn tree(node: NodeValue, children: Vec<ASTNode<NodeValue>>) -> ASTNode<NodeValue> {
+    let start = children.first().map_or(0, |c| c.start);
+    let end = children.last().map_or(0, |c| c.end);
+    ASTNode::new(node, start, end, None, children)
+}
+
+/// Tree of `(0 + 2 * 4) * 6`.
+fn expression() -> ASTNode<NodeValue> {
+    let add = tree(
+        NodeValue::Add,
+        vec![leaf(0), tree(NodeValue::Mul, vec![leaf(2), leaf(4)])],
+    );
+    tree(
+        NodeValue::Mul,
+        vec![tree(NodeValue::Group, vec![add]), leaf(6)],
+    )
+}
+
+#[derive(Default)]
+struct Recorder {
+    events: Vec<String>,
+    skip: Option<NodeValue>,
+    stop_at: Option<usize>,
+}
+
+impl Visitor<NodeValue> for Recorder {
+    fn enter(&mut self, tree: &ASTNode<NodeValue>) -> VisitAction {
+        self.events.push(format!("+{:?}{}", tree.node, tree.start));
+        if self.stop_at == Some(tree.start) && tree.node == NodeValue::Number {
+            VisitAction::Stop
+        } else if self.skip == Some(tree.node) {
+            VisitAction::SkipChildren
+        } else {
+            VisitAction::Continue
+        }
+    }
+
+    fn leave(&mut self, tree: &ASTNode<NodeValue>) -> VisitAction {
+        self.events.push(format!("-{:?}{}", tree.node, tree.start));
+        VisitAction::Continue
+    }
+}
+
+#[test]
+fn visitor_test() {
+    let expression = expression();
+
+    let mut recorder = Recorder::default();
+    assert!(expression.walk(&mut recorder));
+    assert_eq!(
+        recorder.events.join(" "),
+        "+Mul0 +Group0 +Add0 +Number0 -Number0 +Mul2 +Number2 -Number2 +Number4 -Number4 -Mul2 -Add0 -Group0 +Number6 -Number6 -Mul0"
+    );
+
+    let mut recorder = Recorder {
+        skip: Some(NodeValue::Group),
+        ..Default::default()
+    };
+    assert!(expression.walk(&mut recorder));
+    assert_eq!(
+        recorder.events.join(" "),
+        "+Mul0 +Group0 -Group0 +Number6 -Number6 -Mul0"
+    );
+
+    let mut recorder = Recorder {
+        stop_at: Some(2),
+        ..Default::default()
+    };
+    assert!(!expression.walk(&mut recorder));
+    assert_eq!(
+        recorder.events.join(" "),
+        "+Mul0 +Group0 +Add0 +Number0 -Number0 +Mul2 +Number2"
+    );
+}
+
+/// Remove the group trees and stop at the tree starting at the pointer.
+struct Ungroup {
+    stop_at: usize,
+}
+
+impl VisitorMut<NodeValue> for Ungroup {
+    fn enter(&mut self, tree: &mut ASTNode<NodeValue>) -> VisitAction {
+        while tree.node == NodeValue::Group {
+            *tree = tree.children.pop().unwrap();
+        }
+        if tree.start == self.stop_at && tree.node == NodeValue::Number {
+            VisitAction::Stop
+        } else {
+            VisitAction::Continue
+        }
+    }
+
+    fn leave(&mut self, tree: &mut ASTNode<NodeValue>) -> VisitAction {
+        if tree.node == NodeValue::Mul {
+            tree.children.reverse();
+        }
+        VisitAction::Continue
+    }
+}
+
+fn shape(tree: &ASTNode<NodeValue>) -> Vec<(NodeValue, usize)> {
+    tree.pre_order().map(|t| (t.node, t.start)).collect()
+}
+
+#[test]
+fn visitor_mut_test() {
+    let mut expression = expression();
+    assert!(expression.walk_mut(&mut Ungroup {
+        stop_at: usize::MAX
+    }));
+    assert_eq!(
+        shape(&expression),
+        vec![
+            (NodeValue::Mul, 0),
+            (NodeValue::Number, 6),
+            (NodeValue::Add, 0),
+            (NodeValue::Number, 0),
+            (NodeValue::Mul, 2),
+            (NodeValue::Number, 4),
+            (NodeValue::Number, 2),
+        ]
+    );
+
+    // The stopped traversal keeps all the trees.
+    let mut expression = self::expression();
+    assert!(!expression.walk_mut(&mut Ungroup { stop_at: 4 }));
+    assert_eq!(
+        shape(&expression),
+        vec![
+            (NodeValue::Mul, 0),
+            (NodeValue::Add, 0),
+            (NodeValue::Number, 0),
+            (NodeValue::Mul, 2),
+            (NodeValue::Number, 2),
+            (NodeValue::Number, 4),
+            (NodeValue::Number, 6),
+        ]
+    );
+}
+
+#[test]
+fn order_test() {
+    let expression = expression();
+    let pre_order: Vec<_> = expression.pre_order().map(|t| (t.node, t.start)).collect();
+    assert_eq!(
+        pre_order,
+        vec![
+            (NodeValue::Mul, 0),
+            (NodeValue::Group, 0),
+            (NodeValue::Add, 0),
+            (NodeValue::Number, 0),
+            (NodeValue::Mul, 2),
+            (NodeValue::Number, 2),
+            (NodeValue::Number, 4),
+            (NodeValue::Number, 6),
+        ]
+    );
+    let post_order: Vec<_> = expression.post_order().map(|t| (t.node, t.start)).collect();
+    assert_eq!(
+        post_order,
+        vec![
+            (NodeValue::Number, 0),
+            (NodeValue::Number, 2),
+            (NodeValue::Number, 4),
+            (NodeValue::Mul, 2),
+            (NodeValue::Add, 0),
+            (NodeValue::Group, 0),
+            (NodeValue::Number, 6),
+            (NodeValue::Mul, 0),
+        ]
+    );
+}
+
+struct Depth(usize);
+
+impl VisitorMut<NodeValue> for Depth {
+    fn enter(&mut self, _tree: &mut ASTNode<NodeValue>) -> VisitAction {
+        self.0 += 1;
+        VisitAction::Continue
+    }
+}
+
+#[test]
+fn deep_tree_test() {
+    let depth = 200_000;
+    let mut deep = leaf(0);
+    for _ in 0..depth {
+        deep = tree(NodeValue::Group, vec![deep]);
+    }
+
+    assert_eq!(deep.pre_order().count(), depth + 1);
+    assert_eq!(deep.post_order().last().unwrap().node, NodeValue::Group);
+    let mut counter = Depth(0);
+    assert!(deep.walk_mut(&mut counter));
+    assert_eq!(counter.0, depth + 1);
+
+    // Drop the nested trees one by one without the recursive drop.
+    while let Some(child) = deep.children.pop() {
+        deep = child;
+    }
+}
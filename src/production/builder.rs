@@ -1,8 +1,8 @@
 use super::{
-    Hidden, List, Lookahead, Node, Nullable, ProductionBuilder, SeparatedList, Suffixes, Validator,
+    Hidden, List, Lookahead, Node, NotLookahead, Nullable, ProductionBuilder, Repeat,
+    SeparatedList, Suffixes, Validator,
 };
-use crate::{ASTNode, IProduction, ProductionError};
-use std::rc::Rc;
+use crate::{ASTNode, IProduction, ProductionError, Rc};
 
 impl<T: IProduction> ProductionBuilder for T {
     fn into_list(self) -> List<Self>
@@ -33,6 +33,20 @@ impl<T: IProduction> ProductionBuilder for T {
         Lookahead::new(&Rc::new(self), node_value)
     }
 
+    fn into_not_lookahead(self, node_value: Option<Self::Node>) -> NotLookahead<Self>
+    where
+        Self: Sized,
+    {
+        NotLookahead::new(&Rc::new(self), node_value)
+    }
+
+    fn into_repeat(self, min: usize, max: Option<usize>) -> Repeat<Self>
+    where
+        Self: Sized,
+    {
+        Repeat::new(&Rc::new(self), min, max)
+    }
+
     fn into_separated_list<TS: IProduction<Node = Self::Node, Token = Self::Token>>(
         self,
         sep: &Rc<TS>,
@@ -20,7 +20,7 @@
 //!     production::{Concat, EOFProd, Node, SeparatedList, TokenField, TokenFieldSet, Union},
 //!     DefaultParser, NodeImpl, TokenImpl, Tokenizer,
 //! };
-//! use std::rc::Rc;
+//! use lang_pt::Rc;
 //!
 //! # #[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy)]
 //! # pub enum JSONToken {
@@ -192,18 +192,19 @@
 //! ```
 mod builder;
 mod non_terminals;
+mod recovery;
 mod terminals;
 mod wrappers;
-use once_cell::unsync::OnceCell;
 use regex::bytes::Regex;
-use std::{marker::PhantomData, rc::Rc};
+use std::{borrow::Cow, fmt::Display, marker::PhantomData};
 
 #[cfg(test)]
 mod __tests__;
 
+use crate::{trace, TraceEvent};
 use crate::{
-    ASTNode, CacheKey, Code, FieldTree, FltrPtr, IProduction, Log, NodeImpl, ParsedResult,
-    ProductionError, TokenImpl, TokenPtr, TokenStream,
+    ASTNode, CacheKey, Code, FieldTree, FltrPtr, IProduction, Log, NodeImpl, OnceCell,
+    ParsedResult, ProductionError, Rc, TokenImpl, TokenPtr, TokenStream,
 };
 
 /// A terminal symbol which matches a given token with the input.
@@ -281,6 +282,16 @@ struct NTHelper {
     nullability: OnceCell<bool>,
     null_hidden: OnceCell<bool>,
     debugger: OnceCell<Log<&'static str>>,
+    left_recursion: OnceCell<CacheKey>,
+}
+
+/// A recovery point of a production which is used while parsing in the error recovery mode.
+///
+/// The parser skips the tokens after a syntax error until it finds one of the synchronization tokens,
+/// and the skipped tokens are wrapped into an error [ASTNode] with the assigned node value.
+struct Recovery<TN, TL> {
+    sync_tokens: Vec<TL>,
+    node_value: TN,
 }
 
 /// A non-terminal production utility to derive concatenation of production symbols.
@@ -297,7 +308,7 @@ struct NTHelper {
 ///     production::{Concat, ConstantField, EOFProd, Node, PunctuationsField, RegexField},
 ///     LexerlessParser, NodeImpl,
 /// };
-/// use std::rc::Rc;
+/// use lang_pt::Rc;
 ///
 /// #[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, PartialOrd, Ord)]
 /// pub enum NodeValue {
@@ -363,6 +374,7 @@ struct NTHelper {
 pub struct Concat<TN: NodeImpl = u8, TL: TokenImpl = i8> {
     symbols: OnceCell<Vec<Rc<dyn IProduction<Node = TN, Token = TL>>>>,
     nt_helper: NTHelper,
+    recovery: OnceCell<Recovery<TN, TL>>,
 }
 
 /// A non-terminal utility to implement alternative derivations of productions.
@@ -381,7 +393,7 @@ pub struct Concat<TN: NodeImpl = u8, TL: TokenImpl = i8> {
 ///     production::{Concat, ConstantField, RegexField, Union},
 ///     LexerlessParser,
 /// };
-/// use std::rc::Rc;
+/// use lang_pt::Rc;
 ///
 /// #[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, PartialOrd, Ord)]
 /// enum NodeValue {
@@ -465,7 +477,7 @@ pub type TSuffixMap<TN, TL> = (Rc<dyn IProduction<Node = TN, Token = TL>>, TN);
 ///     production::{Concat, RegexField, SeparatedList, Suffixes, Union},
 ///     LexerlessParser,
 /// };
-/// use std::rc::Rc;
+/// use lang_pt::Rc;
 ///
 /// #[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, PartialOrd, Ord)]
 /// enum NodeValue {
@@ -556,6 +568,103 @@ pub struct Suffixes<TP: IProduction> {
     null_suffix_index: OnceCell<Option<usize>>,
 }
 
+/// Associativity of an [Operator] in a [PrecedenceClimb] production.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Associativity {
+    /// Operators of the same precedence are grouped from the left, e.g. `a - b - c` is `(a - b) - c`.
+    Left,
+    /// Operators of the same precedence are grouped from the right, e.g. `a ^ b ^ c` is `a ^ (b ^ c)`.
+    Right,
+}
+
+/// An operator of a [PrecedenceClimb] production.
+///
+/// An operator with higher precedence binds its operands tighter than an operator with lower precedence.
+pub struct Operator<TN: NodeImpl = u8, TL: TokenImpl = i8> {
+    production: Rc<dyn IProduction<Node = TN, Token = TL>>,
+    precedence: usize,
+    associativity: Associativity,
+    node_value: TN,
+}
+
+/// A production utility to parse operator expressions by precedence climbing.
+///
+/// The utility parses an operand production along with the prefix, infix and postfix [Operator]s
+/// and builds a nested [ASTNode] for each operator based on the precedence and the associativity of the operators.
+/// The children of the operator node are the parsed operands and the parsed tree of the operator itself.
+///
+/// The general form for this production is
+/// E -> P E | E I E | E S | X where, X is the operand,
+/// and P, I and S are the prefix, infix and postfix operators respectively.
+/// It is equivalent to writing a layer of [Union] and [Suffixes] for each precedence level, but without the extra layers.
+/// # Example
+/// ```
+/// use lang_pt::production::{Associativity, ConstantField, Operator, PrecedenceClimb, RegexField};
+/// use lang_pt::{LexerlessParser, NodeImpl};
+/// use lang_pt::Rc;
+///
+/// #[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, PartialOrd, Ord)]
+/// enum NodeValue {
+///     Number,
+///     Add,
+///     Mul,
+///     Pow,
+///     Neg,
+///     Fact,
+///     NULL,
+/// }
+///
+/// impl NodeImpl for NodeValue {
+///     fn null() -> Self { Self::NULL }
+/// }
+///
+/// let number = Rc::new(RegexField::new(r"^[0-9]+", Some(NodeValue::Number)).unwrap());
+/// let add = Rc::new(ConstantField::new("+", None));
+/// let mul = Rc::new(ConstantField::new("*", None));
+/// let pow = Rc::new(ConstantField::new("^", None));
+/// let neg = Rc::new(ConstantField::new("-", None));
+/// let fact = Rc::new(ConstantField::new("!", None));
+///
+/// let expression = Rc::new(PrecedenceClimb::new(
+///     "expression",
+///     &number,
+///     vec![Operator::new(neg, 4, Associativity::Right, NodeValue::Neg)],
+///     vec![
+///         Operator::new(add, 1, Associativity::Left, NodeValue::Add),
+///         Operator::new(mul, 2, Associativity::Left, NodeValue::Mul),
+///         Operator::new(pow, 3, Associativity::Right, NodeValue::Pow),
+///     ],
+///     vec![Operator::new(fact, 5, Associativity::Left, NodeValue::Fact)],
+/// ));
+///
+/// let parser = LexerlessParser::new(expression).unwrap();
+/// let tree_list = parser.parse(b"1+-2*3^4!").unwrap();
+/// tree_list[0].print().unwrap();
+/// /*
+/// Add # 0-9
+/// ├─ Number # 0-1
+/// └─ Mul # 2-9
+///    ├─ Neg # 2-4
+///    │  └─ Number # 3-4
+///    └─ Pow # 5-9
+///       ├─ Number # 5-6
+///       └─ Fact # 7-9
+///          └─ Number # 7-8
+/// */
+/// ```
+pub struct PrecedenceClimb<TP: IProduction> {
+    operand: Rc<TP>,
+    operators: OnceCell<OperatorTable<TP::Node, TP::Token>>,
+    nt_helper: NTHelper,
+}
+
+/// Prefix, infix and postfix operators of a [PrecedenceClimb] production.
+struct OperatorTable<TN: NodeImpl, TL: TokenImpl> {
+    prefix: Vec<Operator<TN, TL>>,
+    infix: Vec<Operator<TN, TL>>,
+    postfix: Vec<Operator<TN, TL>>,
+}
+
 /// An utility to parse a terminal or non-terminal symbols one or multiple times.
 ///
 /// The general form for this production is
@@ -566,7 +675,7 @@ pub struct Suffixes<TP: IProduction> {
 ///     production::{Concat, EOFProd, List, ProductionBuilder, PunctuationsField, RegexField},
 ///     LexerlessParser, NodeImpl,
 /// };
-/// use std::rc::Rc;
+/// use lang_pt::Rc;
 ///
 /// #[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, PartialOrd, Ord)]
 /// enum NodeValue {
@@ -632,9 +741,60 @@ pub struct Suffixes<TP: IProduction> {
 ///     tree.print().unwrap();
 /// });
 /// ```
-pub struct List<TProd: IProduction> {
+pub struct List<TProd: IProduction + ?Sized> {
     symbol: Rc<TProd>,
     debugger: OnceCell<Log<&'static str>>,
+    recovery: OnceCell<Recovery<TProd::Node, TProd::Token>>,
+}
+
+/// An utility to parse a terminal or non-terminal symbol a bounded number of times.
+///
+/// The general form for this production is
+/// E -> X{m,n} where, X can be a non-terminal or terminal symbol,
+/// which is parsed at least m times and at most n times.
+/// The symbol is parsed greedily up to the maximum repetitions, and the production fails when
+/// the symbol can not be parsed the minimum number of times.
+/// # Example
+/// ```
+/// use lang_pt::{
+///     production::{Concat, ConstantField, EOFProd, ProductionBuilder, RegexField, Repeat},
+///     LexerlessParser, NodeImpl,
+/// };
+/// use lang_pt::Rc;
+///
+/// #[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, PartialOrd, Ord)]
+/// enum NodeValue {
+///     Digit,
+///     Unicode,
+///     NULL,
+/// }
+///
+/// impl NodeImpl for NodeValue {
+///     fn null() -> Self { Self::NULL }
+/// }
+///
+/// let eof = Rc::new(EOFProd::new(None));
+/// let hex_digit = Rc::new(RegexField::new(r"^[0-9a-fA-F]", Some(NodeValue::Digit)).unwrap());
+/// let escape = Rc::new(ConstantField::new("\\u", None));
+///
+/// // Exactly 4 hex digits.
+/// let hex_digits = Rc::new(Repeat::new(&hex_digit, 4, Some(4)));
+/// let unicode = Rc::new(Concat::new("unicode", vec![escape, hex_digits]).into_node(NodeValue::Unicode));
+/// let root = Rc::new(Concat::new("root", vec![unicode, eof]));
+///
+/// let parser = LexerlessParser::new(root).unwrap();
+///
+/// let tree_list = parser.parse(b"\\u00e9").unwrap();
+/// assert_eq!(tree_list[0].children.len(), 4);
+///
+/// parser.parse(b"\\u0e9").expect_err("Less than 4 hex digits.");
+/// parser.parse(b"\\u00e9f").expect_err("More than 4 hex digits.");
+/// ```
+pub struct Repeat<TProd: IProduction + ?Sized> {
+    symbol: Rc<TProd>,
+    min: usize,
+    max: Option<usize>,
+    debugger: OnceCell<Log<&'static str>>,
 }
 
 /// A production utility to parse list of terminal or non-terminal symbols separated by another symbol.
@@ -649,7 +809,7 @@ pub struct List<TProd: IProduction> {
 ///     production::{Concat, ConstantField, EOFProd, RegexField, SeparatedList, Union},
 ///     LexerlessParser, NodeImpl,
 /// };
-/// use std::rc::Rc;
+/// use lang_pt::Rc;
 ///
 /// #[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, PartialOrd, Ord)]
 /// enum NodeValue {
@@ -717,7 +877,10 @@ pub struct SeparatedList<TP: IProduction, TS: IProduction<Node = TP::Node, Token
     production: Rc<TP>,
     separator: Rc<TS>,
     inclusive: bool,
+    min: usize,
+    max: Option<usize>,
     debugger: OnceCell<Log<&'static str>>,
+    recovery: OnceCell<Recovery<TP::Node, TP::Token>>,
 }
 
 /// A production utility which add null production as alternative symbol.
@@ -732,7 +895,7 @@ pub struct SeparatedList<TP: IProduction, TS: IProduction<Node = TP::Node, Token
 ///     },
 ///     LexerlessParser, NodeImpl,
 /// };
-/// use std::rc::Rc;
+/// use lang_pt::Rc;
 ///
 /// #[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, PartialOrd, Ord)]
 /// enum Token {
@@ -814,7 +977,7 @@ pub struct SeparatedList<TP: IProduction, TS: IProduction<Node = TP::Node, Token
 /// */
 ///
 /// ```
-pub struct Nullable<TP: IProduction> {
+pub struct Nullable<TP: IProduction + ?Sized> {
     symbol: Rc<TP>,
     node_value: Option<TP::Node>,
     debugger: OnceCell<Log<&'static str>>,
@@ -831,7 +994,7 @@ pub struct Nullable<TP: IProduction> {
 ///     production::{Concat, EOFProd, Node, PunctuationsField, RegexField},
 ///     LexerlessParser, NodeImpl,
 /// };
-/// use std::rc::Rc;
+/// use lang_pt::Rc;
 ///
 /// #[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, PartialOrd, Ord)]
 /// enum NodeValue {
@@ -874,7 +1037,7 @@ pub struct Nullable<TP: IProduction> {
 ///
 /// ```
 
-pub struct Node<TP: IProduction> {
+pub struct Node<TP: IProduction + ?Sized> {
     rule_name: OnceCell<&'static str>,
     production: Rc<TP>,
     node_value: TP::Node,
@@ -899,7 +1062,7 @@ pub struct Hidden<TP: IProduction> {
 ///     production::{Concat, EOFProd, RegexField, Validator},
 ///     LexerlessParser, ProductionError,
 /// };
-/// use std::rc::Rc;
+/// use lang_pt::Rc;
 ///
 /// #[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, PartialOrd, Ord)]
 /// enum NodeValue {
@@ -986,7 +1149,7 @@ pub struct Validator<
 ///     production::{Concat, ConstantField, EOFProd, Lookahead, ProductionBuilder, RegexField, Union},
 ///     LexerlessParser, NodeImpl,
 /// };
-/// use std::rc::Rc;
+/// use lang_pt::Rc;
 ///
 /// #[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, PartialOrd, Ord)]
 /// pub enum NodeValue {
@@ -1084,12 +1247,68 @@ pub struct Validator<
 /// └─ LineTermination # 13-13
 /// */
 /// ```
-pub struct Lookahead<TProd: IProduction> {
+pub struct Lookahead<TProd: IProduction + ?Sized> {
     production: Rc<TProd>,
     node_value: Option<TProd::Node>, // hidden: bool,
     debugger: OnceCell<Log<&'static str>>,
 }
 
+/// A production utility to parse the input only if the associated symbol can not be parsed at the position.
+///
+/// Opposite to [Lookahead], the utility succeeds without consuming any input when the child production fails,
+/// and fails when the child production succeeds.
+/// The utility is useful to exclude a derivation by the following input,
+/// e.g. an identifier which is not followed by `(` or a keyword which is not followed by an identifier character.
+/// # Example
+/// ```
+/// use lang_pt::{
+///     production::{Concat, ConstantField, EOFProd, NotLookahead, RegexField, Union},
+///     LexerlessParser, NodeImpl,
+/// };
+/// use lang_pt::Rc;
+///
+/// #[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, PartialOrd, Ord)]
+/// pub enum NodeValue {
+///     ID,
+///     KeywordLet,
+///     Null,
+/// }
+///
+/// impl NodeImpl for NodeValue {
+///     fn null() -> Self {
+///         Self::Null
+///     }
+/// }
+///
+/// let eof = Rc::new(EOFProd::new(None));
+/// let id = Rc::new(RegexField::new(r#"^[_$a-zA-Z][_$\w]*"#, Some(NodeValue::ID)).unwrap());
+/// let id_char = Rc::new(RegexField::new(r#"^[_$\w]"#, None).unwrap());
+///
+/// let keyword_let = Rc::new(Concat::new(
+///     "keyword_let",
+///     vec![
+///         Rc::new(ConstantField::new("let", Some(NodeValue::KeywordLet))),
+///         Rc::new(NotLookahead::new(&id_char, None)),
+///     ],
+/// ));
+///
+/// let word = Rc::new(Union::new("word", vec![keyword_let, id]));
+/// let root = Rc::new(Concat::new("root", vec![word, eof]));
+///
+/// let parser = LexerlessParser::new(root).unwrap();
+///
+/// let keyword_tree = parser.parse(b"let").unwrap();
+/// assert_eq!(keyword_tree[0].node, NodeValue::KeywordLet);
+///
+/// let id_tree = parser.parse(b"letter").unwrap();
+/// assert_eq!(id_tree[0].node, NodeValue::ID);
+/// ```
+pub struct NotLookahead<TProd: IProduction + ?Sized> {
+    production: Rc<TProd>,
+    node_value: Option<TProd::Node>,
+    debugger: OnceCell<Log<&'static str>>,
+}
+
 #[derive(Clone)]
 /// A production utility which makes child symbol to consume input on non filtered token stream.
 ///
@@ -1108,7 +1327,7 @@ pub struct Lookahead<TProd: IProduction> {
 ///     },
 ///     DefaultParser, NodeImpl, TokenImpl, Tokenizer,
 /// };
-/// use std::rc::Rc;
+/// use lang_pt::Rc;
 ///
 /// #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 /// enum Token {
@@ -1316,7 +1535,7 @@ pub struct Lookahead<TProd: IProduction> {
 ///
 /// ```
 
-pub struct NonStructural<TProd: IProduction> {
+pub struct NonStructural<TProd: IProduction + ?Sized> {
     production: Rc<TProd>,
     fill_range: bool,
     debugger: OnceCell<Log<&'static str>>,
@@ -1357,6 +1576,14 @@ pub trait ProductionBuilder: IProduction {
     where
         Self: Sized;
 
+    fn into_not_lookahead(self, node_value: Option<Self::Node>) -> NotLookahead<Self>
+    where
+        Self: Sized;
+
+    fn into_repeat(self, min: usize, max: Option<usize>) -> Repeat<Self>
+    where
+        Self: Sized;
+
     fn into_separated_list<TS: IProduction<Node = Self::Node, Token = Self::Token>>(
         self,
         sep: &Rc<TS>,
@@ -1385,100 +1612,95 @@ pub trait ProductionBuilder: IProduction {
         Self: Sized;
 }
 
-trait ProductionLogger {
+trait ProductionLogger: Display {
     fn get_debugger(&self) -> Option<&Log<&'static str>>;
 
-    fn log_entry(&self) {
-        #[cfg(debug_assertions)]
-        if let Some(log_label) = self.get_debugger() {
-            if log_label.order() >= Log::Verbose(()).order() {
-                println!("Entering '{}'", log_label)
-            }
+    /// Label of the events of the production when no [Log] label is assigned.
+    fn trace_label(&self) -> Cow<'static, str> {
+        Cow::Owned(self.to_string())
+    }
+
+    /// Label of the event of the level, when the event should be created.
+    ///
+    /// A production with a [Log] label creates the events of the level of the log,
+    /// and a production without a label creates all the events while a trace sink is installed
+    /// if the `trace` feature is enabled.
+    fn event_label(&self, level: Log<()>) -> Option<Cow<'static, str>> {
+        match self.get_debugger() {
+            Some(log_label) => (log_label.order() >= level.order())
+                .then(|| Cow::Borrowed(log_label.label())),
+            None => trace::has_sink().then(|| self.trace_label()),
+        }
+    }
+
+    fn log_entry(&self, pointer: usize) {
+        if let Some(label) = self.event_label(Log::Verbose(())) {
+            trace::emit(TraceEvent::Enter { label, pointer })
+        }
+    }
+    fn log_cache_hit(&self, pointer: usize, success: bool) {
+        if let Some(label) = self.event_label(Log::Verbose(())) {
+            trace::emit(TraceEvent::CacheHit {
+                label,
+                pointer,
+                success,
+            })
         }
     }
     fn log_filtered_result<TN: NodeImpl, TL: TokenImpl>(
         &self,
-        _code: &Code,
-        _index: FltrPtr,
-        _stream: &TokenStream<TL>,
-        _result: &ParsedResult<FltrPtr, TN>,
+        code: &Code,
+        index: FltrPtr,
+        stream: &TokenStream<TL>,
+        result: &ParsedResult<FltrPtr, TN>,
     ) {
-        #[cfg(debug_assertions)]
-        match _result {
-            Ok(data) => self.log_success(
-                _code,
-                _stream[_index].start,
-                _stream[data.consumed_index].end,
-            ),
-            Err(err) => self.log_error(_code, _stream[_index].start, err),
+        match result {
+            Ok(data) => self.log_success(code, stream[index].start, stream[data.consumed_index].start),
+            Err(err) => self.log_error(code, stream[index].start, err),
         }
     }
     fn log_lex_result<TN: NodeImpl, TL: TokenImpl>(
         &self,
-        _code: &Code,
-        _index: TokenPtr,
-        _stream: &TokenStream<TL>,
-        _result: &ParsedResult<TokenPtr, TN>,
+        code: &Code,
+        index: TokenPtr,
+        stream: &TokenStream<TL>,
+        result: &ParsedResult<TokenPtr, TN>,
     ) {
-        #[cfg(debug_assertions)]
-        match _result {
-            Ok(data) => self.log_success(
-                _code,
-                _stream[_index].start,
-                _stream[data.consumed_index].end,
-            ),
-            Err(err) => self.log_error(_code, _stream[_index].start, err),
+        match result {
+            Ok(data) => self.log_success(code, stream[index].start, stream[data.consumed_index].start),
+            Err(err) => self.log_error(code, stream[index].start, err),
         }
     }
 
-    fn log_result<TN: NodeImpl>(
-        &self,
-        _code: &Code,
-        _index: usize,
-        _result: &ParsedResult<usize, TN>,
-    ) {
-        #[cfg(debug_assertions)]
-        match _result {
-            Ok(data) => self.log_success(_code, _index, data.consumed_index),
-            Err(err) => self.log_error(_code, _index, err),
+    fn log_result<TN: NodeImpl>(&self, code: &Code, index: usize, result: &ParsedResult<usize, TN>) {
+        match result {
+            Ok(data) => self.log_success(code, index, data.consumed_index),
+            Err(err) => self.log_error(code, index, err),
         }
     }
-    fn log_success(&self, _code: &Code, _start: usize, _end: usize) {
-        #[cfg(debug_assertions)]
-        if let Some(log_label) = self.get_debugger() {
-            if log_label.order() >= Log::Success(()).order() {
-                println!(
-                    "Parsing Success for '{}': from {} to {}.",
-                    log_label,
-                    _code.obtain_position(_start),
-                    _code.obtain_position(_end),
-                )
-            }
+    fn log_success(&self, _code: &Code, start: usize, end: usize) {
+        if let Some(label) = self.event_label(Log::Success(())) {
+            trace::emit(TraceEvent::Success {
+                label,
+                range: start..end,
+            })
         }
     }
 
-    fn log_error(&self, _code: &Code, _index: usize, _err: &ProductionError) {
-        #[cfg(debug_assertions)]
-        if let Some(log_label) = self.get_debugger() {
-            if log_label.order() >= Log::Result(()).order() {
-                match _err {
-                    ProductionError::Unparsed => {
-                        println!(
-                            "Unparsed production '{}': at {}.",
-                            log_label,
-                            _code.obtain_position(_index),
-                        )
-                    }
-                    ProductionError::Validation(pointer, message) => {
-                        println!(
-                            "Validation error '{}': at {}. {}",
-                            log_label,
-                            _code.obtain_position(*pointer),
-                            message
-                        )
-                    }
-                }
-            }
+    fn log_error(&self, _code: &Code, index: usize, err: &ProductionError) {
+        if let Some(label) = self.event_label(Log::Result(())) {
+            trace::emit(match err {
+                ProductionError::Unparsed => TraceEvent::Failure {
+                    label,
+                    pointer: index,
+                    message: None,
+                },
+                ProductionError::Validation(pointer, message) => TraceEvent::Failure {
+                    label,
+                    pointer: *pointer,
+                    message: Some(message.clone()),
+                },
+            })
         }
     }
 }
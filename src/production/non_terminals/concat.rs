@@ -1,16 +1,17 @@
-use crate::production::NTHelper;
-#[cfg(debug_assertions)]
 use crate::production::ProductionLogger;
-use crate::Log;
+use crate::production::{NTHelper, Recovery};
+use crate::profile;
 use crate::ImplementationError;
+use crate::Log;
 use crate::{
-    production::Concat, ASTNode, Cache, IProduction, NodeImpl, ParsedResult, SuccessData, TokenImpl,
+    grammar::{Grammar, GrammarExpr},
+    production::Concat,
+    ASTNode, Cache, CacheKey, Code, FltrPtr, IProduction, NodeImpl, OnceCell, ParsedResult,
+    ProductionError, Rc, SuccessData, TokenImpl, TokenStream,
 };
-use once_cell::unsync::OnceCell;
 use std::collections::{HashMap, HashSet};
 use std::fmt::Display;
 use std::fmt::Write;
-use std::rc::Rc;
 
 impl<TN: NodeImpl, TL: TokenImpl> Concat<TN, TL> {
     /// Create a new [Concat] utility without production symbols.
@@ -20,6 +21,7 @@ impl<TN: NodeImpl, TL: TokenImpl> Concat<TN, TL> {
         Self {
             symbols: OnceCell::new(),
             nt_helper: NTHelper::new(identifier),
+            recovery: OnceCell::new(),
         }
     }
 
@@ -39,6 +41,7 @@ impl<TN: NodeImpl, TL: TokenImpl> Concat<TN, TL> {
         Self {
             symbols: production_cell,
             nt_helper: NTHelper::new(identifier),
+            recovery: OnceCell::new(),
         }
     }
 
@@ -76,6 +79,120 @@ impl<TN: NodeImpl, TL: TokenImpl> Concat<TN, TL> {
         self.nt_helper.assign_debugger(debugger)
     }
 
+    /// Set a recovery point to continue parsing after a syntax error while parsing in the
+    /// [error recovery](crate::DefaultParser::parse_with_recovery) mode.
+    ///
+    /// Once a child symbol fails after the production has consumed some tokens,
+    /// the tokens are skipped until one of the `sync_tokens` or end of file,
+    /// and the skipped tokens are added as an [ASTNode] with `node_value`.
+    /// The parsing will then resume with the first remaining symbol which can be parsed from the synchronization token,
+    /// and a syntax error is recorded for each of the remaining symbols skipped before it.
+    /// ### Arguments
+    /// * `sync_tokens` - Tokens to resynchronize the parser, e.g. `;` or `}`.
+    /// * `node_value` - Node value of the error node.
+    pub fn set_recovery(&self, sync_tokens: Vec<TL>, node_value: TN) -> Result<(), String> {
+        self.recovery
+            .set(Recovery::new(sync_tokens, node_value))
+            .map_err(|_| format!("Recovery is already set for {}.", self.nt_helper.identifier))
+    }
+
+    /// Allow the production to be derived recursively as its own leftmost symbol.
+    ///
+    /// The left recursive production is parsed by growing a seed: the production is parsed repeatedly,
+    /// where each recursive derivation at the same position returns the previously parsed result,
+    /// until the production can not consume more input.
+    /// Thus, the parsed tree of a production like `expr : expr '+' term` will be left associative.
+    /// Only one production in a cycle of indirect left recursion needs to be set as left recursive.
+    /// ### Arguments
+    /// * `cache_key` - An unique key to save the growing parsed result of the production.
+    pub fn set_left_recursive(&self, cache_key: CacheKey) -> Result<(), String> {
+        self.nt_helper.set_left_recursion(cache_key)
+    }
+
+    fn consume_n_recover(
+        &self,
+        recovery: &Recovery<TN, TL>,
+        code: &Code,
+        index: FltrPtr,
+        stream: &TokenStream<TL>,
+        cache: &mut Cache<FltrPtr, TN>,
+    ) -> ParsedResult<FltrPtr, TN> {
+        let productions = self.get_productions();
+        // Each symbol is parsed in isolation to locate its syntax error from the recovery position.
+        let advance_symbol = |symbol: &Rc<dyn IProduction<Node = TN, Token = TL>>,
+                              moved_ptr: FltrPtr,
+                              cache: &mut Cache<FltrPtr, TN>| {
+            cache.isolate(stream.pointer(moved_ptr), |cache| {
+                let result = symbol.advance_fltr_ptr(code, moved_ptr, stream, cache);
+                let syntax_error = match &result {
+                    Err(ProductionError::Unparsed) => {
+                        Some(cache.create_error(code, stream, ProductionError::Unparsed))
+                    }
+                    _ => None,
+                };
+                (result, syntax_error)
+            })
+        };
+        let mut parsed_children: Vec<ASTNode<TN>> = Vec::new();
+        let mut moved_ptr = index;
+        let mut symbol_index = 0;
+        while symbol_index < productions.len() {
+            let checkpoint = cache.recovery_checkpoint();
+            let (result, syntax_error) =
+                advance_symbol(&productions[symbol_index], moved_ptr, cache);
+            let err = match result {
+                Ok(parsed_data) => {
+                    moved_ptr = parsed_data.consumed_index;
+                    parsed_children.extend(parsed_data.children);
+                    symbol_index += 1;
+                    continue;
+                }
+                Err(err) => err,
+            };
+
+            if err.is_invalid() || moved_ptr == index {
+                return Err(err);
+            }
+            cache.rollback_recovery(checkpoint);
+
+            if let Some(syntax_error) = syntax_error {
+                cache.add_recovered_error(syntax_error);
+            }
+
+            let sync_ptr = recovery.sync_index(stream, moved_ptr);
+            parsed_children.push(recovery.error_node(stream, moved_ptr, sync_ptr));
+            moved_ptr = sync_ptr;
+
+            // The symbols failing at the synchronized position are skipped with their own errors.
+            symbol_index += 1;
+            while symbol_index < productions.len() {
+                let checkpoint = cache.recovery_checkpoint();
+                let (result, syntax_error) =
+                    advance_symbol(&productions[symbol_index], moved_ptr, cache);
+                match result {
+                    Ok(parsed_data) => {
+                        moved_ptr = parsed_data.consumed_index;
+                        parsed_children.extend(parsed_data.children);
+                        symbol_index += 1;
+                        break;
+                    }
+                    Err(err) => {
+                        if err.is_invalid() {
+                            return Err(err);
+                        }
+                        cache.rollback_recovery(checkpoint);
+                        if let Some(syntax_error) = syntax_error {
+                            cache.add_recovered_error(syntax_error);
+                        }
+                        symbol_index += 1;
+                    }
+                }
+            }
+        }
+
+        Ok(SuccessData::new(moved_ptr, parsed_children))
+    }
+
     fn consume<
         T,
         TCache,
@@ -136,6 +253,9 @@ impl<TN: NodeImpl, TL: TokenImpl> IProduction for Concat<TN, TL> {
         &'id self,
         mut visited: HashMap<&'id str, usize>,
     ) -> Result<bool, crate::ImplementationError> {
+        if self.nt_helper.is_recurring(&visited) {
+            return Ok(false);
+        }
         self.nt_helper.validate_circular_dependency(&mut visited)?;
 
         match self.nt_helper.nullability.get() {
@@ -149,19 +269,20 @@ impl<TN: NodeImpl, TL: TokenImpl> IProduction for Concat<TN, TL> {
                     }
                 }
 
-                self.nt_helper.nullability.set(is_nullable).unwrap();
-                Ok(is_nullable)
+                Ok(*self.nt_helper.nullability.get_or_init(|| is_nullable))
             }
         }
     }
 
     fn impl_first_set(&self, first_set: &mut HashSet<Self::Token>) {
-        for prod in self.get_productions() {
-            prod.impl_first_set(first_set);
-            if !prod.is_nullable() {
-                break;
+        self.nt_helper.collect_first_set(|| {
+            for prod in self.get_productions() {
+                prod.impl_first_set(first_set);
+                if !prod.is_nullable() {
+                    break;
+                }
             }
-        }
+        })
     }
 
     fn impl_grammar(
@@ -188,12 +309,23 @@ impl<TN: NodeImpl, TL: TokenImpl> IProduction for Concat<TN, TL> {
         Ok(())
     }
 
+    fn grammar_expr(&self, grammar: &mut Grammar) -> GrammarExpr {
+        grammar.add_rule(self.nt_helper.identifier, |grammar| {
+            GrammarExpr::sequence(
+                self.get_productions()
+                    .iter()
+                    .map(|prod| prod.grammar_expr(grammar))
+                    .collect(),
+            )
+        })
+    }
+
     fn validate<'id>(
         &'id self,
         mut connected_set: HashMap<&'id str, usize>,
         visited_prod: &mut HashSet<&'id str>,
     ) -> Result<(), ImplementationError> {
-        if !self
+        if self
             .nt_helper
             .has_visited(&mut connected_set, visited_prod)?
         {
@@ -227,18 +359,27 @@ impl<TN: NodeImpl, TL: TokenImpl> IProduction for Concat<TN, TL> {
         stream: &crate::TokenStream<Self::Token>,
         cache: &mut Cache<crate::FltrPtr, Self::Node>,
     ) -> ParsedResult<crate::FltrPtr, Self::Node> {
-        #[cfg(debug_assertions)]
-        self.nt_helper.log_entry();
+        profile::measure(self, index, || {
+            self.nt_helper.log_entry(stream[index].start);
 
-        let result = self.consume(index, cache, |prod, moved_pointer, cache| {
-            prod.advance_fltr_ptr(code, moved_pointer, stream, cache)
-        });
-
-        #[cfg(debug_assertions)]
-        self.nt_helper
-            .log_filtered_result(code, index, stream, &result);
-
-        result
+            let derive = |cache: &mut Cache<FltrPtr, TN>| match self.recovery.get() {
+                Some(recovery) if cache.is_recovering() => {
+                    self.consume_n_recover(recovery, code, index, stream, cache)
+                }
+                _ => self.consume(index, cache, |prod, moved_pointer, cache| {
+                    prod.advance_fltr_ptr(code, moved_pointer, stream, cache)
+                }),
+            };
+            let result = match self.nt_helper.left_recursion.get() {
+                Some(key) => cache.grow_left_recursion(*key, stream[index].start, derive),
+                None => derive(cache),
+            };
+
+            self.nt_helper
+                .log_filtered_result(code, index, stream, &result);
+
+            result
+        })
     }
 
     fn advance_token_ptr(
@@ -248,17 +389,23 @@ impl<TN: NodeImpl, TL: TokenImpl> IProduction for Concat<TN, TL> {
         stream: &crate::TokenStream<Self::Token>,
         cache: &mut Cache<crate::FltrPtr, Self::Node>,
     ) -> ParsedResult<crate::TokenPtr, Self::Node> {
-        #[cfg(debug_assertions)]
-        self.nt_helper.log_entry();
-
-        let result = self.consume(index, cache, |prod, moved_pointer, cache| {
-            prod.advance_token_ptr(code, moved_pointer, stream, cache)
-        });
-
-        #[cfg(debug_assertions)]
-        self.nt_helper.log_lex_result(code, index, stream, &result);
-
-        result
+        profile::measure(self, index, || {
+            self.nt_helper.log_entry(stream[index].start);
+
+            let derive = |cache: &mut Cache<FltrPtr, TN>| {
+                self.consume(index, cache, |prod, moved_pointer, cache| {
+                    prod.advance_token_ptr(code, moved_pointer, stream, cache)
+                })
+            };
+            let result = match self.nt_helper.left_recursion.get() {
+                Some(key) => cache.grow_token_left_recursion(*key, stream[index].start, derive),
+                None => derive(cache),
+            };
+
+            self.nt_helper.log_lex_result(code, index, stream, &result);
+
+            result
+        })
     }
 
     fn advance_ptr(
@@ -267,16 +414,22 @@ impl<TN: NodeImpl, TL: TokenImpl> IProduction for Concat<TN, TL> {
         index: usize,
         cache: &mut Cache<usize, Self::Node>,
     ) -> ParsedResult<usize, Self::Node> {
-        #[cfg(debug_assertions)]
-        self.nt_helper.log_entry();
-
-        let result = self.consume(index, cache, |prod, moved_pointer, cache| {
-            prod.advance_ptr(code, moved_pointer, cache)
-        });
-
-        #[cfg(debug_assertions)]
-        self.nt_helper.log_result(code, index, &result);
-
-        result
+        profile::measure(self, index, || {
+            self.nt_helper.log_entry(index);
+
+            let derive = |cache: &mut Cache<usize, TN>| {
+                self.consume(index, cache, |prod, moved_pointer, cache| {
+                    prod.advance_ptr(code, moved_pointer, cache)
+                })
+            };
+            let result = match self.nt_helper.left_recursion.get() {
+                Some(key) => cache.grow_left_recursion(*key, index, derive),
+                None => derive(cache),
+            };
+
+            self.nt_helper.log_result(code, index, &result);
+
+            result
+        })
     }
 }
@@ -1,14 +1,21 @@
 use super::{NTHelper, ProductionLogger};
-use crate::{ImplementationError, Log};
-use once_cell::unsync::OnceCell;
+use crate::{CacheKey, ImplementationError, Log, OnceCell};
 use std::{
+    borrow::Cow,
+    cell::RefCell,
     collections::{HashMap, HashSet},
-    fmt::Write,
+    fmt::{Display, Write},
 };
 mod concat;
+mod precedence;
 mod suffixes;
 mod union;
 
+thread_local! {
+    /// Identifiers of the left recursive productions whose first sets are being collected.
+    static FIRST_SET_HEADS: RefCell<Vec<&'static str>> = const { RefCell::new(Vec::new()) };
+}
+
 fn build_circular_format(id: &str, connected_set: &HashMap<&str, usize>) -> String {
     let mut first_set_trees: Vec<(&&str, &usize)> = connected_set.iter().collect();
     first_set_trees.sort_by_key(|s| s.1);
@@ -39,6 +46,41 @@ impl NTHelper {
             nullability: OnceCell::new(),
             null_hidden: OnceCell::new(),
             debugger: OnceCell::new(),
+            left_recursion: OnceCell::new(),
+        }
+    }
+
+    fn set_left_recursion(&self, cache_key: CacheKey) -> Result<(), String> {
+        self.left_recursion
+            .set(cache_key)
+            .map_err(|_| format!("Left recursion is already set for {}.", self.identifier))
+    }
+
+    /// Whether a left recursive production is derived again while obtaining its nullability.
+    ///
+    /// The recursive derivation does not derive an empty input by itself.
+    fn is_recurring(&self, visited_set: &HashMap<&str, usize>) -> bool {
+        self.left_recursion.get().is_some() && visited_set.contains_key(self.identifier)
+    }
+
+    /// Collect the first set of the production.
+    /// The first set of a left recursive production is not collected again from its recursive derivation.
+    fn collect_first_set<F: FnOnce()>(&self, collect: F) {
+        if self.left_recursion.get().is_none() {
+            return collect();
+        }
+        let is_collecting = FIRST_SET_HEADS.with(|heads| {
+            let mut heads = heads.borrow_mut();
+            if heads.contains(&self.identifier) {
+                true
+            } else {
+                heads.push(self.identifier);
+                false
+            }
+        });
+        if !is_collecting {
+            collect();
+            FIRST_SET_HEADS.with(|heads| heads.borrow_mut().pop());
         }
     }
 
@@ -46,6 +88,10 @@ impl NTHelper {
         &'id self,
         visited_set: &mut HashMap<&'id str, usize>,
     ) -> Result<(), ImplementationError> {
+        if self.left_recursion.get().is_some() {
+            // A left recursive production breaks the circular dependency of the derivations.
+            visited_set.clear();
+        }
         let l = visited_set.len();
         if visited_set.insert(self.identifier, l).is_none() {
             Ok(())
@@ -62,13 +108,19 @@ impl NTHelper {
         connected_set: &mut HashMap<&'id str, usize>,
         visited_prod: &mut HashSet<&'id str>,
     ) -> Result<bool, ImplementationError> {
-        if connected_set.contains_key(self.identifier) {
+        if connected_set.contains_key(self.identifier) && self.left_recursion.get().is_some() {
+            // The recursive derivation will be resolved by growing the seed parsed result.
+            Ok(false)
+        } else if connected_set.contains_key(self.identifier) {
             Err(ImplementationError::new(
                 "LeftRecursion.".to_string(),
                 build_circular_format(self.identifier, &connected_set),
             ))
         } else {
             if visited_prod.insert(self.identifier) {
+                if self.left_recursion.get().is_some() {
+                    connected_set.clear();
+                }
                 let l = connected_set.len();
                 connected_set.insert(self.identifier, l);
                 Ok(true)
@@ -94,8 +146,18 @@ impl NTHelper {
     // }
 }
 
+impl Display for NTHelper {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.identifier)
+    }
+}
+
 impl ProductionLogger for NTHelper {
     fn get_debugger(&self) -> Option<&crate::Log<&'static str>> {
         self.debugger.get()
     }
+
+    fn trace_label(&self) -> Cow<'static, str> {
+        Cow::Borrowed(self.identifier)
+    }
 }
@@ -0,0 +1,553 @@
+use crate::production::NTHelper;
+use crate::production::ProductionLogger;
+use crate::profile;
+use crate::{
+    grammar::{Grammar, GrammarExpr},
+    production::{Associativity, Operator, OperatorTable, PrecedenceClimb},
+    ASTNode, Cache, Code, FltrPtr, IProduction, ImplementationError, NodeImpl, OnceCell,
+    ParsedResult, Rc, SuccessData, TokenImpl, TokenPtr, TokenStream,
+};
+use std::{
+    collections::{HashMap, HashSet},
+    fmt::{Display, Write},
+    hash::Hash,
+};
+
+impl<TN: NodeImpl, TL: TokenImpl> Operator<TN, TL> {
+    /// Create a new [Operator].
+    /// ### Arguments
+    /// * `production` - A non nullable production to parse the operator.
+    /// * `precedence` - Binding power of the operator. The higher precedence operator is parsed first.
+    /// * `associativity` - Grouping of the operators with the same precedence.
+    /// * `node_value` - Node value of the parsed tree of the operator expression.
+    pub fn new(
+        production: Rc<dyn IProduction<Node = TN, Token = TL>>,
+        precedence: usize,
+        associativity: Associativity,
+        node_value: TN,
+    ) -> Self {
+        Self {
+            production,
+            precedence,
+            associativity,
+            node_value,
+        }
+    }
+
+    /// Minimum precedence of the operators in the operand after this operator.
+    fn right_precedence(&self) -> usize {
+        match self.associativity {
+            Associativity::Left => self.precedence + 1,
+            Associativity::Right => self.precedence,
+        }
+    }
+}
+
+impl<TP: IProduction> PrecedenceClimb<TP> {
+    /// Create a new [PrecedenceClimb] utility without operators.
+    /// ### Arguments
+    /// * `identifier` - An unique identifier.
+    /// * `operand` - A production utility to parse the operands of the operators.
+    pub fn init(identifier: &'static str, operand: &Rc<TP>) -> Self {
+        Self {
+            operand: operand.clone(),
+            operators: OnceCell::new(),
+            nt_helper: NTHelper::new(identifier),
+        }
+    }
+
+    /// Create a new [PrecedenceClimb] utility with operators.
+    /// ### Arguments
+    /// * `identifier` - An unique identifier.
+    /// * `operand` - A production utility to parse the operands of the operators.
+    /// * `prefix` - Operators which are parsed before an operand.
+    /// * `infix` - Operators which are parsed between two operands.
+    /// * `postfix` - Operators which are parsed after an operand.
+    pub fn new(
+        identifier: &'static str,
+        operand: &Rc<TP>,
+        prefix: Vec<Operator<TP::Node, TP::Token>>,
+        infix: Vec<Operator<TP::Node, TP::Token>>,
+        postfix: Vec<Operator<TP::Node, TP::Token>>,
+    ) -> Self {
+        let production = Self::init(identifier, operand);
+        if production.set_operators(prefix, infix, postfix).is_err() {
+            panic!("Report bug. Operators should not be set.");
+        }
+        production
+    }
+
+    /// Set prefix, infix and postfix operators of the production.
+    pub fn set_operators(
+        &self,
+        prefix: Vec<Operator<TP::Node, TP::Token>>,
+        infix: Vec<Operator<TP::Node, TP::Token>>,
+        postfix: Vec<Operator<TP::Node, TP::Token>>,
+    ) -> Result<(), String> {
+        self.operators
+            .set(OperatorTable {
+                prefix,
+                infix,
+                postfix,
+            })
+            .map_err(|_| {
+                format!(
+                    "Operators are already set for {}.",
+                    self.nt_helper.identifier
+                )
+            })
+    }
+
+    /// Set a log label to debug the production based on the level of [Log](crate::Log).
+    pub fn set_log(&self, debugger: crate::Log<&'static str>) -> Result<(), String> {
+        self.nt_helper.assign_debugger(debugger)
+    }
+
+    fn get_operators(&self) -> &OperatorTable<TP::Node, TP::Token> {
+        self.operators.get_or_init(|| {
+            if cfg!(debug_assertions) {
+                panic!(
+                    "Operators are not set for {}. Validate productions before parsing.",
+                    self.nt_helper.identifier
+                )
+            }
+            OperatorTable {
+                prefix: Vec::new(),
+                infix: Vec::new(),
+                postfix: Vec::new(),
+            }
+        })
+    }
+
+    fn all_operators(&self) -> impl Iterator<Item = &Operator<TP::Node, TP::Token>> {
+        let operators = self.get_operators();
+        operators
+            .prefix
+            .iter()
+            .chain(&operators.infix)
+            .chain(&operators.postfix)
+    }
+
+    /// Parse an expression whose operators have at least the `min_precedence`.
+    ///
+    /// The `build` function creates the [ASTNode] of an operator from the start and the end pointer of the expression.
+    fn climb<
+        T: Copy,
+        TCache: Default + Eq + Hash + Ord + Copy,
+        P: Fn(
+            &dyn IProduction<Node = TP::Node, Token = TP::Token>,
+            T,
+            &mut Cache<TCache, TP::Node>,
+        ) -> ParsedResult<T, TP::Node>,
+        B: Fn(TP::Node, T, T, Vec<ASTNode<TP::Node>>) -> ASTNode<TP::Node>,
+    >(
+        &self,
+        index: T,
+        min_precedence: usize,
+        cache: &mut Cache<TCache, TP::Node>,
+        parse_production: &P,
+        build: &B,
+    ) -> ParsedResult<T, TP::Node> {
+        let operators = self.get_operators();
+
+        let mut left = self.climb_prefix(index, cache, parse_production, build)?;
+
+        'operators: loop {
+            for operator in &operators.postfix {
+                if operator.precedence < min_precedence {
+                    continue;
+                }
+                match parse_production(&*operator.production, left.consumed_index, cache) {
+                    Ok(data) => {
+                        let mut children = left.children;
+                        children.extend(data.children);
+                        let tree = build(
+                            operator.node_value.clone(),
+                            index,
+                            data.consumed_index,
+                            children,
+                        );
+                        left = SuccessData::tree(data.consumed_index, tree);
+                        continue 'operators;
+                    }
+                    Err(err) if err.is_invalid() => return Err(err),
+                    Err(_) => {}
+                }
+            }
+
+            for operator in &operators.infix {
+                if operator.precedence < min_precedence {
+                    continue;
+                }
+                let checkpoint = cache.recovery_checkpoint();
+                let data = match parse_production(&*operator.production, left.consumed_index, cache)
+                {
+                    Ok(data) => data,
+                    Err(err) if err.is_invalid() => return Err(err),
+                    Err(_) => continue,
+                };
+                match self.climb(
+                    data.consumed_index,
+                    operator.right_precedence(),
+                    cache,
+                    parse_production,
+                    build,
+                ) {
+                    Ok(right) => {
+                        let mut children = left.children;
+                        children.extend(data.children);
+                        children.extend(right.children);
+                        let tree = build(
+                            operator.node_value.clone(),
+                            index,
+                            right.consumed_index,
+                            children,
+                        );
+                        left = SuccessData::tree(right.consumed_index, tree);
+                        continue 'operators;
+                    }
+                    Err(err) if err.is_invalid() => return Err(err),
+                    // The operator is not a part of the expression without the right operand.
+                    Err(_) => cache.rollback_recovery(checkpoint),
+                }
+            }
+            break;
+        }
+        Ok(left)
+    }
+
+    /// Parse the operand along with its prefix operators.
+    fn climb_prefix<
+        T: Copy,
+        TCache: Default + Eq + Hash + Ord + Copy,
+        P: Fn(
+            &dyn IProduction<Node = TP::Node, Token = TP::Token>,
+            T,
+            &mut Cache<TCache, TP::Node>,
+        ) -> ParsedResult<T, TP::Node>,
+        B: Fn(TP::Node, T, T, Vec<ASTNode<TP::Node>>) -> ASTNode<TP::Node>,
+    >(
+        &self,
+        index: T,
+        cache: &mut Cache<TCache, TP::Node>,
+        parse_production: &P,
+        build: &B,
+    ) -> ParsedResult<T, TP::Node> {
+        for operator in &self.get_operators().prefix {
+            let checkpoint = cache.recovery_checkpoint();
+            let data = match parse_production(&*operator.production, index, cache) {
+                Ok(data) => data,
+                Err(err) if err.is_invalid() => return Err(err),
+                Err(_) => continue,
+            };
+            match self.climb(
+                data.consumed_index,
+                operator.right_precedence(),
+                cache,
+                parse_production,
+                build,
+            ) {
+                Ok(operand) => {
+                    let mut children = data.children;
+                    children.extend(operand.children);
+                    let tree = build(
+                        operator.node_value.clone(),
+                        index,
+                        operand.consumed_index,
+                        children,
+                    );
+                    return Ok(SuccessData::tree(operand.consumed_index, tree));
+                }
+                Err(err) if err.is_invalid() => return Err(err),
+                Err(_) => cache.rollback_recovery(checkpoint),
+            }
+        }
+        parse_production(&*self.operand, index, cache)
+    }
+}
+
+impl<TP: IProduction> Display for PrecedenceClimb<TP> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.nt_helper.identifier)
+    }
+}
+
+impl<TP: IProduction> IProduction for PrecedenceClimb<TP> {
+    type Node = TP::Node;
+
+    type Token = TP::Token;
+
+    fn is_nullable(&self) -> bool {
+        match self.nt_helper.nullability.get() {
+            Some(t) => *t,
+            None => self
+                .obtain_nullability(HashMap::new())
+                .expect("LeftRecursion: Validate grammar before parsing."),
+        }
+    }
+
+    fn is_nullable_n_hidden(&self) -> bool {
+        *self
+            .nt_helper
+            .null_hidden
+            .get_or_init(|| self.operand.is_nullable_n_hidden())
+    }
+
+    fn obtain_nullability<'id>(
+        &'id self,
+        mut visited: HashMap<&'id str, usize>,
+    ) -> Result<bool, ImplementationError> {
+        self.nt_helper.validate_circular_dependency(&mut visited)?;
+        match self.nt_helper.nullability.get() {
+            Some(t) => Ok(*t),
+            None => {
+                let is_nullable = self.operand.obtain_nullability(visited)?;
+                Ok(*self.nt_helper.nullability.get_or_init(|| is_nullable))
+            }
+        }
+    }
+
+    fn impl_first_set(&self, first_set: &mut HashSet<Self::Token>) {
+        let operators = self.get_operators();
+        for operator in &operators.prefix {
+            operator.production.impl_first_set(first_set);
+        }
+        self.operand.impl_first_set(first_set);
+        if self.operand.is_nullable() {
+            for operator in operators.infix.iter().chain(&operators.postfix) {
+                operator.production.impl_first_set(first_set);
+            }
+        }
+    }
+
+    fn impl_grammar(
+        &self,
+        writer: &mut dyn Write,
+        visited: &mut HashSet<&'static str>,
+    ) -> Result<(), std::fmt::Error> {
+        if visited.insert(self.nt_helper.identifier) {
+            let operators = self.get_operators();
+            let identifier = self.nt_helper.identifier;
+            writeln!(writer, "{}", identifier)?;
+            writeln!(writer, "{:>6} {}", ":", self.operand)?;
+            for operator in &operators.prefix {
+                writeln!(
+                    writer,
+                    "{:>6} [{} {}; @{:?} {}]",
+                    "|", operator.production, identifier, operator.node_value, operator.precedence
+                )?;
+            }
+            for operator in &operators.infix {
+                writeln!(
+                    writer,
+                    "{:>6} [{} {} {}; @{:?} {} {:?}]",
+                    "|",
+                    identifier,
+                    operator.production,
+                    identifier,
+                    operator.node_value,
+                    operator.precedence,
+                    operator.associativity
+                )?;
+            }
+            for operator in &operators.postfix {
+                writeln!(
+                    writer,
+                    "{:>6} [{} {}; @{:?} {}]",
+                    "|", identifier, operator.production, operator.node_value, operator.precedence
+                )?;
+            }
+            writeln!(writer, "{:>6}", ";")?;
+            writeln!(writer)?;
+
+            self.operand.impl_grammar(writer, visited)?;
+            for operator in self.all_operators() {
+                operator.production.impl_grammar(writer, visited)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// The operators are written as `prefix* operand postfix* (infix prefix* operand postfix*)*`
+    /// without the precedence and the associativity of the operators.
+    fn grammar_expr(&self, grammar: &mut Grammar) -> GrammarExpr {
+        grammar.add_rule(self.nt_helper.identifier, |grammar| {
+            let operators = self.get_operators();
+            let mut operator_expr = |operators: &Vec<Operator<TP::Node, TP::Token>>| {
+                GrammarExpr::choice(
+                    operators
+                        .iter()
+                        .map(|operator| {
+                            GrammarExpr::with_node(
+                                Some(&operator.node_value),
+                                operator.production.grammar_expr(grammar),
+                            )
+                        })
+                        .collect(),
+                )
+            };
+            let prefix = operator_expr(&operators.prefix);
+            let infix = operator_expr(&operators.infix);
+            let postfix = operator_expr(&operators.postfix);
+            let operand = GrammarExpr::sequence(vec![
+                GrammarExpr::repeat(prefix, 0, None),
+                self.operand.grammar_expr(grammar),
+                GrammarExpr::repeat(postfix, 0, None),
+            ]);
+            GrammarExpr::sequence(vec![
+                operand.clone(),
+                GrammarExpr::repeat(GrammarExpr::sequence(vec![infix, operand]), 0, None),
+            ])
+        })
+    }
+
+    fn validate<'id>(
+        &'id self,
+        mut connected_set: HashMap<&'id str, usize>,
+        visited_prod: &mut HashSet<&'id str>,
+    ) -> Result<(), ImplementationError> {
+        if self
+            .nt_helper
+            .has_visited(&mut connected_set, visited_prod)?
+        {
+            if self.operators.get().is_none() {
+                return Err(ImplementationError::new(
+                    "InitializationError".into(),
+                    format!(
+                        "Operators are not assigned for {:?}.",
+                        self.nt_helper.identifier
+                    ),
+                ));
+            }
+
+            self.operand.validate(connected_set.clone(), visited_prod)?;
+            let is_nullable = self.operand.obtain_nullability(HashMap::new())?;
+            let operators = self.get_operators();
+            for operator in &operators.prefix {
+                operator
+                    .production
+                    .validate(connected_set.clone(), visited_prod)?;
+            }
+            for operator in operators.infix.iter().chain(&operators.postfix) {
+                if is_nullable {
+                    operator
+                        .production
+                        .validate(connected_set.clone(), visited_prod)?;
+                } else {
+                    operator.production.validate(HashMap::new(), visited_prod)?;
+                }
+            }
+
+            for operator in self.all_operators() {
+                if operator.production.obtain_nullability(HashMap::new())? {
+                    return Err(ImplementationError::new(
+                        "NullableOperator".into(),
+                        format!(
+                            "Operator {} of {} should not be nullable.",
+                            operator.production, self.nt_helper.identifier
+                        ),
+                    ));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn advance_fltr_ptr(
+        &self,
+        code: &Code,
+        index: FltrPtr,
+        token_stream: &TokenStream<Self::Token>,
+        cache: &mut Cache<FltrPtr, Self::Node>,
+    ) -> ParsedResult<FltrPtr, Self::Node> {
+        profile::measure(self, index, || {
+            self.nt_helper.log_entry(token_stream[index].start);
+
+            let result = self.climb(
+                index,
+                0,
+                cache,
+                &|prod, moved_pointer, cache| {
+                    prod.advance_fltr_ptr(code, moved_pointer, token_stream, cache)
+                },
+                &|node_value, start, end, children| {
+                    ASTNode::new(
+                        node_value,
+                        token_stream.pointer(start),
+                        token_stream.pointer(end),
+                        Some((
+                            token_stream.get_token_ptr(start),
+                            token_stream.get_token_ptr(end),
+                        )),
+                        children,
+                    )
+                },
+            );
+
+            self.nt_helper
+                .log_filtered_result(code, index, token_stream, &result);
+
+            result
+        })
+    }
+
+    fn advance_token_ptr(
+        &self,
+        code: &Code,
+        index: TokenPtr,
+        token_stream: &TokenStream<Self::Token>,
+        cache: &mut Cache<FltrPtr, Self::Node>,
+    ) -> ParsedResult<TokenPtr, Self::Node> {
+        profile::measure(self, index, || {
+            self.nt_helper.log_entry(token_stream[index].start);
+
+            let result = self.climb(
+                index,
+                0,
+                cache,
+                &|prod, moved_pointer, cache| {
+                    prod.advance_token_ptr(code, moved_pointer, token_stream, cache)
+                },
+                &|node_value, start: TokenPtr, end: TokenPtr, children| {
+                    ASTNode::new(
+                        node_value,
+                        token_stream[start].start,
+                        token_stream[end].start,
+                        Some((start, end)),
+                        children,
+                    )
+                },
+            );
+
+            self.nt_helper
+                .log_lex_result(code, index, token_stream, &result);
+
+            result
+        })
+    }
+
+    fn advance_ptr(
+        &self,
+        code: &Code,
+        index: usize,
+        cache: &mut Cache<usize, Self::Node>,
+    ) -> ParsedResult<usize, Self::Node> {
+        profile::measure(self, index, || {
+            self.nt_helper.log_entry(index);
+
+            let result = self.climb(
+                index,
+                0,
+                cache,
+                &|prod, moved_pointer, cache| prod.advance_ptr(code, moved_pointer, cache),
+                &|node_value, start, end, children| {
+                    ASTNode::new(node_value, start, end, None, children)
+                },
+            );
+
+            self.nt_helper.log_result(code, index, &result);
+
+            result
+        })
+    }
+}
@@ -1,18 +1,16 @@
 use crate::production::NTHelper;
-#[cfg(debug_assertions)]
 use crate::production::ProductionLogger;
+use crate::profile;
 use crate::{
+    grammar::{Grammar, GrammarExpr},
     production::{Suffixes, TSuffixMap},
-    Code,
-    ASTNode, Cache, FltrPtr, IProduction, ImplementationError, ParsedResult, ProductionError,
-    SuccessData, TokenImpl, TokenPtr, TokenStream,
+    ASTNode, Cache, Code, Expected, FltrPtr, IProduction, ImplementationError, OnceCell,
+    ParsedResult, ProductionError, Rc, SuccessData, TokenImpl, TokenPtr, TokenStream,
 };
 
-use once_cell::unsync::OnceCell;
 use std::{
     collections::{HashMap, HashSet},
     fmt::Display,
-    rc::Rc,
 };
 
 impl<TP: IProduction> Suffixes<TP> {
@@ -139,6 +137,15 @@ impl<TP: IProduction> Suffixes<TP> {
     }
 }
 
+impl<TP: IProduction> Suffixes<TP> {
+    /// Record the first set tokens of the suffixes as expected when no suffix can be derived at the pointer.
+    fn add_expected(&self, pointer: usize, cache: &mut Cache<FltrPtr, TP::Node>) {
+        for (token, _) in &self.obtain_suffixes_set().1 {
+            cache.add_expected(pointer, || Expected::Token(format!("{:?}", token)));
+        }
+    }
+}
+
 impl<TP: IProduction> Display for Suffixes<TP> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{}", self.nt_helper.identifier)
@@ -229,12 +236,30 @@ impl<TP: IProduction> IProduction for Suffixes<TP> {
         Ok(())
     }
 
+    fn grammar_expr(&self, grammar: &mut Grammar) -> GrammarExpr {
+        grammar.add_rule(self.nt_helper.identifier, |grammar| {
+            let left = self.left.grammar_expr(grammar);
+            let suffixes = GrammarExpr::choice(
+                self.get_suffixes()
+                    .iter()
+                    .map(|(prod, node_value)| {
+                        GrammarExpr::with_node(Some(node_value), prod.grammar_expr(grammar))
+                    })
+                    .collect(),
+            );
+            match self.standalone {
+                true => GrammarExpr::sequence(vec![left, GrammarExpr::optional(suffixes)]),
+                false => GrammarExpr::sequence(vec![left, suffixes]),
+            }
+        })
+    }
+
     fn validate<'id>(
         &'id self,
         mut connected_set: HashMap<&'id str, usize>,
         visited_prod: &mut HashSet<&'id str>,
     ) -> Result<(), ImplementationError> {
-        if !self
+        if self
             .nt_helper
             .has_visited(&mut connected_set, visited_prod)?
         {
@@ -269,266 +294,275 @@ impl<TP: IProduction> IProduction for Suffixes<TP> {
         token_stream: &TokenStream<Self::Token>,
         cache: &mut Cache<FltrPtr, Self::Node>,
     ) -> ParsedResult<FltrPtr, Self::Node> {
-        #[cfg(debug_assertions)]
-        self.nt_helper.log_entry();
+        profile::measure(self, fltr_ptr, || {
+            self.nt_helper.log_entry(token_stream[fltr_ptr].start);
 
-        let mut left_success_data =
-            self.left
-                .advance_fltr_ptr(code, fltr_ptr, token_stream, cache)?;
-        // let mut parsed_children: Vec<Rc<AST<TProd::Token>>> = Vec::new();
+            let mut left_success_data =
+                self.left
+                    .advance_fltr_ptr(code, fltr_ptr, token_stream, cache)?;
+            // let mut parsed_children: Vec<Rc<AST<TProd::Token>>> = Vec::new();
 
-        let suffixes = self.get_suffixes();
+            let suffixes = self.get_suffixes();
 
-        let (is_structural, suffix_first_set) = self.obtain_suffixes_set();
+            let (is_structural, suffix_first_set) = self.obtain_suffixes_set();
 
-        let moved_ptr: FltrPtr = left_success_data.consumed_index;
-        let immediate_lex = &token_stream[moved_ptr];
+            let moved_ptr: FltrPtr = left_success_data.consumed_index;
+            let immediate_lex = &token_stream[moved_ptr];
 
-        let mut production_set_index: Option<usize> = None;
-        match suffix_first_set.binary_search_by_key(&immediate_lex.token, |(t, _)| *t) {
-            Ok(p_index) => {
-                production_set_index = Some(p_index);
+            let mut production_set_index: Option<usize> = None;
+            match suffix_first_set.binary_search_by_key(&immediate_lex.token, |(t, _)| *t) {
+                Ok(p_index) => {
+                    production_set_index = Some(p_index);
+                }
+                Err(_) => {
+                    if !is_structural {
+                        let last_token_ptr = if moved_ptr > FltrPtr::default() {
+                            token_stream.get_token_ptr(moved_ptr - 1)
+                        } else {
+                            TokenPtr::default()
+                        };
+
+                        let current_token_ptr = last_token_ptr + 1;
+
+                        let current_token_lex = &token_stream[current_token_ptr];
+
+                        if !current_token_lex.token.is_structural() {
+                            if let Ok(p_index) = suffix_first_set
+                                .binary_search_by_key(&current_token_lex.token, |(t, _)| *t)
+                            {
+                                production_set_index = Some(p_index);
+                            }
+                        }
+                    }
+                }
             }
-            Err(_) => {
-                if !is_structural {
-                    let last_token_ptr = if moved_ptr > FltrPtr::default() {
-                        token_stream.get_token_ptr(moved_ptr - 1)
-                    } else {
-                        TokenPtr::default()
-                    };
-
-                    let current_token_ptr = last_token_ptr + 1;
-
-                    let current_token_lex = &token_stream[current_token_ptr];
 
-                    if !current_token_lex.token.is_structural() {
-                        if let Ok(p_index) = suffix_first_set
-                            .binary_search_by_key(&current_token_lex.token, |(t, _)| *t)
-                        {
-                            production_set_index = Some(p_index);
+            match production_set_index {
+                Some(p_index) => {
+                    for (prod, node_value) in
+                        suffix_first_set[p_index].1.iter().map(|j| &suffixes[*j])
+                    {
+                        let checkpoint = cache.recovery_checkpoint();
+                        match prod.advance_fltr_ptr(
+                            code,
+                            left_success_data.consumed_index,
+                            token_stream,
+                            cache,
+                        ) {
+                            Ok(data) => {
+                                let mut children = left_success_data.children;
+                                children.extend(data.children);
+                                let ast = ASTNode::<TP::Node>::new(
+                                    node_value.clone(),
+                                    token_stream.pointer(fltr_ptr),
+                                    token_stream.pointer(data.consumed_index),
+                                    Some((
+                                        token_stream.get_token_ptr(fltr_ptr),
+                                        token_stream.get_token_ptr(data.consumed_index),
+                                    )),
+                                    children,
+                                );
+
+                                self.nt_helper.log_success(
+                                    code,
+                                    token_stream[fltr_ptr].start,
+                                    token_stream[data.consumed_index].end,
+                                );
+
+                                return Ok(SuccessData::tree(data.consumed_index, ast));
+                            }
+                            Err(err) => {
+                                if err.is_invalid() {
+                                    self.nt_helper.log_error(
+                                        code,
+                                        token_stream[moved_ptr].start,
+                                        &err,
+                                    );
+
+                                    return Err(err);
+                                }
+                                cache.rollback_recovery(checkpoint);
+                            }
                         }
                     }
                 }
+                None => {
+                    self.add_expected(immediate_lex.start, cache);
+
+                    if let Some(i) = self.obtain_first_null_suffix() {
+                        let left_bound = token_stream.get_token_ptr(fltr_ptr);
+                        let right_bound =
+                            token_stream.get_token_ptr(left_success_data.consumed_index);
+
+                        if !suffixes[*i].0.is_nullable_n_hidden() {
+                            left_success_data.children.push(ASTNode::null(
+                                token_stream[left_success_data.consumed_index].start,
+                                Some(right_bound),
+                            ))
+                        }
+                        let tree = ASTNode::new(
+                            suffixes[*i].1.clone(),
+                            token_stream[fltr_ptr].start,
+                            token_stream[left_success_data.consumed_index].start,
+                            Some((left_bound, right_bound)),
+                            left_success_data.children,
+                        );
+                        return Ok(SuccessData::tree(left_success_data.consumed_index, tree));
+                    }
+                }
             }
-        }
+            if self.standalone {
+                self.nt_helper.log_success(
+                    code,
+                    token_stream[fltr_ptr].start,
+                    token_stream[left_success_data.consumed_index].start,
+                );
+
+                Ok(left_success_data)
+            } else {
+                self.nt_helper.log_error(
+                    code,
+                    token_stream[fltr_ptr].start,
+                    &ProductionError::Unparsed,
+                );
+
+                Err(ProductionError::Unparsed)
+            }
+        })
+    }
 
-        match production_set_index {
-            Some(p_index) => {
-                for (prod, node_value) in suffix_first_set[p_index].1.iter().map(|j| &suffixes[*j])
-                {
-                    match prod.advance_fltr_ptr(
-                        code,
-                        left_success_data.consumed_index,
-                        token_stream,
-                        cache,
-                    ) {
-                        Ok(data) => {
-                            let mut children = left_success_data.children;
-                            children.extend(data.children);
+    fn advance_token_ptr(
+        &self,
+        code: &Code,
+        index: TokenPtr,
+        stream: &TokenStream<Self::Token>,
+        cache: &mut Cache<FltrPtr, Self::Node>,
+    ) -> ParsedResult<TokenPtr, Self::Node> {
+        profile::measure(self, index, || {
+            self.nt_helper.log_entry(stream[index].start);
+
+            let mut left_success_data = self.left.advance_token_ptr(code, index, stream, cache)?;
+            // let mut parsed_children: Vec<Rc<AST<TProd::Token>>> = Vec::new();
+            let moved_ptr: TokenPtr = left_success_data.consumed_index;
+            let suffixes = self.get_suffixes();
+
+            let (_, suffix_first_set) = self.obtain_suffixes_set();
+
+            let immediate_lex = &stream[moved_ptr];
+
+            if let Ok(i) = suffix_first_set.binary_search_by_key(&immediate_lex.token, |(t, _)| *t)
+            {
+                for (prod, node_value) in suffix_first_set[i].1.iter().map(|j| &suffixes[*j]) {
+                    let checkpoint = cache.recovery_checkpoint();
+                    match prod.advance_token_ptr(code, moved_ptr, stream, cache) {
+                        Ok(success_data) => {
+                            left_success_data.consumed_index = success_data.consumed_index;
+                            left_success_data.children.extend(success_data.children);
                             let ast = ASTNode::<TP::Node>::new(
                                 node_value.clone(),
-                                token_stream.pointer(fltr_ptr),
-                                token_stream.pointer(data.consumed_index),
-                                Some((
-                                    token_stream.get_token_ptr(fltr_ptr),
-                                    token_stream.get_token_ptr(data.consumed_index),
-                                )),
-                                children,
+                                stream[index].start,
+                                stream[success_data.consumed_index].start,
+                                Some((index, success_data.consumed_index)),
+                                left_success_data.children,
                             );
 
-                            #[cfg(debug_assertions)]
+                            let data = SuccessData::tree(success_data.consumed_index, ast);
                             self.nt_helper.log_success(
                                 code,
-                                token_stream[fltr_ptr].start,
-                                token_stream[data.consumed_index].end,
+                                stream[index].start,
+                                stream[data.consumed_index].start,
                             );
-
-                            return Ok(SuccessData::tree(data.consumed_index, ast));
+                            return Ok(data);
                         }
                         Err(err) => {
                             if err.is_invalid() {
-                                #[cfg(debug_assertions)]
-                                self.nt_helper
-                                    .log_error(code, token_stream[moved_ptr].start, &err);
-
+                                self.nt_helper.log_error(code, stream[index].start, &err);
                                 return Err(err);
                             }
+                            cache.rollback_recovery(checkpoint);
                         }
                     }
                 }
-            }
-            None => {
-                if let Some(i) = self.obtain_first_null_suffix() {
-                    let left_bound = token_stream.get_token_ptr(fltr_ptr);
-                    let right_bound = token_stream.get_token_ptr(left_success_data.consumed_index);
+            } else {
+                self.add_expected(immediate_lex.start, cache);
 
+                if let Some(i) = self.obtain_first_null_suffix() {
                     if !suffixes[*i].0.is_nullable_n_hidden() {
                         left_success_data.children.push(ASTNode::null(
-                            token_stream[left_success_data.consumed_index].start,
-                            Some(right_bound),
+                            stream[left_success_data.consumed_index].start,
+                            Some(left_success_data.consumed_index),
                         ))
                     }
                     let tree = ASTNode::new(
                         suffixes[*i].1.clone(),
-                        token_stream[fltr_ptr].start,
-                        token_stream[left_success_data.consumed_index].start,
-                        Some((left_bound, right_bound)),
+                        stream[index].start,
+                        stream[left_success_data.consumed_index].start,
+                        Some((index, left_success_data.consumed_index)),
                         left_success_data.children,
                     );
                     return Ok(SuccessData::tree(left_success_data.consumed_index, tree));
                 }
             }
-        }
-        if self.standalone {
-            #[cfg(debug_assertions)]
-            self.nt_helper.log_success(
-                code,
-                token_stream[fltr_ptr].start,
-                token_stream[left_success_data.consumed_index].start,
-            );
-
-            Ok(left_success_data)
-        } else {
-            #[cfg(debug_assertions)]
-            self.nt_helper.log_error(
-                code,
-                token_stream[fltr_ptr].start,
-                &ProductionError::Unparsed,
-            );
-
-            Err(ProductionError::Unparsed)
-        }
+            if self.standalone {
+                self.nt_helper.log_success(
+                    code,
+                    stream[index].start,
+                    stream[left_success_data.consumed_index].start,
+                );
+                Ok(left_success_data)
+            } else {
+                self.nt_helper
+                    .log_error(code, stream[index].start, &ProductionError::Unparsed);
+                Err(ProductionError::Unparsed)
+            }
+        })
     }
 
-    fn advance_token_ptr(
+    fn advance_ptr(
         &self,
-        code: &Code,
-        index: TokenPtr,
-        stream: &TokenStream<Self::Token>,
-        cache: &mut Cache<FltrPtr, Self::Node>,
-    ) -> ParsedResult<TokenPtr, Self::Node> {
-        #[cfg(debug_assertions)]
-        self.nt_helper.log_entry();
-
-        let mut left_success_data = self.left.advance_token_ptr(code, index, stream, cache)?;
-        // let mut parsed_children: Vec<Rc<AST<TProd::Token>>> = Vec::new();
-        let moved_ptr: TokenPtr = left_success_data.consumed_index;
-        let suffixes = self.get_suffixes();
-
-        let (_, suffix_first_set) = self.obtain_suffixes_set();
+        code: &crate::Code,
+        index: usize,
+        cache: &mut crate::Cache<usize, Self::Node>,
+    ) -> crate::ParsedResult<usize, Self::Node> {
+        profile::measure(self, index, || {
+            self.nt_helper.log_entry(index);
 
-        let immediate_lex = &stream[moved_ptr];
+            let mut left_parsed_result = self.left.advance_ptr(code, index, cache)?;
+            let moved_ptr: usize = left_parsed_result.consumed_index;
 
-        if let Ok(i) = suffix_first_set.binary_search_by_key(&immediate_lex.token, |(t, _)| *t) {
-            for (prod, node_value) in suffix_first_set[i].1.iter().map(|j| &suffixes[*j]) {
-                match prod.advance_token_ptr(code, moved_ptr, stream, cache) {
+            for (prod, node_value) in self.get_suffixes() {
+                let checkpoint = cache.recovery_checkpoint();
+                match prod.advance_ptr(code, moved_ptr, cache) {
                     Ok(success_data) => {
-                        left_success_data.consumed_index = success_data.consumed_index;
-                        left_success_data.children.extend(success_data.children);
+                        left_parsed_result.consumed_index = success_data.consumed_index;
+                        left_parsed_result.children.extend(success_data.children);
                         let ast = ASTNode::<TP::Node>::new(
                             node_value.clone(),
-                            stream[index].start,
-                            stream[success_data.consumed_index].start,
-                            Some((index, success_data.consumed_index)),
-                            left_success_data.children,
+                            index,
+                            success_data.consumed_index,
+                            None,
+                            left_parsed_result.children,
                         );
-
-                        let data = SuccessData::tree(success_data.consumed_index, ast);
-                        #[cfg(debug_assertions)]
-                        self.nt_helper.log_success(
-                            code,
-                            stream[index].start,
-                            stream[data.consumed_index].start,
-                        );
-                        return Ok(data);
+                        return Ok(SuccessData::tree(success_data.consumed_index, ast));
                     }
                     Err(err) => {
                         if err.is_invalid() {
-                            #[cfg(debug_assertions)]
-                            self.nt_helper.log_error(code, stream[index].start, &err);
+                            self.nt_helper.log_error(code, index, &err);
+
                             return Err(err);
                         }
+                        cache.rollback_recovery(checkpoint);
                     }
                 }
             }
-        } else {
-            if let Some(i) = self.obtain_first_null_suffix() {
-                if !suffixes[*i].0.is_nullable_n_hidden() {
-                    left_success_data.children.push(ASTNode::null(
-                        stream[left_success_data.consumed_index].start,
-                        Some(left_success_data.consumed_index),
-                    ))
-                }
-                let tree = ASTNode::new(
-                    suffixes[*i].1.clone(),
-                    stream[index].start,
-                    stream[left_success_data.consumed_index].start,
-                    Some((index, left_success_data.consumed_index)),
-                    left_success_data.children,
-                );
-                return Ok(SuccessData::tree(left_success_data.consumed_index, tree));
-            }
-        }
-        if self.standalone {
-            #[cfg(debug_assertions)]
-            self.nt_helper.log_success(
-                code,
-                stream[index].start,
-                stream[left_success_data.consumed_index].start,
-            );
-            Ok(left_success_data)
-        } else {
-            #[cfg(debug_assertions)]
-            self.nt_helper
-                .log_error(code, stream[index].start, &ProductionError::Unparsed);
-            Err(ProductionError::Unparsed)
-        }
-    }
-
-    fn advance_ptr(
-        &self,
-        code: &crate::Code,
-        index: usize,
-        cache: &mut crate::Cache<usize, Self::Node>,
-    ) -> crate::ParsedResult<usize, Self::Node> {
-        #[cfg(debug_assertions)]
-        self.nt_helper.log_entry();
-
-        let mut left_parsed_result = self.left.advance_ptr(code, index, cache)?;
-        let moved_ptr: usize = left_parsed_result.consumed_index;
-
-        for (prod, node_value) in self.get_suffixes() {
-            match prod.advance_ptr(code, moved_ptr, cache) {
-                Ok(success_data) => {
-                    left_parsed_result.consumed_index = success_data.consumed_index;
-                    left_parsed_result.children.extend(success_data.children);
-                    let ast = ASTNode::<TP::Node>::new(
-                        node_value.clone(),
-                        index,
-                        success_data.consumed_index,
-                        None,
-                        left_parsed_result.children,
-                    );
-                    return Ok(SuccessData::tree(success_data.consumed_index, ast));
-                }
-                Err(err) => {
-                    if err.is_invalid() {
-                        #[cfg(debug_assertions)]
-                        self.nt_helper.log_error(code, index, &err);
 
-                        return Err(err);
-                    }
-                }
+            if self.standalone {
+                Ok(left_parsed_result)
+            } else {
+                self.nt_helper
+                    .log_error(code, index, &ProductionError::Unparsed);
+                Err(ProductionError::Unparsed)
             }
-        }
-
-        if self.standalone {
-            Ok(left_parsed_result)
-        } else {
-            #[cfg(debug_assertions)]
-            self.nt_helper
-                .log_error(code, index, &ProductionError::Unparsed);
-            Err(ProductionError::Unparsed)
-        }
+        })
     }
 }
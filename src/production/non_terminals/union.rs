@@ -1,17 +1,15 @@
 use crate::production::NTHelper;
-#[cfg(debug_assertions)]
 use crate::production::ProductionLogger;
+use crate::profile;
 use crate::{
-    production::Union, Code, ASTNode, Cache, FltrPtr, IProduction, ImplementationError,
-    NodeImpl, ParsedResult, ProductionError, SuccessData, TokenImpl, TokenPtr, TokenStream,
+    grammar::{Grammar, GrammarExpr},
+    production::Union,
+    ASTNode, Cache, CacheKey, Code, Expected, FltrPtr, IProduction, ImplementationError, NodeImpl,
+    OnceCell, ParsedResult, ProductionError, Rc, SuccessData, TokenImpl, TokenPtr, TokenStream,
 };
 
-use once_cell::unsync::OnceCell;
+use std::collections::{HashMap, HashSet};
 use std::fmt::Display;
-use std::{
-    collections::{HashMap, HashSet},
-    rc::Rc,
-};
 
 impl<TN: NodeImpl, TL: TokenImpl> Union<TN, TL> {
     /// Create a new [Union] utility without alternative production symbols.
@@ -77,6 +75,17 @@ impl<TN: NodeImpl, TL: TokenImpl> Union<TN, TL> {
         })
     }
 
+    /// Allow the production to be derived recursively as the leftmost symbol of its alternatives,
+    /// e.g. `expr : expr '+' term | term`.
+    ///
+    /// The alternatives are parsed repeatedly from the same position to grow the parsed result,
+    /// and each repetition is derived from the result of the previous one.
+    /// ### Arguments
+    /// * `cache_key` - An unique key to save the growing parsed result of the production.
+    pub fn set_left_recursive(&self, cache_key: CacheKey) -> Result<(), String> {
+        self.nt_helper.set_left_recursion(cache_key)
+    }
+
     fn obtain_first_set(&self) -> &(bool, Vec<(TL, Vec<usize>)>) {
         self.first_set.get_or_init(|| {
             let mut children_set: HashMap<TL, Vec<usize>> = HashMap::new();
@@ -115,115 +124,21 @@ impl<TN: NodeImpl, TL: TokenImpl> Union<TN, TL> {
     }
 }
 
-impl<TN: NodeImpl, TL: TokenImpl> Display for Union<TN, TL> {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}", self.nt_helper.identifier)
-    }
-}
-impl<TN: NodeImpl, TL: TokenImpl> IProduction for Union<TN, TL> {
-    type Node = TN;
-
-    type Token = TL;
-
-    fn is_nullable(&self) -> bool {
-        match self.nt_helper.nullability.get() {
-            Some(s) => *s,
-            None => self
-                .obtain_nullability(HashMap::new())
-                .expect("Nullability error should have been caught in validation"),
-        }
-    }
-
-    fn is_nullable_n_hidden(&self) -> bool {
-        *self.nt_helper.null_hidden.get_or_init(|| {
-            self.get_productions()
-                .iter()
-                .find(|prod| prod.is_nullable())
-                .map_or(false, |p| p.is_nullable_n_hidden())
-        })
-    }
-
-    fn obtain_nullability<'id>(
-        &'id self,
-        mut visited: HashMap<&'id str, usize>,
-    ) -> Result<bool, crate::ImplementationError> {
-        self.nt_helper.validate_circular_dependency(&mut visited)?;
-
-        match self.nt_helper.nullability.get() {
-            Some(s) => Ok(*s),
-            None => {
-                let mut is_nullable = false;
-                for prod in self.get_productions() {
-                    if prod.obtain_nullability(visited.clone())? {
-                        is_nullable = true;
-                        break;
-                    }
-                }
-                self.nt_helper.nullability.set(is_nullable).unwrap();
-                Ok(is_nullable)
-            }
-        }
-    }
-
-    fn impl_first_set(&self, first_set: &mut HashSet<Self::Token>) {
-        for prod in self.get_productions() {
-            prod.impl_first_set(first_set)
-        }
-    }
-
-    fn impl_grammar(
-        &self,
-        writer: &mut dyn std::fmt::Write,
-        visited: &mut HashSet<&'static str>,
-    ) -> Result<(), std::fmt::Error> {
-        if visited.insert(self.nt_helper.identifier) {
-            writeln!(writer, "{}", self.nt_helper.identifier)?;
-            for (index, prod) in self.get_productions().iter().enumerate() {
-                if index == 0 {
-                    writeln!(writer, "{:>6} {}", ":", prod)?;
-                } else {
-                    writeln!(writer, "{:>6} {}", "|", prod)?;
-                }
-            }
-        }
-        Ok(())
-    }
-
-    fn validate<'id>(
-        &'id self,
-        mut connected_set: HashMap<&'id str, usize>,
-        visited_prod: &mut HashSet<&'id str>,
-    ) -> Result<(), ImplementationError> {
-        if !self
-            .nt_helper
-            .has_visited(&mut connected_set, visited_prod)?
-        {
-            if self.symbols.get().is_none() {
-                return Err(ImplementationError::new(
-                    "InitializationError".into(),
-                    format!(
-                        "Symbols are not assigned for {:?}.",
-                        self.nt_helper.identifier
-                    ),
-                ));
-            }
-            for prod in self.get_productions() {
-                prod.validate(connected_set.clone(), visited_prod)?;
-            }
+impl<TN: NodeImpl, TL: TokenImpl> Union<TN, TL> {
+    /// Record the first set tokens as expected when no alternative can be derived at the pointer.
+    fn add_expected(&self, pointer: usize, cache: &mut Cache<FltrPtr, TN>) {
+        for (token, _) in &self.obtain_first_set().1 {
+            cache.add_expected(pointer, || Expected::Token(format!("{:?}", token)));
         }
-        Ok(())
     }
 
-    fn advance_fltr_ptr(
+    fn derive_fltr_ptr(
         &self,
         code: &Code,
         fltr_ptr: FltrPtr,
-        token_stream: &TokenStream<Self::Token>,
-        cache: &mut Cache<FltrPtr, Self::Node>,
-    ) -> ParsedResult<FltrPtr, Self::Node> {
-        #[cfg(debug_assertions)]
-        self.nt_helper.log_entry();
-
+        token_stream: &TokenStream<TL>,
+        cache: &mut Cache<FltrPtr, TN>,
+    ) -> ParsedResult<FltrPtr, TN> {
         let immediate_lex = &token_stream[fltr_ptr];
 
         let (is_structural, first_sets) = self.obtain_first_set();
@@ -261,9 +176,9 @@ impl<TN: NodeImpl, TL: TokenImpl> IProduction for Union<TN, TL> {
         match production_set_index {
             Some(p_index) => {
                 for prod in first_sets[p_index].1.iter().map(|j| &productions[*j]) {
+                    let checkpoint = cache.recovery_checkpoint();
                     match prod.advance_fltr_ptr(code, fltr_ptr, token_stream, cache) {
                         Ok(s) => {
-                            #[cfg(debug_assertions)]
                             self.nt_helper.log_success(
                                 code,
                                 token_stream[fltr_ptr].start,
@@ -274,17 +189,23 @@ impl<TN: NodeImpl, TL: TokenImpl> IProduction for Union<TN, TL> {
                         }
                         Err(err) => {
                             if err.is_invalid() {
-                                #[cfg(debug_assertions)]
                                 self.nt_helper
                                     .log_error(code, token_stream[fltr_ptr].start, &err);
                                 // println!("Returning validation Err:{:?}", err);
                                 return Err(err);
                             }
+                            cache.rollback_recovery(checkpoint);
                         }
                     }
                 }
             }
             None => {
+                self.add_expected(token_stream[fltr_ptr].start, cache);
+
+                if self.is_nullable() {
+                    let pointer = token_stream[fltr_ptr].start;
+                    self.nt_helper.log_success(code, pointer, pointer);
+                }
                 if self.is_nullable_n_hidden() {
                     return Ok(SuccessData::hidden(fltr_ptr));
                 } else if self.is_nullable() {
@@ -297,7 +218,6 @@ impl<TN: NodeImpl, TL: TokenImpl> IProduction for Union<TN, TL> {
             }
         }
 
-        #[cfg(debug_assertions)]
         self.nt_helper.log_error(
             code,
             token_stream[fltr_ptr].start,
@@ -307,16 +227,13 @@ impl<TN: NodeImpl, TL: TokenImpl> IProduction for Union<TN, TL> {
         Err(ProductionError::Unparsed)
     }
 
-    fn advance_token_ptr(
+    fn derive_token_ptr(
         &self,
         code: &Code,
         index: TokenPtr,
-        token_stream: &TokenStream<Self::Token>,
-        cache: &mut Cache<FltrPtr, Self::Node>,
-    ) -> ParsedResult<TokenPtr, Self::Node> {
-        #[cfg(debug_assertions)]
-        self.nt_helper.log_entry();
-
+        token_stream: &TokenStream<TL>,
+        cache: &mut Cache<FltrPtr, TN>,
+    ) -> ParsedResult<TokenPtr, TN> {
         let immediate_lex = &token_stream[index];
 
         let (_, first_sets) = self.obtain_first_set();
@@ -326,7 +243,6 @@ impl<TN: NodeImpl, TL: TokenImpl> IProduction for Union<TN, TL> {
             for prod in first_sets[p_index].1.iter().map(|j| &productions[*j]) {
                 match prod.advance_token_ptr(code, index, token_stream, cache) {
                     Ok(s) => {
-                        #[cfg(debug_assertions)]
                         self.nt_helper.log_success(
                             code,
                             immediate_lex.start,
@@ -337,7 +253,6 @@ impl<TN: NodeImpl, TL: TokenImpl> IProduction for Union<TN, TL> {
                     }
                     Err(err) => {
                         if err.is_invalid() {
-                            #[cfg(debug_assertions)]
                             self.nt_helper.log_error(code, immediate_lex.start, &err);
                             // println!("Returning validation Err:{:?}", err);
                             return Err(err);
@@ -345,15 +260,20 @@ impl<TN: NodeImpl, TL: TokenImpl> IProduction for Union<TN, TL> {
                     }
                 }
             }
+        } else {
+            self.add_expected(immediate_lex.start, cache);
         }
 
+        if self.is_nullable() {
+            self.nt_helper
+                .log_success(code, immediate_lex.start, immediate_lex.start);
+        }
         if self.is_nullable_n_hidden() {
             Ok(SuccessData::hidden(index))
         } else if self.is_nullable() {
             let tree = ASTNode::null(token_stream[index].start, Some(index));
             Ok(SuccessData::tree(index, tree))
         } else {
-            #[cfg(debug_assertions)]
             self.nt_helper
                 .log_error(code, token_stream[index].start, &ProductionError::Unparsed);
 
@@ -361,21 +281,21 @@ impl<TN: NodeImpl, TL: TokenImpl> IProduction for Union<TN, TL> {
         }
     }
 
-    fn advance_ptr(
+    fn derive_ptr(
         &self,
-        code: &crate::Code,
+        code: &Code,
         index: usize,
-        cache: &mut Cache<usize, Self::Node>,
-    ) -> ParsedResult<usize, Self::Node> {
-        #[cfg(debug_assertions)]
-        self.nt_helper.log_entry();
-
+        cache: &mut Cache<usize, TN>,
+    ) -> ParsedResult<usize, TN> {
         for prod in self.get_productions() {
             match prod.advance_ptr(code, index, cache) {
-                Ok(s) => return Ok(s),
+                Ok(s) => {
+                    self.nt_helper.log_success(code, index, s.consumed_index);
+
+                    return Ok(s);
+                }
                 Err(err) => {
                     if err.is_invalid() {
-                        #[cfg(debug_assertions)]
                         self.nt_helper.log_error(code, index, &err);
 
                         return Err(err);
@@ -384,10 +304,183 @@ impl<TN: NodeImpl, TL: TokenImpl> IProduction for Union<TN, TL> {
             }
         }
 
-        #[cfg(debug_assertions)]
         self.nt_helper
             .log_error(code, index, &ProductionError::Unparsed);
 
         Err(ProductionError::Unparsed)
     }
 }
+
+impl<TN: NodeImpl, TL: TokenImpl> Display for Union<TN, TL> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.nt_helper.identifier)
+    }
+}
+impl<TN: NodeImpl, TL: TokenImpl> IProduction for Union<TN, TL> {
+    type Node = TN;
+
+    type Token = TL;
+
+    fn is_nullable(&self) -> bool {
+        match self.nt_helper.nullability.get() {
+            Some(s) => *s,
+            None => self
+                .obtain_nullability(HashMap::new())
+                .expect("Nullability error should have been caught in validation"),
+        }
+    }
+
+    fn is_nullable_n_hidden(&self) -> bool {
+        *self.nt_helper.null_hidden.get_or_init(|| {
+            self.get_productions()
+                .iter()
+                .find(|prod| prod.is_nullable())
+                .map_or(false, |p| p.is_nullable_n_hidden())
+        })
+    }
+
+    fn obtain_nullability<'id>(
+        &'id self,
+        mut visited: HashMap<&'id str, usize>,
+    ) -> Result<bool, crate::ImplementationError> {
+        if self.nt_helper.is_recurring(&visited) {
+            return Ok(false);
+        }
+        self.nt_helper.validate_circular_dependency(&mut visited)?;
+
+        match self.nt_helper.nullability.get() {
+            Some(s) => Ok(*s),
+            None => {
+                let mut is_nullable = false;
+                for prod in self.get_productions() {
+                    if prod.obtain_nullability(visited.clone())? {
+                        is_nullable = true;
+                        break;
+                    }
+                }
+                Ok(*self.nt_helper.nullability.get_or_init(|| is_nullable))
+            }
+        }
+    }
+
+    fn impl_first_set(&self, first_set: &mut HashSet<Self::Token>) {
+        self.nt_helper.collect_first_set(|| {
+            for prod in self.get_productions() {
+                prod.impl_first_set(first_set)
+            }
+        })
+    }
+
+    fn impl_grammar(
+        &self,
+        writer: &mut dyn std::fmt::Write,
+        visited: &mut HashSet<&'static str>,
+    ) -> Result<(), std::fmt::Error> {
+        if visited.insert(self.nt_helper.identifier) {
+            writeln!(writer, "{}", self.nt_helper.identifier)?;
+            for (index, prod) in self.get_productions().iter().enumerate() {
+                if index == 0 {
+                    writeln!(writer, "{:>6} {}", ":", prod)?;
+                } else {
+                    writeln!(writer, "{:>6} {}", "|", prod)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn grammar_expr(&self, grammar: &mut Grammar) -> GrammarExpr {
+        grammar.add_rule(self.nt_helper.identifier, |grammar| {
+            GrammarExpr::choice(
+                self.get_productions()
+                    .iter()
+                    .map(|prod| prod.grammar_expr(grammar))
+                    .collect(),
+            )
+        })
+    }
+
+    fn validate<'id>(
+        &'id self,
+        mut connected_set: HashMap<&'id str, usize>,
+        visited_prod: &mut HashSet<&'id str>,
+    ) -> Result<(), ImplementationError> {
+        if self
+            .nt_helper
+            .has_visited(&mut connected_set, visited_prod)?
+        {
+            if self.symbols.get().is_none() {
+                return Err(ImplementationError::new(
+                    "InitializationError".into(),
+                    format!(
+                        "Symbols are not assigned for {:?}.",
+                        self.nt_helper.identifier
+                    ),
+                ));
+            }
+            for prod in self.get_productions() {
+                prod.validate(connected_set.clone(), visited_prod)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn advance_fltr_ptr(
+        &self,
+        code: &Code,
+        fltr_ptr: FltrPtr,
+        token_stream: &TokenStream<Self::Token>,
+        cache: &mut Cache<FltrPtr, Self::Node>,
+    ) -> ParsedResult<FltrPtr, Self::Node> {
+        profile::measure(self, fltr_ptr, || {
+            self.nt_helper.log_entry(token_stream[fltr_ptr].start);
+
+            match self.nt_helper.left_recursion.get() {
+                Some(key) => {
+                    cache.grow_left_recursion(*key, token_stream[fltr_ptr].start, |cache| {
+                        self.derive_fltr_ptr(code, fltr_ptr, token_stream, cache)
+                    })
+                }
+                None => self.derive_fltr_ptr(code, fltr_ptr, token_stream, cache),
+            }
+        })
+    }
+
+    fn advance_token_ptr(
+        &self,
+        code: &Code,
+        index: TokenPtr,
+        token_stream: &TokenStream<Self::Token>,
+        cache: &mut Cache<FltrPtr, Self::Node>,
+    ) -> ParsedResult<TokenPtr, Self::Node> {
+        profile::measure(self, index, || {
+            self.nt_helper.log_entry(token_stream[index].start);
+
+            match self.nt_helper.left_recursion.get() {
+                Some(key) => {
+                    cache.grow_token_left_recursion(*key, token_stream[index].start, |cache| {
+                        self.derive_token_ptr(code, index, token_stream, cache)
+                    })
+                }
+                None => self.derive_token_ptr(code, index, token_stream, cache),
+            }
+        })
+    }
+
+    fn advance_ptr(
+        &self,
+        code: &crate::Code,
+        index: usize,
+        cache: &mut Cache<usize, Self::Node>,
+    ) -> ParsedResult<usize, Self::Node> {
+        profile::measure(self, index, || {
+            self.nt_helper.log_entry(index);
+
+            match self.nt_helper.left_recursion.get() {
+                Some(key) => cache
+                    .grow_left_recursion(*key, index, |cache| self.derive_ptr(code, index, cache)),
+                None => self.derive_ptr(code, index, cache),
+            }
+        })
+    }
+}
@@ -0,0 +1,48 @@
+use super::Recovery;
+use crate::{ASTNode, FltrPtr, NodeImpl, TokenImpl, TokenStream};
+
+impl<TN: NodeImpl, TL: TokenImpl> Recovery<TN, TL> {
+    pub(super) fn new(mut sync_tokens: Vec<TL>, node_value: TN) -> Self {
+        sync_tokens.sort();
+        sync_tokens.dedup();
+        Self {
+            sync_tokens,
+            node_value,
+        }
+    }
+
+    /// Whether the parser can resume parsing from the token at the index.
+    /// The end of file token is always a synchronization token.
+    pub(super) fn is_sync_at(&self, stream: &TokenStream<TL>, index: FltrPtr) -> bool {
+        match stream.get(index) {
+            Some(lex) => {
+                lex.token == TL::eof() || self.sync_tokens.binary_search(&lex.token).is_ok()
+            }
+            None => true,
+        }
+    }
+
+    /// Get the index of the first synchronization token at or after the index.
+    pub(super) fn sync_index(&self, stream: &TokenStream<TL>, mut index: FltrPtr) -> FltrPtr {
+        while !self.is_sync_at(stream, index) {
+            index = index + 1;
+        }
+        index
+    }
+
+    /// Create an error node for the skipped tokens between the start and end index.
+    pub(super) fn error_node(
+        &self,
+        stream: &TokenStream<TL>,
+        start: FltrPtr,
+        end: FltrPtr,
+    ) -> ASTNode<TN> {
+        ASTNode::new(
+            self.node_value.clone(),
+            stream.pointer(start),
+            stream.pointer(end),
+            Some((stream.get_token_ptr(start), stream.get_token_ptr(end))),
+            Vec::new(),
+        )
+    }
+}
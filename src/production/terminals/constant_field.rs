@@ -1,10 +1,10 @@
+use crate::profile;
 use crate::{
+    grammar::{Grammar, GrammarExpr},
     production::{ConstantField, ConstantFieldSet, ProductionLogger},
-    Code,
-    ASTNode, Cache, FltrPtr, IProduction, NodeImpl, ParsedResult, ProductionError, TokenPtr,
-    SuccessData, TokenImpl, TokenStream,
+    ASTNode, Cache, Code, Expected, FltrPtr, IProduction, NodeImpl, OnceCell, ParsedResult,
+    ProductionError, SuccessData, TokenImpl, TokenPtr, TokenStream,
 };
-use once_cell::unsync::OnceCell;
 use std::{
     collections::{HashMap, HashSet},
     fmt::Display,
@@ -91,28 +91,33 @@ impl<TN: NodeImpl, TL: TokenImpl> IProduction for ConstantField<TN, TL> {
         index: usize,
         cache: &mut Cache<usize, Self::Node>,
     ) -> ParsedResult<usize, Self::Node> {
-        if code.value[index..].starts_with(&self.value) {
-            // let s = &code[pointer..consumed_ptr];
-            let consumed_ptr = index + self.value.len();
-            cache.update_index(consumed_ptr);
-
-            #[cfg(debug_assertions)]
-            self.log_success(code, index, consumed_ptr);
-
-            match &self.node_value {
-                Some(n) => {
-                    let cached_tree: ASTNode<Self::Node> =
-                        ASTNode::leaf(n.clone(), index, consumed_ptr, None);
-                    return Ok(SuccessData::tree(consumed_ptr, cached_tree));
+        profile::measure(self, index, || {
+            self.log_entry(index);
+            if code.value[index..].starts_with(&self.value) {
+                // let s = &code[pointer..consumed_ptr];
+                let consumed_ptr = index + self.value.len();
+                cache.update_index(consumed_ptr);
+
+                self.log_success(code, index, consumed_ptr);
+
+                match &self.node_value {
+                    Some(n) => {
+                        let cached_tree: ASTNode<Self::Node> =
+                            ASTNode::leaf(n.clone(), index, consumed_ptr, None);
+                        return Ok(SuccessData::tree(consumed_ptr, cached_tree));
+                    }
+                    None => return Ok(SuccessData::hidden(consumed_ptr)),
                 }
-                None => return Ok(SuccessData::hidden(consumed_ptr)),
-            }
-        } else {
-            #[cfg(debug_assertions)]
-            self.log_error(code, index, &ProductionError::Unparsed);
+            } else {
+                cache.add_expected(index, || {
+                    Expected::Literal(String::from_utf8_lossy(&self.value).into_owned())
+                });
 
-            Err(ProductionError::Unparsed)
-        }
+                self.log_error(code, index, &ProductionError::Unparsed);
+
+                Err(ProductionError::Unparsed)
+            }
+        })
     }
 
     fn is_nullable_n_hidden(&self) -> bool {
@@ -134,6 +139,11 @@ impl<TN: NodeImpl, TL: TokenImpl> IProduction for ConstantField<TN, TL> {
         Ok(())
     }
 
+    fn grammar_expr(&self, _: &mut Grammar) -> GrammarExpr {
+        let value = String::from_utf8_lossy(&self.value).into_owned();
+        GrammarExpr::with_node(self.node_value.as_ref(), GrammarExpr::Literal(value))
+    }
+
     fn validate<'id>(
         &'id self,
         _: HashMap<&'id str, usize>,
@@ -239,32 +249,37 @@ impl<TN: NodeImpl, TL: TokenImpl> IProduction for ConstantFieldSet<TN, TL> {
         index: usize,
         cache: &mut Cache<usize, Self::Node>,
     ) -> ParsedResult<usize, Self::Node> {
-        #[cfg(debug_assertions)]
-        self.log_entry();
-
-        for (key, node_value) in self.fields.iter().rev() {
-            if code.value[index..].starts_with(key) {
-                let consumed_ptr = index + key.len();
-                cache.update_index(consumed_ptr);
-
-                #[cfg(debug_assertions)]
-                self.log_success(code, index, consumed_ptr);
-
-                match node_value {
-                    Some(n) => {
-                        let cached_tree: ASTNode<Self::Node> =
-                            ASTNode::leaf(n.clone(), index, consumed_ptr, None);
-                        return Ok(SuccessData::tree(consumed_ptr, cached_tree));
+        profile::measure(self, index, || {
+            self.log_entry(index);
+
+            for (key, node_value) in self.fields.iter().rev() {
+                if code.value[index..].starts_with(key) {
+                    let consumed_ptr = index + key.len();
+                    cache.update_index(consumed_ptr);
+
+                    self.log_success(code, index, consumed_ptr);
+
+                    match node_value {
+                        Some(n) => {
+                            let cached_tree: ASTNode<Self::Node> =
+                                ASTNode::leaf(n.clone(), index, consumed_ptr, None);
+                            return Ok(SuccessData::tree(consumed_ptr, cached_tree));
+                        }
+                        None => return Ok(SuccessData::hidden(consumed_ptr)),
                     }
-                    None => return Ok(SuccessData::hidden(consumed_ptr)),
                 }
             }
-        }
 
-        #[cfg(debug_assertions)]
-        self.log_error(code, index, &ProductionError::Unparsed);
+            for (key, _) in self.fields.iter().rev() {
+                cache.add_expected(index, || {
+                    Expected::Literal(String::from_utf8_lossy(key).into_owned())
+                });
+            }
 
-        Err(ProductionError::Unparsed)
+            self.log_error(code, index, &ProductionError::Unparsed);
+
+            Err(ProductionError::Unparsed)
+        })
     }
 
     fn is_nullable_n_hidden(&self) -> bool {
@@ -301,6 +316,25 @@ impl<TN: NodeImpl, TL: TokenImpl> IProduction for ConstantFieldSet<TN, TL> {
         Ok(())
     }
 
+    fn grammar_expr(&self, grammar: &mut Grammar) -> GrammarExpr {
+        let expr = |_: &mut Grammar| {
+            GrammarExpr::choice(
+                self.fields
+                    .iter()
+                    .rev()
+                    .map(|(value, node_value)| {
+                        let value = String::from_utf8_lossy(value).into_owned();
+                        GrammarExpr::with_node(node_value.as_ref(), GrammarExpr::Literal(value))
+                    })
+                    .collect(),
+            )
+        };
+        match self.rule_name.get() {
+            Some(rule_name) => grammar.add_rule(rule_name, expr),
+            None => expr(grammar),
+        }
+    }
+
     fn validate<'id>(
         &'id self,
         _: HashMap<&'id str, usize>,
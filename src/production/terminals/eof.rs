@@ -1,10 +1,10 @@
+use crate::profile;
 use crate::{
+    grammar::{Grammar, GrammarExpr},
     production::{EOFProd, ProductionLogger},
-    Code,
-    ASTNode, Cache, FltrPtr, IProduction, ImplementationError, NodeImpl, ParsedResult,
-    ProductionError, TokenPtr, SuccessData, TokenImpl, TokenStream,
+    ASTNode, Cache, Code, Expected, FltrPtr, IProduction, ImplementationError, NodeImpl, OnceCell,
+    ParsedResult, ProductionError, SuccessData, TokenImpl, TokenPtr, TokenStream,
 };
-use once_cell::unsync::OnceCell;
 use std::{
     collections::{HashMap, HashSet},
     fmt::Display,
@@ -78,34 +78,35 @@ impl<TN: NodeImpl, TL: TokenImpl> IProduction for EOFProd<TN, TL> {
         stream: &TokenStream<Self::Token>,
         cache: &mut Cache<FltrPtr, Self::Node>,
     ) -> ParsedResult<FltrPtr, Self::Node> {
-        #[cfg(debug_assertions)]
-        self.log_entry();
-
-        if stream.is_eos(index) {
-            let eof_pointer = stream.eos_pointer();
-            cache.update_index(eof_pointer);
-
-            #[cfg(debug_assertions)]
-            self.log_success(code, eof_pointer, eof_pointer);
-            match &self.node_value {
-                Some(node_value) => {
-                    let lex_index = stream.get_token_ptr(index);
-                    let tree = ASTNode::leaf(
-                        node_value.clone(),
-                        eof_pointer,
-                        code.value.len(),
-                        Some((lex_index, lex_index)),
-                    );
-                    Ok(SuccessData::tree(index, tree))
+        profile::measure(self, index, || {
+            self.log_entry(stream[index].start);
+
+            if stream.is_eos(index) {
+                let eof_pointer = stream.eos_pointer();
+                cache.update_index(eof_pointer);
+
+                self.log_success(code, eof_pointer, eof_pointer);
+                match &self.node_value {
+                    Some(node_value) => {
+                        let lex_index = stream.get_token_ptr(index);
+                        let tree = ASTNode::leaf(
+                            node_value.clone(),
+                            eof_pointer,
+                            code.value.len(),
+                            Some((lex_index, lex_index)),
+                        );
+                        Ok(SuccessData::tree(index, tree))
+                    }
+                    None => Ok(SuccessData::hidden(index)),
                 }
-                None => Ok(SuccessData::hidden(index)),
-            }
-        } else {
-            #[cfg(debug_assertions)]
-            self.log_error(code, stream[index].start, &ProductionError::Unparsed);
+            } else {
+                cache.add_expected(stream[index].start, || Expected::EndOfFile);
 
-            Err(ProductionError::Unparsed)
-        }
+                self.log_error(code, stream[index].start, &ProductionError::Unparsed);
+
+                Err(ProductionError::Unparsed)
+            }
+        })
     }
 
     fn advance_token_ptr(
@@ -115,34 +116,35 @@ impl<TN: NodeImpl, TL: TokenImpl> IProduction for EOFProd<TN, TL> {
         stream: &TokenStream<Self::Token>,
         cache: &mut Cache<FltrPtr, Self::Node>,
     ) -> ParsedResult<TokenPtr, Self::Node> {
-        #[cfg(debug_assertions)]
-        self.log_entry();
-
-        if stream.is_eos_segment(index) {
-            let eof_pointer = stream.eos_pointer();
-            cache.update_index(eof_pointer);
-
-            #[cfg(debug_assertions)]
-            self.log_success(code, eof_pointer, eof_pointer);
-
-            match &self.node_value {
-                Some(node_value) => {
-                    let tree = ASTNode::leaf(
-                        node_value.clone(),
-                        eof_pointer,
-                        code.value.len(),
-                        Some((index, index)),
-                    );
-                    Ok(SuccessData::tree(index, tree))
+        profile::measure(self, index, || {
+            self.log_entry(stream[index].start);
+
+            if stream.is_eos_segment(index) {
+                let eof_pointer = stream.eos_pointer();
+                cache.update_index(eof_pointer);
+
+                self.log_success(code, eof_pointer, eof_pointer);
+
+                match &self.node_value {
+                    Some(node_value) => {
+                        let tree = ASTNode::leaf(
+                            node_value.clone(),
+                            eof_pointer,
+                            code.value.len(),
+                            Some((index, index)),
+                        );
+                        Ok(SuccessData::tree(index, tree))
+                    }
+                    None => Ok(SuccessData::hidden(index)),
                 }
-                None => Ok(SuccessData::hidden(index)),
-            }
-        } else {
-            #[cfg(debug_assertions)]
-            self.log_error(code, stream[index].start, &ProductionError::Unparsed);
+            } else {
+                cache.add_expected(stream[index].start, || Expected::EndOfFile);
 
-            Err(ProductionError::Unparsed)
-        }
+                self.log_error(code, stream[index].start, &ProductionError::Unparsed);
+
+                Err(ProductionError::Unparsed)
+            }
+        })
     }
 
     fn advance_ptr(
@@ -151,29 +153,30 @@ impl<TN: NodeImpl, TL: TokenImpl> IProduction for EOFProd<TN, TL> {
         index: usize,
         cache: &mut Cache<usize, Self::Node>,
     ) -> ParsedResult<usize, Self::Node> {
-        #[cfg(debug_assertions)]
-        self.log_entry();
+        profile::measure(self, index, || {
+            self.log_entry(index);
 
-        if code.value.len() == index {
-            cache.update_index(index);
+            if code.value.len() == index {
+                cache.update_index(index);
 
-            #[cfg(debug_assertions)]
-            self.log_success(code, index, index);
+                self.log_success(code, index, index);
 
-            match &self.node_value {
-                Some(node_value) => {
-                    let tree = ASTNode::leaf(node_value.clone(), index, index, None);
+                match &self.node_value {
+                    Some(node_value) => {
+                        let tree = ASTNode::leaf(node_value.clone(), index, index, None);
 
-                    Ok(SuccessData::tree(index, tree))
+                        Ok(SuccessData::tree(index, tree))
+                    }
+                    None => Ok(SuccessData::hidden(index)),
                 }
-                None => Ok(SuccessData::hidden(index)),
-            }
-        } else {
-            #[cfg(debug_assertions)]
-            self.log_error(code, index, &ProductionError::Unparsed);
+            } else {
+                cache.add_expected(index, || Expected::EndOfFile);
 
-            Err(ProductionError::Unparsed)
-        }
+                self.log_error(code, index, &ProductionError::Unparsed);
+
+                Err(ProductionError::Unparsed)
+            }
+        })
     }
 
     fn impl_grammar(
@@ -183,4 +186,8 @@ impl<TN: NodeImpl, TL: TokenImpl> IProduction for EOFProd<TN, TL> {
     ) -> Result<(), std::fmt::Error> {
         Ok(())
     }
+
+    fn grammar_expr(&self, _: &mut Grammar) -> GrammarExpr {
+        GrammarExpr::with_node(self.node_value.as_ref(), GrammarExpr::EndOfFile)
+    }
 }
@@ -1,10 +1,9 @@
 use crate::{
+    grammar::{Grammar, GrammarExpr},
     production::{NullProd, ProductionLogger},
-    Code,
-    ASTNode, Cache, FltrPtr, IProduction, ImplementationError, NodeImpl, ParsedResult, SuccessData,
-    TokenImpl, TokenPtr, TokenStream,
+    ASTNode, Cache, Code, FltrPtr, IProduction, ImplementationError, NodeImpl, OnceCell,
+    ParsedResult, SuccessData, TokenImpl, TokenPtr, TokenStream,
 };
-use once_cell::unsync::OnceCell;
 use std::{
     collections::{HashMap, HashSet},
     fmt::Display,
@@ -84,14 +83,17 @@ impl<TN: NodeImpl, TL: TokenImpl> IProduction for NullProd<TN, TL> {
 
     fn advance_fltr_ptr(
         &self,
-        _: &Code,
+        code: &Code,
         index: FltrPtr,
         token_stream: &TokenStream<Self::Token>,
         _: &mut Cache<FltrPtr, Self::Node>,
     ) -> ParsedResult<FltrPtr, Self::Node> {
+        let pointer = token_stream.pointer(index);
+        self.log_entry(pointer);
+        self.log_success(code, pointer, pointer);
+
         match &self.node_value {
             Some(node_value) => {
-                let pointer = token_stream.pointer(index);
                 let lex_bound = token_stream.get_token_ptr(index);
                 Ok(SuccessData::tree(
                     index,
@@ -109,29 +111,33 @@ impl<TN: NodeImpl, TL: TokenImpl> IProduction for NullProd<TN, TL> {
 
     fn advance_token_ptr(
         &self,
-        _: &Code,
+        code: &Code,
         index: TokenPtr,
         token_stream: &TokenStream<Self::Token>,
         _: &mut Cache<FltrPtr, Self::Node>,
     ) -> ParsedResult<TokenPtr, Self::Node> {
+        let pointer = token_stream[index].start;
+        self.log_entry(pointer);
+        self.log_success(code, pointer, pointer);
+
         match &self.node_value {
-            Some(node_value) => {
-                let pointer = token_stream[index].start;
-                Ok(SuccessData::tree(
-                    index,
-                    ASTNode::leaf(node_value.clone(), pointer, pointer, Some((index, index))),
-                ))
-            }
+            Some(node_value) => Ok(SuccessData::tree(
+                index,
+                ASTNode::leaf(node_value.clone(), pointer, pointer, Some((index, index))),
+            )),
             None => Ok(SuccessData::hidden(index)),
         }
     }
 
     fn advance_ptr(
         &self,
-        _: &Code,
+        code: &Code,
         index: usize,
         _: &mut Cache<usize, Self::Node>,
     ) -> ParsedResult<usize, Self::Node> {
+        self.log_entry(index);
+        self.log_success(code, index, index);
+
         match &self.node_value {
             Some(node_value) => Ok(SuccessData::tree(
                 index,
@@ -148,4 +154,8 @@ impl<TN: NodeImpl, TL: TokenImpl> IProduction for NullProd<TN, TL> {
     ) -> Result<(), std::fmt::Error> {
         Ok(())
     }
+
+    fn grammar_expr(&self, _: &mut Grammar) -> GrammarExpr {
+        GrammarExpr::with_node(self.node_value.as_ref(), GrammarExpr::Empty)
+    }
 }
@@ -1,10 +1,10 @@
+use crate::profile;
 use crate::{
+    grammar::{Grammar, GrammarExpr},
     production::{ProductionLogger, PunctuationsField},
-    Code,
-    ASTNode, Cache, FieldTree, FltrPtr, IProduction, NodeImpl, ParsedResult, ProductionError,
-    TokenPtr, SuccessData, TokenImpl, TokenStream,
+    ASTNode, Cache, Code, Expected, FieldTree, FltrPtr, IProduction, NodeImpl, OnceCell,
+    ParsedResult, ProductionError, SuccessData, TokenImpl, TokenPtr, TokenStream,
 };
-use once_cell::unsync::OnceCell;
 use std::{
     collections::{HashMap, HashSet},
     fmt::Display,
@@ -115,33 +115,36 @@ impl<TN: NodeImpl, TL: TokenImpl> IProduction for PunctuationsField<TN, TL> {
         index: usize,
         cache: &mut Cache<usize, Self::Node>,
     ) -> ParsedResult<usize, Self::Node> {
-        #[cfg(debug_assertions)]
-        self.log_entry();
-
-        match self.tree.find(&code.value[index..]) {
-            Some((node_value, shift)) => {
-                let consumed_ptr = index + shift;
-                cache.update_index(consumed_ptr);
-
-                #[cfg(debug_assertions)]
-                self.log_success(code, index, consumed_ptr);
-
-                match node_value {
-                    Some(n) => {
-                        let cached_tree: ASTNode<Self::Node> =
-                            ASTNode::leaf(n.clone(), index, consumed_ptr, None);
-                        return Ok(SuccessData::tree(consumed_ptr, cached_tree));
+        profile::measure(self, index, || {
+            self.log_entry(index);
+
+            match self.tree.find(&code.value[index..]) {
+                Some((node_value, shift)) => {
+                    let consumed_ptr = index + shift;
+                    cache.update_index(consumed_ptr);
+
+                    self.log_success(code, index, consumed_ptr);
+
+                    match node_value {
+                        Some(n) => {
+                            let cached_tree: ASTNode<Self::Node> =
+                                ASTNode::leaf(n.clone(), index, consumed_ptr, None);
+                            return Ok(SuccessData::tree(consumed_ptr, cached_tree));
+                        }
+                        None => return Ok(SuccessData::hidden(consumed_ptr)),
                     }
-                    None => return Ok(SuccessData::hidden(consumed_ptr)),
                 }
-            }
-            None => {
-                #[cfg(debug_assertions)]
-                self.log_error(code, index, &ProductionError::Unparsed);
+                None => {
+                    for (value, _) in self.values.iter().rev() {
+                        cache.add_expected(index, || Expected::Literal(value.clone()));
+                    }
+
+                    self.log_error(code, index, &ProductionError::Unparsed);
 
-                Err(ProductionError::Unparsed)
+                    Err(ProductionError::Unparsed)
+                }
             }
-        }
+        })
     }
 
     fn is_nullable_n_hidden(&self) -> bool {
@@ -181,6 +184,27 @@ impl<TN: NodeImpl, TL: TokenImpl> IProduction for PunctuationsField<TN, TL> {
         Ok(())
     }
 
+    fn grammar_expr(&self, grammar: &mut Grammar) -> GrammarExpr {
+        let expr = |_: &mut Grammar| {
+            GrammarExpr::choice(
+                self.values
+                    .iter()
+                    .rev()
+                    .map(|(value, node_value)| {
+                        GrammarExpr::with_node(
+                            node_value.as_ref(),
+                            GrammarExpr::Literal(value.clone()),
+                        )
+                    })
+                    .collect(),
+            )
+        };
+        match self.rule_name.get() {
+            Some(rule_name) => grammar.add_rule(rule_name, expr),
+            None => expr(grammar),
+        }
+    }
+
     fn validate<'id>(
         &'id self,
         _: HashMap<&'id str, usize>,
@@ -4,14 +4,15 @@ use std::{
     marker::PhantomData,
 };
 
-use once_cell::unsync::OnceCell;
 use regex::bytes::Regex;
 
+use crate::lexeme::unicode;
+use crate::profile;
 use crate::{
+    grammar::{Grammar, GrammarExpr},
     production::{ProductionLogger, RegexField},
-    Code,
-    ASTNode, Cache, FltrPtr, IProduction, ImplementationError, NodeImpl, ParsedResult,
-    ProductionError, TokenPtr, SuccessData, TokenImpl, TokenStream,
+    ASTNode, Cache, Code, Expected, FltrPtr, IProduction, ImplementationError, NodeImpl, OnceCell,
+    ParsedResult, ProductionError, SuccessData, TokenImpl, TokenPtr, TokenStream,
 };
 
 impl<TN: NodeImpl> RegexField<TN, i8> {
@@ -27,6 +28,29 @@ impl<TN: NodeImpl> RegexField<TN, i8> {
             Err(err) => Err(format!("{:?}", err)),
         }
     }
+
+    /// Create a [RegexField] of the Unicode identifier which starts with a `XID_Start` character or `_`
+    /// followed by the `XID_Continue` characters according to [UAX #31](https://www.unicode.org/reports/tr31/).
+    pub fn xid_identifier(node_value: Option<TN>) -> Self {
+        Self::new(unicode::XID_IDENTIFIER, node_value).unwrap()
+    }
+
+    /// Create a [RegexField] of a sequence of the Unicode `White_Space` characters including the line terminators.
+    pub fn unicode_whitespace(node_value: Option<TN>) -> Self {
+        Self::new(unicode::UNICODE_WHITESPACE, node_value).unwrap()
+    }
+
+    /// Create a [RegexField] of a sequence of the Unicode `White_Space` characters except the line terminators
+    /// CR, LF, U+2028 and U+2029.
+    pub fn inline_whitespace(node_value: Option<TN>) -> Self {
+        Self::new(unicode::INLINE_WHITESPACE, node_value).unwrap()
+    }
+
+    /// Create a [RegexField] of a single line terminator of Javascript and Python
+    /// where CRLF is matched as one terminator alongside CR, LF, U+2028 and U+2029.
+    pub fn line_terminator(node_value: Option<TN>) -> Self {
+        Self::new(unicode::LINE_TERMINATOR, node_value).unwrap()
+    }
 }
 
 impl<TN: NodeImpl, TL: TokenImpl> RegexField<TN, TL> {
@@ -97,35 +121,36 @@ impl<TN: NodeImpl, TL: TokenImpl> IProduction for RegexField<TN, TL> {
         index: usize,
         cache: &mut Cache<usize, Self::Node>,
     ) -> ParsedResult<usize, Self::Node> {
-        #[cfg(debug_assertions)]
-        self.log_entry();
-
-        if let Some(m) = self.regexp.find(&code.value[index..]) {
-            debug_assert!(
-                m.start() == 0,
-                "Regex expression should be match from beginning."
-            );
-            // let s = &code[pointer..consumed_ptr];
-            let consumed_ptr = index + m.end();
-            cache.update_index(consumed_ptr);
-
-            #[cfg(debug_assertions)]
-            self.log_success(code, index, consumed_ptr);
-
-            match &self.node_value {
-                Some(node_value) => {
-                    let cached_tree: ASTNode<Self::Node> =
-                        ASTNode::leaf(node_value.clone(), index, consumed_ptr, None);
-                    Ok(SuccessData::tree(consumed_ptr, cached_tree))
+        profile::measure(self, index, || {
+            self.log_entry(index);
+
+            if let Some(m) = self.regexp.find(&code.value[index..]) {
+                debug_assert!(
+                    m.start() == 0,
+                    "Regex expression should be match from beginning."
+                );
+                // let s = &code[pointer..consumed_ptr];
+                let consumed_ptr = index + m.end();
+                cache.update_index(consumed_ptr);
+
+                self.log_success(code, index, consumed_ptr);
+
+                match &self.node_value {
+                    Some(node_value) => {
+                        let cached_tree: ASTNode<Self::Node> =
+                            ASTNode::leaf(node_value.clone(), index, consumed_ptr, None);
+                        Ok(SuccessData::tree(consumed_ptr, cached_tree))
+                    }
+                    None => Ok(SuccessData::hidden(consumed_ptr)),
                 }
-                None => Ok(SuccessData::hidden(consumed_ptr)),
-            }
-        } else {
-            #[cfg(debug_assertions)]
-            self.log_error(code, index, &ProductionError::Unparsed);
+            } else {
+                cache.add_expected(index, || Expected::Pattern(self.to_string()));
 
-            Err(ProductionError::Unparsed)
-        }
+                self.log_error(code, index, &ProductionError::Unparsed);
+
+                Err(ProductionError::Unparsed)
+            }
+        })
     }
 
     fn obtain_nullability<'id>(
@@ -160,6 +185,17 @@ impl<TN: NodeImpl, TL: TokenImpl> IProduction for RegexField<TN, TL> {
         Ok(())
     }
 
+    fn grammar_expr(&self, grammar: &mut Grammar) -> GrammarExpr {
+        let expr = |_: &mut Grammar| {
+            let pattern = GrammarExpr::Pattern(self.regexp.as_str().to_string());
+            GrammarExpr::with_node(self.node_value.as_ref(), pattern)
+        };
+        match self.rule_name.get() {
+            Some(rule_name) => grammar.add_rule(rule_name, expr),
+            None => expr(grammar),
+        }
+    }
+
     fn validate<'id>(
         &'id self,
         _: std::collections::HashMap<&'id str, usize>,
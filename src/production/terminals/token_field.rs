@@ -1,9 +1,10 @@
+use crate::profile;
 use crate::{
+    grammar::{Grammar, GrammarExpr},
     production::{ProductionLogger, TokenField, TokenFieldSet},
-    ASTNode, Cache, Code, FltrPtr, IProduction, ImplementationError, Log, NodeImpl, ParsedResult,
-    ProductionError, SuccessData, TokenImpl, TokenPtr, TokenStream,
+    ASTNode, Cache, Code, Expected, FltrPtr, IProduction, ImplementationError, Log, NodeImpl,
+    OnceCell, ParsedResult, ProductionError, SuccessData, TokenImpl, TokenPtr, TokenStream,
 };
-use once_cell::unsync::OnceCell;
 use std::{
     collections::{HashMap, HashSet},
     fmt::Display,
@@ -70,34 +71,39 @@ impl<TN: NodeImpl, TL: TokenImpl> IProduction for TokenField<TN, TL> {
         stream: &TokenStream<Self::Token>,
         cache: &mut Cache<FltrPtr, Self::Node>,
     ) -> ParsedResult<FltrPtr, Self::Node> {
-        if self.token == stream[index].token {
-            cache.update_index(stream[index].end);
+        profile::measure(self, index, || {
+            self.log_entry(stream[index].start);
+            if self.token == stream[index].token {
+                cache.update_index(stream[index].end);
 
-            #[cfg(debug_assertions)]
-            self.log_success(_code, stream[index].start, stream[index].end);
+                self.log_success(_code, stream[index].start, stream[index].end);
 
-            match &self.node_value {
-                Some(node) => {
-                    let bound_start = stream.get_token_ptr(index);
+                match &self.node_value {
+                    Some(node) => {
+                        let bound_start = stream.get_token_ptr(index);
 
-                    Ok(SuccessData::tree(
-                        index + 1,
-                        ASTNode::leaf(
-                            node.clone(),
-                            stream[index].start,
-                            stream[index].end,
-                            Some((bound_start, bound_start + 1)),
-                        ),
-                    ))
+                        Ok(SuccessData::tree(
+                            index + 1,
+                            ASTNode::leaf(
+                                node.clone(),
+                                stream[index].start,
+                                stream[index].end,
+                                Some((bound_start, bound_start + 1)),
+                            ),
+                        ))
+                    }
+                    None => Ok(SuccessData::hidden(index + 1)),
                 }
-                None => Ok(SuccessData::hidden(index + 1)),
-            }
-        } else {
-            #[cfg(debug_assertions)]
-            self.log_error(_code, stream[index].start, &ProductionError::Unparsed);
+            } else {
+                cache.add_expected(stream[index].start, || {
+                    Expected::Token(format!("{:?}", self.token))
+                });
 
-            Err(ProductionError::Unparsed)
-        }
+                self.log_error(_code, stream[index].start, &ProductionError::Unparsed);
+
+                Err(ProductionError::Unparsed)
+            }
+        })
     }
 
     fn advance_token_ptr(
@@ -107,30 +113,35 @@ impl<TN: NodeImpl, TL: TokenImpl> IProduction for TokenField<TN, TL> {
         stream: &TokenStream<Self::Token>,
         cache: &mut Cache<FltrPtr, Self::Node>,
     ) -> ParsedResult<TokenPtr, Self::Node> {
-        if self.token == stream[index].token {
-            cache.update_index(stream[index].end);
-
-            #[cfg(debug_assertions)]
-            self.log_success(_code, stream[index].start, stream[index].end);
-
-            match &self.node_value {
-                Some(node) => Ok(SuccessData::tree(
-                    index + 1,
-                    ASTNode::leaf(
-                        node.clone(),
-                        stream[index].start,
-                        stream[index].end,
-                        Some((index, index + 1)),
-                    ),
-                )),
-                None => Ok(SuccessData::hidden(index + 1)),
-            }
-        } else {
-            #[cfg(debug_assertions)]
-            self.log_error(_code, stream[index].start, &ProductionError::Unparsed);
+        profile::measure(self, index, || {
+            self.log_entry(stream[index].start);
+            if self.token == stream[index].token {
+                cache.update_index(stream[index].end);
 
-            Err(ProductionError::Unparsed)
-        }
+                self.log_success(_code, stream[index].start, stream[index].end);
+
+                match &self.node_value {
+                    Some(node) => Ok(SuccessData::tree(
+                        index + 1,
+                        ASTNode::leaf(
+                            node.clone(),
+                            stream[index].start,
+                            stream[index].end,
+                            Some((index, index + 1)),
+                        ),
+                    )),
+                    None => Ok(SuccessData::hidden(index + 1)),
+                }
+            } else {
+                cache.add_expected(stream[index].start, || {
+                    Expected::Token(format!("{:?}", self.token))
+                });
+
+                self.log_error(_code, stream[index].start, &ProductionError::Unparsed);
+
+                Err(ProductionError::Unparsed)
+            }
+        })
     }
 
     fn advance_ptr(
@@ -161,6 +172,11 @@ impl<TN: NodeImpl, TL: TokenImpl> IProduction for TokenField<TN, TL> {
         Ok(())
     }
 
+    fn grammar_expr(&self, _: &mut Grammar) -> GrammarExpr {
+        let token = GrammarExpr::Token(format!("{:?}", self.token));
+        GrammarExpr::with_node(self.node_value.as_ref(), token)
+    }
+
     fn validate<'id>(
         &'id self,
         _: HashMap<&'id str, usize>,
@@ -206,6 +222,12 @@ impl<TN: NodeImpl, TL: TokenImpl> TokenFieldSet<TN, TL> {
         }
     }
 
+    fn add_expected(&self, pointer: usize, cache: &mut Cache<FltrPtr, TN>) {
+        for (token, _) in &self.token_set {
+            cache.add_expected(pointer, || Expected::Token(format!("{:?}", token)));
+        }
+    }
+
     fn semantics(&self) -> Vec<String> {
         self.token_set
             .iter()
@@ -261,42 +283,43 @@ impl<TN: NodeImpl, TL: TokenImpl> IProduction for TokenFieldSet<TN, TL> {
         stream: &TokenStream<Self::Token>,
         cache: &mut Cache<FltrPtr, Self::Node>,
     ) -> ParsedResult<FltrPtr, Self::Node> {
-        #[cfg(debug_assertions)]
-        self.log_entry();
-        match self
-            .token_set
-            .binary_search_by_key(&stream[index].token, |(t, _)| *t)
-        {
-            Ok(i) => {
-                cache.update_index(stream[index].end);
-
-                #[cfg(debug_assertions)]
-                self.log_success(_code, stream[index].start, stream[index].end);
-
-                match &self.token_set[i].1 {
-                    Some(node) => {
-                        let bound_start = stream.get_token_ptr(index);
-
-                        Ok(SuccessData::tree(
-                            index + 1,
-                            ASTNode::leaf(
-                                node.clone(),
-                                stream[index].start,
-                                stream[index].end,
-                                Some((bound_start, bound_start + 1)),
-                            ),
-                        ))
+        profile::measure(self, index, || {
+            self.log_entry(stream[index].start);
+            match self
+                .token_set
+                .binary_search_by_key(&stream[index].token, |(t, _)| *t)
+            {
+                Ok(i) => {
+                    cache.update_index(stream[index].end);
+
+                    self.log_success(_code, stream[index].start, stream[index].end);
+
+                    match &self.token_set[i].1 {
+                        Some(node) => {
+                            let bound_start = stream.get_token_ptr(index);
+
+                            Ok(SuccessData::tree(
+                                index + 1,
+                                ASTNode::leaf(
+                                    node.clone(),
+                                    stream[index].start,
+                                    stream[index].end,
+                                    Some((bound_start, bound_start + 1)),
+                                ),
+                            ))
+                        }
+                        None => Ok(SuccessData::hidden(index + 1)),
                     }
-                    None => Ok(SuccessData::hidden(index + 1)),
                 }
-            }
-            Err(_) => {
-                #[cfg(debug_assertions)]
-                self.log_error(_code, stream[index].start, &ProductionError::Unparsed);
+                Err(_) => {
+                    self.add_expected(stream[index].start, cache);
 
-                Err(ProductionError::Unparsed)
+                    self.log_error(_code, stream[index].start, &ProductionError::Unparsed);
+
+                    Err(ProductionError::Unparsed)
+                }
             }
-        }
+        })
     }
 
     fn advance_token_ptr(
@@ -306,35 +329,38 @@ impl<TN: NodeImpl, TL: TokenImpl> IProduction for TokenFieldSet<TN, TL> {
         stream: &TokenStream<Self::Token>,
         cache: &mut Cache<FltrPtr, Self::Node>,
     ) -> ParsedResult<TokenPtr, Self::Node> {
-        match self
-            .token_set
-            .binary_search_by_key(&stream[index].token, |(t, _)| *t)
-        {
-            Ok(i) => {
-                cache.update_index(stream[index].end);
-
-                #[cfg(debug_assertions)]
-                self.log_success(_code, stream[index].start, stream[index].end);
+        profile::measure(self, index, || {
+            self.log_entry(stream[index].start);
+            match self
+                .token_set
+                .binary_search_by_key(&stream[index].token, |(t, _)| *t)
+            {
+                Ok(i) => {
+                    cache.update_index(stream[index].end);
+
+                    self.log_success(_code, stream[index].start, stream[index].end);
+
+                    match &self.token_set[i].1 {
+                        Some(node) => Ok(SuccessData::tree(
+                            index + 1,
+                            ASTNode::leaf(
+                                node.clone(),
+                                stream[index].start,
+                                stream[index].end,
+                                Some((index, index + 1)),
+                            ),
+                        )),
+                        None => Ok(SuccessData::hidden(index + 1)),
+                    }
+                }
+                Err(_) => {
+                    self.add_expected(stream[index].start, cache);
 
-                match &self.token_set[i].1 {
-                    Some(node) => Ok(SuccessData::tree(
-                        index + 1,
-                        ASTNode::leaf(
-                            node.clone(),
-                            stream[index].start,
-                            stream[index].end,
-                            Some((index, index + 1)),
-                        ),
-                    )),
-                    None => Ok(SuccessData::hidden(index + 1)),
+                    self.log_error(_code, stream[index].start, &ProductionError::Unparsed);
+                    Err(ProductionError::Unparsed)
                 }
             }
-            Err(_) => {
-                #[cfg(debug_assertions)]
-                self.log_error(_code, stream[index].start, &ProductionError::Unparsed);
-                Err(ProductionError::Unparsed)
-            }
-        }
+        })
     }
 
     fn advance_ptr(
@@ -380,6 +406,24 @@ impl<TN: NodeImpl, TL: TokenImpl> IProduction for TokenFieldSet<TN, TL> {
         Ok(())
     }
 
+    fn grammar_expr(&self, grammar: &mut Grammar) -> GrammarExpr {
+        let expr = |_: &mut Grammar| {
+            GrammarExpr::choice(
+                self.token_set
+                    .iter()
+                    .map(|(token, node_value)| {
+                        let token = GrammarExpr::Token(format!("{:?}", token));
+                        GrammarExpr::with_node(node_value.as_ref(), token)
+                    })
+                    .collect(),
+            )
+        };
+        match self.rule_name.get() {
+            Some(rule_name) => grammar.add_rule(rule_name, expr),
+            None => expr(grammar),
+        }
+    }
+
     fn validate<'id>(
         &'id self,
         _: HashMap<&'id str, usize>,
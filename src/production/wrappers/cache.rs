@@ -1,14 +1,13 @@
+use crate::profile;
 use crate::{
+    grammar::{Grammar, GrammarExpr},
     production::{Cacheable, ProductionLogger},
-    Code,
-    Cache, CacheKey, FltrPtr, IProduction, ImplementationError, ParsedResult, TokenPtr,
-    TokenStream,
+    Cache, CacheKey, Code, FltrPtr, IProduction, ImplementationError, OnceCell, ParsedResult, Rc,
+    TokenPtr, TokenStream,
 };
-use once_cell::unsync::OnceCell;
 use std::{
     collections::{HashMap, HashSet},
     fmt::Display,
-    rc::Rc,
 };
 
 impl<TProd: IProduction> Cacheable<TProd> {
@@ -83,25 +82,38 @@ impl<TProd: IProduction> IProduction for Cacheable<TProd> {
         token_stream: &TokenStream<Self::Token>,
         memory_cache: &mut Cache<FltrPtr, Self::Node>,
     ) -> ParsedResult<FltrPtr, Self::Node> {
-        #[cfg(debug_assertions)]
-        self.log_entry();
+        self.log_entry(token_stream[index].start);
 
         let lex_data = &token_stream[index];
         let result = match memory_cache.find(self.cache_key, lex_data.start) {
-            Some(result) => result.clone(),
-            None => {
-                let advance_result = self.get_production().advance_fltr_ptr(
-                    code,
-                    index,
-                    token_stream,
-                    memory_cache,
-                );
-                memory_cache.insert(self.cache_key, lex_data.start, advance_result.clone());
-                advance_result
+            Some(result) => {
+                self.log_cache_hit(lex_data.start, result.is_ok());
+                profile::cache_lookup(self.get_production().as_ref(), true);
+                let result = result.clone();
+                memory_cache.replay_recovered(self.cache_key, lex_data.start);
+                result
             }
+            None => match memory_cache.find_reusable(self.cache_key, index, token_stream) {
+                Some(result) => {
+                    profile::cache_lookup(self.get_production().as_ref(), true);
+                    result
+                }
+                None => {
+                    profile::cache_lookup(self.get_production().as_ref(), false);
+                    let checkpoint = memory_cache.recovery_checkpoint();
+                    let advance_result = self.get_production().advance_fltr_ptr(
+                        code,
+                        index,
+                        token_stream,
+                        memory_cache,
+                    );
+                    memory_cache.insert(self.cache_key, lex_data.start, advance_result.clone());
+                    memory_cache.save_recovered(self.cache_key, lex_data.start, checkpoint);
+                    advance_result
+                }
+            },
         };
 
-        #[cfg(debug_assertions)]
         self.log_filtered_result(code, index, token_stream, &result);
 
         result
@@ -128,19 +140,26 @@ impl<TProd: IProduction> IProduction for Cacheable<TProd> {
         index: usize,
         cache: &mut Cache<usize, Self::Node>,
     ) -> ParsedResult<usize, Self::Node> {
-        #[cfg(debug_assertions)]
-        self.log_entry();
+        self.log_entry(index);
 
         let result = match cache.find(self.cache_key, index) {
-            Some(result) => result.clone(),
+            Some(result) => {
+                self.log_cache_hit(index, result.is_ok());
+                profile::cache_lookup(self.get_production().as_ref(), true);
+                let result = result.clone();
+                cache.replay_recovered(self.cache_key, index);
+                result
+            }
             None => {
+                profile::cache_lookup(self.get_production().as_ref(), false);
+                let checkpoint = cache.recovery_checkpoint();
                 let advance_result = self.get_production().advance_ptr(code, index, cache);
                 cache.insert(self.cache_key, index, advance_result.clone());
+                cache.save_recovered(self.cache_key, index, checkpoint);
                 advance_result
             }
         };
 
-        #[cfg(debug_assertions)]
         self.log_result(code, index, &result);
 
         result
@@ -153,4 +172,8 @@ impl<TProd: IProduction> IProduction for Cacheable<TProd> {
     ) -> Result<(), std::fmt::Error> {
         self.production.impl_grammar(writer, visited)
     }
+
+    fn grammar_expr(&self, grammar: &mut Grammar) -> GrammarExpr {
+        self.production.grammar_expr(grammar)
+    }
 }
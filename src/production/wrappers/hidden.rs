@@ -1,14 +1,13 @@
 use crate::production::{Hidden, ProductionLogger};
 use crate::Code;
 use crate::{
-    Cache, FltrPtr, IProduction, ImplementationError, ParsedResult, SuccessData, TokenPtr,
-    TokenStream,
+    grammar::{Grammar, GrammarExpr},
+    Cache, FltrPtr, IProduction, ImplementationError, OnceCell, ParsedResult, Rc, SuccessData,
+    TokenPtr, TokenStream,
 };
-use once_cell::unsync::OnceCell;
 use std::{
     collections::{HashMap, HashSet},
     fmt::Display,
-    rc::Rc,
 };
 
 impl<TProd: IProduction> Hidden<TProd> {
@@ -89,6 +88,15 @@ impl<TProd: IProduction> IProduction for Hidden<TProd> {
         self.production.impl_grammar(writer, visited)
     }
 
+    fn grammar_expr(&self, grammar: &mut Grammar) -> GrammarExpr {
+        match self.rule_name.get() {
+            Some(rule_name) => {
+                grammar.add_rule(rule_name, |grammar| self.production.grammar_expr(grammar))
+            }
+            None => self.production.grammar_expr(grammar),
+        }
+    }
+
     fn obtain_nullability<'id>(
         &'id self,
         visited: HashMap<&'id str, usize>,
@@ -120,15 +128,13 @@ impl<TProd: IProduction> IProduction for Hidden<TProd> {
         token_stream: &TokenStream<Self::Token>,
         cached: &mut Cache<FltrPtr, Self::Node>,
     ) -> ParsedResult<FltrPtr, Self::Node> {
-        #[cfg(debug_assertions)]
-        self.log_entry();
+        self.log_entry(token_stream[index].start);
 
         let result = self
             .get_production()
             .advance_fltr_ptr(code, index, token_stream, cached)
             .map(|parsed_data| SuccessData::hidden(parsed_data.consumed_index));
 
-        #[cfg(debug_assertions)]
         self.log_filtered_result(code, index, token_stream, &result);
 
         result
@@ -141,15 +147,13 @@ impl<TProd: IProduction> IProduction for Hidden<TProd> {
         token_stream: &TokenStream<Self::Token>,
         cache: &mut Cache<FltrPtr, Self::Node>,
     ) -> ParsedResult<TokenPtr, Self::Node> {
-        #[cfg(debug_assertions)]
-        self.log_entry();
+        self.log_entry(token_stream[index].start);
 
         let result = self
             .get_production()
             .advance_token_ptr(code, index, token_stream, cache)
             .map(|parsed_data| SuccessData::hidden(parsed_data.consumed_index));
 
-        #[cfg(debug_assertions)]
         self.log_lex_result(code, index, token_stream, &result);
 
         result
@@ -161,15 +165,13 @@ impl<TProd: IProduction> IProduction for Hidden<TProd> {
         index: usize,
         cache: &mut Cache<usize, Self::Node>,
     ) -> ParsedResult<usize, Self::Node> {
-        #[cfg(debug_assertions)]
-        self.log_entry();
+        self.log_entry(index);
 
         let result = self
             .get_production()
             .advance_ptr(code, index, cache)
             .map(|parsed_data| SuccessData::hidden(parsed_data.consumed_index));
 
-        #[cfg(debug_assertions)]
         self.log_result(code, index, &result);
 
         result
@@ -1,17 +1,15 @@
 use crate::{
-    production::{List, ProductionLogger},
-    Code,
-    ASTNode, Cache, FltrPtr, IProduction, ImplementationError, ParsedResult, TokenPtr,
-    SuccessData, TokenStream,
+    grammar::{Grammar, GrammarExpr},
+    production::{List, ProductionLogger, Recovery},
+    ASTNode, Cache, Code, FltrPtr, IProduction, ImplementationError, OnceCell, ParsedResult, Rc,
+    SuccessData, TokenPtr, TokenStream,
 };
-use once_cell::unsync::OnceCell;
 use std::{
     collections::{HashMap, HashSet},
     fmt::Display,
-    rc::Rc,
 };
 
-impl<TProd: IProduction> List<TProd> {
+impl<TProd: IProduction + ?Sized> List<TProd> {
     /// Create a [List] production of the provided symbol.
     ///
     /// ### Arguments
@@ -20,9 +18,30 @@ impl<TProd: IProduction> List<TProd> {
         Self {
             symbol: symbol.clone(),
             debugger: OnceCell::new(),
+            recovery: OnceCell::new(),
         }
     }
 
+    /// Set a recovery point to continue parsing after a syntax error while parsing in the
+    /// [error recovery](crate::DefaultParser::parse_with_recovery) mode.
+    ///
+    /// When a symbol of the list fails at a token other than `sync_tokens`,
+    /// the tokens are skipped until the symbol can be parsed again or a synchronization token is found.
+    /// Therefore the synchronization tokens should include the tokens which can follow the list, e.g. `}` after a statement list.
+    /// The skipped tokens are added as an [ASTNode] with `node_value`.
+    /// ### Arguments
+    /// * `sync_tokens` - Tokens to stop the list.
+    /// * `node_value` - Node value of the error node.
+    pub fn set_recovery(
+        &self,
+        sync_tokens: Vec<TProd::Token>,
+        node_value: TProd::Node,
+    ) -> Result<(), String> {
+        self.recovery
+            .set(Recovery::new(sync_tokens, node_value))
+            .map_err(|_| format!("Recovery is already set for {}.", self))
+    }
+
     #[inline]
     /// Get the associated terminal or non-terminal symbol of the production.
     pub fn get_symbol(&self) -> &TProd {
@@ -68,7 +87,77 @@ impl<TProd: IProduction> List<TProd> {
     }
 }
 
-impl<TP: IProduction> List<TP> {
+impl<TP: IProduction + ?Sized> List<TP> {
+    fn consume_n_recover(
+        &self,
+        recovery: &Recovery<TP::Node, TP::Token>,
+        code: &Code,
+        index: FltrPtr,
+        stream: &TokenStream<TP::Token>,
+        cache: &mut Cache<FltrPtr, TP::Node>,
+    ) -> ParsedResult<FltrPtr, TP::Node> {
+        let success_data = self
+            .get_symbol()
+            .advance_fltr_ptr(code, index, stream, cache)?;
+
+        let mut children = success_data.children;
+        let mut moved_ptr = success_data.consumed_index;
+
+        while moved_ptr != index {
+            let checkpoint = cache.recovery_checkpoint();
+            let err = match self
+                .get_symbol()
+                .advance_fltr_ptr(code, moved_ptr, stream, cache)
+            {
+                Ok(next_success_data) => {
+                    children.extend(next_success_data.children);
+                    if moved_ptr == next_success_data.consumed_index {
+                        break;
+                    }
+                    moved_ptr = next_success_data.consumed_index;
+                    continue;
+                }
+                Err(err) => err,
+            };
+            if err.is_invalid() {
+                return Err(err);
+            }
+            cache.rollback_recovery(checkpoint);
+            if recovery.is_sync_at(stream, moved_ptr) {
+                break;
+            }
+            cache.add_recovered_error(cache.create_error(code, stream, err));
+
+            let error_ptr = moved_ptr;
+            loop {
+                moved_ptr = moved_ptr + 1;
+                if recovery.is_sync_at(stream, moved_ptr) {
+                    children.push(recovery.error_node(stream, error_ptr, moved_ptr));
+                    return Ok(SuccessData::new(moved_ptr, children));
+                }
+                let checkpoint = cache.recovery_checkpoint();
+                match self
+                    .get_symbol()
+                    .advance_fltr_ptr(code, moved_ptr, stream, cache)
+                {
+                    Ok(next_success_data) => {
+                        children.push(recovery.error_node(stream, error_ptr, moved_ptr));
+                        children.extend(next_success_data.children);
+                        moved_ptr = next_success_data.consumed_index;
+                        break;
+                    }
+                    Err(err) => {
+                        if err.is_invalid() {
+                            return Err(err);
+                        }
+                        cache.rollback_recovery(checkpoint);
+                    }
+                }
+            }
+        }
+        Ok(SuccessData::new(moved_ptr, children))
+    }
+
     pub fn assign_debugger(&self, debugger: crate::Log<&'static str>) -> Result<(), String> {
         self.debugger
             .set(debugger)
@@ -76,17 +165,17 @@ impl<TP: IProduction> List<TP> {
     }
 }
 
-impl<TProd: IProduction> ProductionLogger for List<TProd> {
+impl<TProd: IProduction + ?Sized> ProductionLogger for List<TProd> {
     fn get_debugger(&self) -> Option<&crate::Log<&'static str>> {
         self.debugger.get()
     }
 }
-impl<TProd: IProduction> Display for List<TProd> {
+impl<TProd: IProduction + ?Sized> Display for List<TProd> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{}+", self.get_symbol())
     }
 }
-impl<TP: IProduction> IProduction for List<TP> {
+impl<TP: IProduction + ?Sized> IProduction for List<TP> {
     type Node = TP::Node;
     type Token = TP::Token;
 
@@ -98,6 +187,10 @@ impl<TP: IProduction> IProduction for List<TP> {
         self.get_symbol().impl_grammar(writer, visited)
     }
 
+    fn grammar_expr(&self, grammar: &mut Grammar) -> GrammarExpr {
+        GrammarExpr::repeat(self.get_symbol().grammar_expr(grammar), 1, None)
+    }
+
     fn validate<'id>(
         &'id self,
         first_sets: HashMap<&'id str, usize>,
@@ -113,13 +206,16 @@ impl<TP: IProduction> IProduction for List<TP> {
         token_stream: &TokenStream<Self::Token>,
         cache: &mut Cache<FltrPtr, Self::Node>,
     ) -> ParsedResult<FltrPtr, Self::Node> {
-        #[cfg(debug_assertions)]
-        self.log_entry();
-        let result = self.consume(index, cache, |moved_pointer, cache| {
-            self.get_symbol()
-                .advance_fltr_ptr(code, moved_pointer, token_stream, cache)
-        });
-        #[cfg(debug_assertions)]
+        self.log_entry(token_stream[index].start);
+        let result = match self.recovery.get() {
+            Some(recovery) if cache.is_recovering() => {
+                self.consume_n_recover(recovery, code, index, token_stream, cache)
+            }
+            _ => self.consume(index, cache, |moved_pointer, cache| {
+                self.get_symbol()
+                    .advance_fltr_ptr(code, moved_pointer, token_stream, cache)
+            }),
+        };
         self.log_filtered_result(code, index, token_stream, &result);
         result
     }
@@ -131,13 +227,11 @@ impl<TP: IProduction> IProduction for List<TP> {
         token_stream: &TokenStream<Self::Token>,
         cache: &mut Cache<FltrPtr, Self::Node>,
     ) -> ParsedResult<TokenPtr, Self::Node> {
-        #[cfg(debug_assertions)]
-        self.log_entry();
+        self.log_entry(token_stream[index].start);
         let result = self.consume(index, cache, |moved_pointer, cache| {
             self.get_symbol()
                 .advance_token_ptr(code, moved_pointer, token_stream, cache)
         });
-        #[cfg(debug_assertions)]
         self.log_lex_result(code, index, token_stream, &result);
         result
     }
@@ -148,13 +242,11 @@ impl<TP: IProduction> IProduction for List<TP> {
         index: usize,
         cache: &mut Cache<usize, Self::Node>,
     ) -> ParsedResult<usize, Self::Node> {
-        #[cfg(debug_assertions)]
-        self.log_entry();
+        self.log_entry(index);
         let result = self.consume(index, cache, |moved_pointer, cache| {
             self.get_symbol().advance_ptr(code, moved_pointer, cache)
         });
 
-        #[cfg(debug_assertions)]
         self.log_result(code, index, &result);
 
         result
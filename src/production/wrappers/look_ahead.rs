@@ -1,17 +1,15 @@
 use crate::{
+    grammar::{Grammar, GrammarExpr},
     production::{Lookahead, ProductionLogger},
-    Code,
-    ASTNode, Cache, FltrPtr, IProduction, ImplementationError, ParsedResult, TokenPtr,
-    SuccessData, TokenStream,
+    ASTNode, Cache, Code, FltrPtr, IProduction, ImplementationError, OnceCell, ParsedResult, Rc,
+    SuccessData, TokenPtr, TokenStream,
 };
-use once_cell::unsync::OnceCell;
 use std::{
     collections::{HashMap, HashSet},
     fmt::Display,
-    rc::Rc,
 };
 
-impl<TProd: IProduction> Lookahead<TProd> {
+impl<TProd: IProduction + ?Sized> Lookahead<TProd> {
     /// Create a [Lookahead] production of the provided symbol.
     ///
     /// ### Arguments
@@ -31,7 +29,7 @@ impl<TProd: IProduction> Lookahead<TProd> {
     }
 }
 
-impl<TP: IProduction> Lookahead<TP> {
+impl<TP: IProduction + ?Sized> Lookahead<TP> {
     pub fn assign_debugger(&self, debugger: crate::Log<&'static str>) -> Result<(), String> {
         self.debugger
             .set(debugger)
@@ -39,17 +37,17 @@ impl<TP: IProduction> Lookahead<TP> {
     }
 }
 
-impl<TProd: IProduction> ProductionLogger for Lookahead<TProd> {
+impl<TProd: IProduction + ?Sized> ProductionLogger for Lookahead<TProd> {
     fn get_debugger(&self) -> Option<&crate::Log<&'static str>> {
         self.debugger.get()
     }
 }
-impl<TProd: IProduction> Display for Lookahead<TProd> {
+impl<TProd: IProduction + ?Sized> Display for Lookahead<TProd> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "?={}", self.get_production())
     }
 }
-impl<TProd: IProduction> IProduction for Lookahead<TProd> {
+impl<TProd: IProduction + ?Sized> IProduction for Lookahead<TProd> {
     type Node = TProd::Node;
     type Token = TProd::Token;
 
@@ -66,6 +64,10 @@ impl<TProd: IProduction> IProduction for Lookahead<TProd> {
         self.production.impl_grammar(writer, visited)
     }
 
+    fn grammar_expr(&self, grammar: &mut Grammar) -> GrammarExpr {
+        GrammarExpr::Lookahead(Box::new(self.production.grammar_expr(grammar)))
+    }
+
     fn obtain_nullability<'id>(
         &'id self,
         visited: HashMap<&'id str, usize>,
@@ -96,9 +98,9 @@ impl<TProd: IProduction> IProduction for Lookahead<TProd> {
         token_stream: &TokenStream<Self::Token>,
         cached: &mut Cache<FltrPtr, Self::Node>,
     ) -> ParsedResult<FltrPtr, Self::Node> {
-        #[cfg(debug_assertions)]
-        self.log_entry();
+        self.log_entry(token_stream[index].start);
 
+        let checkpoint = cached.recovery_checkpoint();
         let result = self
             .get_production()
             .advance_fltr_ptr(code, index, token_stream, cached)
@@ -119,8 +121,9 @@ impl<TProd: IProduction> IProduction for Lookahead<TProd> {
                 }
                 None => SuccessData::hidden(index),
             });
+        // The derivation of the symbol is discarded along with the errors recovered by the derivation.
+        cached.rollback_recovery(checkpoint);
 
-        #[cfg(debug_assertions)]
         self.log_filtered_result(code, index, token_stream, &result);
 
         result
@@ -133,6 +136,9 @@ impl<TProd: IProduction> IProduction for Lookahead<TProd> {
         token_stream: &TokenStream<Self::Token>,
         cache: &mut Cache<FltrPtr, Self::Node>,
     ) -> ParsedResult<TokenPtr, Self::Node> {
+        self.log_entry(token_stream[index].start);
+
+        let checkpoint = cache.recovery_checkpoint();
         let result = self
             .get_production()
             .advance_token_ptr(code, index, token_stream, cache)
@@ -152,8 +158,8 @@ impl<TProd: IProduction> IProduction for Lookahead<TProd> {
                 }
                 None => SuccessData::hidden(index),
             });
+        cache.rollback_recovery(checkpoint);
 
-        #[cfg(debug_assertions)]
         self.log_lex_result(code, index, token_stream, &result);
 
         result
@@ -165,24 +171,27 @@ impl<TProd: IProduction> IProduction for Lookahead<TProd> {
         index: usize,
         cache: &mut Cache<usize, Self::Node>,
     ) -> ParsedResult<usize, Self::Node> {
-        let result =
-            self.get_production()
-                .advance_ptr(code, index, cache)
-                .map(|_| match &self.node_value {
-                    Some(node) => SuccessData::new(
+        self.log_entry(index);
+
+        let checkpoint = cache.recovery_checkpoint();
+        let result = self
+            .get_production()
+            .advance_ptr(code, index, cache)
+            .map(|_| match &self.node_value {
+                Some(node) => SuccessData::new(
+                    index,
+                    vec![ASTNode::new(
+                        node.clone(),
                         index,
-                        vec![ASTNode::new(
-                            node.clone(),
-                            index,
-                            index,
-                            None,
-                            Vec::with_capacity(0),
-                        )],
-                    ),
-                    None => SuccessData::hidden(index),
-                });
+                        index,
+                        None,
+                        Vec::with_capacity(0),
+                    )],
+                ),
+                None => SuccessData::hidden(index),
+            });
+        cache.rollback_recovery(checkpoint);
 
-        #[cfg(debug_assertions)]
         self.log_result(code, index, &result);
 
         result
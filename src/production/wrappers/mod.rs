@@ -4,7 +4,17 @@ mod list;
 mod look_ahead;
 mod node;
 mod non_structural;
+mod not_lookahead;
 mod nullable;
+mod repeat;
 mod separated_list;
 mod structural;
 mod validated;
+
+/// Write the bounds of a repetition as `{m,n}`, or as `{m,}` when the repetition has no maximum.
+fn write_bounds(f: &mut dyn std::fmt::Write, min: usize, max: Option<usize>) -> std::fmt::Result {
+    match max {
+        Some(max) => write!(f, "{{{},{}}}", min, max),
+        None => write!(f, "{{{},}}", min),
+    }
+}
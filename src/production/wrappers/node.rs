@@ -1,17 +1,16 @@
 use crate::production::{Node, ProductionLogger};
 use crate::Code;
 use crate::{
-    ASTNode, Cache, FltrPtr, IProduction, ImplementationError, ParsedResult, SuccessData, TokenPtr,
-    TokenStream,
+    grammar::{Grammar, GrammarExpr},
+    ASTNode, Cache, FltrPtr, IProduction, ImplementationError, OnceCell, ParsedResult, Rc,
+    SuccessData, TokenPtr, TokenStream,
 };
-use once_cell::unsync::OnceCell;
 use std::{
     collections::{HashMap, HashSet},
     fmt::Display,
-    rc::Rc,
 };
 
-impl<TProd: IProduction> Node<TProd> {
+impl<TProd: IProduction + ?Sized> Node<TProd> {
     pub fn new(production: &Rc<TProd>, node_value: TProd::Node) -> Self {
         Self {
             rule_name: OnceCell::new(),
@@ -31,7 +30,7 @@ impl<TProd: IProduction> Node<TProd> {
     }
 }
 
-impl<TP: IProduction> Node<TP> {
+impl<TP: IProduction + ?Sized> Node<TP> {
     pub fn set_log(&self, debugger: crate::Log<&'static str>) -> Result<(), String> {
         self.debugger
             .set(debugger)
@@ -39,13 +38,13 @@ impl<TP: IProduction> Node<TP> {
     }
 }
 
-impl<TProd: IProduction> ProductionLogger for Node<TProd> {
+impl<TProd: IProduction + ?Sized> ProductionLogger for Node<TProd> {
     fn get_debugger(&self) -> Option<&crate::Log<&'static str>> {
         self.debugger.get()
     }
 }
 
-impl<TProd: IProduction> Display for Node<TProd> {
+impl<TProd: IProduction + ?Sized> Display for Node<TProd> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self.rule_name.get() {
             Some(&s) => write!(f, "{}", s),
@@ -55,7 +54,7 @@ impl<TProd: IProduction> Display for Node<TProd> {
         }
     }
 }
-impl<TProd: IProduction> IProduction for Node<TProd> {
+impl<TProd: IProduction + ?Sized> IProduction for Node<TProd> {
     type Node = TProd::Node;
     type Token = TProd::Token;
 
@@ -89,6 +88,17 @@ impl<TProd: IProduction> IProduction for Node<TProd> {
         self.production.impl_grammar(writer, visited)
     }
 
+    fn grammar_expr(&self, grammar: &mut Grammar) -> GrammarExpr {
+        let expr = |grammar: &mut Grammar| {
+            let expr = self.production.grammar_expr(grammar);
+            GrammarExpr::with_node(Some(&self.node_value), expr)
+        };
+        match self.rule_name.get() {
+            Some(rule_name) => grammar.add_rule(rule_name, expr),
+            None => expr(grammar),
+        }
+    }
+
     fn obtain_nullability<'id>(
         &'id self,
         visited: HashMap<&'id str, usize>,
@@ -120,8 +130,7 @@ impl<TProd: IProduction> IProduction for Node<TProd> {
         token_stream: &TokenStream<Self::Token>,
         cached: &mut Cache<FltrPtr, Self::Node>,
     ) -> ParsedResult<FltrPtr, Self::Node> {
-        #[cfg(debug_assertions)]
-        self.log_entry();
+        self.log_entry(token_stream[index].start);
 
         let result = self
             .get_production()
@@ -141,7 +150,6 @@ impl<TProd: IProduction> IProduction for Node<TProd> {
                 SuccessData::tree(parsed_data.consumed_index, tree)
             });
 
-        #[cfg(debug_assertions)]
         self.log_filtered_result(code, index, token_stream, &result);
 
         result
@@ -154,8 +162,7 @@ impl<TProd: IProduction> IProduction for Node<TProd> {
         token_stream: &TokenStream<Self::Token>,
         cache: &mut Cache<FltrPtr, Self::Node>,
     ) -> ParsedResult<TokenPtr, Self::Node> {
-        #[cfg(debug_assertions)]
-        self.log_entry();
+        self.log_entry(token_stream[index].start);
 
         let result = self
             .get_production()
@@ -172,7 +179,6 @@ impl<TProd: IProduction> IProduction for Node<TProd> {
                 SuccessData::tree(parsed_data.consumed_index, tree)
             });
 
-        #[cfg(debug_assertions)]
         self.log_lex_result(code, index, token_stream, &result);
 
         result
@@ -184,8 +190,7 @@ impl<TProd: IProduction> IProduction for Node<TProd> {
         index: usize,
         cache: &mut Cache<usize, Self::Node>,
     ) -> ParsedResult<usize, Self::Node> {
-        #[cfg(debug_assertions)]
-        self.log_entry();
+        self.log_entry(index);
 
         let result = self
             .get_production()
@@ -202,7 +207,6 @@ impl<TProd: IProduction> IProduction for Node<TProd> {
                 SuccessData::tree(parsed_data.consumed_index, tree)
             });
 
-        #[cfg(debug_assertions)]
         self.log_result(code, index, &result);
 
         result
@@ -1,17 +1,18 @@
 use crate::production::ProductionLogger;
-use crate::{production::NonStructural, Code, Cache, FltrPtr, IProduction, TokenStream};
-use crate::{ImplementationError, ParsedResult, ProductionError, TokenPtr, SuccessData};
-use once_cell::unsync::OnceCell;
+use crate::{
+    grammar::{Grammar, GrammarExpr},
+    production::NonStructural,
+    Cache, Code, FltrPtr, IProduction, OnceCell, Rc, TokenStream,
+};
+use crate::{ImplementationError, ParsedResult, ProductionError, SuccessData, TokenPtr};
 use std::{
     collections::{HashMap, HashSet},
     fmt::Display,
-    rc::Rc,
 };
 
-impl<TProd: IProduction> NonStructural<TProd> {
-
+impl<TProd: IProduction + ?Sized> NonStructural<TProd> {
     /// Create a new [NonStructural] production utility.
-    /// ### Arguments 
+    /// ### Arguments
     /// * `symbol` - A terminal or non terminal symbol.
     /// * `shall_fill_range` - A [bool] value to indicate whether it is required to consume all non structural tokens.
     pub fn new(symbol: &Rc<TProd>, shall_fill_range: bool) -> Self {
@@ -28,7 +29,7 @@ impl<TProd: IProduction> NonStructural<TProd> {
     }
 }
 
-impl<TP: IProduction> NonStructural<TP> {
+impl<TP: IProduction + ?Sized> NonStructural<TP> {
     /// Set a [Log](crate::Log) to debug the production.
     pub fn set_log(&self, debugger: crate::Log<&'static str>) -> Result<(), String> {
         self.debugger
@@ -37,18 +38,18 @@ impl<TP: IProduction> NonStructural<TP> {
     }
 }
 
-impl<TProd: IProduction> ProductionLogger for NonStructural<TProd> {
+impl<TProd: IProduction + ?Sized> ProductionLogger for NonStructural<TProd> {
     fn get_debugger(&self) -> Option<&crate::Log<&'static str>> {
         self.debugger.get()
     }
 }
 
-impl<TProd: IProduction> Display for NonStructural<TProd> {
+impl<TProd: IProduction + ?Sized> Display for NonStructural<TProd> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "%{}%", self.get_symbol())
     }
 }
-impl<TProd: IProduction> IProduction for NonStructural<TProd> {
+impl<TProd: IProduction + ?Sized> IProduction for NonStructural<TProd> {
     type Node = TProd::Node;
     type Token = TProd::Token;
 
@@ -65,6 +66,10 @@ impl<TProd: IProduction> IProduction for NonStructural<TProd> {
         self.production.impl_grammar(writer, visited)
     }
 
+    fn grammar_expr(&self, grammar: &mut Grammar) -> GrammarExpr {
+        GrammarExpr::NonStructural(Box::new(self.production.grammar_expr(grammar)))
+    }
+
     fn obtain_nullability<'id>(
         &'id self,
         visited: HashMap<&'id str, usize>,
@@ -94,8 +99,7 @@ impl<TProd: IProduction> IProduction for NonStructural<TProd> {
         token_stream: &TokenStream<Self::Token>,
         cache: &mut Cache<FltrPtr, Self::Node>,
     ) -> ParsedResult<FltrPtr, Self::Node> {
-        #[cfg(debug_assertions)]
-        self.log_entry();
+        self.log_entry(token_stream[fltr_ptr].start);
 
         let start_segment = if fltr_ptr > FltrPtr::default() {
             token_stream.get_token_ptr(fltr_ptr - 1)
@@ -103,22 +107,19 @@ impl<TProd: IProduction> IProduction for NonStructural<TProd> {
             TokenPtr::default()
         };
 
-        let parsed_data =
-            self.get_symbol()
-                .advance_token_ptr(code, start_segment + 1, token_stream, cache)?;
-
-        let result = if self.fill_range {
-            let end_segment = token_stream.get_token_ptr(fltr_ptr);
-            if end_segment == parsed_data.consumed_index {
-                Ok(SuccessData::new(fltr_ptr, parsed_data.children))
-            } else {
-                Err(ProductionError::Unparsed)
-            }
-        } else {
-            Ok(SuccessData::new(fltr_ptr, parsed_data.children))
-        };
+        let result = self
+            .get_symbol()
+            .advance_token_ptr(code, start_segment + 1, token_stream, cache)
+            .and_then(|parsed_data| {
+                if !self.fill_range
+                    || token_stream.get_token_ptr(fltr_ptr) == parsed_data.consumed_index
+                {
+                    Ok(SuccessData::new(fltr_ptr, parsed_data.children))
+                } else {
+                    Err(ProductionError::Unparsed)
+                }
+            });
 
-        #[cfg(debug_assertions)]
         self.log_filtered_result(code, fltr_ptr, token_stream, &result);
 
         result
@@ -131,8 +132,15 @@ impl<TProd: IProduction> IProduction for NonStructural<TProd> {
         stream: &TokenStream<Self::Token>,
         cache: &mut Cache<FltrPtr, Self::Node>,
     ) -> ParsedResult<TokenPtr, Self::Node> {
-        self.get_symbol()
-            .advance_token_ptr(code, index, stream, cache)
+        self.log_entry(stream[index].start);
+
+        let result = self
+            .get_symbol()
+            .advance_token_ptr(code, index, stream, cache);
+
+        self.log_lex_result(code, index, stream, &result);
+
+        result
     }
 
     fn advance_ptr(
@@ -141,6 +149,12 @@ impl<TProd: IProduction> IProduction for NonStructural<TProd> {
         index: usize,
         cache: &mut Cache<usize, Self::Node>,
     ) -> ParsedResult<usize, Self::Node> {
-        self.get_symbol().advance_ptr(code, index, cache)
+        self.log_entry(index);
+
+        let result = self.get_symbol().advance_ptr(code, index, cache);
+
+        self.log_result(code, index, &result);
+
+        result
     }
 }
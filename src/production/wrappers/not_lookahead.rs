@@ -0,0 +1,232 @@
+use crate::{
+    grammar::{Grammar, GrammarExpr},
+    production::{NotLookahead, ProductionLogger},
+    ASTNode, Cache, Code, Expected, FltrPtr, IProduction, ImplementationError, OnceCell,
+    ParsedResult, ProductionError, Rc, SuccessData, TokenPtr, TokenStream,
+};
+use std::{
+    collections::{HashMap, HashSet},
+    fmt::Display,
+    hash::Hash,
+};
+
+/// The max parsed point, the recovery checkpoint and the expected checkpoint before the child production is derived.
+type Checkpoint = (usize, usize, (usize, Vec<Expected>));
+
+impl<TProd: IProduction + ?Sized> NotLookahead<TProd> {
+    /// Create a [NotLookahead] production of the provided symbol.
+    ///
+    /// ### Arguments
+    /// * `symbol` - A terminal or non-terminal symbol which should not be parsed at the position.
+    /// * `node_value` - An optional node value of the empty tree to be added when the symbol is not parsed.
+    pub fn new(symbol: &Rc<TProd>, node_value: Option<TProd::Node>) -> Self {
+        Self {
+            node_value,
+            production: symbol.clone(),
+            debugger: OnceCell::new(),
+        }
+    }
+    #[inline]
+    pub fn get_production(&self) -> &TProd {
+        &self.production
+    }
+
+    pub fn assign_debugger(&self, debugger: crate::Log<&'static str>) -> Result<(), String> {
+        self.debugger
+            .set(debugger)
+            .map_err(|err| format!("Debugger {} is already set for this production.", err))
+    }
+
+    /// Return the success data when the child production has failed.
+    fn negate<
+        T,
+        TCache: Default + Eq + Hash + Ord + Copy,
+        F: FnOnce() -> SuccessData<T, TProd::Node>,
+    >(
+        result: ParsedResult<T, TProd::Node>,
+        cache: &mut Cache<TCache, TProd::Node>,
+        checkpoint: Checkpoint,
+        success: F,
+    ) -> ParsedResult<T, TProd::Node> {
+        let (index, recovery, expected) = checkpoint;
+        // The derivation of the child production is discarded along with the errors recovered by the derivation.
+        cache.rollback_recovery(recovery);
+        match result {
+            Ok(_) => {
+                // The syntax error is reported at the position rather than the end of the child production.
+                cache.restore_index(index);
+                Err(ProductionError::Unparsed)
+            }
+            Err(err) if err.is_invalid() => Err(err),
+            Err(_) => {
+                // The terminals failed by the child production are not expected at the position.
+                cache.rollback_expected(expected);
+                Ok(success())
+            }
+        }
+    }
+}
+
+impl<TProd: IProduction + ?Sized> ProductionLogger for NotLookahead<TProd> {
+    fn get_debugger(&self) -> Option<&crate::Log<&'static str>> {
+        self.debugger.get()
+    }
+}
+impl<TProd: IProduction + ?Sized> Display for NotLookahead<TProd> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "?!{}", self.get_production())
+    }
+}
+impl<TProd: IProduction + ?Sized> IProduction for NotLookahead<TProd> {
+    type Node = TProd::Node;
+    type Token = TProd::Token;
+
+    #[inline]
+    fn is_nullable(&self) -> bool {
+        true
+    }
+
+    fn impl_grammar(
+        &self,
+        writer: &mut dyn std::fmt::Write,
+        visited: &mut HashSet<&'static str>,
+    ) -> Result<(), std::fmt::Error> {
+        self.production.impl_grammar(writer, visited)
+    }
+
+    fn grammar_expr(&self, grammar: &mut Grammar) -> GrammarExpr {
+        GrammarExpr::NotLookahead(Box::new(self.production.grammar_expr(grammar)))
+    }
+
+    fn obtain_nullability<'id>(
+        &'id self,
+        visited: HashMap<&'id str, usize>,
+    ) -> Result<bool, ImplementationError> {
+        self.production.obtain_nullability(visited)?;
+        Ok(true)
+    }
+
+    fn impl_first_set(&self, _first_set: &mut HashSet<TProd::Token>) {
+        // The production does not consume any input,
+        // therefore the first set is derived from the following symbols.
+    }
+
+    fn is_nullable_n_hidden(&self) -> bool {
+        self.node_value.is_none()
+    }
+
+    #[inline]
+    fn validate<'id>(
+        &'id self,
+        first_sets: HashMap<&'id str, usize>,
+        visited_prod: &mut HashSet<&'id str>,
+    ) -> Result<(), ImplementationError> {
+        self.get_production().validate(first_sets, visited_prod)
+    }
+
+    fn advance_fltr_ptr(
+        &self,
+        code: &Code,
+        index: FltrPtr,
+        token_stream: &TokenStream<Self::Token>,
+        cache: &mut Cache<FltrPtr, Self::Node>,
+    ) -> ParsedResult<FltrPtr, Self::Node> {
+        self.log_entry(token_stream[index].start);
+
+        let checkpoint = (
+            cache.get_index(),
+            cache.recovery_checkpoint(),
+            cache.expected_checkpoint(),
+        );
+        let child_result = self
+            .get_production()
+            .advance_fltr_ptr(code, index, token_stream, cache);
+        let result = Self::negate(child_result, cache, checkpoint, || match &self.node_value {
+            Some(node) => {
+                let pointer = token_stream[index].start;
+                let segment_index = token_stream.get_token_ptr(index);
+                SuccessData::tree(
+                    index,
+                    ASTNode::new(
+                        node.clone(),
+                        pointer,
+                        pointer,
+                        Some((segment_index, segment_index)),
+                        Vec::with_capacity(0),
+                    ),
+                )
+            }
+            None => SuccessData::hidden(index),
+        });
+
+        self.log_filtered_result(code, index, token_stream, &result);
+
+        result
+    }
+
+    fn advance_token_ptr(
+        &self,
+        code: &Code,
+        index: TokenPtr,
+        token_stream: &TokenStream<Self::Token>,
+        cache: &mut Cache<FltrPtr, Self::Node>,
+    ) -> ParsedResult<TokenPtr, Self::Node> {
+        self.log_entry(token_stream[index].start);
+
+        let checkpoint = (
+            cache.get_index(),
+            cache.recovery_checkpoint(),
+            cache.expected_checkpoint(),
+        );
+        let child_result =
+            self.get_production()
+                .advance_token_ptr(code, index, token_stream, cache);
+        let result = Self::negate(child_result, cache, checkpoint, || match &self.node_value {
+            Some(node) => {
+                let pointer = token_stream[index].start;
+                SuccessData::tree(
+                    index,
+                    ASTNode::new(
+                        node.clone(),
+                        pointer,
+                        pointer,
+                        Some((index, index)),
+                        Vec::with_capacity(0),
+                    ),
+                )
+            }
+            None => SuccessData::hidden(index),
+        });
+
+        self.log_lex_result(code, index, token_stream, &result);
+
+        result
+    }
+
+    fn advance_ptr(
+        &self,
+        code: &Code,
+        index: usize,
+        cache: &mut Cache<usize, Self::Node>,
+    ) -> ParsedResult<usize, Self::Node> {
+        self.log_entry(index);
+
+        let checkpoint = (
+            cache.get_index(),
+            cache.recovery_checkpoint(),
+            cache.expected_checkpoint(),
+        );
+        let child_result = self.get_production().advance_ptr(code, index, cache);
+        let result = Self::negate(child_result, cache, checkpoint, || match &self.node_value {
+            Some(node) => SuccessData::tree(
+                index,
+                ASTNode::new(node.clone(), index, index, None, Vec::with_capacity(0)),
+            ),
+            None => SuccessData::hidden(index),
+        });
+
+        self.log_result(code, index, &result);
+
+        result
+    }
+}
@@ -1,18 +1,16 @@
 use crate::NodeImpl;
 use crate::{
+    grammar::{Grammar, GrammarExpr},
     production::{Nullable, ProductionLogger},
-    Code,
-    ASTNode, Cache, FltrPtr, IProduction, ImplementationError, ParsedResult, SuccessData, TokenPtr,
-    TokenStream,
+    ASTNode, Cache, Code, FltrPtr, IProduction, ImplementationError, OnceCell, ParsedResult, Rc,
+    SuccessData, TokenPtr, TokenStream,
 };
-use once_cell::unsync::OnceCell;
 use std::{
     collections::{HashMap, HashSet},
     fmt::Display,
-    rc::Rc,
 };
 
-impl<TProd: IProduction> Nullable<TProd> {
+impl<TProd: IProduction + ?Sized> Nullable<TProd> {
     /// Create a nullable production i.e. add a null production as alternative production.
     ///
     /// The null derivation create a tree with [null](NodeImpl::null) node value in the [ASTNode].
@@ -44,7 +42,7 @@ impl<TProd: IProduction> Nullable<TProd> {
     }
 }
 
-impl<TP: IProduction> Nullable<TP> {
+impl<TP: IProduction + ?Sized> Nullable<TP> {
     pub fn set_log(&self, debugger: crate::Log<&'static str>) -> Result<(), String> {
         self.debugger
             .set(debugger)
@@ -52,18 +50,18 @@ impl<TP: IProduction> Nullable<TP> {
     }
 }
 
-impl<TProd: IProduction> ProductionLogger for Nullable<TProd> {
+impl<TProd: IProduction + ?Sized> ProductionLogger for Nullable<TProd> {
     fn get_debugger(&self) -> Option<&crate::Log<&'static str>> {
         self.debugger.get()
     }
 }
 
-impl<TProd: IProduction> Display for Nullable<TProd> {
+impl<TProd: IProduction + ?Sized> Display for Nullable<TProd> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "({})?", self.get_production())
     }
 }
-impl<TProd: IProduction> IProduction for Nullable<TProd> {
+impl<TProd: IProduction + ?Sized> IProduction for Nullable<TProd> {
     type Node = TProd::Node;
     type Token = TProd::Token;
 
@@ -80,6 +78,10 @@ impl<TProd: IProduction> IProduction for Nullable<TProd> {
         self.get_production().impl_grammar(writer, visited)
     }
 
+    fn grammar_expr(&self, grammar: &mut Grammar) -> GrammarExpr {
+        GrammarExpr::optional(self.get_production().grammar_expr(grammar))
+    }
+
     fn obtain_nullability<'id>(
         &'id self,
         _: HashMap<&'id str, usize>,
@@ -110,6 +112,9 @@ impl<TProd: IProduction> IProduction for Nullable<TProd> {
         token_stream: &TokenStream<Self::Token>,
         cached: &mut Cache<FltrPtr, Self::Node>,
     ) -> ParsedResult<FltrPtr, Self::Node> {
+        self.log_entry(token_stream[index].start);
+
+        let checkpoint = cached.recovery_checkpoint();
         let result = self
             .get_production()
             .advance_fltr_ptr(code, index, token_stream, cached)
@@ -117,6 +122,7 @@ impl<TProd: IProduction> IProduction for Nullable<TProd> {
                 if err.is_invalid() {
                     Err(err)
                 } else {
+                    cached.rollback_recovery(checkpoint);
                     match &self.node_value {
                         Some(node_value) => {
                             let pointer_start = token_stream.pointer(index);
@@ -134,7 +140,6 @@ impl<TProd: IProduction> IProduction for Nullable<TProd> {
                 }
             });
 
-        #[cfg(debug_assertions)]
         self.log_filtered_result(code, index, token_stream, &result);
         result
     }
@@ -146,6 +151,8 @@ impl<TProd: IProduction> IProduction for Nullable<TProd> {
         token_stream: &TokenStream<Self::Token>,
         cache: &mut Cache<FltrPtr, Self::Node>,
     ) -> ParsedResult<TokenPtr, Self::Node> {
+        self.log_entry(token_stream[token_ptr].start);
+
         let result = self
             .get_production()
             .advance_token_ptr(code, token_ptr, token_stream, cache)
@@ -169,7 +176,6 @@ impl<TProd: IProduction> IProduction for Nullable<TProd> {
                 }
             });
 
-        #[cfg(debug_assertions)]
         self.log_lex_result(code, token_ptr, token_stream, &result);
         result
     }
@@ -180,6 +186,8 @@ impl<TProd: IProduction> IProduction for Nullable<TProd> {
         index: usize,
         cache: &mut Cache<usize, Self::Node>,
     ) -> ParsedResult<usize, Self::Node> {
+        self.log_entry(index);
+
         let result = self
             .get_production()
             .advance_ptr(code, index, cache)
@@ -196,7 +204,6 @@ impl<TProd: IProduction> IProduction for Nullable<TProd> {
                     }
                 }
             });
-        #[cfg(debug_assertions)]
         self.log_result(code, index, &result);
         result
     }
@@ -0,0 +1,191 @@
+use super::write_bounds;
+use crate::{
+    grammar::{Grammar, GrammarExpr},
+    production::{ProductionLogger, Repeat},
+    ASTNode, Cache, Code, FltrPtr, IProduction, ImplementationError, OnceCell, ParsedResult, Rc,
+    SuccessData, TokenPtr, TokenStream,
+};
+use std::{
+    collections::{HashMap, HashSet},
+    fmt::Display,
+};
+
+impl<TProd: IProduction + ?Sized> Repeat<TProd> {
+    /// Create a [Repeat] production of the provided symbol.
+    ///
+    /// ### Arguments
+    /// * `symbol` - A terminal or non-terminal symbol which will be parsed repeatedly.
+    /// * `min` - Minimum number of repetitions of the symbol.
+    /// * `max` - Maximum number of repetitions of the symbol. The repetitions are unbounded for [None].
+    pub fn new(symbol: &Rc<TProd>, min: usize, max: Option<usize>) -> Self {
+        Self {
+            symbol: symbol.clone(),
+            min,
+            max,
+            debugger: OnceCell::new(),
+        }
+    }
+
+    #[inline]
+    /// Get the associated terminal or non-terminal symbol of the production.
+    pub fn get_symbol(&self) -> &TProd {
+        &self.symbol
+    }
+
+    pub fn assign_debugger(&self, debugger: crate::Log<&'static str>) -> Result<(), String> {
+        self.debugger
+            .set(debugger)
+            .map_err(|err| format!("Debugger {} is already set for this production.", err))
+    }
+
+    fn consume<
+        T: PartialEq + Copy,
+        TCache,
+        P: Fn(T, &mut Cache<TCache, TProd::Node>) -> ParsedResult<T, TProd::Node>,
+    >(
+        &self,
+        index: T,
+        cache: &mut Cache<TCache, TProd::Node>,
+        parse_production: P,
+    ) -> ParsedResult<T, TProd::Node> {
+        let mut children: Vec<ASTNode<TProd::Node>> = Vec::new();
+        let mut moved_ptr = index;
+        let mut count = 0;
+        while !matches!(self.max, Some(max) if count >= max) {
+            match parse_production(moved_ptr, cache) {
+                Ok(success_data) => {
+                    children.extend(success_data.children);
+                    if moved_ptr == success_data.consumed_index {
+                        // The remaining repetitions will derive the same empty input.
+                        break;
+                    }
+                    moved_ptr = success_data.consumed_index;
+                    count += 1;
+                }
+                Err(err) => {
+                    if err.is_invalid() || count < self.min {
+                        return Err(err);
+                    }
+                    break;
+                }
+            }
+        }
+        Ok(SuccessData::new(moved_ptr, children))
+    }
+}
+
+impl<TProd: IProduction + ?Sized> ProductionLogger for Repeat<TProd> {
+    fn get_debugger(&self) -> Option<&crate::Log<&'static str>> {
+        self.debugger.get()
+    }
+}
+impl<TProd: IProduction + ?Sized> Display for Repeat<TProd> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.get_symbol())?;
+        write_bounds(f, self.min, self.max)
+    }
+}
+impl<TP: IProduction + ?Sized> IProduction for Repeat<TP> {
+    type Node = TP::Node;
+    type Token = TP::Token;
+
+    fn impl_grammar(
+        &self,
+        writer: &mut dyn std::fmt::Write,
+        visited: &mut HashSet<&'static str>,
+    ) -> Result<(), std::fmt::Error> {
+        self.get_symbol().impl_grammar(writer, visited)
+    }
+
+    fn grammar_expr(&self, grammar: &mut Grammar) -> GrammarExpr {
+        GrammarExpr::repeat(self.get_symbol().grammar_expr(grammar), self.min, self.max)
+    }
+
+    fn validate<'id>(
+        &'id self,
+        first_sets: HashMap<&'id str, usize>,
+        visited_prod: &mut HashSet<&'id str>,
+    ) -> Result<(), ImplementationError> {
+        if self.max.is_some_and(|max| max == 0 || max < self.min) {
+            return Err(ImplementationError::new(
+                "InvalidRepetition".into(),
+                format!(
+                    "Maximum repetitions should be positive and not less than the minimum repetitions for {}.",
+                    self
+                ),
+            ));
+        }
+        self.get_symbol().validate(first_sets, visited_prod)
+    }
+
+    fn advance_fltr_ptr(
+        &self,
+        code: &Code,
+        index: FltrPtr,
+        token_stream: &TokenStream<Self::Token>,
+        cache: &mut Cache<FltrPtr, Self::Node>,
+    ) -> ParsedResult<FltrPtr, Self::Node> {
+        self.log_entry(token_stream[index].start);
+        let result = self.consume(index, cache, |moved_pointer, cache| {
+            self.get_symbol()
+                .advance_fltr_ptr(code, moved_pointer, token_stream, cache)
+        });
+        self.log_filtered_result(code, index, token_stream, &result);
+        result
+    }
+
+    fn advance_token_ptr(
+        &self,
+        code: &Code,
+        index: TokenPtr,
+        token_stream: &TokenStream<Self::Token>,
+        cache: &mut Cache<FltrPtr, Self::Node>,
+    ) -> ParsedResult<TokenPtr, Self::Node> {
+        self.log_entry(token_stream[index].start);
+        let result = self.consume(index, cache, |moved_pointer, cache| {
+            self.get_symbol()
+                .advance_token_ptr(code, moved_pointer, token_stream, cache)
+        });
+        self.log_lex_result(code, index, token_stream, &result);
+        result
+    }
+
+    fn advance_ptr(
+        &self,
+        code: &Code,
+        index: usize,
+        cache: &mut Cache<usize, Self::Node>,
+    ) -> ParsedResult<usize, Self::Node> {
+        self.log_entry(index);
+        let result = self.consume(index, cache, |moved_pointer, cache| {
+            self.get_symbol().advance_ptr(code, moved_pointer, cache)
+        });
+
+        self.log_result(code, index, &result);
+
+        result
+    }
+
+    fn is_nullable(&self) -> bool {
+        self.min == 0 || self.get_symbol().is_nullable()
+    }
+
+    fn is_nullable_n_hidden(&self) -> bool {
+        if self.get_symbol().is_nullable() {
+            self.get_symbol().is_nullable_n_hidden()
+        } else {
+            self.min == 0
+        }
+    }
+
+    fn obtain_nullability<'id>(
+        &'id self,
+        visited: HashMap<&'id str, usize>,
+    ) -> Result<bool, ImplementationError> {
+        Ok(self.get_symbol().obtain_nullability(visited)? || self.min == 0)
+    }
+
+    fn impl_first_set(&self, first_set: &mut HashSet<Self::Token>) {
+        self.get_symbol().impl_first_set(first_set)
+    }
+}
@@ -1,24 +1,45 @@
-use crate::production::ProductionLogger;
+use super::write_bounds;
+use crate::production::{ProductionLogger, Recovery};
 use crate::{
-    production::SeparatedList, Code, Cache, FltrPtr, IProduction, ImplementationError,
-    ParsedResult, TokenPtr, SuccessData, TokenStream,
+    grammar::{Grammar, GrammarExpr},
+    production::SeparatedList,
+    ASTNode, Cache, Code, FltrPtr, IProduction, ImplementationError, OnceCell, ParsedResult,
+    ProductionError, Rc, SuccessData, TokenPtr, TokenStream,
 };
-use once_cell::unsync::OnceCell;
 use std::hash::Hash;
 use std::{
     collections::{HashMap, HashSet},
     fmt::Display,
-    rc::Rc,
 };
 
 impl<TP: IProduction, TS: IProduction<Node = TP::Node, Token = TP::Token>> SeparatedList<TP, TS> {
     pub fn new(production: &Rc<TP>, separator: &Rc<TS>, inclusive: bool) -> Self {
+        Self::bounded(production, separator, inclusive, 1, None)
+    }
+
+    /// Create a [SeparatedList] which parses a bounded number of symbols.
+    /// ### Arguments
+    /// * `production` - A symbol of the list items.
+    /// * `separator` - A symbol of the separator between the items.
+    /// * `inclusive` - Whether the list can be terminated with a separator.
+    /// * `min` - Minimum number of items in the list. The list is nullable for zero minimum items.
+    /// * `max` - Maximum number of items in the list. The items are unbounded for [None].
+    pub fn bounded(
+        production: &Rc<TP>,
+        separator: &Rc<TS>,
+        inclusive: bool,
+        min: usize,
+        max: Option<usize>,
+    ) -> Self {
         Self {
             rule_name: OnceCell::new(),
             inclusive,
+            min,
+            max,
             production: production.clone(),
             separator: separator.clone(),
             debugger: OnceCell::new(),
+            recovery: OnceCell::new(),
         }
     }
     #[inline]
@@ -37,6 +58,122 @@ impl<TP: IProduction, TS: IProduction<Node = TP::Node, Token = TP::Token>> Separ
             .map_err(|err| format!("Rule name {} is already assigned", err))
     }
 
+    /// Set a recovery point to continue parsing after a syntax error while parsing in the
+    /// [error recovery](crate::DefaultParser::parse_with_recovery) mode.
+    ///
+    /// When a symbol fails after a separator, the tokens are skipped until the next separator or one of the `sync_tokens`.
+    /// The skipped tokens are added as an [ASTNode](crate::ASTNode) with `node_value`
+    /// and the list will continue from the next separator.
+    /// ### Arguments
+    /// * `sync_tokens` - Tokens to stop the list, e.g. `]` for an array.
+    /// * `node_value` - Node value of the error node.
+    pub fn set_recovery(
+        &self,
+        sync_tokens: Vec<TP::Token>,
+        node_value: TP::Node,
+    ) -> Result<(), String> {
+        self.recovery
+            .set(Recovery::new(sync_tokens, node_value))
+            .map_err(|_| format!("Recovery is already set for {}.", self))
+    }
+
+    fn consume_n_recover(
+        &self,
+        recovery: &Recovery<TP::Node, TP::Token>,
+        code: &Code,
+        index: FltrPtr,
+        stream: &TokenStream<TP::Token>,
+        cache: &mut Cache<FltrPtr, TP::Node>,
+    ) -> ParsedResult<FltrPtr, TP::Node> {
+        let success_data = match self
+            .get_production()
+            .advance_fltr_ptr(code, index, stream, cache)
+        {
+            Ok(success_data) => success_data,
+            Err(err) => return self.empty_list(index, err),
+        };
+
+        let mut moved_ptr = success_data.consumed_index;
+        let mut children = success_data.children;
+        let mut count = 1;
+        loop {
+            let checkpoint = cache.recovery_checkpoint();
+            let separator_success_data = match self
+                .get_separator()
+                .advance_fltr_ptr(code, moved_ptr, stream, cache)
+            {
+                Ok(separator_success_data) => separator_success_data,
+                Err(err) => {
+                    if err.is_invalid() {
+                        return Err(err);
+                    }
+                    cache.rollback_recovery(checkpoint);
+                    break self.bounded_list(moved_ptr, children, count);
+                }
+            };
+            let item_ptr = separator_success_data.consumed_index;
+            if self.is_full(count) {
+                if self.inclusive {
+                    children.extend(separator_success_data.children);
+                    break self.bounded_list(item_ptr, children, count);
+                } else {
+                    break self.bounded_list(moved_ptr, children, count);
+                }
+            }
+            let checkpoint = cache.recovery_checkpoint();
+            let err = match self
+                .get_production()
+                .advance_fltr_ptr(code, item_ptr, stream, cache)
+            {
+                Ok(next_success_data) => {
+                    children.extend(separator_success_data.children);
+                    children.extend(next_success_data.children);
+                    moved_ptr = next_success_data.consumed_index;
+                    count += 1;
+                    continue;
+                }
+                Err(err) => err,
+            };
+            if err.is_invalid() {
+                return Err(err);
+            }
+            cache.rollback_recovery(checkpoint);
+
+            if recovery.is_sync_at(stream, item_ptr) {
+                if self.inclusive {
+                    children.extend(separator_success_data.children);
+                    break self.bounded_list(item_ptr, children, count);
+                } else {
+                    break self.bounded_list(moved_ptr, children, count);
+                }
+            }
+
+            cache.add_recovered_error(cache.create_error(code, stream, err));
+            children.extend(separator_success_data.children);
+
+            let mut error_end = item_ptr + 1;
+            while !recovery.is_sync_at(stream, error_end) {
+                let checkpoint = cache.recovery_checkpoint();
+                let separator_result = self
+                    .get_separator()
+                    .advance_fltr_ptr(code, error_end, stream, cache);
+                cache.rollback_recovery(checkpoint);
+                match separator_result {
+                    Ok(_) => break,
+                    Err(err) => {
+                        if err.is_invalid() {
+                            return Err(err);
+                        }
+                    }
+                }
+                error_end = error_end + 1;
+            }
+            children.push(recovery.error_node(stream, item_ptr, error_end));
+            moved_ptr = error_end;
+            count += 1;
+        }
+    }
+
     fn consume<
         T: Copy,
         TCache: Copy + Default + Eq + Hash + Ord,
@@ -49,30 +186,41 @@ impl<TP: IProduction, TS: IProduction<Node = TP::Node, Token = TP::Token>> Separ
         parse_production: P,
         parse_separator: S,
     ) -> ParsedResult<T, TP::Node> {
-        let success_data = parse_production(index, cache)?;
+        let success_data = match parse_production(index, cache) {
+            Ok(success_data) => success_data,
+            Err(err) => return self.empty_list(index, err),
+        };
 
         let mut moved_ptr = success_data.consumed_index;
         let mut children = success_data.children;
+        let mut count = 1;
         loop {
             match parse_separator(moved_ptr, cache) {
                 Ok(separator_success_data) => {
-                    match parse_production(separator_success_data.consumed_index, cache) {
+                    let next_result = if self.is_full(count) {
+                        Err(ProductionError::Unparsed)
+                    } else {
+                        parse_production(separator_success_data.consumed_index, cache)
+                    };
+                    match next_result {
                         Ok(next_success_data) => {
                             children.extend(separator_success_data.children);
                             children.extend(next_success_data.children);
                             moved_ptr = next_success_data.consumed_index;
+                            count += 1;
                         }
                         Err(err) => {
                             if err.is_invalid() {
                                 return Err(err);
                             } else if self.inclusive {
                                 children.extend(separator_success_data.children);
-                                break Ok(SuccessData::new(
+                                break self.bounded_list(
                                     separator_success_data.consumed_index,
                                     children,
-                                ));
+                                    count,
+                                );
                             } else {
-                                break Ok(SuccessData::new(moved_ptr, children));
+                                break self.bounded_list(moved_ptr, children, count);
                             }
                         }
                     }
@@ -81,15 +229,67 @@ impl<TP: IProduction, TS: IProduction<Node = TP::Node, Token = TP::Token>> Separ
                     if err.is_invalid() {
                         break Err(err);
                     } else {
-                        break Ok(SuccessData::new(moved_ptr, children));
+                        break self.bounded_list(moved_ptr, children, count);
                     }
                 }
             }
         }
     }
+
+    /// Whether the list has parsed the maximum number of items.
+    fn is_full(&self, count: usize) -> bool {
+        self.max.is_some_and(|max| count >= max)
+    }
+
+    /// Return the list of parsed items if the list has parsed the minimum number of items.
+    fn bounded_list<T>(
+        &self,
+        moved_ptr: T,
+        children: Vec<ASTNode<TP::Node>>,
+        count: usize,
+    ) -> ParsedResult<T, TP::Node> {
+        if count < self.min {
+            Err(ProductionError::Unparsed)
+        } else {
+            Ok(SuccessData::new(moved_ptr, children))
+        }
+    }
+
+    /// Return an empty list if the first item can not be parsed and the list is nullable.
+    fn empty_list<T>(&self, index: T, err: ProductionError) -> ParsedResult<T, TP::Node> {
+        if self.min == 0 && !err.is_invalid() {
+            Ok(SuccessData::hidden(index))
+        } else {
+            Err(err)
+        }
+    }
 }
 
 impl<TP: IProduction, TS: IProduction<Node = TP::Node, Token = TP::Token>> SeparatedList<TP, TS> {
+    /// Write the rule of the list, e.g. `p (s p)*` or `p (s p){0,2}` for at most 3 items.
+    fn write_rule(&self, writer: &mut dyn std::fmt::Write) -> std::fmt::Result {
+        if self.min == 0 {
+            write!(writer, "(")?;
+        }
+        write!(
+            writer,
+            "{p} ({s} {p})",
+            p = self.get_production(),
+            s = self.get_separator()
+        )?;
+        match (self.min, self.max) {
+            (0 | 1, None) => write!(writer, "*")?,
+            (min, max) => write_bounds(writer, min.saturating_sub(1), max.map(|max| max - 1))?,
+        }
+        if !self.inclusive {
+            write!(writer, " ({})?", self.get_separator())?;
+        }
+        if self.min == 0 {
+            write!(writer, ")?")?;
+        }
+        Ok(())
+    }
+
     pub fn assign_debugger(&self, debugger: crate::Log<&'static str>) -> Result<(), String> {
         self.debugger
             .set(debugger)
@@ -111,18 +311,7 @@ impl<TP: IProduction, TS: IProduction<Node = TP::Node, Token = TP::Token>> Displ
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self.rule_name.get() {
             Some(&rule_name) => write!(f, "{}", rule_name),
-            None => {
-                write!(
-                    f,
-                    "{p} ({s} {p})*",
-                    p = self.get_production(),
-                    s = self.get_separator()
-                )?;
-                if !self.inclusive {
-                    write!(f, " ({})?", self.get_separator())?;
-                }
-                Ok(())
-            }
+            None => self.write_rule(f),
         }
     }
 }
@@ -138,6 +327,15 @@ impl<TP: IProduction, TS: IProduction<Node = TP::Node, Token = TP::Token>> IProd
         connected_sets: HashMap<&'id str, usize>,
         visited_prod: &mut HashSet<&'id str>,
     ) -> Result<(), ImplementationError> {
+        if self.max.is_some_and(|max| max == 0 || max < self.min) {
+            return Err(ImplementationError::new(
+                "InvalidRepetition".into(),
+                format!(
+                    "Maximum items should be positive and not less than the minimum items for {}.",
+                    self
+                ),
+            ));
+        }
         // vps.push(&self.separator);
         if self.get_production().obtain_nullability(HashMap::new())? {
             self.get_production()
@@ -163,19 +361,9 @@ impl<TP: IProduction, TS: IProduction<Node = TP::Node, Token = TP::Token>> IProd
             Some(&s) => {
                 if visited.insert(s.into()) {
                     writeln!(writer, "{}", s)?;
-                    write!(
-                        writer,
-                        "{:>6} {p} ({s} {p})*",
-                        ":",
-                        p = self.get_production(),
-                        s = self.get_separator()
-                    )?;
-
-                    if !self.inclusive {
-                        writeln!(writer, " ({})?", self.get_separator()).unwrap();
-                    } else {
-                        writeln!(writer, "")?;
-                    }
+                    write!(writer, "{:>6} ", ":")?;
+                    self.write_rule(writer)?;
+                    writeln!(writer)?;
                     writeln!(writer, ";")?;
                 }
             }
@@ -186,6 +374,33 @@ impl<TP: IProduction, TS: IProduction<Node = TP::Node, Token = TP::Token>> IProd
         self.separator.impl_grammar(writer, visited)
     }
 
+    fn grammar_expr(&self, grammar: &mut Grammar) -> GrammarExpr {
+        let expr = |grammar: &mut Grammar| {
+            let production = self.production.grammar_expr(grammar);
+            let separator = self.separator.grammar_expr(grammar);
+            let rest = GrammarExpr::sequence(vec![separator.clone(), production.clone()]);
+            let mut items = vec![
+                production,
+                GrammarExpr::repeat(
+                    rest,
+                    self.min.saturating_sub(1),
+                    self.max.map(|max| max - 1),
+                ),
+            ];
+            if !self.inclusive {
+                items.push(GrammarExpr::optional(separator));
+            }
+            match self.min {
+                0 => GrammarExpr::optional(GrammarExpr::sequence(items)),
+                _ => GrammarExpr::sequence(items),
+            }
+        };
+        match self.rule_name.get() {
+            Some(rule_name) => grammar.add_rule(rule_name, expr),
+            None => expr(grammar),
+        }
+    }
+
     fn advance_token_ptr(
         &self,
         code: &Code,
@@ -193,8 +408,7 @@ impl<TP: IProduction, TS: IProduction<Node = TP::Node, Token = TP::Token>> IProd
         token_stream: &TokenStream<Self::Token>,
         cache: &mut Cache<FltrPtr, Self::Node>,
     ) -> ParsedResult<TokenPtr, TP::Node> {
-        #[cfg(debug_assertions)]
-        self.log_entry();
+        self.log_entry(token_stream[index].start);
         let result = self.consume(
             index,
             cache,
@@ -207,7 +421,6 @@ impl<TP: IProduction, TS: IProduction<Node = TP::Node, Token = TP::Token>> IProd
                     .advance_token_ptr(code, moved_pointer, token_stream, c)
             },
         );
-        #[cfg(debug_assertions)]
         self.log_lex_result(code, index, token_stream, &result);
         result
     }
@@ -218,8 +431,7 @@ impl<TP: IProduction, TS: IProduction<Node = TP::Node, Token = TP::Token>> IProd
         index: usize,
         cache: &mut Cache<usize, Self::Node>,
     ) -> ParsedResult<usize, Self::Node> {
-        #[cfg(debug_assertions)]
-        self.log_entry();
+        self.log_entry(index);
         let result = self.consume(
             index,
             cache,
@@ -229,7 +441,6 @@ impl<TP: IProduction, TS: IProduction<Node = TP::Node, Token = TP::Token>> IProd
             },
             |moved_pointer, c| self.get_separator().advance_ptr(code, moved_pointer, c),
         );
-        #[cfg(debug_assertions)]
         self.log_result(code, index, &result);
         result
     }
@@ -241,42 +452,47 @@ impl<TP: IProduction, TS: IProduction<Node = TP::Node, Token = TP::Token>> IProd
         token_stream: &TokenStream<Self::Token>,
         cache: &mut Cache<FltrPtr, Self::Node>,
     ) -> ParsedResult<FltrPtr, Self::Node> {
-        #[cfg(debug_assertions)]
-        self.log_entry();
+        self.log_entry(token_stream[index].start);
 
-        let result = self.consume(
-            index,
-            cache,
-            |moved_pointer, c| {
-                self.get_production()
-                    .advance_fltr_ptr(code, moved_pointer, token_stream, c)
-            },
-            |moved_pointer, c| {
-                self.get_separator()
-                    .advance_fltr_ptr(code, moved_pointer, token_stream, c)
-            },
-        );
+        let result = match self.recovery.get() {
+            Some(recovery) if cache.is_recovering() => {
+                self.consume_n_recover(recovery, code, index, token_stream, cache)
+            }
+            _ => self.consume(
+                index,
+                cache,
+                |moved_pointer, c| {
+                    self.get_production()
+                        .advance_fltr_ptr(code, moved_pointer, token_stream, c)
+                },
+                |moved_pointer, c| {
+                    self.get_separator()
+                        .advance_fltr_ptr(code, moved_pointer, token_stream, c)
+                },
+            ),
+        };
 
-        #[cfg(debug_assertions)]
         self.log_filtered_result(code, index, token_stream, &result);
 
         result
     }
 
     fn is_nullable(&self) -> bool {
-        self.production.is_nullable() && self.separator.is_nullable()
+        self.min == 0 || (self.production.is_nullable() && self.separator.is_nullable())
     }
 
     fn is_nullable_n_hidden(&self) -> bool {
-        self.production.is_nullable_n_hidden() && self.separator.is_nullable_n_hidden()
+        (self.min == 0 && !self.production.is_nullable())
+            || (self.production.is_nullable_n_hidden() && self.separator.is_nullable_n_hidden())
     }
 
     fn obtain_nullability<'id>(
         &'id self,
         visited: HashMap<&'id str, usize>,
     ) -> Result<bool, ImplementationError> {
-        Ok(self.production.obtain_nullability(visited.clone())?
-            && self.separator.obtain_nullability(visited)?)
+        let is_nullable = self.production.obtain_nullability(visited.clone())?
+            && self.separator.obtain_nullability(visited)?;
+        Ok(self.min == 0 || is_nullable)
     }
 
     fn impl_first_set(&self, first_set: &mut HashSet<Self::Token>) {
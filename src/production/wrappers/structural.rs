@@ -1,11 +1,13 @@
 use crate::production::ProductionLogger;
-use crate::{production::Structural, Cache, Code, FltrPtr, IProduction, TokenStream};
+use crate::{
+    grammar::{Grammar, GrammarExpr},
+    production::Structural,
+    Cache, Code, FltrPtr, IProduction, OnceCell, Rc, TokenStream,
+};
 use crate::{ImplementationError, ParsedResult, SuccessData, TokenPtr};
-use once_cell::unsync::OnceCell;
 use std::{
     collections::{HashMap, HashSet},
     fmt::Display,
-    rc::Rc,
 };
 
 impl<TProd: IProduction> Structural<TProd> {
@@ -62,6 +64,10 @@ impl<TProd: IProduction> IProduction for Structural<TProd> {
         self.production.impl_grammar(writer, visited)
     }
 
+    fn grammar_expr(&self, grammar: &mut Grammar) -> GrammarExpr {
+        self.production.grammar_expr(grammar)
+    }
+
     fn obtain_nullability<'id>(
         &'id self,
         visited: HashMap<&'id str, usize>,
@@ -91,11 +97,12 @@ impl<TProd: IProduction> IProduction for Structural<TProd> {
         token_stream: &TokenStream<Self::Token>,
         cache: &mut Cache<FltrPtr, Self::Node>,
     ) -> ParsedResult<FltrPtr, Self::Node> {
+        self.log_entry(token_stream[fltr_ptr].start);
+
         let result = self
             .get_symbol()
             .advance_fltr_ptr(code, fltr_ptr, token_stream, cache);
 
-        #[cfg(debug_assertions)]
         self.log_filtered_result(code, fltr_ptr, token_stream, &result);
 
         result
@@ -108,8 +115,7 @@ impl<TProd: IProduction> IProduction for Structural<TProd> {
         token_stream: &TokenStream<Self::Token>,
         cache: &mut Cache<FltrPtr, Self::Node>,
     ) -> ParsedResult<TokenPtr, Self::Node> {
-        #[cfg(debug_assertions)]
-        self.log_entry();
+        self.log_entry(token_stream[index].start);
 
         let next_fltr_ptr = match token_stream.find_filter_ptr(index) {
             Ok(fp) => fp + 1,
@@ -120,7 +126,6 @@ impl<TProd: IProduction> IProduction for Structural<TProd> {
             self.get_symbol()
                 .advance_fltr_ptr(code, next_fltr_ptr, token_stream, cache)?;
 
-        #[cfg(debug_assertions)]
         self.log_success(
             code,
             token_stream[index].start,
@@ -142,6 +147,12 @@ impl<TProd: IProduction> IProduction for Structural<TProd> {
         index: usize,
         cache: &mut Cache<usize, Self::Node>,
     ) -> ParsedResult<usize, Self::Node> {
-        self.get_symbol().advance_ptr(code, index, cache)
+        self.log_entry(index);
+
+        let result = self.get_symbol().advance_ptr(code, index, cache);
+
+        self.log_result(code, index, &result);
+
+        result
     }
 }
@@ -1,14 +1,12 @@
 use crate::{
+    grammar::{Grammar, GrammarExpr},
     production::{ProductionLogger, Validator},
-    Code,
-    ASTNode, Cache, FltrPtr, IProduction, ImplementationError, ParsedResult, ProductionError,
-    TokenPtr, TokenStream,
+    ASTNode, Cache, Code, FltrPtr, IProduction, ImplementationError, OnceCell, ParsedResult,
+    ProductionError, Rc, Shareable, TokenPtr, TokenStream,
 };
-use once_cell::unsync::OnceCell;
 use std::{
     collections::{HashMap, HashSet},
     fmt::Display,
-    rc::Rc,
 };
 
 impl<
@@ -62,7 +60,7 @@ impl<
 }
 impl<
         TProd: IProduction,
-        TF: Fn(&Vec<ASTNode<TProd::Node>>, &[u8]) -> Result<(), ProductionError>,
+        TF: Fn(&Vec<ASTNode<TProd::Node>>, &[u8]) -> Result<(), ProductionError> + Shareable,
     > IProduction for Validator<TProd, TF>
 {
     type Node = TProd::Node;
@@ -81,6 +79,10 @@ impl<
         self.production.impl_grammar(writer, visited)
     }
 
+    fn grammar_expr(&self, grammar: &mut Grammar) -> GrammarExpr {
+        self.production.grammar_expr(grammar)
+    }
+
     fn obtain_nullability<'id>(
         &'id self,
         visited: HashMap<&'id str, usize>,
@@ -111,8 +113,7 @@ impl<
         token_stream: &TokenStream<Self::Token>,
         cached: &mut Cache<FltrPtr, Self::Node>,
     ) -> ParsedResult<FltrPtr, Self::Node> {
-        #[cfg(debug_assertions)]
-        self.log_entry();
+        self.log_entry(token_stream[index].start);
 
         let result = self
             .get_production()
@@ -122,7 +123,6 @@ impl<
                 Ok(parsed_data)
             });
 
-        #[cfg(debug_assertions)]
         self.log_filtered_result(code, index, token_stream, &result);
 
         result
@@ -135,8 +135,7 @@ impl<
         token_stream: &TokenStream<Self::Token>,
         cache: &mut Cache<FltrPtr, Self::Node>,
     ) -> ParsedResult<TokenPtr, Self::Node> {
-        #[cfg(debug_assertions)]
-        self.log_entry();
+        self.log_entry(token_stream[index].start);
 
         let result = self
             .get_production()
@@ -146,7 +145,6 @@ impl<
                 Ok(parsed_data)
             });
 
-        #[cfg(debug_assertions)]
         self.log_lex_result(code, index, token_stream, &result);
 
         result
@@ -158,8 +156,7 @@ impl<
         index: usize,
         cache: &mut Cache<usize, Self::Node>,
     ) -> ParsedResult<usize, Self::Node> {
-        #[cfg(debug_assertions)]
-        self.log_entry();
+        self.log_entry(index);
 
         let result = self
             .get_production()
@@ -170,7 +167,6 @@ impl<
                 Ok(parsed_data)
             });
 
-        #[cfg(debug_assertions)]
         self.log_result(code, index, &result);
 
         result
@@ -0,0 +1,237 @@
+use crate::{FltrPtr, ParsedResult, ProductionProfile, ProfileReport, TokenPtr};
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+    fmt::{Display, Formatter},
+    time::{Duration, Instant},
+};
+
+/// Profiles of the productions identified by the addresses of the productions.
+type Profiles = HashMap<usize, ProductionProfile>;
+
+thread_local! {
+    /// The profiles of the productions which are parsed by the current thread.
+    static ACTIVE_PROFILE: RefCell<Option<Profiles>> = const { RefCell::new(None) };
+}
+
+/// Restore the previous profiles of the thread even if the profiled function panics.
+#[cfg(feature = "profile")]
+struct ProfileGuard(Option<Profiles>);
+
+#[cfg(feature = "profile")]
+impl Drop for ProfileGuard {
+    fn drop(&mut self) {
+        ACTIVE_PROFILE.with(|cell| *cell.borrow_mut() = self.0.take());
+    }
+}
+
+/// Run the function while the productions of the current thread are profiled and return the [ProfileReport].
+///
+/// The parsers call this function from [parse_with_profile](crate::DefaultParser::parse_with_profile)
+/// and [parse_with_profile](crate::LexerlessParser::parse_with_profile).
+/// A nested call creates a separate report and the outer report does not include the productions of the inner function.
+/// The function is available with the `profile` feature,
+/// since the productions check the profiles of the thread only if the feature is enabled.
+/// ## Example
+/// ```
+/// use lang_pt::{production::RegexField, IProduction, LexerlessParser, Rc};
+///
+/// let number: Rc<RegexField<u8, i8>> = Rc::new(RegexField::new(r"^[0-9]+", None).unwrap());
+/// let parser = LexerlessParser::new(number).unwrap();
+/// let (_, report) = lang_pt::with_profile(|| parser.parse(b"42"));
+/// let profile = report.get("/^[0-9]+/").unwrap();
+/// assert_eq!((profile.attempts, profile.successes, profile.consumed), (1, 1, 2));
+/// ```
+#[cfg(feature = "profile")]
+pub fn with_profile<R>(f: impl FnOnce() -> R) -> (R, ProfileReport) {
+    let guard = ProfileGuard(ACTIVE_PROFILE.with(|cell| cell.replace(Some(HashMap::new()))));
+    let result = f();
+    let profiles = ACTIVE_PROFILE.with(|cell| cell.take()).unwrap_or_default();
+    drop(guard);
+    let mut report = ProfileReport {
+        productions: profiles.into_values().collect(),
+    };
+    report.sort_by_time();
+    (result, report)
+}
+
+/// A pointer which measures the number of consumed tokens or bytes of a production.
+pub(crate) trait ProfilePtr: Copy {
+    fn distance(self, from: Self) -> usize;
+}
+
+impl ProfilePtr for usize {
+    fn distance(self, from: Self) -> usize {
+        self - from
+    }
+}
+
+impl ProfilePtr for FltrPtr {
+    fn distance(self, from: Self) -> usize {
+        self.0 - from.0
+    }
+}
+
+impl ProfilePtr for TokenPtr {
+    fn distance(self, from: Self) -> usize {
+        self.0 - from.0
+    }
+}
+
+/// Update the profile of the production.
+///
+/// The productions with the same name e.g. the terminals of the same token are profiled separately,
+/// while the name is only used as the label of the profile.
+fn record(production: &dyn Display, update: impl FnOnce(&mut ProductionProfile)) {
+    ACTIVE_PROFILE.with(|cell| {
+        if let Some(profiles) = cell.borrow_mut().as_mut() {
+            let address = production as *const dyn Display as *const () as usize;
+            let profile = profiles
+                .entry(address)
+                .or_insert_with(|| ProductionProfile::new(production.to_string()));
+            update(profile);
+        }
+    })
+}
+
+/// Whether the productions of the current thread are profiled.
+///
+/// The check is compiled out without the `profile` feature.
+fn is_active() -> bool {
+    cfg!(feature = "profile") && ACTIVE_PROFILE.with(|cell| cell.borrow().is_some())
+}
+
+/// Parse with the function and add the attempt, the outcome and the elapsed time to the profile of the production.
+pub(crate) fn measure<TP: ProfilePtr, TN>(
+    production: &dyn Display,
+    index: TP,
+    f: impl FnOnce() -> ParsedResult<TP, TN>,
+) -> ParsedResult<TP, TN> {
+    if !is_active() {
+        return f();
+    }
+    let instant = Instant::now();
+    let result = f();
+    let elapsed = instant.elapsed();
+    record(production, |profile| {
+        profile.attempts += 1;
+        profile.time += elapsed;
+        match &result {
+            Ok(data) => {
+                profile.successes += 1;
+                profile.consumed += data.consumed_index.distance(index);
+            }
+            Err(_) => profile.failures += 1,
+        }
+    });
+    result
+}
+
+/// Add a lookup of the [Cacheable](crate::production::Cacheable) memory to the profile of the cached production.
+pub(crate) fn cache_lookup(production: &dyn Display, hit: bool) {
+    if is_active() {
+        record(production, |profile| match hit {
+            true => profile.cache_hits += 1,
+            false => profile.cache_misses += 1,
+        })
+    }
+}
+
+impl ProductionProfile {
+    fn new(name: String) -> Self {
+        Self {
+            name,
+            attempts: 0,
+            successes: 0,
+            failures: 0,
+            consumed: 0,
+            time: Duration::ZERO,
+            cache_hits: 0,
+            cache_misses: 0,
+        }
+    }
+
+    /// Ratio of the cache hits to the lookups of the [Cacheable](crate::production::Cacheable) memory.
+    ///
+    /// Returns [None] if the production is not cached.
+    pub fn cache_hit_ratio(&self) -> Option<f64> {
+        let lookups = self.cache_hits + self.cache_misses;
+        match lookups {
+            0 => None,
+            _ => Some(self.cache_hits as f64 / lookups as f64),
+        }
+    }
+}
+
+impl ProfileReport {
+    /// Find the profile of the production by its identifier or its terminal name.
+    ///
+    /// The first profile is returned if the label is shared by the multiple productions.
+    pub fn get(&self, name: &str) -> Option<&ProductionProfile> {
+        self.productions.iter().find(|profile| profile.name == name)
+    }
+
+    /// Sort the productions with the comparator function.
+    pub fn sort_by<F: FnMut(&ProductionProfile, &ProductionProfile) -> std::cmp::Ordering>(
+        &mut self,
+        compare: F,
+    ) {
+        self.productions.sort_by(compare)
+    }
+
+    /// Sort the productions in the descending order of the time spent.
+    pub fn sort_by_time(&mut self) {
+        self.sort_by(|a, b| b.time.cmp(&a.time).then_with(|| a.name.cmp(&b.name)))
+    }
+
+    /// Sort the productions in the descending order of the attempts.
+    pub fn sort_by_attempts(&mut self) {
+        self.sort_by(|a, b| {
+            b.attempts
+                .cmp(&a.attempts)
+                .then_with(|| a.name.cmp(&b.name))
+        })
+    }
+
+    /// Sort the productions in the descending order of the failures which is the count of the backtracking attempts.
+    pub fn sort_by_failures(&mut self) {
+        self.sort_by(|a, b| {
+            b.failures
+                .cmp(&a.failures)
+                .then_with(|| a.name.cmp(&b.name))
+        })
+    }
+}
+
+impl Display for ProfileReport {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let width = self
+            .productions
+            .iter()
+            .map(|profile| profile.name.len())
+            .max()
+            .unwrap_or(0)
+            .max("Production".len());
+        writeln!(
+            f,
+            "{:<width$} {:>10} {:>10} {:>10} {:>10} {:>12} {:>10}",
+            "Production", "Attempts", "Successes", "Failures", "Consumed", "Time", "Cache hit"
+        )?;
+        for profile in &self.productions {
+            writeln!(
+                f,
+                "{:<width$} {:>10} {:>10} {:>10} {:>10} {:>12} {:>10}",
+                profile.name,
+                profile.attempts,
+                profile.successes,
+                profile.failures,
+                profile.consumed,
+                format!("{:.2?}", profile.time),
+                profile
+                    .cache_hit_ratio()
+                    .map_or(String::from("-"), |ratio| format!("{:.1}%", ratio * 100.0)),
+            )?;
+        }
+        Ok(())
+    }
+}
@@ -1,14 +1,49 @@
+use crate::grammar::LexerRule;
+use crate::pattern_scan::PatternScan;
 use crate::Code;
-use crate::{CombinedTokenizer, ILexeme, Log, TokenImpl, Tokenizer};
-use crate::{ITokenization, Lex, ParseError};
-use once_cell::unsync::OnceCell;
+use crate::{trace, TraceEvent};
+use crate::{
+    CheckpointedTokens, CombinedTokenizer, ILexeme, Log, MatchPolicy, OnceCell, Rc, ShadowedLexeme,
+    TokenImpl, Tokenizer,
+};
+use crate::{ITokenization, Lex, ParseError, ParseErrorKind, Shareable, TextEdit};
+use std::borrow::Cow;
 use std::fmt::Debug;
 use std::fmt::Write;
-use std::rc::Rc;
+
+type Lexemes<TToken, TState> = Vec<Rc<dyn ILexeme<Token = TToken, State = TState>>>;
+/// Lexemes of the tokenizer state with their merged patterns.
+type Analyzer<'a, TToken, TState> = (&'a Lexemes<TToken, TState>, &'a PatternScan<TToken>);
+/// Tokens and checkpoints of the code before the edit.
+type PreviousTokens<'p, TToken> = (&'p [Lex<TToken>], &'p [usize], &'p TextEdit);
 
 impl<TToken> Tokenizer<TToken, u8> {
     pub fn new(lexers: Vec<Rc<dyn ILexeme<Token = TToken, State = u8>>>) -> Self {
-        Self { lexers }
+        Self {
+            lexers,
+            policy: MatchPolicy::default(),
+            scan: OnceCell::new(),
+        }
+    }
+}
+
+impl<TToken, TState> Tokenizer<TToken, TState> {
+    /// Set the [MatchPolicy] to select the token among the matching lexemes.
+    pub fn set_match_policy(&mut self, policy: MatchPolicy) {
+        self.policy = policy;
+        self.scan = OnceCell::new();
+    }
+}
+
+impl<TToken, TState> Tokenizer<TToken, TState>
+where
+    TToken: Copy + Debug + Eq + Ord + Shareable,
+    TState: Copy + Debug + Eq + Ord + Shareable,
+{
+    /// The pattern lexemes are merged at the first tokenization.
+    fn scan(&self) -> &PatternScan<TToken> {
+        self.scan
+            .get_or_init(|| PatternScan::new(&self.lexers, &self.policy))
     }
 }
 
@@ -18,15 +53,24 @@ impl<TT, TS: Ord + Eq + Copy> CombinedTokenizer<TT, TS> {
             analyzers: vec![(default_state, lexemes)],
             default_state,
             debug: OnceCell::new(),
+            policy: MatchPolicy::default(),
+            scans: OnceCell::new(),
         }
     }
 
+    /// Set the [MatchPolicy] to select the token among the matching lexemes of each state.
+    pub fn set_match_policy(&mut self, policy: MatchPolicy) {
+        self.policy = policy;
+        self.scans = OnceCell::new();
+    }
+
     pub fn add_state(&mut self, state: TS, lexemes: Vec<Rc<dyn ILexeme<Token = TT, State = TS>>>) {
         let index = match self.analyzers.binary_search_by_key(&state, |a| a.0) {
             Ok(i) => i + 1,
             Err(i) => i,
         };
-        self.analyzers.insert(index, (state, lexemes))
+        self.analyzers.insert(index, (state, lexemes));
+        self.scans = OnceCell::new();
     }
 
     pub fn set_log(&mut self, log_label: Log<&'static str>) -> Result<(), String> {
@@ -36,7 +80,43 @@ impl<TT, TS: Ord + Eq + Copy> CombinedTokenizer<TT, TS> {
     }
 }
 
-impl<TToken: TokenImpl, TState: Copy + Debug + Ord + Eq> ITokenization
+impl<TT, TS> CombinedTokenizer<TT, TS>
+where
+    TT: Copy + Debug + Eq + Ord + Shareable,
+    TS: Copy + Debug + Eq + Ord + Shareable,
+{
+    /// The pattern lexemes of each state are merged at the first tokenization.
+    fn scans(&self) -> &Vec<PatternScan<TT>> {
+        self.scans.get_or_init(|| {
+            self.analyzers
+                .iter()
+                .map(|(_, lexemes)| PatternScan::new(lexemes, &self.policy))
+                .collect()
+        })
+    }
+
+    /// Label of the event of the level, when the event should be created.
+    ///
+    /// A tokenizer without a [Log] label creates all the events while a trace sink is installed
+    /// if the `trace` feature is enabled.
+    fn event_label(&self, level: Log<()>) -> Option<Cow<'static, str>> {
+        match self.debug.get() {
+            Some(log_label) => (log_label.order() >= level.order())
+                .then(|| Cow::Borrowed(log_label.label())),
+            None => trace::has_sink().then_some(Cow::Borrowed("tokenizer")),
+        }
+    }
+
+    fn analyzer(&self, state_stack: &[TS]) -> Analyzer<'_, TT, TS> {
+        let state = state_stack.last().map_or(self.default_state, |s| *s);
+        match self.analyzers.binary_search_by_key(&state, |(s, _)| *s) {
+            Ok(index) => (&self.analyzers[index].1, &self.scans()[index]),
+            Err(_) => panic!("Tokenize state '{:?}' not implemented", state),
+        }
+    }
+}
+
+impl<TToken: TokenImpl, TState: Copy + Debug + Ord + Eq + Shareable> ITokenization
     for CombinedTokenizer<TToken, TState>
 {
     type Token = TToken;
@@ -52,34 +132,35 @@ impl<TToken: TokenImpl, TState: Copy + Debug + Ord + Eq> ITokenization
             .analyzers
             .binary_search_by_key(&&current_state, |(b, _)| b)
         {
-            Ok(index) => &self.analyzers[index],
+            Ok(index) => (&self.analyzers[index].1, &self.scans()[index]),
             Err(_) => panic!("TokenizationState '{:?}' is not implemented", current_state),
         };
 
-        #[cfg(debug_assertions)]
-        let debug = self.debug.get().map_or(Log::None, |s| s.clone());
-
-        #[cfg(debug_assertions)]
-        if debug.order() >= Log::Verbose(()).order() {
-            println!("Begin tokenization for state: {:?}", current_state);
+        if let Some(label) = self.event_label(Log::Verbose(())) {
+            trace::emit(TraceEvent::Enter { label, pointer });
         }
 
         loop {
-            match current_analyzer
-                .1
-                .iter()
-                .find_map(|lexer| lexer.consume(code, pointer, &tokenized_stream, &mut state_stack))
-            {
-                Some(lex_data) => {
+            match self.policy.consume(
+                current_analyzer.0,
+                current_analyzer.1,
+                code,
+                pointer,
+                &tokenized_stream,
+                &mut state_stack,
+            ) {
+                Ok(Some(lex_data)) => {
                     debug_assert_eq!(pointer, lex_data.start);
                     pointer = lex_data.end;
 
                     tokenized_stream.push(lex_data);
 
                     if pointer == eof_pointer {
-                        #[cfg(debug_assertions)]
-                        if debug.order() >= Log::Success(()).order() {
-                            println!("[{}; Tokenization success]", debug);
+                        if let Some(label) = self.event_label(Log::Success(())) {
+                            trace::emit(TraceEvent::Success {
+                                label,
+                                range: 0..eof_pointer,
+                            });
                         }
                         let eof_token = TToken::eof();
 
@@ -87,23 +168,25 @@ impl<TToken: TokenImpl, TState: Copy + Debug + Ord + Eq> ITokenization
                         break Ok(tokenized_stream);
                     }
                 }
-                None => {
-                    #[cfg(debug_assertions)]
-                    if debug.order() >= Log::Default(()).order() {
-                        println!(
-                            "{}: Tokenization failed in state {:?} at {}",
-                            debug,
-                            current_state,
-                            code.obtain_position(pointer)
-                        );
+                result => {
+                    if let Some(label) = self.event_label(Log::Default(())) {
+                        trace::emit(TraceEvent::Failure {
+                            label,
+                            pointer,
+                            message: Some(format!(
+                                "Tokenization failed in state {:?}.",
+                                current_state
+                            )),
+                        });
                     }
-                    break Err(ParseError::new(
-                        pointer,
-                        format!(
-                            "Failed to tokenize code @ {}",
-                            code.obtain_position(pointer)
-                        ),
-                    ));
+                    break Err(result.err().unwrap_or_else(|| {
+                        ParseError::new(
+                            ParseErrorKind::Tokenization,
+                            code,
+                            pointer..code.char_end(pointer),
+                            "Failed to tokenize the code.".to_string(),
+                        )
+                    }));
                 }
             }
 
@@ -113,18 +196,16 @@ impl<TToken: TokenImpl, TState: Copy + Debug + Ord + Eq> ITokenization
                     .analyzers
                     .binary_search_by_key(&latest_state, |(b, _)| *b)
                 {
-                    Ok(index) => &self.analyzers[index],
+                    Ok(index) => (&self.analyzers[index].1, &self.scans()[index]),
                     Err(_) => panic!("Tokenize state '{:?}' not implemented", current_state),
                 };
-                #[cfg(debug_assertions)]
-                if debug.order() >= Log::Default(()).order() {
-                    println!(
-                        "{} : Switching state {:?} -> {:?} at {}",
-                        debug,
-                        current_state,
-                        latest_state,
-                        code.obtain_position(pointer)
-                    );
+                if let Some(label) = self.event_label(Log::Default(())) {
+                    trace::emit(TraceEvent::StateSwitch {
+                        label,
+                        from: format!("{:?}", current_state),
+                        to: format!("{:?}", latest_state),
+                        pointer,
+                    });
                 }
 
                 current_state = latest_state;
@@ -132,6 +213,32 @@ impl<TToken: TokenImpl, TState: Copy + Debug + Ord + Eq> ITokenization
         }
     }
 
+    fn tokenize_with_checkpoints(
+        &self,
+        code: &Code,
+    ) -> Result<CheckpointedTokens<TToken>, ParseError> {
+        relex(
+            code,
+            Vec::new(),
+            vec![0],
+            &self.policy,
+            |stack| self.analyzer(stack),
+            None,
+        )
+    }
+
+    fn retokenize(
+        &self,
+        code: &Code,
+        tokens: &[Lex<TToken>],
+        checkpoints: &[usize],
+        edit: &TextEdit,
+    ) -> Result<CheckpointedTokens<TToken>, ParseError> {
+        retokenize_with(code, tokens, checkpoints, edit, &self.policy, |stack| {
+            self.analyzer(stack)
+        })
+    }
+
     fn build_grammar(&self) -> Result<String, std::fmt::Error> {
         let mut writer = String::new();
         for (state, lexers) in &self.analyzers {
@@ -147,9 +254,24 @@ impl<TToken: TokenImpl, TState: Copy + Debug + Ord + Eq> ITokenization
         }
         Ok(writer)
     }
+
+    fn lexer_rules(&self) -> Vec<LexerRule> {
+        LexerRule::group(
+            self.analyzers
+                .iter()
+                .flat_map(|(_, lexers)| lexers.iter().flat_map(|l| l.lexer_patterns())),
+        )
+    }
+
+    fn shadowed_lexemes(&self) -> Vec<ShadowedLexeme> {
+        self.analyzers
+            .iter()
+            .flat_map(|(state, lexers)| self.policy.shadowed_lexemes(state, lexers))
+            .collect()
+    }
 }
 
-impl<TToken: TokenImpl, TState: Copy + Debug + Default + Ord + Eq> ITokenization
+impl<TToken: TokenImpl, TState: Copy + Debug + Default + Ord + Eq + Shareable> ITokenization
     for Tokenizer<TToken, TState>
 {
     type Token = TToken;
@@ -162,12 +284,15 @@ impl<TToken: TokenImpl, TState: Copy + Debug + Default + Ord + Eq> ITokenization
         let mut state_stack = Vec::new();
 
         loop {
-            match self
-                .lexers
-                .iter()
-                .find_map(|lexer| lexer.consume(code, pointer, &tokenized_stream, &mut state_stack))
-            {
-                Some(lex_data) => {
+            match self.policy.consume(
+                &self.lexers,
+                self.scan(),
+                code,
+                pointer,
+                &tokenized_stream,
+                &mut state_stack,
+            ) {
+                Ok(Some(lex_data)) => {
                     debug_assert_eq!(pointer, lex_data.start);
                     pointer = lex_data.end;
 
@@ -180,19 +305,45 @@ impl<TToken: TokenImpl, TState: Copy + Debug + Default + Ord + Eq> ITokenization
                         break Ok(tokenized_stream);
                     }
                 }
-                None => {
+                Err(err) => break Err(err),
+                Ok(None) => {
                     break Err(ParseError::new(
-                        pointer,
-                        format!(
-                            "Failed to tokenize code @ {}",
-                            code.obtain_position(pointer)
-                        ),
+                        ParseErrorKind::Tokenization,
+                        code,
+                        pointer..code.char_end(pointer),
+                        "Failed to tokenize the code.".to_string(),
                     ));
                 }
             }
         }
     }
 
+    fn tokenize_with_checkpoints(
+        &self,
+        code: &Code,
+    ) -> Result<CheckpointedTokens<TToken>, ParseError> {
+        relex(
+            code,
+            Vec::new(),
+            vec![0],
+            &self.policy,
+            |_| (&self.lexers, self.scan()),
+            None,
+        )
+    }
+
+    fn retokenize(
+        &self,
+        code: &Code,
+        tokens: &[Lex<TToken>],
+        checkpoints: &[usize],
+        edit: &TextEdit,
+    ) -> Result<CheckpointedTokens<TToken>, ParseError> {
+        retokenize_with(code, tokens, checkpoints, edit, &self.policy, |_| {
+            (&self.lexers, self.scan())
+        })
+    }
+
     fn build_grammar(&self) -> Result<String, std::fmt::Error> {
         let mut writer = String::new();
         writeln!(writer, "fragment {{")?;
@@ -204,4 +355,129 @@ impl<TToken: TokenImpl, TState: Copy + Debug + Default + Ord + Eq> ITokenization
         writeln!(writer, "}}")?;
         Ok(writer)
     }
+
+    fn lexer_rules(&self) -> Vec<LexerRule> {
+        LexerRule::group(self.lexers.iter().flat_map(|l| l.lexer_patterns()))
+    }
+
+    fn shadowed_lexemes(&self) -> Vec<ShadowedLexeme> {
+        self.policy
+            .shadowed_lexemes(&TState::default(), &self.lexers)
+    }
+}
+
+/// Restart the tokenization after the last token of the stream and synchronize the new tokens
+/// with the previous tokens after the edit.
+///
+/// The tokenization starts with an empty state stack, therefore the tokens should end at a checkpoint.
+fn relex<'a, TToken: TokenImpl + 'a, TState: Copy + Debug + Ord + Shareable + 'a>(
+    code: &Code,
+    mut tokens: Vec<Lex<TToken>>,
+    mut checkpoints: Vec<usize>,
+    policy: &MatchPolicy,
+    analyzer: impl Fn(&Vec<TState>) -> Analyzer<'a, TToken, TState>,
+    previous: Option<PreviousTokens<TToken>>,
+) -> Result<CheckpointedTokens<TToken>, ParseError> {
+    let mut pointer = tokens.last().map_or(0, |lex| lex.end);
+    let eof_pointer = code.value.len();
+    let mut state_stack = Vec::new();
+
+    loop {
+        let (lexemes, scan) = analyzer(&state_stack);
+        match policy.consume(lexemes, scan, code, pointer, &tokens, &mut state_stack) {
+            Ok(Some(lex_data)) => {
+                debug_assert_eq!(pointer, lex_data.start);
+                pointer = lex_data.end;
+
+                tokens.push(lex_data);
+
+                if pointer == eof_pointer {
+                    tokens.push(Lex::new(TToken::eof(), eof_pointer, eof_pointer));
+                    break Ok((tokens, checkpoints));
+                }
+            }
+            Err(err) => break Err(err),
+            Ok(None) => {
+                break Err(ParseError::new(
+                    ParseErrorKind::Tokenization,
+                    code,
+                    pointer..code.char_end(pointer),
+                    "Failed to tokenize the code.".to_string(),
+                ));
+            }
+        }
+
+        if !state_stack.is_empty() {
+            continue;
+        }
+        checkpoints.push(tokens.len());
+
+        if let Some((previous_tokens, previous_checkpoints, edit)) = previous {
+            if pointer < edit.new_end() {
+                continue;
+            }
+            let shift = edit.shift();
+            let shifted = |lex: &Lex<TToken>| {
+                Lex::new(
+                    lex.token,
+                    (lex.start as isize + shift) as usize,
+                    (lex.end as isize + shift) as usize,
+                )
+            };
+            let previous_pointer = (pointer as isize - shift) as usize;
+
+            // The tokens are synchronized if the last token is same as the previous one,
+            // and the previous tokenization had also restarted from the same state.
+            if let Ok(index) = previous_tokens.binary_search_by_key(&previous_pointer, |l| l.start)
+            {
+                if index > 0
+                    && previous_checkpoints.binary_search(&index).is_ok()
+                    && tokens.last() == Some(&shifted(&previous_tokens[index - 1]))
+                {
+                    let token_shift = tokens.len() as isize - index as isize;
+                    tokens.extend(previous_tokens[index..].iter().map(shifted));
+                    checkpoints.pop();
+                    checkpoints.extend(
+                        previous_checkpoints
+                            .iter()
+                            .filter(|c| **c >= index)
+                            .map(|c| (*c as isize + token_shift) as usize),
+                    );
+                    break Ok((tokens, checkpoints));
+                }
+            }
+        }
+    }
+}
+
+/// Restart the tokenization from the last checkpoint before the edit.
+fn retokenize_with<'a, TToken: TokenImpl + 'a, TState: Copy + Debug + Ord + Shareable + 'a>(
+    code: &Code,
+    tokens: &[Lex<TToken>],
+    checkpoints: &[usize],
+    edit: &TextEdit,
+    policy: &MatchPolicy,
+    analyzer: impl Fn(&Vec<TState>) -> Analyzer<'a, TToken, TState>,
+) -> Result<CheckpointedTokens<TToken>, ParseError> {
+    // The previous token which touches the edit may be extended by the edit.
+    let restart_index = checkpoints
+        .iter()
+        .rev()
+        .find(|index| **index == 0 || tokens[**index - 1].end < edit.range.start)
+        .map_or(0, |index| *index);
+
+    let restart_checkpoints = checkpoints
+        .iter()
+        .take_while(|index| **index <= restart_index)
+        .copied()
+        .collect();
+
+    relex(
+        code,
+        tokens[..restart_index].to_vec(),
+        restart_checkpoints,
+        policy,
+        analyzer,
+        Some((tokens, checkpoints, edit)),
+    )
 }
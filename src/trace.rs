@@ -0,0 +1,206 @@
+use crate::{StdoutSink, TraceEvent, TraceRecorder, TraceSink};
+use std::{
+    cell::Cell,
+    fmt::{Display, Formatter},
+    ptr::NonNull,
+};
+
+type SinkPtr = NonNull<dyn TraceSink + 'static>;
+
+thread_local! {
+    /// The sink which receives the events of the current thread.
+    static ACTIVE_SINK: Cell<Option<SinkPtr>> = const { Cell::new(None) };
+}
+
+/// Restore the previous sink of the thread even if the traced function panics.
+struct SinkGuard(Option<SinkPtr>);
+
+impl Drop for SinkGuard {
+    fn drop(&mut self) {
+        ACTIVE_SINK.with(|cell| cell.set(self.0));
+    }
+}
+
+/// Run the function where the events of the lexemes, the productions and the tokenizers are sent to the sink.
+///
+/// The parsers call this function from [parse_with_trace](crate::DefaultParser::parse_with_trace)
+/// and [tokenize_with_trace](crate::DefaultParser::tokenize_with_trace),
+/// while it can be used to trace any other entry point like [ITokenization::tokenize](crate::ITokenization::tokenize).
+/// The sinks can be nested where the inner sink receives the events until the inner function returns.
+///
+/// The utilities with a [Log](crate::Log) label send the events of the level of the log,
+/// while the productions and the tokenizers without a label send all their events only with the `trace` feature,
+/// so that the release builds without the feature do not check the sink on every production call.
+/// ## Example
+/// ```
+/// use lang_pt::{lexeme::Pattern, ILexeme, Code, Log, TraceRecorder, TraceEvent};
+///
+/// let number: Pattern<i8, u8> = Pattern::new(1, r"^[0-9]+").unwrap();
+/// number.set_log(Log::Success("number")).unwrap();
+/// let mut recorder = TraceRecorder::new();
+/// lang_pt::with_trace_sink(&mut recorder, || {
+///     number.consume(&Code::new(b"42"), 0, &Vec::new(), &mut Vec::new())
+/// });
+/// assert!(recorder.events.contains(&TraceEvent::Token {
+///     label: "number".into(),
+///     token: String::from("1"),
+///     range: 0..2,
+/// }));
+/// ```
+pub fn with_trace_sink<R>(sink: &mut dyn TraceSink, f: impl FnOnce() -> R) -> R {
+    let sink: NonNull<dyn TraceSink + '_> = NonNull::from(sink);
+    // SAFETY: The lifetime of the sink is erased to store it in the thread local slot,
+    // and the guard removes the sink from the slot before the borrow of the sink ends.
+    let sink: SinkPtr = unsafe { std::mem::transmute(sink) };
+    let _guard = SinkGuard(ACTIVE_SINK.with(|cell| cell.replace(Some(sink))));
+    f()
+}
+
+/// Whether a sink is installed for the current thread to receive the events of the unlabeled utilities.
+///
+/// The check is compiled out without the `trace` feature.
+pub(crate) fn has_sink() -> bool {
+    cfg!(feature = "trace") && ACTIVE_SINK.with(|cell| cell.get().is_some())
+}
+
+/// Send the event to the sink of the current thread.
+///
+/// If there is no sink, the event is printed to the standard output in the debug build.
+pub(crate) fn emit(event: TraceEvent) {
+    // The sink is taken out of the slot so that a sink which parses another input does not receive its own events.
+    match ACTIVE_SINK.with(|cell| cell.take()) {
+        Some(mut sink) => {
+            let _guard = SinkGuard(Some(sink));
+            // SAFETY: The sink is alive while it is installed by `with_trace_sink`,
+            // and no other reference to the sink exists while it is taken out of the slot.
+            unsafe { sink.as_mut() }.receive(event);
+        }
+        None => {
+            if cfg!(debug_assertions) {
+                StdoutSink.receive(event)
+            }
+        }
+    }
+}
+
+impl Display for TraceEvent {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TraceEvent::Enter { label, pointer } => {
+                write!(f, "Entering '{}' at {}.", label, pointer)
+            }
+            TraceEvent::Success { label, range } => write!(
+                f,
+                "Parsing Success for '{}': from {} to {}.",
+                label, range.start, range.end
+            ),
+            TraceEvent::Token {
+                label,
+                token,
+                range,
+            } => write!(
+                f,
+                "Lexeme Success for '{}': token {} from {} to {}.",
+                label, token, range.start, range.end
+            ),
+            TraceEvent::Failure {
+                label,
+                pointer,
+                message: None,
+            } => write!(f, "Unparsed '{}': at {}.", label, pointer),
+            TraceEvent::Failure {
+                label,
+                pointer,
+                message: Some(message),
+            } => write!(f, "Error '{}': at {}. {}", label, pointer, message),
+            TraceEvent::CacheHit {
+                label,
+                pointer,
+                success,
+            } => write!(
+                f,
+                "Cached {} of '{}' at {}.",
+                match success {
+                    true => "success",
+                    false => "failure",
+                },
+                label,
+                pointer
+            ),
+            TraceEvent::StateSwitch {
+                label,
+                from,
+                to,
+                pointer,
+            } => write!(
+                f,
+                "'{}': Switching state {} -> {} at {}.",
+                label, from, to, pointer
+            ),
+        }
+    }
+}
+
+impl TraceEvent {
+    /// Label of the [Log](crate::Log) of the utility which created the event,
+    /// or the identifier or the description of the utility without a label.
+    pub fn label(&self) -> &str {
+        match self {
+            TraceEvent::Enter { label, .. }
+            | TraceEvent::Success { label, .. }
+            | TraceEvent::Token { label, .. }
+            | TraceEvent::Failure { label, .. }
+            | TraceEvent::CacheHit { label, .. }
+            | TraceEvent::StateSwitch { label, .. } => label,
+        }
+    }
+}
+
+impl TraceSink for StdoutSink {
+    fn receive(&mut self, event: TraceEvent) {
+        println!("{}", event)
+    }
+}
+
+impl TraceRecorder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl TraceSink for TraceRecorder {
+    fn receive(&mut self, event: TraceEvent) {
+        self.events.push(event)
+    }
+}
+
+impl<F: FnMut(TraceEvent)> TraceSink for F {
+    fn receive(&mut self, event: TraceEvent) {
+        self(event)
+    }
+}
+
+#[cfg(feature = "log")]
+impl TraceSink for crate::LogSink {
+    /// The entering and the cache events are logged at the trace level and the other events at the debug level.
+    fn receive(&mut self, event: TraceEvent) {
+        let level = match event {
+            TraceEvent::Enter { .. } | TraceEvent::CacheHit { .. } => log::Level::Trace,
+            _ => log::Level::Debug,
+        };
+        log::log!(target: "lang_pt", level, "{}", event);
+    }
+}
+
+#[cfg(feature = "tracing")]
+impl TraceSink for crate::TracingSink {
+    /// The entering and the cache events are recorded at the trace level and the other events at the debug level.
+    fn receive(&mut self, event: TraceEvent) {
+        match event {
+            TraceEvent::Enter { .. } | TraceEvent::CacheHit { .. } => {
+                tracing::trace!(target: "lang_pt", label = event.label(), "{}", event)
+            }
+            _ => tracing::debug!(target: "lang_pt", label = event.label(), "{}", event),
+        }
+    }
+}
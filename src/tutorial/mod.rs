@@ -90,7 +90,7 @@
 //! use lang_pt::Code;
 //! use lang_pt::Lex;
 //! use lang_pt::{ITokenization, Tokenizer};
-//! use std::rc::Rc;
+//! use lang_pt::Rc;
 //! let identifier: Pattern<Token> = Pattern::new(Token::ID, r#"^[_$a-zA-Z][_$\w]*"#).unwrap();
 //! let number_literal =
 //!     Pattern::new(Token::Number, r"^(0|[\d--0]\d*)(\.\d+)?([eE][+-]?\d+)?").unwrap();
@@ -264,7 +264,7 @@
 //! use lang_pt::Lex;
 //! use lang_pt::TokenImpl;
 //! use lang_pt::{CombinedTokenizer, ITokenization};
-//! use std::rc::Rc;
+//! use lang_pt::Rc;
 //! const MAIN: u8 = 0;
 //! const TEMPLATE: u8 = 1;
 //! let expression_punctuations = Punctuations::new(vec![
@@ -0,0 +1,112 @@
+use crate::{ASTNode, NodeCursor, NodeImpl, ShapeError, TypedNode};
+use std::fmt::{Debug, Display, Formatter};
+
+impl ShapeError {
+    pub fn new(pointer: usize, message: String) -> Self {
+        Self { pointer, message }
+    }
+
+    /// Create a [ShapeError] for a tree which does not match the expected view.
+    pub fn mismatch<TNode: Debug>(tree: &ASTNode<TNode>, expected: &str) -> Self {
+        Self::new(
+            tree.start,
+            format!("Expected {} but found {:?}.", expected, tree.node),
+        )
+    }
+}
+
+impl Display for ShapeError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "ShapeError: {} at {}", self.message, self.pointer)
+    }
+}
+
+impl<'tree, TNode, T: TypedNode<'tree, TNode>> TypedNode<'tree, TNode> for Box<T> {
+    fn is_match(tree: &ASTNode<TNode>) -> bool {
+        T::is_match(tree)
+    }
+
+    fn from_tree(tree: &'tree ASTNode<TNode>) -> Result<Self, ShapeError> {
+        T::from_tree(tree).map(Box::new)
+    }
+}
+
+impl<'tree, TNode: NodeImpl> NodeCursor<'tree, TNode> {
+    /// Create a cursor at the first child of the tree.
+    pub fn new(tree: &'tree ASTNode<TNode>) -> Self {
+        Self { tree, index: 0 }
+    }
+
+    fn peek(&self) -> Option<&'tree ASTNode<TNode>> {
+        self.tree.children.get(self.index)
+    }
+
+    /// Whether the next child is the placeholder tree of a null production.
+    fn is_null(&self) -> bool {
+        self.peek().is_some_and(|child| child.node.is_null())
+    }
+
+    /// Consume the next child into the view.
+    /// ## Arguments
+    /// * `field` - Name of the consuming field to be reported when the child does not match the view.
+    pub fn one<T: TypedNode<'tree, TNode>>(&mut self, field: &str) -> Result<T, ShapeError> {
+        match self.peek() {
+            Some(child) if T::is_match(child) => {
+                self.index += 1;
+                T::from_tree(child)
+            }
+            Some(child) => Err(ShapeError::mismatch(
+                child,
+                &format!("{} of {:?}", field, self.tree.node),
+            )),
+            None => Err(ShapeError::new(
+                self.tree.end,
+                format!("Missing {} of {:?}.", field, self.tree.node),
+            )),
+        }
+    }
+
+    /// Consume the next child into the view if the child matches the view.
+    /// A placeholder tree of a null production is consumed as [None].
+    pub fn optional<T: TypedNode<'tree, TNode>>(&mut self) -> Result<Option<T>, ShapeError> {
+        match self.peek() {
+            Some(child) if T::is_match(child) => {
+                self.index += 1;
+                T::from_tree(child).map(Some)
+            }
+            Some(_) if self.is_null() => {
+                self.index += 1;
+                Ok(None)
+            }
+            _ => Ok(None),
+        }
+    }
+
+    /// Consume the consecutive children which match the view.
+    /// A placeholder tree of a null production is consumed as an empty list.
+    pub fn many<T: TypedNode<'tree, TNode>>(&mut self) -> Result<Vec<T>, ShapeError> {
+        let mut items = Vec::new();
+        while let Some(child) = self.peek() {
+            if !T::is_match(child) {
+                if items.is_empty() && self.is_null() {
+                    self.index += 1;
+                }
+                break;
+            }
+            self.index += 1;
+            items.push(T::from_tree(child)?);
+        }
+        Ok(items)
+    }
+
+    /// Check that all the children of the tree have been consumed.
+    pub fn finish(self) -> Result<(), ShapeError> {
+        match self.peek() {
+            Some(child) => Err(ShapeError::new(
+                child.start,
+                format!("Unexpected {:?} in {:?}.", child.node, self.tree.node),
+            )),
+            None => Ok(()),
+        }
+    }
+}
@@ -0,0 +1,173 @@
+use crate::{ASTNode, PostOrder, PreOrder, VisitAction, Visitor, VisitorMut};
+
+/// A tree whose children have been detached while walking the children with [walk_mut](ASTNode::walk_mut).
+struct DetachedTree<TNode> {
+    /// The detached tree or [None] for the root of the walk.
+    tree: Option<ASTNode<TNode>>,
+    visited: Vec<ASTNode<TNode>>,
+    pending: std::vec::IntoIter<ASTNode<TNode>>,
+}
+
+impl<TNode> DetachedTree<TNode> {
+    fn new(tree: Option<ASTNode<TNode>>, children: Vec<ASTNode<TNode>>) -> Self {
+        Self {
+            tree,
+            visited: Vec::with_capacity(children.len()),
+            pending: children.into_iter(),
+        }
+    }
+}
+
+impl<TNode> ASTNode<TNode> {
+    /// Walk the tree and its nested children in depth first order with the [Visitor].
+    ///
+    /// Returns `false` if the traversal has been stopped by the visitor.
+    pub fn walk<V: Visitor<TNode>>(&self, visitor: &mut V) -> bool {
+        let mut stack: Vec<(&ASTNode<TNode>, usize)> = Vec::new();
+        let mut entering = Some(self);
+        loop {
+            if let Some(tree) = entering.take() {
+                match visitor.enter(tree) {
+                    VisitAction::Continue => stack.push((tree, 0)),
+                    VisitAction::SkipChildren => {
+                        if visitor.leave(tree) == VisitAction::Stop {
+                            return false;
+                        }
+                    }
+                    VisitAction::Stop => return false,
+                }
+            }
+            let Some((tree, index)) = stack.last_mut() else {
+                return true;
+            };
+            match tree.children.get(*index) {
+                Some(child) => {
+                    *index += 1;
+                    entering = Some(child);
+                }
+                None => {
+                    let tree = *tree;
+                    stack.pop();
+                    if visitor.leave(tree) == VisitAction::Stop {
+                        return false;
+                    }
+                }
+            }
+        }
+    }
+
+    /// Walk the tree and its nested children in depth first order with the [VisitorMut].
+    ///
+    /// While the children of a tree are walked, they are detached from the tree.
+    /// Therefore, the ancestors of the visited tree are not reachable from the visitor.
+    /// Returns `false` if the traversal has been stopped by the visitor.
+    pub fn walk_mut<V: VisitorMut<TNode>>(&mut self, visitor: &mut V) -> bool {
+        match visitor.enter(self) {
+            VisitAction::Continue => {}
+            VisitAction::SkipChildren => return visitor.leave(self) != VisitAction::Stop,
+            VisitAction::Stop => return false,
+        }
+        let children = std::mem::take(&mut self.children);
+        let mut stack = vec![DetachedTree::new(None, children)];
+
+        while let Some(detached) = stack.last_mut() {
+            match detached.pending.next() {
+                Some(mut child) => match visitor.enter(&mut child) {
+                    VisitAction::Continue => {
+                        let children = std::mem::take(&mut child.children);
+                        stack.push(DetachedTree::new(Some(child), children));
+                    }
+                    VisitAction::SkipChildren => {
+                        let action = visitor.leave(&mut child);
+                        detached.visited.push(child);
+                        if action == VisitAction::Stop {
+                            break;
+                        }
+                    }
+                    VisitAction::Stop => {
+                        detached.visited.push(child);
+                        break;
+                    }
+                },
+                None => {
+                    let DetachedTree { tree, visited, .. } = stack.pop().unwrap();
+                    match tree {
+                        Some(mut tree) => {
+                            tree.children = visited;
+                            let action = visitor.leave(&mut tree);
+                            stack.last_mut().unwrap().visited.push(tree);
+                            if action == VisitAction::Stop {
+                                break;
+                            }
+                        }
+                        None => {
+                            self.children = visited;
+                            return visitor.leave(self) != VisitAction::Stop;
+                        }
+                    }
+                }
+            }
+        }
+
+        // Attach the children of the trees which have not been left when the traversal was stopped.
+        while let Some(DetachedTree {
+            tree,
+            mut visited,
+            pending,
+        }) = stack.pop()
+        {
+            visited.extend(pending);
+            match tree {
+                Some(mut tree) => {
+                    tree.children = visited;
+                    stack.last_mut().unwrap().visited.push(tree);
+                }
+                None => self.children = visited,
+            }
+        }
+        false
+    }
+
+    /// Iterate the tree and its nested children in pre-order i.e. a tree before its children.
+    pub fn pre_order(&self) -> PreOrder<'_, TNode> {
+        PreOrder { stack: vec![self] }
+    }
+
+    /// Iterate the tree and its nested children in post-order i.e. a tree after its children.
+    pub fn post_order(&self) -> PostOrder<'_, TNode> {
+        PostOrder {
+            stack: vec![(self, 0)],
+        }
+    }
+}
+
+impl<'tree, TNode> Iterator for PreOrder<'tree, TNode> {
+    type Item = &'tree ASTNode<TNode>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let tree = self.stack.pop()?;
+        self.stack.extend(tree.children.iter().rev());
+        Some(tree)
+    }
+}
+
+impl<'tree, TNode> Iterator for PostOrder<'tree, TNode> {
+    type Item = &'tree ASTNode<TNode>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let (tree, index) = self.stack.last_mut()?;
+            match tree.children.get(*index) {
+                Some(child) => {
+                    *index += 1;
+                    self.stack.push((child, 0));
+                }
+                None => {
+                    let tree = *tree;
+                    self.stack.pop();
+                    return Some(tree);
+                }
+            }
+        }
+    }
+}
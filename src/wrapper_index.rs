@@ -1,4 +1,4 @@
-use crate::{TokenPtr, FltrPtr};
+use crate::{CacheKey, FltrPtr, TokenPtr};
 use std::{
     fmt::Display,
     ops::{Add, Sub},
@@ -94,3 +94,9 @@ impl Sub<usize> for FltrPtr {
         FltrPtr::new(self.0 - rhs)
     }
 }
+
+impl From<usize> for CacheKey {
+    fn from(key: usize) -> Self {
+        CacheKey(key)
+    }
+}